@@ -104,6 +104,7 @@ use crate::api::opt::Endpoint;
 	feature = "kv-tikv",
 	feature = "kv-rocksdb",
 	feature = "kv-speedb",
+	feature = "kv-redb",
 	feature = "kv-fdb",
 	feature = "kv-indxdb",
 ))]
@@ -186,6 +187,7 @@ where
 	feature = "kv-tikv",
 	feature = "kv-rocksdb",
 	feature = "kv-speedb",
+	feature = "kv-redb",
 	feature = "kv-fdb",
 	feature = "kv-indxdb",
 ))]
@@ -196,6 +198,7 @@ where
 		feature = "kv-tikv",
 		feature = "kv-rocksdb",
 		feature = "kv-speedb",
+		feature = "kv-redb",
 		feature = "kv-fdb",
 		feature = "kv-indxdb",
 	)))
@@ -348,6 +351,7 @@ where
 		feature = "kv-tikv",
 		feature = "kv-rocksdb",
 		feature = "kv-speedb",
+		feature = "kv-redb",
 		feature = "kv-fdb",
 		feature = "kv-indxdb",
 	),
@@ -361,6 +365,7 @@ where
 			feature = "kv-tikv",
 			feature = "kv-rocksdb",
 			feature = "kv-speedb",
+			feature = "kv-redb",
 			feature = "kv-fdb",
 			feature = "kv-indxdb",
 		),
@@ -119,6 +119,21 @@ impl Connection for Any {
 					.into());
 				}
 
+				"redb" => {
+					#[cfg(feature = "kv-redb")]
+					{
+						features.insert(ExtraFeatures::Backup);
+						engine::local::native::router(address, conn_tx, route_rx);
+						conn_rx.into_recv_async().await??
+					}
+
+					#[cfg(not(feature = "kv-redb"))]
+					return Err(DbError::Ds(
+						"Cannot connect to the `redb` storage engine as it is not enabled in this build of SurrealDB".to_owned(),
+					)
+					.into());
+				}
+
 				"tikv" => {
 					#[cfg(feature = "kv-tikv")]
 					{
@@ -29,6 +29,7 @@ pub(crate) mod wasm;
 use crate::api::conn::DbResponse;
 use crate::api::conn::Method;
 use crate::api::conn::Param;
+use crate::api::conn::Route;
 use crate::api::engine::create_statement;
 use crate::api::engine::delete_statement;
 use crate::api::engine::merge_statement;
@@ -43,19 +44,24 @@ use crate::api::Result;
 use crate::api::Surreal;
 #[cfg(not(target_arch = "wasm32"))]
 use crate::channel;
+use crate::dbs::Notification;
 use crate::dbs::Response;
 use crate::dbs::Session;
 use crate::kvs::Datastore;
 use crate::opt::auth::Root;
 use crate::opt::IntoEndpoint;
+use crate::sql::statements::KillStatement;
 use crate::sql::Array;
 use crate::sql::Query;
 use crate::sql::Statement;
 use crate::sql::Statements;
 use crate::sql::Strand;
+use crate::sql::Uuid;
 use crate::sql::Value;
+use flume::Sender;
 use indexmap::IndexMap;
 use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::mem;
 #[cfg(not(target_arch = "wasm32"))]
@@ -228,6 +234,41 @@ pub struct RocksDb;
 #[derive(Debug)]
 pub struct SpeeDb;
 
+/// Redb database
+///
+/// # Examples
+///
+/// Instantiating a redb-backed instance
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// use surrealdb::Surreal;
+/// use surrealdb::engine::local::Redb;
+///
+/// let db = Surreal::new::<Redb>("temp.db").await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Instantiating a redb-backed strict instance
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// use surrealdb::opt::Strict;
+/// use surrealdb::Surreal;
+/// use surrealdb::engine::local::Redb;
+///
+/// let db = Surreal::new::<Redb>(("temp.db", Strict)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "kv-redb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "kv-redb")))]
+#[derive(Debug)]
+pub struct Redb;
+
 /// IndxDB database
 ///
 /// # Examples
@@ -343,6 +384,11 @@ pub struct Db {
 	pub(crate) method: crate::api::conn::Method,
 }
 
+pub(crate) enum Either {
+	Request(Option<Route>),
+	Notification(Notification),
+}
+
 impl Surreal<Db> {
 	/// Connects to a specific database endpoint, saving the connection on the static client
 	pub fn connect<P>(&self, address: impl IntoEndpoint<P, Client = Db>) -> Connect<Db, ()> {
@@ -368,7 +414,7 @@ fn process(responses: Vec<Response>) -> Result<QueryResponse> {
 			Err(error) => map.insert(index, Err(error.into())),
 		};
 	}
-	Ok(QueryResponse(map))
+	Ok(QueryResponse::new(map))
 }
 
 async fn take(one: bool, responses: Vec<Response>) -> Result<Value> {
@@ -403,6 +449,7 @@ async fn router(
 	configured_root: &Option<Root<'_>>,
 	session: &mut Session,
 	vars: &mut BTreeMap<String, Value>,
+	live_queries: &mut HashMap<Uuid, Sender<Notification>>,
 ) -> Result<DbResponse> {
 	let mut params = param.other;
 
@@ -632,26 +679,41 @@ async fn router(
 			Ok(DbResponse::Other(Value::None))
 		}
 		Method::Live => {
+			let Some(sender) = param.notification_sender else {
+				return Err(Error::InternalError(
+					"live queries require a notification channel".to_owned(),
+				)
+				.into());
+			};
 			let table = match &mut params[..] {
+				// The live query was started by a `LIVE SELECT` statement
+				[Value::Uuid(id), ..] => {
+					live_queries.insert(mem::take(id), sender);
+					return Ok(DbResponse::Other(Value::None));
+				}
 				[value] => mem::take(value),
 				_ => unreachable!(),
 			};
 			let mut vars = BTreeMap::new();
 			vars.insert("table".to_owned(), table);
-			let response = kvs
-				.execute("LIVE SELECT * FROM type::table($table)", &*session, Some(vars))
-				.await?;
+			let response = kvs.execute("LIVE SELECT * FROM $table", &*session, Some(vars)).await?;
 			let value = take(true, response).await?;
+			if let Value::Uuid(id) = &value {
+				live_queries.insert(id.clone(), sender);
+			}
 			Ok(DbResponse::Other(value))
 		}
 		Method::Kill => {
 			let id = match &mut params[..] {
-				[value] => mem::take(value),
+				[Value::Uuid(id)] => mem::take(id),
 				_ => unreachable!(),
 			};
-			let mut vars = BTreeMap::new();
-			vars.insert("id".to_owned(), id);
-			let response = kvs.execute("KILL type::string($id)", &*session, Some(vars)).await?;
+			live_queries.remove(&id);
+			let statement = KillStatement {
+				id,
+			};
+			let query = Query(Statements(vec![Statement::Kill(statement)]));
+			let response = kvs.process(query, &*session, Some(vars.clone())).await?;
 			let value = take(true, response).await?;
 			Ok(DbResponse::Other(value))
 		}
@@ -5,6 +5,7 @@ use crate::api::conn::Param;
 use crate::api::conn::Route;
 use crate::api::conn::Router;
 use crate::api::engine::local::Db;
+use crate::api::engine::local::Either;
 use crate::api::err::Error;
 use crate::api::opt::Endpoint;
 use crate::api::ExtraFeatures;
@@ -17,8 +18,10 @@ use crate::opt::auth::Root;
 use flume::Receiver;
 use flume::Sender;
 use futures::StreamExt;
+use futures_concurrency::stream::Merge as _;
 use once_cell::sync::OnceCell;
 use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::future::Future;
 use std::marker::PhantomData;
@@ -92,8 +95,9 @@ pub(crate) fn router(
 
 		let kvs = {
 			let path = match url.scheme() {
-				"mem" => "memory".to_owned(),
-				"fdb" | "rocksdb" | "speedb" | "file" => match url.to_file_path() {
+				"mem" if url.path().is_empty() || url.path() == "/" => "memory".to_owned(),
+				"mem" | "fdb" | "rocksdb" | "speedb" | "redb" | "file" => match url.to_file_path() {
+					Ok(path) if url.scheme() == "mem" => format!("memory:{}", path.display()),
 					Ok(path) => format!("{}://{}", url.scheme(), path.display()),
 					Err(_) => {
 						let error = Error::InvalidUrl(url.as_str().to_owned());
@@ -116,10 +120,23 @@ pub(crate) fn router(
 			}
 		};
 
-		let kvs = kvs.with_strict_mode(address.strict);
+		let kvs = kvs.with_strict_mode(address.strict).with_notifications();
+
+		// Drain the notifications into an unbounded channel, so that
+		// queries are never blocked by notifications nobody has read yet
+		let (notification_tx, notification_rx) = flume::unbounded();
+		if let Some(channel) = kvs.notifications() {
+			tokio::spawn(async move {
+				while let Ok(notification) = channel.recv().await {
+					if notification_tx.send_async(notification).await.is_err() {
+						break;
+					}
+				}
+			});
+		}
 
 		let mut vars = BTreeMap::new();
-		let mut stream = route_rx.into_stream();
+		let mut live_queries = HashMap::new();
 		let configured_root = match address.auth {
 			Level::Kv => Some(Root {
 				username: &address.username,
@@ -134,17 +151,46 @@ pub(crate) fn router(
 			// If no root user is specified, the database should be open
 			Session::for_kv()
 		};
+		// Allow live queries to be run
+		session.rt = true;
+
+		let streams = (
+			route_rx.into_stream().map(Either::Request),
+			notification_rx.into_stream().map(Either::Notification),
+		);
 
-		while let Some(Some(route)) = stream.next().await {
-			match super::router(route.request, &kvs, &configured_root, &mut session, &mut vars)
-				.await
-			{
-				Ok(value) => {
-					let _ = route.response.into_send_async(Ok(value)).await;
+		let mut merged = streams.merge();
+
+		while let Some(either) = merged.next().await {
+			match either {
+				Either::Request(Some(route)) => {
+					match super::router(
+						route.request,
+						&kvs,
+						&configured_root,
+						&mut session,
+						&mut vars,
+						&mut live_queries,
+					)
+					.await
+					{
+						Ok(value) => {
+							let _ = route.response.into_send_async(Ok(value)).await;
+						}
+						Err(error) => {
+							let _ = route.response.into_send_async(Err(error)).await;
+						}
+					}
 				}
-				Err(error) => {
-					let _ = route.response.into_send_async(Err(error)).await;
+				Either::Notification(notification) => {
+					let id = notification.id.clone();
+					if let Some(sender) = live_queries.get(&id) {
+						if sender.send_async(notification).await.is_err() {
+							live_queries.remove(&id);
+						}
+					}
 				}
+				Either::Request(None) => break,
 			}
 		}
 	});
@@ -6,6 +6,7 @@ pub mod any;
 	feature = "kv-tikv",
 	feature = "kv-rocksdb",
 	feature = "kv-speedb",
+	feature = "kv-redb",
 	feature = "kv-fdb",
 	feature = "kv-indxdb",
 ))]
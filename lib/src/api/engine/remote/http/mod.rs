@@ -191,7 +191,7 @@ async fn query(request: RequestBuilder) -> Result<QueryResponse> {
 		}
 	}
 
-	Ok(QueryResponse(map))
+	Ok(QueryResponse::new(map))
 }
 
 async fn take(one: bool, request: RequestBuilder) -> Result<Value> {
@@ -546,35 +546,6 @@ async fn router(
 			}
 			Ok(DbResponse::Other(Value::None))
 		}
-		Method::Live => {
-			let path = base_url.join(SQL_PATH)?;
-			let table = match &params[..] {
-				[table] => table.to_string(),
-				_ => unreachable!(),
-			};
-			let request = client
-				.post(path)
-				.headers(headers.clone())
-				.auth(auth)
-				.query(&[("table", table)])
-				.body("LIVE SELECT * FROM type::table($table)");
-			let value = take(true, request).await?;
-			Ok(DbResponse::Other(value))
-		}
-		Method::Kill => {
-			let path = base_url.join(SQL_PATH)?;
-			let id = match &params[..] {
-				[id] => id.to_string(),
-				_ => unreachable!(),
-			};
-			let request = client
-				.post(path)
-				.headers(headers.clone())
-				.auth(auth)
-				.query(&[("id", id)])
-				.body("KILL type::string($id)");
-			let value = take(true, request).await?;
-			Ok(DbResponse::Other(value))
-		}
+		Method::Live | Method::Kill => Err(Error::LiveQueriesNotSupported.into()),
 	}
 }
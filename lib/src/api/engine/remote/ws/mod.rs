@@ -12,13 +12,23 @@ use crate::api::err::Error;
 use crate::api::Connect;
 use crate::api::Result;
 use crate::api::Surreal;
+use crate::dbs::Action;
+use crate::dbs::Notification;
 use crate::dbs::Status;
 use crate::opt::IntoEndpoint;
+use crate::sql::statements::show::ShowSince;
+use crate::sql::statements::show::ShowStatement;
 use crate::sql::Array;
+use crate::sql::Datetime;
 use crate::sql::Strand;
+use crate::sql::Table;
+use crate::sql::Uuid;
 use crate::sql::Value;
+use flume::Sender;
 use futures::Stream;
 use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::pin::Pin;
 use std::task::Context;
@@ -36,6 +46,13 @@ use wasmtimer::tokio::Interval;
 pub(crate) const PATH: &str = "rpc";
 const PING_INTERVAL: Duration = Duration::from_secs(5);
 const PING_METHOD: &str = "ping";
+const RECONNECT_MIN_DELAY: Duration = Duration::from_millis(100);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(10);
+const CATCH_UP_LIMIT: u32 = 100;
+/// The ID of the requests which read the current time of the server
+const SERVER_TIME_REQUEST_ID: i64 = i64::MIN;
+/// The ID of the requests which ask for notifications in the binary format
+const FORMAT_REQUEST_ID: i64 = i64::MIN + 1;
 
 /// The WS scheme used to connect to `ws://` endpoints
 #[derive(Debug)]
@@ -90,10 +107,12 @@ pub(crate) struct Failure {
 	pub(crate) message: String,
 }
 
+// The variants here should be in exactly the same order as `Data` in the server's `src/rpc/res.rs`
 #[derive(Debug, Deserialize)]
 pub(crate) enum Data {
 	Other(Value),
 	Query(Vec<QueryMethodResponse>),
+	Live(Notification),
 }
 
 type ServerResult = std::result::Result<Data, Failure>;
@@ -122,7 +141,7 @@ impl DbResponse {
 	fn from(result: ServerResult) -> Result<Self> {
 		match result.map_err(Error::from)? {
 			Data::Other(value) => Ok(DbResponse::Other(value)),
-			Data::Query(results) => Ok(DbResponse::Query(api::Response(
+			Data::Query(results) => Ok(DbResponse::Query(api::Response::new(
 				results
 					.into_iter()
 					.map(|response| match response.status {
@@ -139,6 +158,8 @@ impl DbResponse {
 					.enumerate()
 					.collect(),
 			))),
+			// Live query notifications are routed to their streams before we get here
+			Data::Live(..) => unreachable!(),
 		}
 	}
 }
@@ -149,6 +170,250 @@ pub(crate) struct Response {
 	pub(crate) result: ServerResult,
 }
 
+impl Data {
+	/// Extracts the server ID of a live query from the response to the request which started it
+	fn into_live_query_id(self) -> Option<Uuid> {
+		match self {
+			Data::Other(Value::Uuid(id)) => Some(id),
+			Data::Query(results) => match results.into_iter().next() {
+				Some(QueryMethodResponse {
+					status: Status::Ok,
+					result: Value::Uuid(id),
+					..
+				}) => Some(id),
+				_ => None,
+			},
+			_ => None,
+		}
+	}
+}
+
+/// Builds the payload of a request
+fn request(id: i64, method: Method, params: Vec<Value>) -> Value {
+	let mut request = BTreeMap::new();
+	request.insert("id".to_owned(), Value::from(id));
+	request.insert("method".to_owned(), method.as_str().into());
+	if !params.is_empty() {
+		request.insert("params".to_owned(), params.into());
+	}
+	Value::from(request)
+}
+
+/// Builds the request which asks the server to send live query notifications in
+/// the binary format, which the server otherwise only uses to respond to requests
+fn format_request() -> Value {
+	let mut request = BTreeMap::new();
+	request.insert("id".to_owned(), Value::from(FORMAT_REQUEST_ID));
+	request.insert("method".to_owned(), "format".into());
+	request.insert("params".to_owned(), vec![Value::from("full")].into());
+	Value::from(request)
+}
+
+/// Doubles the delay before the next reconnection attempt, up to a maximum
+fn backoff(delay: Duration) -> Duration {
+	(delay * 2).min(RECONNECT_MAX_DELAY)
+}
+
+/// A live query registered on a connection
+struct LiveQuery {
+	/// The ID of the live query on the server, which changes every time it is started again
+	server_id: Uuid,
+	/// The request which starts the live query again after reconnecting
+	request: (Method, Vec<Value>),
+	/// The table whose change feed can be used to catch up on missed changes
+	table: Option<Table>,
+	sender: Sender<Notification>,
+}
+
+/// The live queries registered on a connection, keyed by the IDs handed out to their streams
+///
+/// Live queries are killed by the server when a connection is closed, so they are started again
+/// when the connection is re-established. The new server IDs are mapped back to the original
+/// ones, so the streams keep on working as if nothing happened.
+///
+/// The time of the server is read at regular intervals while connected. Missed changes are
+/// read from the change feed starting at the versionstamp the server recorded at that time,
+/// so the clock of the client is never compared with the clock of the server.
+#[derive(Default)]
+pub(crate) struct LiveQueries {
+	queries: HashMap<Uuid, LiveQuery>,
+	server_ids: HashMap<Uuid, Uuid>,
+	server_time: Option<Datetime>,
+}
+
+impl LiveQueries {
+	/// Registers a live query started by a `live` request
+	fn insert_live(&mut self, id: Uuid, params: Vec<Value>, sender: Sender<Notification>) {
+		let table = match &params[..] {
+			[Value::Table(table)] => Some(table.clone()),
+			_ => None,
+		};
+		self.insert(id, (Method::Live, params), table, sender);
+	}
+
+	/// Registers a live query started by a `LIVE SELECT` statement
+	fn insert_query(&mut self, id: Uuid, params: Vec<Value>, sender: Sender<Notification>) {
+		self.insert(id, (Method::Query, params), None, sender);
+	}
+
+	fn insert(
+		&mut self,
+		id: Uuid,
+		request: (Method, Vec<Value>),
+		table: Option<Table>,
+		sender: Sender<Notification>,
+	) {
+		self.server_ids.insert(id.clone(), id.clone());
+		self.queries.insert(
+			id.clone(),
+			LiveQuery {
+				server_id: id,
+				request,
+				table,
+				sender,
+			},
+		);
+	}
+
+	/// Unregisters a live query, returning its current ID on the server
+	fn remove(&mut self, id: &Uuid) -> Option<Uuid> {
+		let query = self.queries.remove(id)?;
+		self.server_ids.remove(&query.server_id);
+		Some(query.server_id)
+	}
+
+	/// Forwards a notification to the stream of its live query
+	async fn notify(&mut self, mut notification: Notification) {
+		let Some(id) = self.server_ids.get(&notification.id).cloned() else {
+			return;
+		};
+		if let Some(query) = self.queries.get(&id) {
+			notification.id = id.clone();
+			if query.sender.send_async(notification).await.is_err() {
+				self.remove(&id);
+			}
+		}
+	}
+
+	/// The requests which start the registered live queries again
+	fn requests(&self) -> Vec<(Uuid, Method, Vec<Value>)> {
+		self.queries
+			.iter()
+			.map(|(id, query)| (id.clone(), query.request.0, query.request.1.clone()))
+			.collect()
+	}
+
+	/// Maps the new server ID of a live query, which was started again, to its original ID
+	fn rebind(&mut self, id: &Uuid, server_id: Uuid) {
+		if let Some(query) = self.queries.get_mut(id) {
+			self.server_ids.remove(&query.server_id);
+			self.server_ids.insert(server_id.clone(), id.clone());
+			query.server_id = server_id;
+		}
+	}
+
+	/// The request which reads the current time of the server
+	///
+	/// The time is only needed if a live query can catch up on missed changes.
+	fn server_time_request(&self) -> Option<Value> {
+		self.queries.values().any(|query| query.table.is_some()).then(|| {
+			let params = vec!["RETURN time::now()".into()];
+			request(SERVER_TIME_REQUEST_ID, Method::Query, params)
+		})
+	}
+
+	/// Records the time of the server from the response to a server time request
+	fn set_server_time(&mut self, result: ServerResult) {
+		if let Ok(Data::Query(results)) = result {
+			if let Some(QueryMethodResponse {
+				status: Status::Ok,
+				result: Value::Datetime(time),
+				..
+			}) = results.into_iter().next()
+			{
+				self.server_time = Some(time);
+			}
+		}
+	}
+
+	/// Where to start reading the changes missed while disconnected
+	///
+	/// Returns `None` if the time of the server was never read.
+	fn catch_up_since(&self) -> Option<ShowSince> {
+		self.server_time.clone().map(ShowSince::Timestamp)
+	}
+
+	/// The query which reads the changes a live query may have missed from the change feed
+	fn catch_up_query(&self, id: &Uuid, since: ShowSince) -> Option<String> {
+		let table = self.queries.get(id)?.table.clone()?;
+		let statement = ShowStatement {
+			table: Some(table),
+			consumer: None,
+			since: Some(since),
+			limit: Some(CATCH_UP_LIMIT),
+		};
+		Some(statement.to_string())
+	}
+
+	/// Forwards the changes read from a change feed to the stream of a live query
+	///
+	/// Returns where to continue reading from if there may be more changes.
+	async fn catch_up(&mut self, id: &Uuid, data: Data) -> Option<ShowSince> {
+		let Data::Query(results) = data else {
+			return None;
+		};
+		let Some(QueryMethodResponse {
+			status: Status::Ok,
+			result: Value::Array(Array(change_sets)),
+			..
+		}) = results.into_iter().next()
+		else {
+			return None;
+		};
+		let count = change_sets.len();
+		let mut next = None;
+		for change_set in change_sets {
+			let Value::Object(mut change_set) = change_set else {
+				continue;
+			};
+			if let Some(Ok(vs)) = change_set.remove("versionstamp").map(u128::try_from) {
+				// The change feed can be read from the versionstamp right after this one
+				next = Some(ShowSince::Versionstamp((vs >> 16) as u64 + 1));
+			}
+			let Some(Value::Array(Array(changes))) = change_set.remove("changes") else {
+				continue;
+			};
+			for change in changes {
+				let Value::Object(mut change) = change else {
+					continue;
+				};
+				// Any schema changes are skipped
+				let (action, result) = if let Some(record) = change.remove("update") {
+					(Action::Update, record)
+				} else if let Some(Value::Object(mut record)) = change.remove("delete") {
+					(Action::Delete, record.remove("id").unwrap_or_default())
+				} else {
+					continue;
+				};
+				let query = self.queries.get(id)?;
+				let notification = Notification {
+					id: id.clone(),
+					action,
+					result,
+				};
+				if query.sender.send_async(notification).await.is_err() {
+					self.remove(id);
+					return None;
+				}
+			}
+		}
+		match count < CATCH_UP_LIMIT as usize {
+			true => None,
+			false => next,
+		}
+	}
+}
+
 struct IntervalStream {
 	inner: Interval,
 }
@@ -168,3 +433,101 @@ impl Stream for IntervalStream {
 		self.inner.poll_tick(cx).map(Some)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::thing;
+
+	#[tokio::test]
+	async fn notifications_keep_their_original_id_after_reconnecting() {
+		let mut live_queries = LiveQueries::default();
+		let (sender, receiver) = flume::unbounded();
+		let id = Uuid::new_v4();
+		live_queries.insert_live(id.clone(), vec![Table("user".to_owned()).into()], sender);
+		let server_id = Uuid::new_v4();
+		live_queries.rebind(&id, server_id.clone());
+		// Notifications sent to the old ID are ignored
+		live_queries
+			.notify(Notification {
+				id: id.clone(),
+				action: Action::Create,
+				result: Value::None,
+			})
+			.await;
+		assert!(receiver.is_empty());
+		live_queries
+			.notify(Notification {
+				id: server_id.clone(),
+				action: Action::Create,
+				result: Value::None,
+			})
+			.await;
+		assert_eq!(receiver.recv().unwrap().id, id);
+		// Killing the live query uses the new ID
+		assert_eq!(live_queries.remove(&id), Some(server_id));
+	}
+
+	#[tokio::test]
+	async fn missed_changes_are_sent_as_notifications() {
+		let mut live_queries = LiveQueries::default();
+		let (sender, receiver) = flume::unbounded();
+		let id = Uuid::new_v4();
+		live_queries.insert_live(id.clone(), vec![Table("user".to_owned()).into()], sender);
+		let query = live_queries.catch_up_query(&id, ShowSince::Versionstamp(0)).unwrap();
+		assert_eq!(query, "SHOW CHANGES FOR TABLE user SINCE 0 LIMIT 100");
+		let changes = crate::sql::value(
+			r#"[{
+				versionstamp: 65536,
+				changes: [
+					{ define_table: "DEFINE TABLE user CHANGEFEED 1h" },
+					{ update: { id: user:john, name: "John Doe" } },
+					{ delete: { id: user:jane } }
+				]
+			}]"#,
+		)
+		.unwrap();
+		let data = Data::Query(vec![QueryMethodResponse {
+			time: String::new(),
+			status: Status::Ok,
+			result: changes,
+		}]);
+		assert_eq!(live_queries.catch_up(&id, data).await, None);
+		let update = receiver.recv().unwrap();
+		assert_eq!(update.id, id);
+		assert_eq!(update.action, Action::Update);
+		assert_eq!(update.result.pick(&["id".into()]), thing("user:john").unwrap().into());
+		let delete = receiver.recv().unwrap();
+		assert_eq!(delete.action, Action::Delete);
+		assert_eq!(delete.result, thing("user:jane").unwrap().into());
+		assert!(receiver.is_empty());
+	}
+
+	#[test]
+	fn missed_changes_are_read_from_the_time_of_the_server() {
+		let mut live_queries = LiveQueries::default();
+		assert_eq!(live_queries.server_time_request(), None);
+		let (sender, _receiver) = flume::unbounded();
+		let id = Uuid::new_v4();
+		live_queries.insert_live(id, vec![Table("user".to_owned()).into()], sender);
+		assert!(live_queries.server_time_request().is_some());
+		assert_eq!(live_queries.catch_up_since(), None);
+		let time = crate::sql::Datetime::default();
+		live_queries.set_server_time(Ok(Data::Query(vec![QueryMethodResponse {
+			time: String::new(),
+			status: Status::Ok,
+			result: time.clone().into(),
+		}])));
+		assert_eq!(live_queries.catch_up_since(), Some(ShowSince::Timestamp(time)));
+	}
+
+	#[test]
+	fn live_queries_on_statements_do_not_catch_up() {
+		let mut live_queries = LiveQueries::default();
+		let (sender, _receiver) = flume::unbounded();
+		let id = Uuid::new_v4();
+		let params = vec!["LIVE SELECT * FROM user WHERE age > 18".into(), Value::None];
+		live_queries.insert_query(id.clone(), params, sender);
+		assert_eq!(live_queries.catch_up_query(&id, ShowSince::Versionstamp(0)), None);
+	}
+}
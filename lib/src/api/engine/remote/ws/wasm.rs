@@ -5,14 +5,24 @@ use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Route;
 use crate::api::conn::Router;
+use crate::api::engine::remote::ws::backoff;
+use crate::api::engine::remote::ws::format_request;
+use crate::api::engine::remote::ws::request;
 use crate::api::engine::remote::ws::Client;
+use crate::api::engine::remote::ws::Data;
+use crate::api::engine::remote::ws::LiveQueries;
 use crate::api::engine::remote::ws::Response;
+use crate::api::engine::remote::ws::ServerResult;
+use crate::api::engine::remote::ws::FORMAT_REQUEST_ID;
 use crate::api::engine::remote::ws::PING_INTERVAL;
 use crate::api::engine::remote::ws::PING_METHOD;
+use crate::api::engine::remote::ws::RECONNECT_MIN_DELAY;
+use crate::api::engine::remote::ws::SERVER_TIME_REQUEST_ID;
 use crate::api::err::Error;
 use crate::api::opt::Endpoint;
 use crate::api::Result;
 use crate::api::Surreal;
+use crate::dbs::Notification;
 use crate::engine::remote::ws::IntervalStream;
 use crate::sql::serde::deserialize;
 use crate::sql::Strand;
@@ -37,7 +47,6 @@ use std::marker::PhantomData;
 use std::pin::Pin;
 use std::sync::atomic::AtomicI64;
 use std::sync::Arc;
-use std::time::Duration;
 use trice::Instant;
 use wasm_bindgen_futures::spawn_local;
 use wasmtimer::tokio as time;
@@ -45,6 +54,7 @@ use wasmtimer::tokio::MissedTickBehavior;
 use ws_stream_wasm::WsEvent;
 use ws_stream_wasm::WsMessage as Message;
 use ws_stream_wasm::WsMeta;
+use ws_stream_wasm::WsStream;
 
 pub(crate) enum Either {
 	Request(Option<Route>),
@@ -139,6 +149,11 @@ pub(crate) fn router(
 			}
 		};
 
+		if let Err(error) = binary_notifications(&mut socket, &mut Vec::new()).await {
+			let _ = conn_tx.into_send_async(Err(error)).await;
+			return;
+		}
+
 		let _ = conn_tx.into_send_async(Ok(())).await;
 
 		let ping = {
@@ -150,6 +165,7 @@ pub(crate) fn router(
 
 		let mut vars = IndexMap::new();
 		let mut replay = IndexMap::new();
+		let mut live_queries = LiveQueries::default();
 
 		'router: loop {
 			let (mut socket_sink, socket_stream) = socket.split();
@@ -184,7 +200,8 @@ pub(crate) fn router(
 						response,
 					})) => {
 						let (id, method, param) = request;
-						let params = match param.query {
+						let notification_sender = param.notification_sender;
+						let mut params = match param.query {
 							Some((query, bindings)) => {
 								vec![query.to_string().into(), bindings.into()]
 							}
@@ -201,8 +218,37 @@ pub(crate) fn router(
 									vars.remove(key);
 								}
 							}
+							Method::Kill => {
+								// The live query may have a new ID on the server after reconnecting
+								if let [Value::Uuid(query_id)] = &mut params[..] {
+									if let Some(server_id) = live_queries.remove(query_id) {
+										*query_id = server_id;
+									}
+								}
+							}
+							Method::Live => {
+								// The live query was started by a `LIVE SELECT` statement,
+								// so there is nothing to send to the server
+								if let (
+									Some(sender),
+									[Value::Uuid(query_id), statement, bindings],
+								) = (&notification_sender, &params[..])
+								{
+									live_queries.insert_query(
+										query_id.clone(),
+										vec![statement.clone(), bindings.clone()],
+										sender.clone(),
+									);
+									let value = Ok(DbResponse::Other(Value::None));
+									if response.into_send_async(value).await.is_err() {
+										trace!("Receiver dropped");
+									}
+									continue;
+								}
+							}
 							_ => {}
 						}
+						let live = notification_sender.map(|sender| (params.clone(), sender));
 						let method_str = match method {
 							Method::Health => PING_METHOD,
 							_ => method.as_str(),
@@ -224,14 +270,14 @@ pub(crate) fn router(
 						| Method::Signup
 						| Method::Use = method
 						{
-							replay.insert(method, message.clone());
+							replay.insert(method, (id, message.clone()));
 						}
 						match socket_sink.send(message).await {
 							Ok(..) => {
 								last_activity = Instant::now();
 								match routes.entry(id) {
 									Entry::Vacant(entry) => {
-										entry.insert((method, response));
+										entry.insert((method, response, live));
 									}
 									Entry::Occupied(..) => {
 										let error = Error::DuplicateRequestId(id);
@@ -260,11 +306,53 @@ pub(crate) fn router(
 							Ok(option) => {
 								if let Some(response) = option {
 									trace!("{response:?}");
-									if let Some(Ok(id)) = response.id.map(Value::coerce_to_i64) {
-										if let Some((_method, sender)) = routes.remove(&id) {
-											let _res = sender
-												.into_send_async(DbResponse::from(response.result))
-												.await;
+									match response.id {
+										// If `id` is set this is a normal response
+										Some(id) => {
+											if let Ok(id) = id.coerce_to_i64() {
+												if id == SERVER_TIME_REQUEST_ID {
+													live_queries.set_server_time(response.result);
+												} else if let Some((_method, sender, live)) =
+													routes.remove(&id)
+												{
+													// Register the notification channel of a new live query
+													if let (
+														Some((params, live)),
+														Ok(Data::Other(Value::Uuid(query_id))),
+													) = (live, &response.result)
+													{
+														live_queries.insert_live(
+															query_id.clone(),
+															params,
+															live,
+														);
+														// Read the time of the server straight away, in case the connection
+														// drops before it is read again
+														if let Some(request) =
+															live_queries.server_time_request()
+														{
+															let message =
+																Message::Binary(request.into());
+															if let Err(error) =
+																socket_sink.send(message).await
+															{
+																trace!("failed to read the time of the server; {error:?}");
+															}
+														}
+													}
+													let _res = sender
+														.into_send_async(DbResponse::from(
+															response.result,
+														))
+														.await;
+												}
+											}
+										}
+										// If `id` is not set, this may be a live query notification
+										None => {
+											if let Ok(Data::Live(notification)) = response.result {
+												live_queries.notify(notification).await;
+											}
 										}
 									}
 								}
@@ -283,7 +371,9 @@ pub(crate) fn router(
 									{
 										// Return an error if an ID was returned
 										if let Some(Ok(id)) = id.map(Value::coerce_to_i64) {
-											if let Some((_method, sender)) = routes.remove(&id) {
+											if let Some((_method, sender, _live)) =
+												routes.remove(&id)
+											{
 												let _res = sender.into_send_async(Err(error)).await;
 											}
 										}
@@ -310,8 +400,18 @@ pub(crate) fn router(
 						_ => {}
 					},
 					Either::Ping => {
+						// Read the time of the server, which live queries catch up from after reconnecting
+						if let Some(request) = live_queries.server_time_request() {
+							trace!("Reading the time of the server");
+							if let Err(error) =
+								socket_sink.send(Message::Binary(request.into())).await
+							{
+								trace!("failed to read the time of the server; {error:?}");
+								break;
+							}
+						}
 						// only ping if we haven't talked to the server recently
-						if last_activity.elapsed() >= PING_INTERVAL {
+						else if last_activity.elapsed() >= PING_INTERVAL {
 							trace!("Pinging the server");
 							if let Err(error) = socket_sink.send(ping.clone()).await {
 								trace!("failed to ping the server; {error:?}");
@@ -325,6 +425,8 @@ pub(crate) fn router(
 				}
 			}
 
+			let mut delay = RECONNECT_MIN_DELAY;
+
 			'reconnect: loop {
 				trace!("Reconnecting...");
 				match WsMeta::connect(&address.endpoint, None).await {
@@ -339,46 +441,158 @@ pub(crate) fn router(
 								Ok(events) => events,
 								Err(error) => {
 									trace!("{error}");
-									time::sleep(Duration::from_secs(1)).await;
+									time::sleep(delay).await;
+									delay = backoff(delay);
 									continue 'reconnect;
 								}
 							}
 						};
-						for (_, message) in &replay {
-							if let Err(error) = socket.send(message.clone()).await {
-								trace!("{error}");
-								time::sleep(Duration::from_secs(1)).await;
-								continue 'reconnect;
+						match restore(&mut socket, &replay, &vars, &mut live_queries).await {
+							Ok(..) => {
+								trace!("Reconnected successfully");
+								break;
 							}
-						}
-						for (key, value) in &vars {
-							let mut request = BTreeMap::new();
-							request.insert("method".to_owned(), Method::Set.as_str().into());
-							request.insert(
-								"params".to_owned(),
-								vec![key.as_str().into(), value.clone()].into(),
-							);
-							let payload = Value::from(request);
-							trace!("Request {payload}");
-							if let Err(error) = socket.send(Message::Binary(payload.into())).await {
-								trace!("{error}");
-								time::sleep(Duration::from_secs(1)).await;
-								continue 'reconnect;
+							Err(error) => {
+								trace!("Failed to restore the connection; {error}");
 							}
 						}
-						trace!("Reconnected successfully");
-						break;
 					}
 					Err(error) => {
 						trace!("Failed to reconnect; {error}");
-						time::sleep(Duration::from_secs(1)).await;
 					}
 				}
+				time::sleep(delay).await;
+				delay = backoff(delay);
 			}
 		}
 	});
 }
 
+/// Restores the state of the connection after reconnecting
+///
+/// The server handles requests concurrently, so each request waits for its response
+/// before the next one is sent.
+async fn restore(
+	socket: &mut WsStream,
+	replay: &IndexMap<Method, (i64, Message)>,
+	vars: &IndexMap<String, Value>,
+	live_queries: &mut LiveQueries,
+) -> Result<()> {
+	// Negative IDs never clash with the IDs of the replayed requests
+	let mut last_id = 0;
+	let mut next_id = || {
+		last_id -= 1;
+		last_id
+	};
+	// Notifications of the live queries which were already started again
+	let mut notifications = Vec::new();
+	let notifications = &mut notifications;
+	binary_notifications(socket, notifications).await?;
+	for (id, message) in replay.values() {
+		if let Err(failure) = call(socket, *id, message.clone(), notifications).await? {
+			warn!("Failed to replay a request after reconnecting; {}", failure.message);
+		}
+	}
+	for (key, value) in vars {
+		let params = vec![key.as_str().into(), value.clone()];
+		if let Err(failure) =
+			call_method(socket, next_id(), Method::Set, params, notifications).await?
+		{
+			warn!("Failed to set `${key}` after reconnecting; {}", failure.message);
+		}
+	}
+	let catch_up_since = live_queries.catch_up_since();
+	for (query_id, method, params) in live_queries.requests() {
+		let result = call_method(socket, next_id(), method, params, notifications).await?;
+		match result.map(Data::into_live_query_id) {
+			Ok(Some(server_id)) => live_queries.rebind(&query_id, server_id),
+			_ => {
+				warn!("Failed to restart live query '{query_id}' after reconnecting");
+				live_queries.remove(&query_id);
+				continue;
+			}
+		}
+		// Catch up on the changes missed while disconnected, if the table has a change feed
+		let Some(mut since) = catch_up_since.clone() else {
+			continue;
+		};
+		while let Some(query) = live_queries.catch_up_query(&query_id, since) {
+			let params = vec![query.into()];
+			match call_method(socket, next_id(), Method::Query, params, notifications).await? {
+				Ok(data) => match live_queries.catch_up(&query_id, data).await {
+					Some(next) => since = next,
+					None => break,
+				},
+				Err(failure) => {
+					trace!("Failed to read the change feed; {}", failure.message);
+					break;
+				}
+			}
+		}
+	}
+	// Catch up from the time of the server on the new connection next time
+	if let Some(request) = live_queries.server_time_request() {
+		let message = Message::Binary(request.into());
+		let result = call(socket, SERVER_TIME_REQUEST_ID, message, notifications).await?;
+		live_queries.set_server_time(result);
+	}
+	for notification in notifications.drain(..) {
+		live_queries.notify(notification).await;
+	}
+	Ok(())
+}
+
+/// Asks the server to send live query notifications in the binary format
+async fn binary_notifications(
+	socket: &mut WsStream,
+	notifications: &mut Vec<Notification>,
+) -> Result<()> {
+	let message = Message::Binary(format_request().into());
+	if let Err(failure) = call(socket, FORMAT_REQUEST_ID, message, notifications).await? {
+		warn!("Failed to ask for binary notifications; {}", failure.message);
+	}
+	Ok(())
+}
+
+async fn call_method(
+	socket: &mut WsStream,
+	id: i64,
+	method: Method,
+	params: Vec<Value>,
+	notifications: &mut Vec<Notification>,
+) -> Result<ServerResult> {
+	let payload = request(id, method, params);
+	trace!("Request {payload}");
+	call(socket, id, Message::Binary(payload.into()), notifications).await
+}
+
+/// Sends a request and waits for its response
+///
+/// Live query notifications received in the meantime are buffered, so that they can be
+/// delivered once the connection has been restored.
+async fn call(
+	socket: &mut WsStream,
+	id: i64,
+	message: Message,
+	notifications: &mut Vec<Notification>,
+) -> Result<ServerResult> {
+	socket.send(message).await?;
+	while let Some(message) = socket.next().await {
+		match Response::try_from(&message) {
+			Ok(Some(Response {
+				id: Some(response_id),
+				result,
+			})) if response_id.clone().coerce_to_i64().ok() == Some(id) => return Ok(result),
+			Ok(Some(Response {
+				id: None,
+				result: Ok(Data::Live(notification)),
+			})) => notifications.push(notification),
+			_ => {}
+		}
+	}
+	Err(Error::Ws("The connection was closed".to_owned()).into())
+}
+
 impl Response {
 	fn try_from(message: &Message) -> Result<Option<Self>> {
 		match message {
@@ -146,6 +146,14 @@ pub enum Error {
 	/// it's running on
 	#[error("The protocol or storage engine does not support backups on this architecture")]
 	BackupsNotSupported,
+
+	/// The protocol or storage engine being used does not support live queries
+	#[error("The protocol or storage engine does not support live queries")]
+	LiveQueriesNotSupported,
+
+	/// Tried to take a notification stream from a statement which is not a live query
+	#[error("Query statement {0} is not a live query")]
+	NotLiveQuery(usize),
 }
 
 #[cfg(feature = "protocol-http")]
@@ -1,34 +1,197 @@
 use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Router;
+use crate::api::err::Error;
 use crate::api::Connection;
 use crate::api::Result;
+use crate::dbs;
+use crate::dbs::Action;
+use crate::opt::from_value;
 use crate::sql::Table;
 use crate::sql::Uuid;
 use crate::sql::Value;
+use flume::r#async::RecvStream;
+use flume::Receiver;
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use std::fmt;
 use std::future::Future;
 use std::future::IntoFuture;
+use std::marker::PhantomData;
 use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::spawn;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_futures::spawn_local as spawn;
 
 /// A live query future
 #[derive(Debug)]
 #[must_use = "futures do nothing unless you `.await` or poll them"]
-pub struct Live<'r, C: Connection> {
-	pub(super) router: Result<&'r Router<C>>,
+pub struct Live<'r, C: Connection, R = Value> {
+	pub(super) router: Result<&'r Arc<Router<C>>>,
 	pub(super) table_name: String,
+	pub(super) response_type: PhantomData<R>,
 }
 
-impl<'r, Client> IntoFuture for Live<'r, Client>
+impl<'r, Client, R> IntoFuture for Live<'r, Client, R>
 where
 	Client: Connection,
+	R: DeserializeOwned + 'r,
 {
-	type Output = Result<Uuid>;
+	type Output = Result<Stream<R>>;
 	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
 
 	fn into_future(self) -> Self::IntoFuture {
 		Box::pin(async move {
+			let router = self.router?;
+			let (sender, receiver) = flume::unbounded();
+			let param =
+				Param::notification_sender(vec![Value::Table(Table(self.table_name))], sender);
 			let mut conn = Client::new(Method::Live);
-			conn.execute(self.router?, Param::new(vec![Value::Table(Table(self.table_name))])).await
+			match conn.execute_value(router, param).await? {
+				Value::Uuid(id) => Ok(Stream::new(router.clone(), id, receiver)),
+				value => Err(Error::FromValue {
+					value,
+					error: "expected the live query ID".to_owned(),
+				}
+				.into()),
+			}
 		})
 	}
 }
+
+/// Registers the notification channel of a live query which was started with a `LIVE SELECT` statement
+///
+/// The statement and its bindings are passed along so that the live query can be started again
+/// if the connection is re-established.
+pub(crate) async fn register<Client>(
+	router: &Arc<Router<Client>>,
+	id: Uuid,
+	statement: String,
+	bindings: BTreeMap<String, Value>,
+) -> Result<Stream<Value>>
+where
+	Client: Connection,
+{
+	let (sender, receiver) = flume::unbounded();
+	let params = vec![id.clone().into(), statement.into(), bindings.into()];
+	let param = Param::notification_sender(params, sender);
+	let mut conn = Client::new(Method::Live);
+	conn.execute_unit(router, param).await?;
+	Ok(Stream::new(router.clone(), id, receiver))
+}
+
+/// A notification sent by a live query
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Notification<R> {
+	/// The ID of the live query which sent this notification
+	pub query_id: Uuid,
+	/// The action which triggered this notification
+	pub action: Action,
+	/// The record, the record ID of a deleted record, or the JSON Patch for `LIVE SELECT DIFF` queries
+	pub data: R,
+}
+
+/// A stream of live query notifications
+///
+/// The live query is killed when the stream is dropped.
+///
+/// When the WebSocket engine reconnects, the live query is started again under the same ID.
+/// Live queries on whole tables also catch up on the changes they missed in the meantime, if
+/// the table has a change feed. Changes made shortly before the connection dropped may be sent
+/// more than once.
+#[must_use = "streams do nothing unless you poll them"]
+pub struct Stream<R> {
+	id: Uuid,
+	rx: RecvStream<'static, dbs::Notification>,
+	kill: Option<Box<dyn FnOnce(Uuid) + Send + Sync>>,
+	response_type: PhantomData<fn() -> R>,
+}
+
+impl<R> Stream<R> {
+	pub(crate) fn new<Client>(
+		router: Arc<Router<Client>>,
+		id: Uuid,
+		receiver: Receiver<dbs::Notification>,
+	) -> Self
+	where
+		Client: Connection,
+	{
+		Self {
+			id,
+			rx: receiver.into_stream(),
+			kill: Some(Box::new(move |id| kill(router, id))),
+			response_type: PhantomData,
+		}
+	}
+
+	/// The ID of the live query
+	pub fn id(&self) -> &Uuid {
+		&self.id
+	}
+
+	/// Changes the type the notifications are deserialised into
+	pub(crate) fn cast<T>(mut self) -> Stream<T> {
+		Stream {
+			id: self.id.clone(),
+			rx: std::mem::replace(&mut self.rx, flume::bounded(0).1.into_stream()),
+			kill: self.kill.take(),
+			response_type: PhantomData,
+		}
+	}
+}
+
+impl<R> fmt::Debug for Stream<R> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Stream").field("id", &self.id).finish_non_exhaustive()
+	}
+}
+
+impl<R> futures::Stream for Stream<R>
+where
+	R: DeserializeOwned,
+{
+	type Item = Result<Notification<R>>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		self.rx.poll_next_unpin(cx).map(|option| {
+			option.map(|notification| {
+				Ok(Notification {
+					query_id: notification.id,
+					action: notification.action,
+					data: from_value(notification.result)?,
+				})
+			})
+		})
+	}
+}
+
+impl<R> Drop for Stream<R> {
+	fn drop(&mut self) {
+		if let Some(kill) = self.kill.take() {
+			kill(self.id.clone());
+		}
+	}
+}
+
+fn kill<Client>(router: Arc<Router<Client>>, id: Uuid)
+where
+	Client: Connection,
+{
+	// The runtime may already have been shut down
+	#[cfg(not(target_arch = "wasm32"))]
+	if tokio::runtime::Handle::try_current().is_err() {
+		return;
+	}
+	spawn(async move {
+		let mut conn = Client::new(Method::Kill);
+		if let Err(error) = conn.execute_unit(&router, Param::new(vec![id.clone().into()])).await {
+			warn!("Failed to kill live query '{id}'; {error}");
+		}
+	});
+}
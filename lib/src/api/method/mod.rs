@@ -46,10 +46,10 @@ pub use export::Export;
 pub use health::Health;
 pub use import::Import;
 pub use invalidate::Invalidate;
-#[doc(hidden)] // Not supported yet
 pub use kill::Kill;
-#[doc(hidden)] // Not supported yet
 pub use live::Live;
+pub use live::Notification;
+pub use live::Stream;
 pub use merge::Merge;
 pub use patch::Patch;
 pub use query::Query;
@@ -615,7 +615,7 @@ where
 	/// ```
 	pub fn query(&self, query: impl opt::IntoQuery) -> Query<C> {
 		Query {
-			router: self.router.extract(),
+			router: self.router.extract_shared(),
 			query: vec![query.into_query()],
 			bindings: Ok(Default::default()),
 		}
@@ -936,7 +936,21 @@ where
 		}
 	}
 
-	#[doc(hidden)] // Not supported yet
+	/// Kills a live query
+	///
+	/// Live queries are also killed when their notification stream is dropped.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// # let query_id = surrealdb::sql::Uuid::new();
+	/// db.kill(query_id).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
 	pub fn kill(&self, query_id: Uuid) -> Kill<C> {
 		Kill {
 			router: self.router.extract(),
@@ -944,11 +958,45 @@ where
 		}
 	}
 
-	#[doc(hidden)] // Not supported yet
-	pub fn live(&self, table_name: impl Into<String>) -> Live<C> {
+	/// Starts a live query on a table, returning a stream of notifications
+	///
+	/// The live query is killed when the stream is dropped.
+	///
+	/// # Support
+	///
+	/// Currently only supported by WebSocket and the local engines.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use futures::StreamExt;
+	///
+	/// # #[derive(serde::Deserialize)]
+	/// # struct Person;
+	/// #
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// // Listen to all changes on a table
+	/// let mut stream = db.live::<Person>("person").await?;
+	///
+	/// while let Some(notification) = stream.next().await {
+	///     let notification = notification?;
+	///     // Do something with `notification.action` and `notification.data`
+	/// }
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn live<R>(&self, table_name: impl Into<String>) -> Live<'_, C, R> {
 		Live {
-			router: self.router.extract(),
+			router: self.router.extract_shared(),
 			table_name: table_name.into(),
+			response_type: PhantomData,
 		}
 	}
 
@@ -2,6 +2,8 @@ use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Router;
 use crate::api::err::Error;
+use crate::api::method::live;
+use crate::api::method::Stream;
 use crate::api::opt;
 use crate::api::Connection;
 use crate::api::Result;
@@ -22,12 +24,13 @@ use std::future::Future;
 use std::future::IntoFuture;
 use std::mem;
 use std::pin::Pin;
+use std::sync::Arc;
 
 /// A query future
 #[derive(Debug)]
 #[must_use = "futures do nothing unless you `.await` or poll them"]
 pub struct Query<'r, C: Connection> {
-	pub(super) router: Result<&'r Router<C>>,
+	pub(super) router: Result<&'r Arc<Router<C>>>,
 	pub(super) query: Vec<Result<Vec<Statement>>>,
 	pub(super) bindings: Result<BTreeMap<String, Value>>,
 }
@@ -45,10 +48,29 @@ where
 			for query in self.query {
 				statements.extend(query?);
 			}
+			let live_queries: Vec<(usize, String)> = statements
+				.iter()
+				.enumerate()
+				.filter(|(_, statement)| matches!(statement, Statement::Live(..)))
+				.map(|(index, statement)| (index, statement.to_string()))
+				.collect();
+			let router = self.router?;
+			let bindings = self.bindings?;
 			let query = sql::Query(Statements(statements));
-			let param = Param::query(query, self.bindings?);
+			let param = Param::query(query, bindings.clone());
 			let mut conn = Client::new(Method::Query);
-			conn.execute_query(self.router?, param).await
+			let mut response = conn.execute_query(router, param).await?;
+			// Register the notification channels of any live queries
+			for (index, statement) in live_queries {
+				if let Some(Ok(values)) = response.0.get(&index) {
+					if let [Value::Uuid(id)] = &values[..] {
+						let stream =
+							live::register(router, id.clone(), statement, bindings.clone()).await;
+						response.1.insert(index, stream);
+					}
+				}
+			}
+			Ok(response)
 		})
 	}
 }
@@ -135,9 +157,18 @@ pub(crate) type QueryResult = Result<Vec<Value>>;
 
 /// The response type of a `Surreal::query` request
 #[derive(Debug)]
-pub struct Response(pub(crate) IndexMap<usize, QueryResult>);
+pub struct Response(
+	pub(crate) IndexMap<usize, QueryResult>,
+	// The notification streams of the live queries started by the query,
+	// boxed so that errors which carry a response stay small
+	pub(crate) Box<IndexMap<usize, Result<Stream<Value>>>>,
+);
 
 impl Response {
+	pub(crate) fn new(results: IndexMap<usize, QueryResult>) -> Self {
+		Self(results, Default::default())
+	}
+
 	/// Takes and returns records returned from the database
 	///
 	/// A query that only returns one result can be deserialized into an
@@ -207,6 +238,48 @@ impl Response {
 		index.query_result(self)
 	}
 
+	/// Takes the notification stream of a `LIVE SELECT` statement
+	///
+	/// The live query is killed when the stream is dropped.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use futures::StreamExt;
+	/// use serde::Deserialize;
+	///
+	/// #[derive(Debug, Deserialize)]
+	/// # #[allow(dead_code)]
+	/// struct User {
+	///     id: String,
+	///     balance: String
+	/// }
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// let mut response = db.query("LIVE SELECT * FROM user WHERE balance > 100").await?;
+	///
+	/// let mut stream = response.stream::<User>(0)?;
+	///
+	/// while let Some(notification) = stream.next().await {
+	///     println!("{:?}", notification?.data);
+	/// }
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn stream<R>(&mut self, index: usize) -> Result<Stream<R>>
+	where
+		R: DeserializeOwned,
+	{
+		match self.1.remove(&index) {
+			Some(stream) => stream.map(Stream::cast),
+			None => Err(Error::NotLiveQuery(index).into()),
+		}
+	}
+
 	/// Take all errors from the query response
 	///
 	/// The errors are keyed by the corresponding index of the statement that failed.
@@ -316,36 +389,36 @@ mod tests {
 
 	#[test]
 	fn take_from_an_empty_response() {
-		let mut response = Response(Default::default());
+		let mut response = Response::new(Default::default());
 		let value: Value = response.take(0).unwrap();
 		assert!(value.is_none());
 
-		let mut response = Response(Default::default());
+		let mut response = Response::new(Default::default());
 		let option: Option<String> = response.take(0).unwrap();
 		assert!(option.is_none());
 
-		let mut response = Response(Default::default());
+		let mut response = Response::new(Default::default());
 		let vec: Vec<String> = response.take(0).unwrap();
 		assert!(vec.is_empty());
 	}
 
 	#[test]
 	fn take_from_an_errored_query() {
-		let mut response = Response(to_map(vec![Err(Error::ConnectionUninitialised.into())]));
+		let mut response = Response::new(to_map(vec![Err(Error::ConnectionUninitialised.into())]));
 		response.take::<Option<()>>(0).unwrap_err();
 	}
 
 	#[test]
 	fn take_from_empty_records() {
-		let mut response = Response(to_map(vec![Ok(vec![])]));
+		let mut response = Response::new(to_map(vec![Ok(vec![])]));
 		let value: Value = response.take(0).unwrap();
 		assert_eq!(value, Value::Array(Default::default()));
 
-		let mut response = Response(to_map(vec![Ok(vec![])]));
+		let mut response = Response::new(to_map(vec![Ok(vec![])]));
 		let option: Option<String> = response.take(0).unwrap();
 		assert!(option.is_none());
 
-		let mut response = Response(to_map(vec![Ok(vec![])]));
+		let mut response = Response::new(to_map(vec![Ok(vec![])]));
 		let vec: Vec<String> = response.take(0).unwrap();
 		assert!(vec.is_empty());
 	}
@@ -354,36 +427,36 @@ mod tests {
 	fn take_from_a_scalar_response() {
 		let scalar = 265;
 
-		let mut response = Response(to_map(vec![Ok(vec![scalar.into()])]));
+		let mut response = Response::new(to_map(vec![Ok(vec![scalar.into()])]));
 		let value: Value = response.take(0).unwrap();
 		assert_eq!(value, vec![Value::from(scalar)].into());
 
-		let mut response = Response(to_map(vec![Ok(vec![scalar.into()])]));
+		let mut response = Response::new(to_map(vec![Ok(vec![scalar.into()])]));
 		let option: Option<_> = response.take(0).unwrap();
 		assert_eq!(option, Some(scalar));
 
-		let mut response = Response(to_map(vec![Ok(vec![scalar.into()])]));
+		let mut response = Response::new(to_map(vec![Ok(vec![scalar.into()])]));
 		let vec: Vec<usize> = response.take(0).unwrap();
 		assert_eq!(vec, vec![scalar]);
 
 		let scalar = true;
 
-		let mut response = Response(to_map(vec![Ok(vec![scalar.into()])]));
+		let mut response = Response::new(to_map(vec![Ok(vec![scalar.into()])]));
 		let value: Value = response.take(0).unwrap();
 		assert_eq!(value, vec![Value::from(scalar)].into());
 
-		let mut response = Response(to_map(vec![Ok(vec![scalar.into()])]));
+		let mut response = Response::new(to_map(vec![Ok(vec![scalar.into()])]));
 		let option: Option<_> = response.take(0).unwrap();
 		assert_eq!(option, Some(scalar));
 
-		let mut response = Response(to_map(vec![Ok(vec![scalar.into()])]));
+		let mut response = Response::new(to_map(vec![Ok(vec![scalar.into()])]));
 		let vec: Vec<bool> = response.take(0).unwrap();
 		assert_eq!(vec, vec![scalar]);
 	}
 
 	#[test]
 	fn take_preserves_order() {
-		let mut response = Response(to_map(vec![
+		let mut response = Response::new(to_map(vec![
 			Ok(vec![0.into()]),
 			Ok(vec![1.into()]),
 			Ok(vec![2.into()]),
@@ -416,17 +489,17 @@ mod tests {
 		};
 		let value = to_value(summary.clone()).unwrap();
 
-		let mut response = Response(to_map(vec![Ok(vec![value.clone()])]));
+		let mut response = Response::new(to_map(vec![Ok(vec![value.clone()])]));
 		let title: Value = response.take("title").unwrap();
 		assert_eq!(title, vec![Value::from(summary.title.as_str())].into());
 
-		let mut response = Response(to_map(vec![Ok(vec![value.clone()])]));
+		let mut response = Response::new(to_map(vec![Ok(vec![value.clone()])]));
 		let Some(title): Option<String> = response.take("title").unwrap() else {
             panic!("title not found");
         };
 		assert_eq!(title, summary.title);
 
-		let mut response = Response(to_map(vec![Ok(vec![value])]));
+		let mut response = Response::new(to_map(vec![Ok(vec![value])]));
 		let vec: Vec<String> = response.take("title").unwrap();
 		assert_eq!(vec, vec![summary.title]);
 
@@ -436,7 +509,7 @@ mod tests {
 		};
 		let value = to_value(article.clone()).unwrap();
 
-		let mut response = Response(to_map(vec![Ok(vec![value.clone()])]));
+		let mut response = Response::new(to_map(vec![Ok(vec![value.clone()])]));
 		let Some(title): Option<String> = response.take("title").unwrap() else {
             panic!("title not found");
         };
@@ -446,27 +519,27 @@ mod tests {
         };
 		assert_eq!(body, article.body);
 
-		let mut response = Response(to_map(vec![Ok(vec![value.clone()])]));
+		let mut response = Response::new(to_map(vec![Ok(vec![value.clone()])]));
 		let vec: Vec<String> = response.take("title").unwrap();
 		assert_eq!(vec, vec![article.title.clone()]);
 
-		let mut response = Response(to_map(vec![Ok(vec![value])]));
+		let mut response = Response::new(to_map(vec![Ok(vec![value])]));
 		let value: Value = response.take("title").unwrap();
 		assert_eq!(value, vec![Value::from(article.title)].into());
 	}
 
 	#[test]
 	fn take_partial_records() {
-		let mut response = Response(to_map(vec![Ok(vec![true.into(), false.into()])]));
+		let mut response = Response::new(to_map(vec![Ok(vec![true.into(), false.into()])]));
 		let value: Value = response.take(0).unwrap();
 		assert_eq!(value, vec![Value::from(true), Value::from(false)].into());
 
-		let mut response = Response(to_map(vec![Ok(vec![true.into(), false.into()])]));
+		let mut response = Response::new(to_map(vec![Ok(vec![true.into(), false.into()])]));
 		let vec: Vec<bool> = response.take(0).unwrap();
 		assert_eq!(vec, vec![true, false]);
 
-		let mut response = Response(to_map(vec![Ok(vec![true.into(), false.into()])]));
-		let Err(Api(Error::LossyTake(Response(mut map)))): Result<Option<bool>> = response.take(0) else {
+		let mut response = Response::new(to_map(vec![Ok(vec![true.into(), false.into()])]));
+		let Err(Api(Error::LossyTake(Response(mut map, ..)))): Result<Option<bool>> = response.take(0) else {
             panic!("silently dropping records not allowed");
         };
 		let records = map.remove(&0).unwrap().unwrap();
@@ -488,7 +561,7 @@ mod tests {
 			Ok(vec![7.into()]),
 			Err(Error::DuplicateRequestId(0).into()),
 		];
-		let response = Response(to_map(response));
+		let response = Response::new(to_map(response));
 		let crate::Error::Api(Error::ConnectionUninitialised) = response.check().unwrap_err() else {
             panic!("check did not return the first error");
         };
@@ -509,7 +582,7 @@ mod tests {
 			Ok(vec![7.into()]),
 			Err(Error::DuplicateRequestId(0).into()),
 		];
-		let mut response = Response(to_map(response));
+		let mut response = Response::new(to_map(response));
 		let errors = response.take_errors();
 		assert_eq!(response.num_statements(), 8);
 		assert_eq!(errors.len(), 3);
@@ -61,7 +61,7 @@ pub(super) fn mock(route_rx: Receiver<Option<Route>>) {
 					_ => unreachable!(),
 				},
 				Method::Query => match param.query {
-					Some(_) => Ok(DbResponse::Query(QueryResponse(Default::default()))),
+					Some(_) => Ok(DbResponse::Query(QueryResponse::new(Default::default()))),
 					_ => unreachable!(),
 				},
 				Method::Create => match &params[..] {
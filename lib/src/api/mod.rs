@@ -181,6 +181,8 @@ where
 	C: Connection,
 {
 	fn extract(&self) -> Result<&Router<C>>;
+
+	fn extract_shared(&self) -> Result<&Arc<Router<C>>>;
 }
 
 impl<C> ExtractRouter<C> for OnceCell<Arc<Router<C>>>
@@ -191,4 +193,9 @@ where
 		let router = self.get().ok_or(Error::ConnectionUninitialised)?;
 		Ok(router)
 	}
+
+	fn extract_shared(&self) -> Result<&Arc<Router<C>>> {
+		let router = self.get().ok_or(Error::ConnectionUninitialised)?;
+		Ok(router)
+	}
 }
@@ -185,7 +185,7 @@ where
 }
 
 impl QueryResult<Value> for usize {
-	fn query_result(self, QueryResponse(map): &mut QueryResponse) -> Result<Value> {
+	fn query_result(self, QueryResponse(map, ..): &mut QueryResponse) -> Result<Value> {
 		match map.remove(&self) {
 			Some(result) => Ok(result?.into()),
 			None => Ok(Value::None),
@@ -197,7 +197,7 @@ impl<T> QueryResult<Option<T>> for usize
 where
 	T: DeserializeOwned,
 {
-	fn query_result(self, QueryResponse(map): &mut QueryResponse) -> Result<Option<T>> {
+	fn query_result(self, QueryResponse(map, ..): &mut QueryResponse) -> Result<Option<T>> {
 		let vec = match map.get_mut(&self) {
 			Some(result) => match result {
 				Ok(vec) => vec,
@@ -217,7 +217,7 @@ where
 				let value = mem::take(value);
 				from_value(value).map_err(Into::into)
 			}
-			_ => Err(Error::LossyTake(QueryResponse(mem::take(map))).into()),
+			_ => Err(Error::LossyTake(QueryResponse::new(mem::take(map))).into()),
 		};
 		map.remove(&self);
 		result
@@ -225,7 +225,7 @@ where
 }
 
 impl QueryResult<Value> for (usize, &str) {
-	fn query_result(self, QueryResponse(map): &mut QueryResponse) -> Result<Value> {
+	fn query_result(self, QueryResponse(map, ..): &mut QueryResponse) -> Result<Value> {
 		let (index, key) = self;
 		let response = match map.get_mut(&index) {
 			Some(result) => match result {
@@ -256,7 +256,7 @@ impl<T> QueryResult<Option<T>> for (usize, &str)
 where
 	T: DeserializeOwned,
 {
-	fn query_result(self, QueryResponse(map): &mut QueryResponse) -> Result<Option<T>> {
+	fn query_result(self, QueryResponse(map, ..): &mut QueryResponse) -> Result<Option<T>> {
 		let (index, key) = self;
 		let vec = match map.get_mut(&index) {
 			Some(result) => match result {
@@ -278,7 +278,7 @@ where
 			}
 			[value] => value,
 			_ => {
-				return Err(Error::LossyTake(QueryResponse(mem::take(map))).into());
+				return Err(Error::LossyTake(QueryResponse::new(mem::take(map))).into());
 			}
 		};
 		match &mut value {
@@ -292,8 +292,8 @@ where
 					return Ok(None);
 				}
 				let Some(value) = object.remove(key) else {
-                    return Ok(None);
-                };
+					return Ok(None);
+				};
 				from_value(value).map_err(Into::into)
 			}
 			_ => Ok(None),
@@ -305,7 +305,7 @@ impl<T> QueryResult<Vec<T>> for usize
 where
 	T: DeserializeOwned,
 {
-	fn query_result(self, QueryResponse(map): &mut QueryResponse) -> Result<Vec<T>> {
+	fn query_result(self, QueryResponse(map, ..): &mut QueryResponse) -> Result<Vec<T>> {
 		let vec = match map.remove(&self) {
 			Some(result) => result?,
 			None => {
@@ -320,7 +320,7 @@ impl<T> QueryResult<Vec<T>> for (usize, &str)
 where
 	T: DeserializeOwned,
 {
-	fn query_result(self, QueryResponse(map): &mut QueryResponse) -> Result<Vec<T>> {
+	fn query_result(self, QueryResponse(map, ..): &mut QueryResponse) -> Result<Vec<T>> {
 		let (index, key) = self;
 		let response = match map.get_mut(&index) {
 			Some(result) => match result {
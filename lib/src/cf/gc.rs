@@ -1,7 +1,10 @@
 use crate::err::Error;
 use crate::key::change;
+use crate::key::database;
+use crate::kvs::Stats;
 use crate::kvs::Transaction;
 use crate::vs;
+use std::ops::Range;
 use std::str;
 
 // gc_all deletes all change feed entries that are older than the given watermark.
@@ -44,7 +47,119 @@ pub async fn gc_db(
 
 	let limit = limit.unwrap_or(100);
 
-	tx.delr(beg..end, limit).await?;
+	delete_changes(tx, beg..end, limit).await?;
 
 	Ok(())
 }
+
+// gc_all_at deletes all change feed entries that are older than the retention
+// period of their database or table, as of the given timestamp.
+pub async fn gc_all_at(tx: &mut Transaction, ts: u64, limit: Option<u32>) -> Result<(), Error> {
+	let nses = tx.all_ns().await?;
+	let nses = nses.as_ref();
+	for ns in nses {
+		gc_ns_at(tx, ns.name.as_str(), ts, limit).await?;
+	}
+	Ok(())
+}
+
+// gc_ns_at deletes all change feed entries in the given namespace that are older
+// than the retention period of their database or table, as of the given timestamp.
+pub async fn gc_ns_at(
+	tx: &mut Transaction,
+	ns: &str,
+	ts: u64,
+	limit: Option<u32>,
+) -> Result<(), Error> {
+	let dbs = tx.all_db(ns).await?;
+	let dbs = dbs.as_ref();
+	for db in dbs {
+		gc_db_at(tx, ns, db.name.as_str(), ts, limit).await?;
+	}
+	Ok(())
+}
+
+// gc_db_at deletes all change feed entries in the given database that are older
+// than the retention period of the database or its tables, as of the given timestamp.
+// Entries which have not yet been acknowledged by a consumer are kept, unless they
+// are older than the maximum lag allowed for that consumer.
+pub async fn gc_db_at(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	ts: u64,
+	limit: Option<u32>,
+) -> Result<(), Error> {
+	let dbs = tx.get_db(ns, db).await?;
+	let tbs = tx.all_tb(ns, db).await?;
+	// Find the longest retention period in this database
+	let mut expiry = dbs.changefeed.as_ref().map_or(0, |cf| cf.expiry.as_secs());
+	for tb in tbs.iter() {
+		if let Some(cf) = &tb.changefeed {
+			expiry = expiry.max(cf.expiry.as_secs());
+		}
+	}
+	// There is no change feed in this database
+	if expiry == 0 {
+		return Ok(());
+	}
+	// Find the versionstamp at the start of the retention period, and the
+	// oldest point in time at which timestamps are still looked up
+	let mut horizon = expiry;
+	let mut watermark =
+		match tx.get_versionstamp_from_timestamp(ts.saturating_sub(expiry), ns, db).await? {
+			Some(vs) => vs,
+			None => return Ok(()),
+		};
+	// Keep the changes which consumers still need
+	for tb in tbs.iter() {
+		let cos = tx.all_co(ns, db, &tb.name).await?;
+		for co in cos.iter() {
+			// Everything up to the acknowledged versionstamp can go
+			let mut keep = match tx.get_cp(ns, db, &tb.name, &co.name).await? {
+				Some(vs) => vs::try_u128_to_versionstamp(vs::to_u128_be(vs) + 1)?,
+				None => vs::u64_to_versionstamp(0),
+			};
+			// Unless the consumer is lagging behind for too long
+			if let Some(maxlag) = &co.maxlag {
+				horizon = horizon.max(maxlag.as_secs());
+				let at = ts.saturating_sub(maxlag.as_secs());
+				if let Some(vs) = tx.get_versionstamp_from_timestamp(at, ns, db).await? {
+					keep = keep.max(vs);
+				}
+			}
+			watermark = watermark.min(keep);
+		}
+	}
+
+	let limit = limit.unwrap_or(100);
+
+	let beg: Vec<u8> = change::prefix_ts(ns, db, vs::u64_to_versionstamp(0));
+	let end = change::prefix_ts(ns, db, watermark);
+	delete_changes(tx, beg..end, limit).await?;
+
+	// Remove the timestamps which will no longer be looked up
+	let beg = database::ts::prefix(ns, db);
+	let end = database::ts::new(ns, db, ts.saturating_sub(horizon).saturating_add(1));
+	if let Some((end, _)) = tx.scanr(beg.clone()..end.into(), 1).await?.pop() {
+		tx.delr(beg..end, limit).await?;
+	}
+
+	Ok(())
+}
+
+// delete_changes deletes the change feed entries in the given range, up to the given limit,
+// and removes their size from the storage statistics of their tables.
+async fn delete_changes(
+	tx: &mut Transaction,
+	rng: Range<Vec<u8>>,
+	limit: u32,
+) -> Result<(), Error> {
+	let res = tx.scan(rng, limit).await?;
+	for (k, v) in res {
+		let cf = change::Cf::decode(&k)?;
+		tx.record_stats(cf.ns, cf.db, cf.tb, Stats::changes(-((k.len() + v.len()) as i64)));
+		tx.del(k).await?;
+	}
+	Ok(())
+}
@@ -1,9 +1,11 @@
 pub(crate) mod gc;
 pub(crate) mod mutations;
 pub(crate) mod reader;
+pub(crate) mod replay;
 pub(crate) mod writer;
 
 pub use self::gc::*;
 pub use self::mutations::*;
-pub use self::reader::read;
+pub use self::reader::{read, read_after, read_at};
+pub(crate) use self::replay::Replay;
 pub use self::writer::Writer;
@@ -1,5 +1,9 @@
 use crate::sql::array::Array;
 use crate::sql::object::Object;
+use crate::sql::statements::{
+	DefineEventStatement, DefineFieldStatement, DefineIndexStatement, DefineTableStatement,
+	RemoveEventStatement, RemoveFieldStatement, RemoveIndexStatement, RemoveTableStatement,
+};
 use crate::sql::thing::Thing;
 use crate::sql::value::Value;
 use crate::vs::to_u128_be;
@@ -10,15 +14,25 @@ use std::fmt::{self, Display, Formatter};
 use derive::Store;
 
 // Mutation is a single mutation to a table.
-#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
 pub enum TableMutation {
 	// Although the Value is supposed to contain a field "id" of Thing,
 	// we do include it in the first field for convenience.
 	Set(Thing, Value),
 	Del(Thing),
+	// Schema changes are recorded alongside the record mutations of the
+	// affected table, so that consumers see them in commit order.
+	DefineTable(DefineTableStatement),
+	RemoveTable(RemoveTableStatement),
+	DefineField(DefineFieldStatement),
+	RemoveField(RemoveFieldStatement),
+	DefineIndex(DefineIndexStatement),
+	RemoveIndex(RemoveIndexStatement),
+	DefineEvent(DefineEventStatement),
+	RemoveEvent(RemoveEventStatement),
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
 pub struct TableMutations(pub String, pub Vec<TableMutation>);
 
 impl TableMutations {
@@ -27,7 +41,7 @@ impl TableMutations {
 	}
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
 pub struct DatabaseMutation(pub Vec<TableMutations>);
 
 impl DatabaseMutation {
@@ -42,7 +56,7 @@ impl Default for DatabaseMutation {
 	}
 }
 // Change is a set of mutations made to a table at the specific timestamp.
-#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
 pub struct ChangeSet(pub [u8; 10], pub DatabaseMutation);
 
 impl TableMutation {
@@ -55,6 +69,14 @@ impl TableMutation {
 				let o = Object::from(h);
 				("delete".to_string(), Value::Object(o))
 			}
+			TableMutation::DefineTable(s) => ("define_table".to_string(), s.to_string().into()),
+			TableMutation::RemoveTable(s) => ("remove_table".to_string(), s.to_string().into()),
+			TableMutation::DefineField(s) => ("define_field".to_string(), s.to_string().into()),
+			TableMutation::RemoveField(s) => ("remove_field".to_string(), s.to_string().into()),
+			TableMutation::DefineIndex(s) => ("define_index".to_string(), s.to_string().into()),
+			TableMutation::RemoveIndex(s) => ("remove_index".to_string(), s.to_string().into()),
+			TableMutation::DefineEvent(s) => ("define_event".to_string(), s.to_string().into()),
+			TableMutation::RemoveEvent(s) => ("remove_event".to_string(), s.to_string().into()),
 		};
 
 		let mut h = BTreeMap::<String, Value>::new();
@@ -92,6 +114,14 @@ impl Display for TableMutation {
 		match self {
 			TableMutation::Set(id, v) => write!(f, "SET {} {}", id, v),
 			TableMutation::Del(id) => write!(f, "DEL {}", id),
+			TableMutation::DefineTable(s) => Display::fmt(s, f),
+			TableMutation::RemoveTable(s) => Display::fmt(s, f),
+			TableMutation::DefineField(s) => Display::fmt(s, f),
+			TableMutation::RemoveField(s) => Display::fmt(s, f),
+			TableMutation::DefineIndex(s) => Display::fmt(s, f),
+			TableMutation::RemoveIndex(s) => Display::fmt(s, f),
+			TableMutation::DefineEvent(s) => Display::fmt(s, f),
+			TableMutation::RemoveEvent(s) => Display::fmt(s, f),
 		}
 	}
 }
@@ -122,7 +152,7 @@ impl Display for ChangeSet {
 }
 
 // WriteMutationSet is a set of mutations to be to a table at the specific timestamp.
-#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
 pub struct WriteMutationSet(pub Vec<TableMutations>);
 
 impl WriteMutationSet {
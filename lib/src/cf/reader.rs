@@ -31,6 +31,61 @@ pub async fn read(
 			change::prefix_ts(ns, db, ts)
 		} // None => dc::prefix(ns, db),
 	};
+	read_from(tx, ns, db, tb, beg, limit).await
+}
+
+// Reads the change feed for a specific database or a table,
+// starting right after the versionstamp acknowledged by a consumer.
+//
+// If the consumer has not acknowledged any versionstamp yet,
+// the change feed is read from the beginning.
+pub async fn read_after(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: Option<&str>,
+	after: Option<vs::Versionstamp>,
+	limit: Option<u32>,
+) -> Result<Vec<ChangeSet>, Error> {
+	let beg = match after {
+		Some(x) => {
+			// Skip every change recorded at the acknowledged versionstamp
+			let mut k = change::prefix_ts(ns, db, x);
+			k.push(0xff);
+			k
+		}
+		None => change::prefix(ns, db),
+	};
+	read_from(tx, ns, db, tb, beg, limit).await
+}
+
+// Reads the change feed for a specific database or a table,
+// starting from the versionstamp recorded at a point in time.
+//
+// If no versionstamp was recorded, the change feed is read from the beginning.
+pub async fn read_at(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: Option<&str>,
+	at: Option<vs::Versionstamp>,
+	limit: Option<u32>,
+) -> Result<Vec<ChangeSet>, Error> {
+	let beg = match at {
+		Some(x) => change::prefix_ts(ns, db, x),
+		None => change::prefix(ns, db),
+	};
+	read_from(tx, ns, db, tb, beg, limit).await
+}
+
+async fn read_from(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: Option<&str>,
+	beg: Vec<u8>,
+	limit: Option<u32>,
+) -> Result<Vec<ChangeSet>, Error> {
 	let end = change::suffix(ns, db);
 
 	let limit = limit.unwrap_or(100);
@@ -0,0 +1,151 @@
+use crate::cf::{TableMutation, TableMutations};
+use crate::dbs::Session;
+use crate::err::Error;
+use crate::key::change;
+use crate::key::database;
+use crate::kvs::{Datastore, Key, RestorePoint, Val};
+use crate::vs::Versionstamp;
+use std::fmt::Write;
+
+// Replays the change feeds read from a backup on top of a datastore.
+//
+// The entries of a backup are read in key order, so all of the entries for
+// a database are read together, and the timestamps of a database are read
+// before its change feed. Each change set is replayed in its own transaction.
+pub(crate) struct Replay {
+	until: RestorePoint,
+	// The database whose change feed is currently being replayed
+	cur: Option<Database>,
+}
+
+struct Database {
+	ns: String,
+	db: String,
+	// Changes up to this versionstamp are already contained in the datastore
+	from: Option<Versionstamp>,
+	// Changes before this versionstamp were committed before the restore timestamp
+	before: Option<Versionstamp>,
+	// The change set which is currently being collected
+	set: Option<(Versionstamp, Vec<TableMutations>)>,
+}
+
+impl Replay {
+	pub fn new(until: RestorePoint) -> Self {
+		Self {
+			until,
+			cur: None,
+		}
+	}
+
+	// Process a single entry from a backup
+	pub async fn push(&mut self, ds: &Datastore, k: &Key, v: Val) -> Result<(), Error> {
+		// Only entries within a database are relevant
+		if !k.starts_with(b"/*") {
+			return Ok(());
+		}
+		// Process the timestamps recorded for a database
+		if let Ok(ts) = database::ts::Ts::decode(k) {
+			if k.starts_with(&timestamp_prefix(ts.ns, ts.db)) {
+				let until = self.until;
+				let db = self.database(ds, ts.ns, ts.db).await?;
+				if let RestorePoint::Timestamp(until) = until {
+					if ts.ts <= until {
+						db.before = v.as_slice().try_into().ok();
+					}
+				}
+				return Ok(());
+			}
+		}
+		// Process the change feed entries for a database
+		if let Ok(cf) = change::Cf::decode(k) {
+			if k.starts_with(&change::prefix(cf.ns, cf.db)) {
+				let until = self.until;
+				let db = self.database(ds, cf.ns, cf.db).await?;
+				// Skip changes which are already contained in the datastore
+				if matches!(db.from, Some(from) if cf.vs <= from) {
+					return Ok(());
+				}
+				// Skip changes which were made after the restore point
+				let include = match until {
+					RestorePoint::Versionstamp(v) => cf.vs <= v,
+					RestorePoint::Timestamp(_) => matches!(db.before, Some(v) if cf.vs < v),
+				};
+				if !include {
+					return Ok(());
+				}
+				// Replay the previous change set once all of its changes are read
+				if !matches!(&db.set, Some((vs, _)) if *vs == cf.vs) {
+					db.apply(ds).await?;
+					db.set = Some((cf.vs, Vec::new()));
+				}
+				if let Some((_, set)) = &mut db.set {
+					set.push(v.into());
+				}
+			}
+		}
+		Ok(())
+	}
+
+	// Replay any remaining changes once the entire backup has been read
+	pub async fn finish(&mut self, ds: &Datastore) -> Result<(), Error> {
+		match &mut self.cur {
+			Some(db) => db.apply(ds).await,
+			None => Ok(()),
+		}
+	}
+
+	// Fetch the state of the database, switching to it if necessary
+	async fn database(
+		&mut self,
+		ds: &Datastore,
+		ns: &str,
+		db: &str,
+	) -> Result<&mut Database, Error> {
+		if !matches!(&self.cur, Some(cur) if cur.ns == ns && cur.db == db) {
+			// Replay the last change set of the previous database
+			self.finish(ds).await?;
+			// Fetch the latest versionstamp which the datastore contains
+			let mut tx = ds.transaction(false, false).await?;
+			let from = tx.get_database_versionstamp(ns, db).await;
+			tx.cancel().await?;
+			self.cur = Some(Database {
+				ns: ns.to_owned(),
+				db: db.to_owned(),
+				from: from?,
+				before: None,
+				set: None,
+			});
+		}
+		Ok(self.cur.as_mut().unwrap())
+	}
+}
+
+impl Database {
+	// Replay the change set which has been collected
+	async fn apply(&mut self, ds: &Datastore) -> Result<(), Error> {
+		let Some((_, set)) = self.set.take() else {
+			return Ok(());
+		};
+		let mut sql = String::from("OPTION IMPORT;\nBEGIN TRANSACTION;\n");
+		for mutation in set.into_iter().flat_map(|tb| tb.1) {
+			let _ = match mutation {
+				TableMutation::Set(id, v) => writeln!(sql, "UPDATE {id} CONTENT {v};"),
+				TableMutation::Del(id) => writeln!(sql, "DELETE {id};"),
+				stm => writeln!(sql, "{stm};"),
+			};
+		}
+		sql.push_str("COMMIT TRANSACTION;");
+		let ses = Session::for_kv().with_ns(&self.ns).with_db(&self.db);
+		for res in ds.execute(&sql, &ses, None).await? {
+			res.result?;
+		}
+		Ok(())
+	}
+}
+
+// Returns the prefix for the timestamps recorded for a database
+fn timestamp_prefix(ns: &str, db: &str) -> Vec<u8> {
+	let mut k = database::all::new(ns, db).encode().unwrap();
+	k.extend_from_slice(b"!ts");
+	k
+}
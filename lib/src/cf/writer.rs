@@ -15,15 +15,17 @@ use std::collections::HashMap;
 // value = serialized table mutations
 type PreparedWrite = (Vec<u8>, Vec<u8>, Vec<u8>, crate::kvs::Val);
 
+#[derive(Clone)]
 pub struct Writer {
 	buf: Buffer,
 }
 
+#[derive(Clone)]
 pub struct Buffer {
 	pub b: HashMap<ChangeKey, TableMutations>,
 }
 
-#[derive(Hash, Eq, PartialEq, Debug)]
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
 pub struct ChangeKey {
 	pub ns: String,
 	pub db: String,
@@ -72,13 +74,19 @@ impl Writer {
 		}
 	}
 
+	// define records a schema change made to the specified table.
+	pub(crate) fn define(&mut self, ns: &str, db: &str, tb: &str, m: TableMutation) {
+		self.buf.push(ns.to_string(), db.to_string(), tb.to_string(), m);
+	}
+
 	// get returns all the mutations buffered for this transaction,
 	// that are to be written onto the key composed of the specified prefix + the current timestamp + the specified suffix.
-	pub(crate) fn get(&self) -> Vec<PreparedWrite> {
-		let mut r = Vec::<(Vec<u8>, Vec<u8>, Vec<u8>, crate::kvs::Val)>::new();
+	// Each write is returned along with the table it belongs to.
+	pub(crate) fn get(&self) -> Vec<(ChangeKey, PreparedWrite)> {
+		let mut r = Vec::<(ChangeKey, PreparedWrite)>::new();
 		// Get the current timestamp
 		for (
-			ChangeKey {
+			ck @ ChangeKey {
 				ns,
 				db,
 				tb,
@@ -90,7 +98,7 @@ impl Writer {
 			let tc_key_prefix: Key = crate::key::change::versionstamped_key_prefix(ns, db);
 			let tc_key_suffix: Key = crate::key::change::versionstamped_key_suffix(tb.as_str());
 
-			r.push((ts_key, tc_key_prefix, tc_key_suffix, mutations.into()))
+			r.push((ck.clone(), (ts_key, tc_key_prefix, tc_key_suffix, mutations.into())))
 		}
 		r
 	}
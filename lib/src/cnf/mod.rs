@@ -1,4 +1,5 @@
 use once_cell::sync::Lazy;
+use std::time::Duration;
 
 #[cfg(not(target_arch = "wasm32"))]
 #[allow(dead_code)]
@@ -12,6 +13,22 @@ pub static MAX_COMPUTATION_DEPTH: Lazy<u8> = Lazy::new(|| {
 	option_env!("SURREAL_MAX_COMPUTATION_DEPTH").and_then(|s| s.parse::<u8>().ok()).unwrap_or(120)
 });
 
+/// Specifies the size in bytes of the durable in-memory datastore log, after which a snapshot is taken.
+pub static MEMORY_SNAPSHOT_THRESHOLD: Lazy<u64> = Lazy::new(|| {
+	option_env!("SURREAL_MEMORY_SNAPSHOT_THRESHOLD")
+		.and_then(|s| s.parse::<u64>().ok())
+		.unwrap_or(64 * 1024 * 1024)
+});
+
+/// Specifies the initial delay before retrying a transaction which conflicted with another transaction.
+pub const TRANSACTION_RETRY_BACKOFF: Duration = Duration::from_millis(10);
+
+/// Specifies the maximum delay before retrying a transaction which conflicted with another transaction.
+pub const MAX_TRANSACTION_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Specifies how long a node holds the lease of a cluster-wide background job, unless it is renewed.
+pub const LEASE_DURATION: Duration = Duration::from_secs(30);
+
 /// Specifies the names of parameters which can not be specified in a query.
 pub const PROTECTED_PARAM_NAMES: &[&str] = &["auth", "scope", "token", "session"];
 
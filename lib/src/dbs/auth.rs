@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 /// The authentication level for a datastore execution context.
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd)]
 pub enum Level {
@@ -9,7 +11,7 @@ pub enum Level {
 }
 
 /// Specifies the current authentication for the datastore execution context.
-#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
 pub enum Auth {
 	/// Specifies that the user is not authenticated
 	#[default]
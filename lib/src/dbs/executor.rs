@@ -1,4 +1,6 @@
+use crate::cnf::MAX_TRANSACTION_RETRY_BACKOFF;
 use crate::cnf::PROTECTED_PARAM_NAMES;
+use crate::cnf::TRANSACTION_RETRY_BACKOFF;
 use crate::ctx::Context;
 use crate::dbs::response::Response;
 use crate::dbs::Level;
@@ -12,9 +14,12 @@ use crate::sql::paths::DB;
 use crate::sql::paths::NS;
 use crate::sql::query::Query;
 use crate::sql::statement::Statement;
+use crate::sql::statements::{BeginStatement, CommitStatement};
 use crate::sql::value::Value;
 use channel::Receiver;
 use futures::lock::Mutex;
+use rand::Rng;
+use std::collections::VecDeque;
 use std::sync::Arc;
 use tracing::instrument;
 use trice::Instant;
@@ -114,6 +119,14 @@ impl<'a> Executor<'a> {
 			time: v.time,
 			result: Err(Error::QueryCancelled),
 			query_type: QueryType::Other,
+			retries: v.retries,
+		}
+	}
+
+	fn buf_rollback(&self, v: Response) -> Response {
+		match v.result {
+			Ok(_) => self.buf_cancel(v),
+			Err(_) => v,
 		}
 	}
 
@@ -131,11 +144,26 @@ impl<'a> Executor<'a> {
 					Err(e) => Err(e),
 				},
 				query_type: QueryType::Other,
+				retries: v.retries,
 			},
 			_ => v,
 		}
 	}
 
+	/// Wait before retrying a transaction which conflicted with another transaction
+	async fn backoff(&self, retries: u32) {
+		// Double the delay with each retry
+		let max = TRANSACTION_RETRY_BACKOFF
+			.saturating_mul(2u32.saturating_pow(retries.saturating_sub(1)))
+			.min(MAX_TRANSACTION_RETRY_BACKOFF);
+		// Add jitter so that conflicting transactions are spread out
+		let dur = rand::thread_rng().gen_range(max / 2..=max);
+		#[cfg(target_arch = "wasm32")]
+		wasmtimer::tokio::sleep(dur).await;
+		#[cfg(not(target_arch = "wasm32"))]
+		tokio::time::sleep(dur).await;
+	}
+
 	/// Consume the live query notifications
 	async fn clear(&self, _: &Context<'_>, rcv: Receiver<Notification>) {
 		while rcv.try_recv().is_ok() {
@@ -186,10 +214,29 @@ impl<'a> Executor<'a> {
 		let mut buf: Vec<Response> = vec![];
 		// Initialise array of responses
 		let mut out: Vec<Response> = vec![];
+		// Initialise savepoints, with the number of buffered responses and notifications
+		let mut sps: Vec<(String, usize, usize)> = vec![];
+		// Initialise notifications held back by savepoints
+		let mut ntf: Vec<Notification> = vec![];
+		// Initialise statements in the transaction, kept in case it needs to be retried
+		let mut blk: Vec<Statement> = vec![];
+		// Initialise the number of times the transaction has been retried
+		let mut tries: u32 = 0;
+		// Initialise the statements to be processed
+		let mut stms: VecDeque<Statement> = qry.into_iter().collect();
 		// Process all statements in query
-		for stm in qry.into_iter() {
+		while let Some(stm) = stms.pop_front() {
 			// Log the statement
 			debug!("Executing: {}", stm);
+			// Keep the statement in case the transaction needs to be retried
+			if self.txn.is_some()
+				&& self.kvs.transaction_retries > 0
+				&& !matches!(stm, Statement::Cancel(_) | Statement::Commit(_))
+			{
+				blk.push(stm.clone());
+			}
+			// Initialise the number of times the statement has been retried
+			let mut retries: u32 = 0;
 			// Reset errors
 			if self.txn.is_none() {
 				self.err = false;
@@ -226,13 +273,19 @@ impl<'a> Executor<'a> {
 				}
 				// Begin a new transaction
 				Statement::Begin(_) => {
-					self.begin(true).await;
+					if self.begin(true).await {
+						blk.clear();
+					}
 					continue;
 				}
 				// Cancel a running transaction
 				Statement::Cancel(_) => {
 					self.cancel(true).await;
 					self.clear(&ctx, recv.clone()).await;
+					sps.clear();
+					ntf.clear();
+					blk.clear();
+					tries = 0;
 					buf = buf.into_iter().map(|v| self.buf_cancel(v)).collect();
 					out.append(&mut buf);
 					debug_assert!(self.txn.is_none(), "cancel(true) should have unset txn");
@@ -241,14 +294,113 @@ impl<'a> Executor<'a> {
 				}
 				// Commit a running transaction
 				Statement::Commit(_) => {
+					let failed = self.err;
 					let commit_error = self.commit(true).await.err();
-					buf = buf.into_iter().map(|v| self.buf_commit(v, &commit_error)).collect();
+					// Check if the transaction conflicted with another transaction
+					let conflict = match &commit_error {
+						Some(e) => e.is_retryable(),
+						None => {
+							failed
+								&& buf
+									.iter()
+									.any(|v| matches!(&v.result, Err(e) if e.is_retryable()))
+						}
+					};
+					// Retry the transaction from the beginning
+					if conflict && tries < self.kvs.transaction_retries {
+						tries += 1;
+						self.err = false;
+						self.clear(&ctx, recv.clone()).await;
+						buf.clear();
+						sps.clear();
+						ntf.clear();
+						stms.push_front(Statement::Commit(CommitStatement));
+						while let Some(stm) = blk.pop() {
+							stms.push_front(stm);
+						}
+						stms.push_front(Statement::Begin(BeginStatement));
+						self.backoff(tries).await;
+						continue;
+					}
+					buf = buf
+						.into_iter()
+						.map(|v| Response {
+							retries: tries,
+							..self.buf_commit(v, &commit_error)
+						})
+						.collect();
+					if let Some(chn) = ctx.notifications() {
+						for v in ntf.drain(..) {
+							let _ = chn.send(v).await;
+						}
+					}
 					self.flush(&ctx, recv.clone()).await;
+					sps.clear();
+					ntf.clear();
+					blk.clear();
+					tries = 0;
 					out.append(&mut buf);
 					debug_assert!(self.txn.is_none(), "commit(true) should have unset txn");
 					self.txn = None;
 					continue;
 				}
+				// Create a savepoint within a running transaction
+				Statement::Savepoint(stm) => match self.txn.clone() {
+					None => Err(Error::TxSavepointOutsideTransaction),
+					Some(_) if self.err => Err(Error::QueryNotExecuted),
+					Some(txn) => {
+						txn.lock().await.savepoint(&stm.name);
+						// Hold back the notifications which precede the savepoint
+						while let Ok(v) = recv.try_recv() {
+							ntf.push(v);
+						}
+						sps.push((stm.name.0, buf.len(), ntf.len()));
+						continue;
+					}
+				},
+				// Roll back a running transaction to a savepoint
+				Statement::Rollback(stm) => match self.txn.clone() {
+					None => Err(Error::TxSavepointOutsideTransaction),
+					Some(txn) => match sps.iter().rposition(|(v, ..)| *v == stm.name.0) {
+						None => Err(Error::TxSavepointNotFound {
+							name: stm.name.0,
+						}),
+						Some(pos) => match txn.lock().await.rollback_to(&stm.name) {
+							Err(e) => Err(e),
+							Ok(_) => {
+								let (_, len, num) = sps[pos];
+								sps.truncate(pos + 1);
+								// Cancel the statements since the savepoint
+								let len = len.min(buf.len());
+								let res = buf.split_off(len);
+								buf.extend(res.into_iter().map(|v| self.buf_rollback(v)));
+								// Discard the notifications since the savepoint
+								self.clear(&ctx, recv.clone()).await;
+								ntf.truncate(num);
+								// The transaction can continue
+								self.err = false;
+								continue;
+							}
+						},
+					},
+				},
+				// Release a savepoint within a running transaction
+				Statement::Release(stm) => match self.txn.clone() {
+					None => Err(Error::TxSavepointOutsideTransaction),
+					Some(_) if self.err => Err(Error::QueryNotExecuted),
+					Some(txn) => match sps.iter().rposition(|(v, ..)| *v == stm.name.0) {
+						None => Err(Error::TxSavepointNotFound {
+							name: stm.name.0,
+						}),
+						Some(pos) => match txn.lock().await.release(&stm.name).await {
+							Err(e) => Err(e),
+							Ok(_) => {
+								sps.truncate(pos);
+								continue;
+							}
+						},
+					},
+				},
 				// Switch to a different NS or DB
 				Statement::Use(stm) => {
 					if let Some(ref ns) = stm.ns {
@@ -340,16 +492,20 @@ impl<'a> Executor<'a> {
 						}
 					}
 				}
+				// Rename statements move their data in batches, when not within a transaction
+				Statement::Rename(stm) if self.txn.is_none() => {
+					stm.compute_batched(&ctx, &opt, self.kvs).await
+				}
 				// Process all other normal statements
 				_ => match self.err {
 					// This transaction has failed
 					true => Err(Error::QueryNotExecuted),
 					// Compute the statement normally
-					false => {
+					false => loop {
 						// Create a transaction
 						let loc = self.begin(stm.writeable()).await;
 						// Check the transaction
-						match self.err {
+						let res = match self.err {
 							// We failed to create a transaction
 							true => Err(Error::TxFailure),
 							// The transaction began successfully
@@ -382,6 +538,15 @@ impl<'a> Executor<'a> {
 									if let Err(e) = self.commit(loc).await {
 										// Clear live query notification details
 										self.clear(&ctx, recv.clone()).await;
+										// Retry the statement if it conflicted with another transaction
+										if e.is_retryable()
+											&& retries < self.kvs.transaction_retries
+										{
+											retries += 1;
+											self.err = false;
+											self.backoff(retries).await;
+											continue;
+										}
 										// The commit failed
 										Err(Error::QueryNotExecutedDetail {
 											message: e.to_string(),
@@ -396,12 +561,22 @@ impl<'a> Executor<'a> {
 									self.cancel(loc).await;
 									// Clear live query notification details
 									self.clear(&ctx, recv.clone()).await;
+									// Retry the statement if it conflicted with another transaction
+									if loc
+										&& retries < self.kvs.transaction_retries
+										&& matches!(&res, Err(e) if e.is_retryable())
+									{
+										retries += 1;
+										self.backoff(retries).await;
+										continue;
+									}
 									// Return an error
 									res
 								}
 							}
-						}
-					}
+						};
+						break res;
+					},
 				},
 			};
 			// Produce the response
@@ -419,6 +594,7 @@ impl<'a> Executor<'a> {
 					(_, true) => QueryType::Kill,
 					_ => QueryType::Other,
 				},
+				retries,
 			};
 			// Output the response
 			if self.txn.is_some() {
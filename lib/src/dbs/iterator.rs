@@ -69,6 +69,8 @@ pub(crate) struct Iterator {
 	results: Vec<Value>,
 	// Iterator input values
 	entries: Vec<Iterable>,
+	// Iterator output is already sorted
+	sorted: bool,
 }
 
 impl Iterator {
@@ -99,6 +101,8 @@ impl Iterator {
 		self.setup_limit(&cancel_ctx, opt, txn, stm).await?;
 		// Process the query START clause
 		self.setup_start(&cancel_ctx, opt, txn, stm).await?;
+		// Check if the records are scanned in the sorted order
+		self.setup_sorted(stm);
 
 		// Extract the expected behaviour depending on the presence of EXPLAIN with or without FULL
 		let (do_iterate, mut explanation) = Explanation::new(stm.explain(), &self.entries);
@@ -167,6 +171,15 @@ impl Iterator {
 		Ok(())
 	}
 
+	#[inline]
+	fn setup_sorted(&mut self, stm: &Statement<'_>) {
+		// A single table or range is scanned in record id order, but
+		// records processed in parallel can complete in any order
+		self.sorted = stm.id_order().is_some()
+			&& !stm.parallel()
+			&& matches!(self.entries.as_slice(), [Iterable::Table(_) | Iterable::Range(_)]);
+	}
+
 	#[inline]
 	async fn output_split(
 		&mut self,
@@ -536,7 +549,7 @@ impl Iterator {
 			Ok(v) => self.results.push(v),
 		}
 		// Check if we can exit
-		if stm.group().is_none() && stm.order().is_none() {
+		if stm.group().is_none() && (stm.order().is_none() || self.sorted) {
 			if let Some(l) = self.limit {
 				if let Some(s) = self.start {
 					if self.results.len() == l + s {
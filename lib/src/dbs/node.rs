@@ -1,5 +1,7 @@
 use crate::err::Error;
 use crate::err::Error::TimestampOverflow;
+use crate::sql;
+use crate::sql::Datetime;
 use derive::{Key, Store};
 use serde::{Deserialize, Serialize};
 use std::ops::{Add, Sub};
@@ -14,6 +16,29 @@ pub struct ClusterMembership {
 	// not TiKV = local nanos as u64
 	pub heartbeat: Timestamp,
 }
+// A lease which allows a single node of the cluster to run a background job,
+// until the lease expires or is taken over after the node has been removed.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize, PartialOrd, Hash, Store)]
+pub struct Lease {
+	// The node which holds the lease
+	pub nd: uuid::Uuid,
+	// The timestamp at which the lease expires, unless it is renewed
+	pub expires: Timestamp,
+}
+// The outcome of the last run of a scheduled job, and the time at which it is next due.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize, Hash, Store)]
+pub struct JobRun {
+	// The time at which the job last started running
+	pub at: Option<Datetime>,
+	// How long the last run of the job took
+	pub duration: Option<sql::Duration>,
+	// The error with which the last run of the job failed
+	pub error: Option<String>,
+	// The time at which the job is next due to run
+	pub next: Option<Datetime>,
+	// The claim of the node which is running the job, until it has recorded the run
+	pub claim: Option<Lease>,
+}
 // This struct is meant to represent a timestamp that can be used to partially order
 // events in a cluster. It should be derived from a timestamp oracle, such as the
 // one available in TiKV via the client `TimestampExt` implementation.
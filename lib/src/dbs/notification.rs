@@ -1,4 +1,5 @@
 use crate::sql::{Object, Uuid, Value};
+use derive::Store;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Debug, Display};
 
@@ -20,7 +21,7 @@ impl Display for Action {
 	}
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, Store)]
 pub struct Notification {
 	pub id: Uuid,
 	pub action: Action,
@@ -42,6 +42,8 @@ pub struct Options {
 	pub indexes: bool,
 	/// Should we process function futures?
 	pub futures: bool,
+	/// Should we process records which have expired?
+	pub expired: bool,
 	/// The channel over which we send notifications
 	pub sender: Option<Sender<Notification>>,
 }
@@ -69,6 +71,7 @@ impl Options {
 			tables: true,
 			indexes: true,
 			futures: false,
+			expired: false,
 			sender: None,
 			auth: Arc::new(Auth::No),
 		}
@@ -174,6 +177,13 @@ impl Options {
 		self
 	}
 
+	/// Specify whether records which have expired should be
+	/// processed by code which uses this `Options`, with chaining.
+	pub fn with_expired(mut self, expired: bool) -> Self {
+		self.expired = expired;
+		self
+	}
+
 	/// Create a new Options object for a subquery
 	pub fn with_import(mut self, import: bool) -> Self {
 		self.fields = !import;
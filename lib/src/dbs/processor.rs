@@ -7,10 +7,12 @@ use crate::err::Error;
 use crate::idx::planner::executor::IteratorRef;
 use crate::idx::planner::plan::IndexOption;
 use crate::key::{graph, thing};
+use crate::kvs::{Key, Scanner};
 use crate::sql::dir::Dir;
 use crate::sql::{Edges, Range, Table, Thing, Value};
 #[cfg(not(target_arch = "wasm32"))]
 use channel::Sender;
+use futures::TryStreamExt;
 use std::ops::Bound;
 
 impl Iterable {
@@ -141,7 +143,11 @@ impl<'a> Processor<'a> {
 		txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &v.tb, opt.strict).await?;
 		// Fetch the data from the store
 		let key = thing::new(opt.ns(), opt.db(), &v.tb, &v.id);
-		let val = txn.clone().lock().await.get(key).await?;
+		let val = {
+			let mut run = txn.lock().await;
+			let base = run.tb_base(opt.ns(), opt.db(), &v.tb).await?;
+			run.get(base.key(key)).await?
+		};
 		// Parse the data from the store
 		let val = Operable::Value(match val {
 			Some(v) => Value::from(v),
@@ -172,7 +178,11 @@ impl<'a> Processor<'a> {
 		txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &v.tb, opt.strict).await?;
 		// Fetch the data from the store
 		let key = thing::new(opt.ns(), opt.db(), &v.tb, &v.id);
-		let val = txn.clone().lock().await.get(key).await?;
+		let val = {
+			let mut run = txn.lock().await;
+			let base = run.tb_base(opt.ns(), opt.db(), &v.tb).await?;
+			run.get(base.key(key)).await?
+		};
 		// Parse the data from the store
 		let x = match val {
 			Some(v) => Value::from(v),
@@ -207,7 +217,11 @@ impl<'a> Processor<'a> {
 		txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &v.tb, opt.strict).await?;
 		// Fetch the data from the store
 		let key = thing::new(opt.ns(), opt.db(), &v.tb, &v.id);
-		let val = txn.clone().lock().await.get(key).await?;
+		let val = {
+			let mut run = txn.lock().await;
+			let base = run.tb_base(opt.ns(), opt.db(), &v.tb).await?;
+			run.get(base.key(key)).await?
+		};
 		// Parse the data from the store
 		let x = match val {
 			Some(v) => Value::from(v),
@@ -240,63 +254,8 @@ impl<'a> Processor<'a> {
 		// Prepare the start and end keys
 		let beg = thing::prefix(opt.ns(), opt.db(), &v);
 		let end = thing::suffix(opt.ns(), opt.db(), &v);
-		// Prepare the next holder key
-		let mut nxt: Option<Vec<u8>> = None;
-		// Loop until no more keys
-		loop {
-			// Check if the context is finished
-			if ctx.is_done() {
-				break;
-			}
-			// Get the next 1000 key-value entries
-			let res = match nxt {
-				None => {
-					let min = beg.clone();
-					let max = end.clone();
-					txn.clone().lock().await.scan(min..max, 1000).await?
-				}
-				Some(ref mut beg) => {
-					beg.push(0x00);
-					let min = beg.clone();
-					let max = end.clone();
-					txn.clone().lock().await.scan(min..max, 1000).await?
-				}
-			};
-			// If there are key-value entries then fetch them
-			if !res.is_empty() {
-				// Get total results
-				let n = res.len();
-				// Loop over results
-				for (i, (k, v)) in res.into_iter().enumerate() {
-					// Check the context
-					if ctx.is_done() {
-						break;
-					}
-					// Ready the next
-					if n == i + 1 {
-						nxt = Some(k.clone());
-					}
-					// Parse the data from the store
-					let key: thing::Thing = (&k).into();
-					let val: Value = (&v).into();
-					let rid = Thing::from((key.tb, key.id));
-					// Create a new operable value
-					let val = Operable::Value(val);
-					// Process the record
-					let pro = Processed {
-						ir: None,
-						rid: Some(rid),
-						doc_id: None,
-						val,
-					};
-					self.process(ctx, opt, txn, stm, pro).await?;
-				}
-				continue;
-			}
-			break;
-		}
-		// Everything ok
-		Ok(())
+		// Process the records in the table
+		self.process_records(ctx, opt, txn, stm, &v, beg..end).await
 	}
 
 	async fn process_range(
@@ -329,60 +288,55 @@ impl<'a> Processor<'a> {
 				key
 			}
 		};
-		// Prepare the next holder key
-		let mut nxt: Option<Vec<u8>> = None;
-		// Loop until no more keys
-		loop {
-			// Check if the context is finished
+		// Process the records in the range
+		self.process_records(ctx, opt, txn, stm, &v.tb, beg..end).await
+	}
+
+	async fn process_records(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		tb: &str,
+		rng: std::ops::Range<Key>,
+	) -> Result<(), Error> {
+		// Fetch the base under which the records are stored
+		let base = txn.lock().await.tb_base(opt.ns(), opt.db(), tb).await?;
+		let rng = base.range(rng);
+		// Scan in descending order if the records are sorted by id descending
+		let scanner = match stm.id_order() {
+			Some(false) => Scanner::new(rng).reverse(),
+			_ => Scanner::new(rng),
+		};
+		// Stream the key-value entries, locking the transaction for each batch
+		let stream = scanner.into_stream(txn.clone());
+		futures::pin_mut!(stream);
+		// Loop until no more entries
+		while let Some((k, v)) = stream.try_next().await? {
+			// Check the context
 			if ctx.is_done() {
 				break;
 			}
-			// Get the next 1000 key-value entries
-			let res = match nxt {
-				None => {
-					let min = beg.clone();
-					let max = end.clone();
-					txn.clone().lock().await.scan(min..max, 1000).await?
-				}
-				Some(ref mut beg) => {
-					beg.push(0x00);
-					let min = beg.clone();
-					let max = end.clone();
-					txn.clone().lock().await.scan(min..max, 1000).await?
-				}
-			};
-			// If there are key-value entries then fetch them
-			if !res.is_empty() {
-				// Get total results
-				let n = res.len();
-				// Loop over results
-				for (i, (k, v)) in res.into_iter().enumerate() {
-					// Check the context
-					if ctx.is_done() {
-						break;
-					}
-					// Ready the next
-					if n == i + 1 {
-						nxt = Some(k.clone());
-					}
-					// Parse the data from the store
-					let key: thing::Thing = (&k).into();
-					let val: Value = (&v).into();
-					let rid = Thing::from((key.tb, key.id));
-					// Create a new operable value
-					let val = Operable::Value(val);
-					// Process the record
-					let pro = Processed {
-						ir: None,
-						rid: Some(rid),
-						doc_id: None,
-						val,
-					};
-					self.process(ctx, opt, txn, stm, pro).await?;
-				}
+			// Parse the data from the store
+			let key = base.name(k);
+			let key: thing::Thing = (&key).into();
+			let val: Value = (&v).into();
+			let rid = Thing::from((key.tb, key.id));
+			// Skip the record if it has expired
+			if expired(opt, txn, &rid).await? {
 				continue;
 			}
-			break;
+			// Create a new operable value
+			let val = Operable::Value(val);
+			// Process the record
+			let pro = Processed {
+				ir: None,
+				rid: Some(rid),
+				doc_id: None,
+				val,
+			};
+			self.process(ctx, opt, txn, stm, pro).await?;
 		}
 		// Everything ok
 		Ok(())
@@ -464,71 +418,48 @@ impl<'a> Processor<'a> {
 					.collect::<Vec<_>>(),
 			},
 		};
+		// Fetch the base under which the edges are stored
+		let base = txn.lock().await.tb_base(ns, db, tb).await?;
 		//
-		for (beg, end) in keys.iter() {
-			// Prepare the next holder key
-			let mut nxt: Option<Vec<u8>> = None;
+		for (beg, end) in keys.into_iter() {
+			// Stream the edge keys, without fetching the edge values
+			let scanner = Scanner::new(base.range(beg..end)).keys_only();
+			let stream = scanner.into_stream(txn.clone());
+			futures::pin_mut!(stream);
 			// Loop until no more keys
-			loop {
-				// Check if the context is finished
+			while let Some((k, _)) = stream.try_next().await? {
+				// Check the context
 				if ctx.is_done() {
 					break;
 				}
-				// Get the next 1000 key-value entries
-				let res = match nxt {
-					None => {
-						let min = beg.clone();
-						let max = end.clone();
-						txn.lock().await.scan(min..max, 1000).await?
-					}
-					Some(ref mut beg) => {
-						beg.push(0x00);
-						let min = beg.clone();
-						let max = end.clone();
-						txn.lock().await.scan(min..max, 1000).await?
-					}
-				};
-				// If there are key-value entries then fetch them
-				if !res.is_empty() {
-					// Get total results
-					let n = res.len();
-					// Exit when settled
-					if n == 0 {
-						break;
-					}
-					// Loop over results
-					for (i, (k, _)) in res.into_iter().enumerate() {
-						// Check the context
-						if ctx.is_done() {
-							break;
-						}
-						// Ready the next
-						if n == i + 1 {
-							nxt = Some(k.clone());
-						}
-						// Parse the data from the store
-						let gra: graph::Graph = (&k).into();
-						// Fetch the data from the store
-						let key = thing::new(opt.ns(), opt.db(), gra.ft, &gra.fk);
-						let val = txn.lock().await.get(key).await?;
-						let rid = Thing::from((gra.ft, gra.fk));
-						// Parse the data from the store
-						let val = Operable::Value(match val {
-							Some(v) => Value::from(v),
-							None => Value::None,
-						});
-						// Process the record
-						let pro = Processed {
-							ir: None,
-							rid: Some(rid),
-							doc_id: None,
-							val,
-						};
-						self.process(ctx, opt, txn, stm, pro).await?;
-					}
+				// Parse the data from the store
+				let k = base.name(k);
+				let gra: graph::Graph = (&k).into();
+				let rid = Thing::from((gra.ft, gra.fk));
+				// Skip the record if it has expired
+				if expired(opt, txn, &rid).await? {
 					continue;
 				}
-				break;
+				// Fetch the data from the store
+				let key = thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+				let val = {
+					let mut run = txn.lock().await;
+					let base = run.tb_base(opt.ns(), opt.db(), &rid.tb).await?;
+					run.get(base.key(key)).await?
+				};
+				// Parse the data from the store
+				let val = Operable::Value(match val {
+					Some(v) => Value::from(v),
+					None => Value::None,
+				});
+				// Process the record
+				let pro = Processed {
+					ir: None,
+					rid: Some(rid),
+					doc_id: None,
+					val,
+				};
+				self.process(ctx, opt, txn, stm, pro).await?;
 			}
 		}
 		// Everything ok
@@ -548,9 +479,11 @@ impl<'a> Processor<'a> {
 	) -> Result<(), Error> {
 		// Check that the table exists
 		txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &table.0, opt.strict).await?;
+		// Fetch the base under which the records are stored
+		let base = txn.lock().await.tb_base(opt.ns(), opt.db(), &table.0).await?;
 		if let Some(pla) = ctx.get_query_planner() {
 			if let Some(exe) = pla.get_query_executor(&table.0) {
-				if let Some(mut iterator) = exe.new_iterator(opt, ir, io).await? {
+				if let Some(mut iterator) = exe.new_iterator(opt, txn, ir, io).await? {
 					let mut things = iterator.next_batch(txn, 1000).await?;
 					while !things.is_empty() {
 						// Check if the context is finished
@@ -569,9 +502,14 @@ impl<'a> Processor<'a> {
 								continue;
 							}
 
+							// Skip the record if it has expired
+							if expired(opt, txn, &thing).await? {
+								continue;
+							}
+
 							// Fetch the data from the store
 							let key = thing::new(opt.ns(), opt.db(), &table.0, &thing.id);
-							let val = txn.lock().await.get(key.clone()).await?;
+							let val = txn.lock().await.get(base.key(key.clone())).await?;
 							let rid = Thing::from((key.tb, key.id));
 							// Parse the data from the store
 							let val = Operable::Value(match val {
@@ -601,3 +539,16 @@ impl<'a> Processor<'a> {
 		})
 	}
 }
+
+// Check if a record which was found by scanning a table, range, index, or
+// graph edges has expired, in which case it is not processed. Records which
+// are requested by id are checked as part of the document processing.
+async fn expired(opt: &Options, txn: &Transaction, rid: &Thing) -> Result<bool, Error> {
+	// Forced statements process every stored record
+	if opt.force || opt.expired {
+		return Ok(false);
+	}
+	let mut run = txn.lock().await;
+	Ok(run.tb_expires(opt.ns(), opt.db(), &rid.tb).await?
+		&& run.is_expired(opt.ns(), opt.db(), &rid.tb, &rid.id).await?)
+}
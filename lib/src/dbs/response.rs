@@ -24,6 +24,8 @@ pub struct Response {
 	pub result: Result<Value, Error>,
 	// Record the query type in case processing the response is necessary (such as tracking live queries).
 	pub query_type: QueryType,
+	// The number of times the transaction was retried after conflicting with another transaction
+	pub retries: u32,
 }
 
 impl Response {
@@ -49,8 +51,12 @@ impl Serialize for Response {
 	where
 		S: serde::Serializer,
 	{
-		let mut val = serializer.serialize_struct(TOKEN, 3)?;
+		let mut val = serializer.serialize_struct(TOKEN, 4)?;
 		val.serialize_field("time", self.speed().as_str())?;
+		match self.retries {
+			0 => val.skip_field("retries")?,
+			v => val.serialize_field("retries", &v)?,
+		}
 		match &self.result {
 			Ok(v) => {
 				val.serialize_field("status", &Status::Ok)?;
@@ -6,6 +6,7 @@ use crate::sql::group::Groups;
 use crate::sql::limit::Limit;
 use crate::sql::order::Orders;
 use crate::sql::output::Output;
+use crate::sql::paths::ID;
 use crate::sql::split::Splits;
 use crate::sql::start::Start;
 use crate::sql::statements::create::CreateStatement;
@@ -160,6 +161,19 @@ impl<'a> Statement<'a> {
 			_ => None,
 		}
 	}
+	/// Returns the sort direction if the ORDER clause only sorts by record id
+	///
+	/// Records are stored in record id order, so the records in a table or
+	/// range can be scanned in the sorted order, when ascending is `true`.
+	#[inline]
+	pub fn id_order(&self) -> Option<bool> {
+		match self.order().map(|v| v.as_slice()) {
+			Some([o]) if !o.random && !o.collate && !o.numeric && o.0 == ID.as_ref() => {
+				Some(o.direction)
+			}
+			_ => None,
+		}
+	}
 	/// Returns any FETCH clause if specified
 	#[inline]
 	pub fn fetch(&self) -> Option<&Fetchs> {
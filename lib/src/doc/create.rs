@@ -13,6 +13,8 @@ impl<'a> Document<'a> {
 		txn: &Transaction,
 		stm: &Statement<'_>,
 	) -> Result<Value, Error> {
+		// Check if record has expired
+		self.expire(ctx, opt, txn, stm).await?;
 		// Check if exists
 		self.exist(ctx, opt, txn, stm).await?;
 		// Alter record data
@@ -21,6 +21,7 @@ pub(crate) struct Document<'a> {
 	pub(super) extras: Workable,
 	pub(super) initial: CursorDoc<'a>,
 	pub(super) current: CursorDoc<'a>,
+	pub(super) expired: Option<CursorDoc<'a>>,
 }
 
 pub struct CursorDoc<'a> {
@@ -93,6 +94,7 @@ impl<'a> Document<'a> {
 			extras,
 			current: CursorDoc::new(ir, id, doc_id, val),
 			initial: CursorDoc::new(ir, id, doc_id, val),
+			expired: None,
 		}
 	}
 }
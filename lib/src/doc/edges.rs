@@ -30,18 +30,23 @@ impl<'a> Document<'a> {
 		if let Workable::Relate(l, r) = &self.extras {
 			// Get temporary edge references
 			let (ref o, ref i) = (Dir::Out, Dir::In);
+			// Fetch the bases under which the edges are stored, which
+			// are the names of the related tables if they are not defined
+			let base = run.tb_base(opt.ns(), opt.db(), &rid.tb).await?;
+			let lb = run.tb_base(opt.ns(), opt.db(), &l.tb).await?;
+			let rb = run.tb_base(opt.ns(), opt.db(), &r.tb).await?;
 			// Store the left pointer edge
 			let key = crate::key::graph::new(opt.ns(), opt.db(), &l.tb, &l.id, o, rid);
-			run.set(key, vec![]).await?;
+			run.set(lb.key(key), vec![]).await?;
 			// Store the left inner edge
 			let key = crate::key::graph::new(opt.ns(), opt.db(), &rid.tb, &rid.id, i, l);
-			run.set(key, vec![]).await?;
+			run.set(base.key(key), vec![]).await?;
 			// Store the right inner edge
 			let key = crate::key::graph::new(opt.ns(), opt.db(), &rid.tb, &rid.id, o, r);
-			run.set(key, vec![]).await?;
+			run.set(base.key(key), vec![]).await?;
 			// Store the right pointer edge
 			let key = crate::key::graph::new(opt.ns(), opt.db(), &r.tb, &r.id, i, rid);
-			run.set(key, vec![]).await?;
+			run.set(rb.key(key), vec![]).await?;
 			// Store the edges on the record
 			self.current.doc.to_mut().put(&*EDGE, Value::Bool(true));
 			self.current.doc.to_mut().put(&*IN, l.clone().into());
@@ -0,0 +1,53 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Statement;
+use crate::dbs::Transaction;
+use crate::doc::{CursorDoc, Document};
+use crate::err::Error;
+use crate::sql::value::Value;
+use std::borrow::Cow;
+
+impl<'a> Document<'a> {
+	pub async fn expire(
+		&mut self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<(), Error> {
+		// Forced statements process every stored record
+		if opt.force || opt.expired {
+			return Ok(());
+		}
+		// Check if this record exists
+		if let Some(rid) = self.id {
+			// Check if there is a current record
+			if self.current.doc.is_none() {
+				return Ok(());
+			}
+			// Check if the records in the table expire
+			if self.tb(opt, txn).await?.ttl.is_some() {
+				// Claim transaction
+				let mut run = txn.lock().await;
+				// Check if the record has expired
+				if run.is_expired(opt.ns(), opt.db(), &rid.tb, &rid.id).await? {
+					match stm {
+						// Ignore this expired record
+						Statement::Select(_) | Statement::Delete(_) => return Err(Error::Ignore),
+						// The record is written as a new record, replacing the expired data
+						_ => {
+							let initial = CursorDoc {
+								doc: Cow::Owned(Value::None),
+								..self.initial
+							};
+							self.expired = Some(std::mem::replace(&mut self.initial, initial));
+							self.current.doc = Cow::Owned(Value::None);
+						}
+					}
+				}
+			}
+		}
+		// Carry on
+		Ok(())
+	}
+}
@@ -6,9 +6,12 @@ use crate::err::Error;
 use crate::idx::btree::store::BTreeStoreType;
 use crate::idx::ft::FtIndex;
 use crate::idx::IndexKeyBase;
+use crate::key::data::Base;
+use crate::kvs::Stats;
 use crate::sql::array::Array;
 use crate::sql::index::Index;
 use crate::sql::scoring::Scoring;
+use crate::sql::serde::serialized_size;
 use crate::sql::statements::DefineIndexStatement;
 use crate::sql::{Ident, Thing};
 use crate::{key, kvs};
@@ -37,8 +40,9 @@ impl<'a> Document<'a> {
 		let rid = self.id.as_ref().unwrap();
 		// Loop through all index statements
 		for ix in self.ix(opt, txn).await?.iter() {
-			// Calculate old values
-			let o = Self::build_opt_array(ctx, opt, txn, ix, &self.initial).await?;
+			// Calculate old values, removing the entries of any replaced expired record
+			let old = self.expired.as_ref().unwrap_or(&self.initial);
+			let o = Self::build_opt_array(ctx, opt, txn, ix, old).await?;
 
 			// Calculate new values
 			let n = Self::build_opt_array(ctx, opt, txn, ix, &self.current).await?;
@@ -48,8 +52,11 @@ impl<'a> Document<'a> {
 				// Claim transaction
 				let mut run = txn.lock().await;
 
+				// Fetch the base under which the index entries are stored
+				let base = run.ix_base(opt.ns(), opt.db(), ix).await?;
+
 				// Store all the variable and parameters required by the index operation
-				let ic = IndexOperation::new(opt, ix, o, n, rid);
+				let ic = IndexOperation::new(opt, ix, base, o, n, rid);
 
 				// Index operation dispatching
 				match &ix.index {
@@ -94,6 +101,8 @@ impl<'a> Document<'a> {
 struct IndexOperation<'a> {
 	opt: &'a Options,
 	ix: &'a DefineIndexStatement,
+	/// The base under which the index entries are stored
+	base: Base,
 	/// The old value (if existing)
 	o: Option<Array>,
 	/// The new value (if existing)
@@ -105,6 +114,7 @@ impl<'a> IndexOperation<'a> {
 	fn new(
 		opt: &'a Options,
 		ix: &'a DefineIndexStatement,
+		base: Base,
 		o: Option<Array>,
 		n: Option<Array>,
 		rid: &'a Thing,
@@ -112,68 +122,87 @@ impl<'a> IndexOperation<'a> {
 		Self {
 			opt,
 			ix,
+			base,
 			o,
 			n,
 			rid,
 		}
 	}
 
-	fn get_non_unique_index_key(&self, v: &Array) -> key::index::Index {
-		key::index::Index::new(
+	fn get_non_unique_index_key(&self, v: &Array) -> kvs::Key {
+		self.base.key(key::index::Index::new(
 			self.opt.ns(),
 			self.opt.db(),
 			&self.ix.what,
 			&self.ix.name,
 			v.to_owned(),
 			Some(self.rid.id.to_owned()),
-		)
+		))
 	}
 
 	async fn index_non_unique(&self, run: &mut kvs::Transaction) -> Result<(), Error> {
 		// Delete the old index data
 		if let Some(o) = &self.o {
 			let key = self.get_non_unique_index_key(o);
-			let _ = run.delc(key, Some(self.rid)).await; // Ignore this error
+			let len = key.len();
+			if run.delc(key, Some(self.rid)).await.is_ok() {
+				self.record_stats(run, len, -1)?;
+			}
 		}
 		// Create the new index data
 		if let Some(n) = &self.n {
 			let key = self.get_non_unique_index_key(n);
+			let len = key.len();
 			if run.putc(key, self.rid, None).await.is_err() {
 				return self.err_index_exists(n);
 			}
+			self.record_stats(run, len, 1)?;
 		}
 		Ok(())
 	}
 
-	fn get_unique_index_key(&self, v: &Array) -> key::index::Index {
-		key::index::Index::new(
+	fn get_unique_index_key(&self, v: &Array) -> kvs::Key {
+		self.base.key(key::index::Index::new(
 			self.opt.ns(),
 			self.opt.db(),
 			&self.ix.what,
 			&self.ix.name,
 			v.to_owned(),
 			None,
-		)
+		))
 	}
 
 	async fn index_unique(&self, run: &mut kvs::Transaction) -> Result<(), Error> {
 		// Delete the old index data
 		if let Some(o) = &self.o {
 			let key = self.get_unique_index_key(o);
-			let _ = run.delc(key, Some(self.rid)).await; // Ignore this error
+			let len = key.len();
+			if run.delc(key, Some(self.rid)).await.is_ok() {
+				self.record_stats(run, len, -1)?;
+			}
 		}
 		// Create the new index data
 		if let Some(n) = &self.n {
 			if !n.is_all_none_or_null() {
 				let key = self.get_unique_index_key(n);
+				let len = key.len();
 				if run.putc(key, self.rid, None).await.is_err() {
 					return self.err_index_exists(n);
 				}
+				self.record_stats(run, len, 1)?;
 			}
 		}
 		Ok(())
 	}
 
+	/// Add or remove the size of an index entry from the storage statistics of the table
+	fn record_stats(&self, run: &mut kvs::Transaction, key: usize, sign: i64) -> Result<(), Error> {
+		let size = key as u64 + serialized_size(self.rid)?;
+		let st = Stats::index(&self.ix.name, sign * size as i64);
+		run.record_stats(self.opt.ns(), self.opt.db(), &self.ix.what, st);
+		Ok(())
+	}
+
 	fn err_index_exists(&self, n: &Array) -> Result<(), Error> {
 		Err(Error::IndexExists {
 			thing: self.rid.to_string(),
@@ -193,7 +222,7 @@ impl<'a> IndexOperation<'a> {
 		scoring: &Scoring,
 		hl: bool,
 	) -> Result<(), Error> {
-		let ikb = IndexKeyBase::new(self.opt, self.ix);
+		let ikb = IndexKeyBase::new(self.opt, self.ix, self.base.clone());
 		let az = run.get_az(self.opt.ns(), self.opt.db(), az.as_str()).await?;
 		let mut ft = FtIndex::new(run, az, ikb, order, scoring, hl, BTreeStoreType::Write).await?;
 		if let Some(n) = &self.n {
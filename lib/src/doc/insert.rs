@@ -13,6 +13,8 @@ impl<'a> Document<'a> {
 		txn: &Transaction,
 		stm: &Statement<'_>,
 	) -> Result<Value, Error> {
+		// Check if record has expired
+		self.expire(ctx, opt, txn, stm).await?;
 		// Check current record
 		match self.current.doc.is_some() {
 			// Run INSERT clause
@@ -3,9 +3,15 @@ use crate::dbs::Notification;
 use crate::dbs::Options;
 use crate::dbs::Statement;
 use crate::dbs::{Action, Transaction};
+use crate::doc::CursorDoc;
 use crate::doc::Document;
 use crate::err::Error;
+use crate::sql::paths::SC;
+use crate::sql::paths::SD;
+use crate::sql::paths::TK;
+use crate::sql::permission::Permission;
 use crate::sql::Value;
+use std::sync::Arc;
 
 impl<'a> Document<'a> {
 	pub async fn lives(
@@ -29,47 +35,71 @@ impl<'a> Document<'a> {
 			for lv in self.lv(opt, txn).await?.iter() {
 				// Create a new statement
 				let lq = Statement::from(lv);
+				// Use the session which registered the live query
+				let mut ctx = Context::new(ctx);
+				if let Some(sess) = &lv.session {
+					ctx.add_value("auth", sess.pick(SD.as_ref()));
+					ctx.add_value("scope", sess.pick(SC.as_ref()));
+					ctx.add_value("token", sess.pick(TK.as_ref()));
+					ctx.add_value("session", sess);
+				}
+				let opt = &match &lv.auth {
+					Some(au) => opt.new_with_perms(true).with_auth(Arc::new(au.clone())),
+					None => opt.clone(),
+				};
+				let ctx = &ctx;
 				// Check LIVE SELECT where condition
 				if self.check(ctx, opt, txn, &lq).await.is_err() {
 					continue;
 				}
+				// Check the table SELECT permissions
+				let doc = match stm.is_delete() {
+					true => &self.initial,
+					false => &self.current,
+				};
+				if !self.lq_allow(ctx, opt, txn, doc).await? {
+					continue;
+				}
 				// Check what type of data change this is
 				if stm.is_delete() {
 					// Send a DELETE notification
+					let thing = (*rid).clone();
+					let notification = Notification {
+						id: lv.id.clone(),
+						action: Action::Delete,
+						result: Value::Thing(thing),
+					};
 					if opt.id()? == lv.node.0 {
-						let thing = (*rid).clone();
-						chn.send(Notification {
-							id: lv.id.clone(),
-							action: Action::Delete,
-							result: Value::Thing(thing),
-						})
-						.await?;
+						chn.send(notification).await?;
 					} else {
-						// TODO: Send to storage
+						// Queue the notification for the remote node
+						txn.lock().await.set_nt(&lv.node.0, notification).await?;
 					}
 				} else if self.is_new() {
 					// Send a CREATE notification
+					let notification = Notification {
+						id: lv.id.clone(),
+						action: Action::Create,
+						result: self.pluck(ctx, opt, txn, &lq).await?,
+					};
 					if opt.id()? == lv.node.0 {
-						chn.send(Notification {
-							id: lv.id.clone(),
-							action: Action::Create,
-							result: self.pluck(ctx, opt, txn, &lq).await?,
-						})
-						.await?;
+						chn.send(notification).await?;
 					} else {
-						// TODO: Send to storage
+						// Queue the notification for the remote node
+						txn.lock().await.set_nt(&lv.node.0, notification).await?;
 					}
 				} else {
 					// Send a UPDATE notification
+					let notification = Notification {
+						id: lv.id.clone(),
+						action: Action::Update,
+						result: self.pluck(ctx, opt, txn, &lq).await?,
+					};
 					if opt.id()? == lv.node.0 {
-						chn.send(Notification {
-							id: lv.id.clone(),
-							action: Action::Update,
-							result: self.pluck(ctx, opt, txn, &lq).await?,
-						})
-						.await?;
+						chn.send(notification).await?;
 					} else {
-						// TODO: Send to storage
+						// Queue the notification for the remote node
+						txn.lock().await.set_nt(&lv.node.0, notification).await?;
 					}
 				};
 			}
@@ -77,4 +107,32 @@ impl<'a> Document<'a> {
 		// Carry on
 		Ok(())
 	}
+	/// Check whether the session which registered a
+	/// live query is permitted to select this record.
+	async fn lq_allow(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: &CursorDoc<'_>,
+	) -> Result<bool, Error> {
+		// Should we run permissions checks?
+		if opt.perms && opt.auth.perms() {
+			// Get the table
+			let tb = self.tb(opt, txn).await?;
+			// Process the table permissions
+			match &tb.permissions.select {
+				Permission::None => return Ok(false),
+				Permission::Full => return Ok(true),
+				Permission::Specific(e) => {
+					// Disable permissions
+					let opt = &opt.new_with_perms(false);
+					// Process the PERMISSION clause
+					return Ok(e.compute(ctx, opt, txn, Some(doc)).await?.is_truthy());
+				}
+			}
+		}
+		// Carry on
+		Ok(true)
+	}
 }
@@ -3,6 +3,7 @@
 //! - `Thing`: name of the table and ID of the record
 //! - `current`: value after the transaction
 //! - `initial`: value before the transaction
+//! - `expired`: expired value which is replaced by the transaction
 //! - `id`: traditionally an integer but can be an object or collection such as an array
 pub(crate) use self::document::*;
 
@@ -28,6 +29,7 @@ mod empty; // Checks whether the specified document actually exists
 mod erase; // Removes all content and field data for this document
 mod event; // Processes any table events relevant for this document
 mod exist; // Checks whether the specified document actually exists
+mod expire; // Checks whether the specified document has expired
 mod field; // Processes any schema-defined fields for this document
 mod index; // Attempts to store the index data for this document
 mod lives; // Processes any live queries relevant for this document
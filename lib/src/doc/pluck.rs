@@ -1,9 +1,11 @@
 use crate::ctx::Context;
 use crate::dbs::Statement;
 use crate::dbs::{Options, Transaction};
+use crate::doc::CursorDoc;
 use crate::doc::Document;
 use crate::err::Error;
 use crate::sql::idiom::Idiom;
+use crate::sql::operation::{Op, Operation};
 use crate::sql::output::Output;
 use crate::sql::paths::META;
 use crate::sql::permission::Permission;
@@ -34,8 +36,10 @@ impl<'a> Document<'a> {
 				Output::Fields(v) => v.compute(ctx, opt, txn, Some(&self.current), false).await,
 			},
 			None => match stm {
+				// Live queries project fields like a SELECT statement without a GROUP BY
+				// clause, so aggregates are read from the records of a table view
 				Statement::Live(s) => match s.expr.len() {
-					0 => Ok(self.initial.doc.diff(&self.current.doc, Idiom::default()).into()),
+					0 => return Ok(self.patch(ctx, opt, txn).await?.into()),
 					_ => s.expr.compute(ctx, opt, txn, Some(&self.current), false).await,
 				},
 				Statement::Select(s) => {
@@ -56,6 +60,62 @@ impl<'a> Document<'a> {
 				_ => Err(Error::Ignore),
 			},
 		}?;
+		// Process the field permissions
+		self.reduce(ctx, opt, txn, &self.current, &mut out).await?;
+		// Remove metadata fields on output
+		out.del(ctx, opt, txn, &*META).await?;
+		// Fetch any record links for live queries
+		if let Statement::Live(s) = stm {
+			if let Some(fetchs) = &s.fetch {
+				for fetch in fetchs.iter() {
+					out.fetch(ctx, opt, txn, fetch).await?;
+				}
+			}
+		}
+		// Output result
+		Ok(out)
+	}
+	/// Compute the changes to this document as RFC 6902 JSON Patch operations,
+	/// including only the fields which the current session is permitted to select.
+	/// A newly created document is patched from an empty object.
+	pub(super) async fn patch(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Vec<Operation>, Error> {
+		// Process the initial document
+		let mut initial = match self.is_new() {
+			true => Value::base(),
+			false => self.initial.doc.as_ref().clone(),
+		};
+		self.reduce(ctx, opt, txn, &self.initial, &mut initial).await?;
+		initial.del(ctx, opt, txn, &*META).await?;
+		// Process the current document
+		let mut current = self.current.doc.as_ref().clone();
+		self.reduce(ctx, opt, txn, &self.current, &mut current).await?;
+		current.del(ctx, opt, txn, &*META).await?;
+		// Compute the changes
+		let mut ops = initial.diff(&current, Idiom::default());
+		// Text changes are sent as plain replacements
+		for op in ops.iter_mut() {
+			if op.op == Op::Change {
+				op.op = Op::Replace;
+				op.value = current.pick(&op.path);
+			}
+		}
+		Ok(ops)
+	}
+	/// Remove the fields of an output value which the
+	/// current session is not permitted to select.
+	async fn reduce(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: &CursorDoc<'_>,
+		out: &mut Value,
+	) -> Result<(), Error> {
 		// Check if this record exists
 		if self.id.is_some() {
 			// Should we run permissions checks?
@@ -72,13 +132,13 @@ impl<'a> Document<'a> {
 								// Disable permissions
 								let opt = &opt.new_with_perms(false);
 								// Get the current value
-								let val = self.current.doc.pick(k);
+								let val = doc.doc.pick(k);
 								// Configure the context
 								let mut ctx = Context::new(ctx);
 								ctx.add_value("value", &val);
 								// Process the PERMISSION clause
 								if !e
-									.compute(&ctx, opt, txn, Some(&self.current))
+									.compute(&ctx, opt, txn, Some(doc))
 									.await?
 									.is_truthy()
 								{
@@ -90,9 +150,7 @@ impl<'a> Document<'a> {
 				}
 			}
 		}
-		// Remove metadata fields on output
-		out.del(ctx, opt, txn, &*META).await?;
-		// Output result
-		Ok(out)
+		// Carry on
+		Ok(())
 	}
 }
@@ -3,11 +3,13 @@ use crate::dbs::Statement;
 use crate::dbs::{Options, Transaction};
 use crate::doc::Document;
 use crate::err::Error;
+use crate::kvs::Stats;
 use crate::sql::dir::Dir;
 use crate::sql::edges::Edges;
 use crate::sql::paths::EDGE;
 use crate::sql::paths::IN;
 use crate::sql::paths::OUT;
+use crate::sql::serde::serialized_size;
 use crate::sql::statements::DeleteStatement;
 use crate::sql::table::Tables;
 use crate::sql::value::{Value, Values};
@@ -24,6 +26,11 @@ impl<'a> Document<'a> {
 		if !opt.force && !self.changed() {
 			return Ok(());
 		}
+		// Check if the records in the table expire
+		let ttl = match self.id {
+			Some(_) => self.tb(opt, txn).await?.ttl.is_some(),
+			None => false,
+		};
 		// Clone transaction
 		let run = txn.clone();
 		// Claim transaction
@@ -32,7 +39,21 @@ impl<'a> Document<'a> {
 		if let Some(rid) = self.id {
 			// Purge the record data
 			let key = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+			let base = run.tb_base(opt.ns(), opt.db(), &rid.tb).await?;
+			let key = base.key(key);
+			// Update the storage statistics of the table
+			let old = serialized_size(self.initial.doc.as_ref())?;
+			run.record_stats(
+				opt.ns(),
+				opt.db(),
+				&rid.tb,
+				Stats::record(key.len(), Some(old), None),
+			);
 			run.del(key).await?;
+			// Purge the record expiry
+			if ttl {
+				run.set_expiry(opt.ns(), opt.db(), &rid.tb, &rid.id, None).await?;
+			}
 			// Purge the record edges
 			match (
 				self.initial.doc.pick(&*EDGE),
@@ -42,18 +63,21 @@ impl<'a> Document<'a> {
 				(Value::Bool(true), Value::Thing(ref l), Value::Thing(ref r)) => {
 					// Get temporary edge references
 					let (ref o, ref i) = (Dir::Out, Dir::In);
+					// Fetch the bases under which the edges are stored
+					let lb = run.tb_base(opt.ns(), opt.db(), &l.tb).await?;
+					let rb = run.tb_base(opt.ns(), opt.db(), &r.tb).await?;
 					// Purge the left pointer edge
 					let key = crate::key::graph::new(opt.ns(), opt.db(), &l.tb, &l.id, o, rid);
-					run.del(key).await?;
+					run.del(lb.key(key)).await?;
 					// Purge the left inner edge
 					let key = crate::key::graph::new(opt.ns(), opt.db(), &rid.tb, &rid.id, i, l);
-					run.del(key).await?;
+					run.del(base.key(key)).await?;
 					// Purge the right inner edge
 					let key = crate::key::graph::new(opt.ns(), opt.db(), &rid.tb, &rid.id, o, r);
-					run.del(key).await?;
+					run.del(base.key(key)).await?;
 					// Purge the right pointer edge
 					let key = crate::key::graph::new(opt.ns(), opt.db(), &r.tb, &r.id, i, rid);
-					run.del(key).await?;
+					run.del(rb.key(key)).await?;
 				}
 				_ => {
 					// Release the transaction
@@ -7,7 +7,7 @@ use crate::sql::value::Value;
 
 impl<'a> Document<'a> {
 	pub async fn select(
-		&self,
+		&mut self,
 		ctx: &Context<'_>,
 		opt: &Options,
 		txn: &Transaction,
@@ -15,6 +15,8 @@ impl<'a> Document<'a> {
 	) -> Result<Value, Error> {
 		// Check if record exists
 		self.empty(ctx, opt, txn, stm).await?;
+		// Check if record has expired
+		self.expire(ctx, opt, txn, stm).await?;
 		// Check where clause
 		self.check(ctx, opt, txn, stm).await?;
 		// Check if allowed
@@ -3,6 +3,8 @@ use crate::dbs::Statement;
 use crate::dbs::{Options, Transaction};
 use crate::doc::Document;
 use crate::err::Error;
+use crate::kvs::Stats;
+use crate::sql::serde::serialized_size;
 
 impl<'a> Document<'a> {
 	pub async fn store(
@@ -16,8 +18,10 @@ impl<'a> Document<'a> {
 		if !opt.force && !self.changed() {
 			return Ok(());
 		}
+		// Get the table definition
+		let tb = self.tb(opt, txn).await?;
 		// Check if the table is a view
-		if self.tb(opt, txn).await?.drop {
+		if tb.drop {
 			return Ok(());
 		}
 		// Claim transaction
@@ -26,7 +30,27 @@ impl<'a> Document<'a> {
 		let rid = self.id.as_ref().unwrap();
 		// Store the record data
 		let key = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
-		run.set(key, self).await?;
+		let base = run.tb_base(opt.ns(), opt.db(), &rid.tb).await?;
+		let key = base.key(key);
+		let val: Vec<u8> = self.into();
+		// Update the storage statistics of the table, counting any expired data as replaced
+		let old = match (self.is_new(), &self.expired) {
+			(true, None) => None,
+			(true, Some(expired)) => Some(serialized_size(expired.doc.as_ref())?),
+			(false, _) => Some(serialized_size(self.initial.doc.as_ref())?),
+		};
+		let st = Stats::record(key.len(), old, Some(val.len() as u64));
+		// Check the quotas of the namespace and database
+		run.check_quota(opt.ns(), opt.db(), &st).await?;
+		run.record_stats(opt.ns(), opt.db(), &rid.tb, st);
+		run.set(key, val).await?;
+		// Schedule the expiry of the record, unless it is only being rewritten
+		if let Some(ttl) = &tb.ttl {
+			if self.is_new() || !opt.force {
+				let at = run.clock().value / 1000 + ttl.as_secs();
+				run.set_expiry(opt.ns(), opt.db(), &rid.tb, &rid.id, Some(at)).await?;
+			}
+		}
 		// Carry on
 		Ok(())
 	}
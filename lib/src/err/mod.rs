@@ -55,6 +55,20 @@ pub enum Error {
 	#[error("The key being inserted already exists")]
 	TxKeyAlreadyExists,
 
+	/// The transaction conflicted with another transaction, and can be retried
+	#[error("Failed to commit transaction due to a read or write conflict. This transaction can be retried")]
+	TxRetryable,
+
+	/// The savepoint was used outside of a transaction
+	#[error("Savepoints can only be used within a transaction")]
+	TxSavepointOutsideTransaction,
+
+	/// The specified savepoint does not exist in the transaction
+	#[error("The savepoint '{name}' does not exist")]
+	TxSavepointNotFound {
+		name: String,
+	},
+
 	/// The key exceeds a limit set by the KV store
 	#[error("Record id or key is too large")]
 	TxKeyTooLarge,
@@ -67,6 +81,22 @@ pub enum Error {
 	#[error("Transaction is too large")]
 	TxTooLarge,
 
+	/// There was an error when encrypting or decrypting a value
+	#[error("There was a problem with datastore encryption: {0}")]
+	Encryption(String),
+
+	/// The datastore was encrypted with a different encryption key
+	#[error("The datastore is encrypted with a different encryption key")]
+	EncryptionKeyInvalid,
+
+	/// The datastore is encrypted, but no encryption key was specified
+	#[error("The datastore is encrypted, but no encryption key was specified")]
+	EncryptionKeyMissing,
+
+	/// There was a problem with a datastore backup
+	#[error("There was a problem with the backup: {0}")]
+	Backup(String),
+
 	/// No namespace has been selected
 	#[error("Specify a namespace to use")]
 	NsEmpty,
@@ -207,6 +237,12 @@ pub enum Error {
 		value: String,
 	},
 
+	/// The requested namespace already exists
+	#[error("The namespace '{value}' already exists")]
+	NsAlreadyExists {
+		value: String,
+	},
+
 	/// The requested namespace token does not exist
 	#[error("The namespace token '{value}' does not exist")]
 	NtNotFound {
@@ -225,6 +261,12 @@ pub enum Error {
 		value: String,
 	},
 
+	/// The requested database already exists
+	#[error("The database '{value}' already exists")]
+	DbAlreadyExists {
+		value: String,
+	},
+
 	/// The requested database token does not exist
 	#[error("The database token '{value}' does not exist")]
 	DtNotFound {
@@ -261,12 +303,42 @@ pub enum Error {
 		value: String,
 	},
 
+	/// A node can not evict itself from the cluster
+	#[error("The node '{value}' can not evict itself from the cluster")]
+	NdEvictSelf {
+		value: String,
+	},
+
+	/// A node which is still sending heartbeats can only be evicted forcibly
+	#[error("The node '{value}' is still sending heartbeats, and can only be removed with FORCE")]
+	NdAlive {
+		value: String,
+	},
+
+	/// The node was removed from the cluster after it stopped sending heartbeats
+	#[error("The node '{value}' was removed from the cluster, and its live queries were archived")]
+	NdRemoved {
+		value: String,
+	},
+
+	/// The lease of a cluster-wide background job was taken over by another node
+	#[error("The lease of the background job '{value}' is held by another node")]
+	LdNotHeld {
+		value: String,
+	},
+
 	/// The requested scope token does not exist
 	#[error("The scope token '{value}' does not exist")]
 	StNotFound {
 		value: String,
 	},
 
+	/// The requested job does not exist
+	#[error("The job '{value}' does not exist")]
+	JbNotFound {
+		value: String,
+	},
+
 	/// The requested param does not exist
 	#[error("The param '${value}' does not exist")]
 	PaNotFound {
@@ -279,6 +351,27 @@ pub enum Error {
 		value: String,
 	},
 
+	/// The requested table already exists
+	#[error("The table '{value}' already exists")]
+	TbAlreadyExists {
+		value: String,
+	},
+
+	/// A rename which moves its data in batches was interrupted before it completed
+	#[error("The rename of '{name}' to '{to}' was interrupted, and has to be completed by running it again")]
+	RenameIncomplete {
+		name: String,
+		to: String,
+	},
+
+	/// The write would exceed the quota of a namespace or database
+	#[error("The {level} '{name}' has reached its limit of {limit}")]
+	QuotaExceeded {
+		level: String,
+		name: String,
+		limit: String,
+	},
+
 	/// The requested live query does not exist
 	#[error("The live query '{value}' does not exist")]
 	LvNotFound {
@@ -303,6 +396,18 @@ pub enum Error {
 		value: String,
 	},
 
+	/// The requested change feed consumer does not exist
+	#[error("The consumer '{value}' does not exist")]
+	CoNotFound {
+		value: String,
+	},
+
+	/// The table does not record a change feed
+	#[error("The table '{value}' does not have a change feed")]
+	CfNotEnabled {
+		value: String,
+	},
+
 	/// Unable to perform the realtime query
 	#[error("Unable to perform the realtime query")]
 	RealtimeDisabled,
@@ -532,6 +637,13 @@ pub enum Error {
 	CorruptedVersionstampInKey(#[from] VersionstampError),
 }
 
+impl Error {
+	/// Check if this error was caused by a conflict with another transaction
+	pub fn is_retryable(&self) -> bool {
+		matches!(self, Error::TxRetryable)
+	}
+}
+
 impl From<Error> for String {
 	fn from(e: Error) -> String {
 		e.to_string()
@@ -579,6 +691,10 @@ impl From<tikv::Error> for Error {
 				abort,
 				..
 			}) if abort.contains("KeyTooLarge") => Error::TxKeyTooLarge,
+			tikv::Error::KeyError(tikv_client_proto::kvrpcpb::KeyError {
+				conflict,
+				..
+			}) if conflict.is_some() => Error::TxRetryable,
 			tikv::Error::RegionError(tikv_client_proto::errorpb::Error {
 				raft_entry_too_large,
 				..
@@ -591,13 +707,54 @@ impl From<tikv::Error> for Error {
 #[cfg(feature = "kv-speedb")]
 impl From<speedb::Error> for Error {
 	fn from(e: speedb::Error) -> Error {
-		Error::Tx(e.to_string())
+		match e.kind() {
+			speedb::ErrorKind::Busy | speedb::ErrorKind::TryAgain => Error::TxRetryable,
+			_ => Error::Tx(e.to_string()),
+		}
 	}
 }
 
 #[cfg(feature = "kv-rocksdb")]
 impl From<rocksdb::Error> for Error {
 	fn from(e: rocksdb::Error) -> Error {
+		match e.kind() {
+			rocksdb::ErrorKind::Busy | rocksdb::ErrorKind::TryAgain => Error::TxRetryable,
+			_ => Error::Tx(e.to_string()),
+		}
+	}
+}
+
+#[cfg(feature = "kv-redb")]
+impl From<redb::DatabaseError> for Error {
+	fn from(e: redb::DatabaseError) -> Error {
+		Error::Tx(e.to_string())
+	}
+}
+
+#[cfg(feature = "kv-redb")]
+impl From<redb::TransactionError> for Error {
+	fn from(e: redb::TransactionError) -> Error {
+		Error::Tx(e.to_string())
+	}
+}
+
+#[cfg(feature = "kv-redb")]
+impl From<redb::TableError> for Error {
+	fn from(e: redb::TableError) -> Error {
+		Error::Tx(e.to_string())
+	}
+}
+
+#[cfg(feature = "kv-redb")]
+impl From<redb::StorageError> for Error {
+	fn from(e: redb::StorageError) -> Error {
+		Error::Tx(e.to_string())
+	}
+}
+
+#[cfg(feature = "kv-redb")]
+impl From<redb::CommitError> for Error {
+	fn from(e: redb::CommitError) -> Error {
 		Error::Tx(e.to_string())
 	}
 }
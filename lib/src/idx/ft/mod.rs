@@ -70,6 +70,16 @@ pub(crate) struct Statistics {
 	postings: btree::Statistics,
 }
 
+impl Statistics {
+	/// The total size of the nodes of each of the trees, in bytes
+	pub(crate) fn total_size(&self) -> u64 {
+		self.doc_ids.total_size
+			+ self.terms.total_size
+			+ self.doc_lengths.total_size
+			+ self.postings.total_size
+	}
+}
+
 impl From<Statistics> for Value {
 	fn from(stats: Statistics) -> Self {
 		let mut res = Object::default();
@@ -8,6 +8,7 @@ use crate::err::Error;
 use crate::idx::btree::NodeId;
 use crate::idx::ft::docids::DocId;
 use crate::idx::ft::terms::TermId;
+use crate::key::data::Base;
 use crate::key::index::bc::Bc;
 use crate::key::index::bd::Bd;
 use crate::key::index::bf::Bf;
@@ -37,140 +38,131 @@ struct Inner {
 	db: String,
 	tb: String,
 	ix: String,
+	base: Base,
 }
 
 impl IndexKeyBase {
-	pub(crate) fn new(opt: &Options, ix: &DefineIndexStatement) -> Self {
+	pub(crate) fn new(opt: &Options, ix: &DefineIndexStatement, base: Base) -> Self {
 		Self {
 			inner: Arc::new(Inner {
 				ns: opt.ns().to_string(),
 				db: opt.db().to_string(),
 				tb: ix.what.to_string(),
 				ix: ix.name.to_string(),
+				base,
 			}),
 		}
 	}
 
 	fn new_bc_key(&self, term_id: TermId) -> Key {
-		Bc::new(
+		self.inner.base.key(Bc::new(
 			self.inner.ns.as_str(),
 			self.inner.db.as_str(),
 			self.inner.tb.as_str(),
 			self.inner.ix.as_str(),
 			term_id,
-		)
-		.into()
+		))
 	}
 
 	fn new_bd_key(&self, node_id: Option<NodeId>) -> Key {
-		Bd::new(
+		self.inner.base.key(Bd::new(
 			self.inner.ns.as_str(),
 			self.inner.db.as_str(),
 			self.inner.tb.as_str(),
 			self.inner.ix.as_str(),
 			node_id,
-		)
-		.into()
+		))
 	}
 
 	fn new_bi_key(&self, doc_id: DocId) -> Key {
-		Bi::new(
+		self.inner.base.key(Bi::new(
 			self.inner.ns.as_str(),
 			self.inner.db.as_str(),
 			self.inner.tb.as_str(),
 			self.inner.ix.as_str(),
 			doc_id,
-		)
-		.into()
+		))
 	}
 
 	fn new_bk_key(&self, doc_id: DocId) -> Key {
-		Bk::new(
+		self.inner.base.key(Bk::new(
 			self.inner.ns.as_str(),
 			self.inner.db.as_str(),
 			self.inner.tb.as_str(),
 			self.inner.ix.as_str(),
 			doc_id,
-		)
-		.into()
+		))
 	}
 
 	fn new_bl_key(&self, node_id: Option<NodeId>) -> Key {
-		Bl::new(
+		self.inner.base.key(Bl::new(
 			self.inner.ns.as_str(),
 			self.inner.db.as_str(),
 			self.inner.tb.as_str(),
 			self.inner.ix.as_str(),
 			node_id,
-		)
-		.into()
+		))
 	}
 
 	fn new_bo_key(&self, doc_id: DocId, term_id: TermId) -> Key {
-		Bo::new(
+		self.inner.base.key(Bo::new(
 			self.inner.ns.as_str(),
 			self.inner.db.as_str(),
 			self.inner.tb.as_str(),
 			self.inner.ix.as_str(),
 			doc_id,
 			term_id,
-		)
-		.into()
+		))
 	}
 
 	fn new_bp_key(&self, node_id: Option<NodeId>) -> Key {
-		Bp::new(
+		self.inner.base.key(Bp::new(
 			self.inner.ns.as_str(),
 			self.inner.db.as_str(),
 			self.inner.tb.as_str(),
 			self.inner.ix.as_str(),
 			node_id,
-		)
-		.into()
+		))
 	}
 
 	fn new_bf_key(&self, term_id: TermId, doc_id: DocId) -> Key {
-		Bf::new(
+		self.inner.base.key(Bf::new(
 			self.inner.ns.as_str(),
 			self.inner.db.as_str(),
 			self.inner.tb.as_str(),
 			self.inner.ix.as_str(),
 			term_id,
 			doc_id,
-		)
-		.into()
+		))
 	}
 
 	fn new_bt_key(&self, node_id: Option<NodeId>) -> Key {
-		Bt::new(
+		self.inner.base.key(Bt::new(
 			self.inner.ns.as_str(),
 			self.inner.db.as_str(),
 			self.inner.tb.as_str(),
 			self.inner.ix.as_str(),
 			node_id,
-		)
-		.into()
+		))
 	}
 
 	fn new_bs_key(&self) -> Key {
-		Bs::new(
+		self.inner.base.key(Bs::new(
 			self.inner.ns.as_str(),
 			self.inner.db.as_str(),
 			self.inner.tb.as_str(),
 			self.inner.ix.as_str(),
-		)
-		.into()
+		))
 	}
 
 	fn new_bu_key(&self, term_id: TermId) -> Key {
-		Bu::new(
+		self.inner.base.key(Bu::new(
 			self.inner.ns.as_str(),
 			self.inner.db.as_str(),
 			self.inner.tb.as_str(),
 			self.inner.ix.as_str(),
 			term_id,
-		)
-		.into()
+		))
 	}
 }
 
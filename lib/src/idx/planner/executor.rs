@@ -12,6 +12,7 @@ use crate::idx::planner::iterators::{
 use crate::idx::planner::plan::IndexOption;
 use crate::idx::planner::tree::IndexMap;
 use crate::idx::IndexKeyBase;
+use crate::key::data::Base;
 use crate::kvs;
 use crate::kvs::Key;
 use crate::sql::index::Index;
@@ -60,7 +61,8 @@ impl QueryExecutor {
 						entry = FtEntry::new(&mut run, ft, io).await?;
 					}
 				} else {
-					let ikb = IndexKeyBase::new(opt, io.ix());
+					let base = run.ix_base(opt.ns(), opt.db(), io.ix()).await?;
+					let ikb = IndexKeyBase::new(opt, io.ix(), base);
 					let az = run.get_az(opt.ns(), opt.db(), az.as_str()).await?;
 					let ft = FtIndex::new(&mut run, az, ikb, *order, sc, *hl, BTreeStoreType::Read)
 						.await?;
@@ -119,22 +121,34 @@ impl QueryExecutor {
 	pub(crate) async fn new_iterator(
 		&self,
 		opt: &Options,
+		txn: &Transaction,
 		ir: IteratorRef,
 		io: IndexOption,
 	) -> Result<Option<ThingIterator>, Error> {
 		match &io.ix().index {
-			Index::Idx => Self::new_index_iterator(opt, io),
-			Index::Uniq => Self::new_unique_index_iterator(opt, io),
+			Index::Idx => {
+				let base = txn.lock().await.ix_base(opt.ns(), opt.db(), io.ix()).await?;
+				Self::new_index_iterator(opt, &base, io)
+			}
+			Index::Uniq => {
+				let base = txn.lock().await.ix_base(opt.ns(), opt.db(), io.ix()).await?;
+				Self::new_unique_index_iterator(opt, &base, io)
+			}
 			Index::Search {
 				..
 			} => self.new_search_index_iterator(ir, io).await,
 		}
 	}
 
-	fn new_index_iterator(opt: &Options, io: IndexOption) -> Result<Option<ThingIterator>, Error> {
+	fn new_index_iterator(
+		opt: &Options,
+		base: &Base,
+		io: IndexOption,
+	) -> Result<Option<ThingIterator>, Error> {
 		if io.op() == &Operator::Equal {
 			return Ok(Some(ThingIterator::NonUniqueEqual(NonUniqueEqualThingIterator::new(
 				opt,
+				base,
 				io.ix(),
 				io.value(),
 			)?)));
@@ -144,11 +158,13 @@ impl QueryExecutor {
 
 	fn new_unique_index_iterator(
 		opt: &Options,
+		base: &Base,
 		io: IndexOption,
 	) -> Result<Option<ThingIterator>, Error> {
 		if io.op() == &Operator::Equal {
 			return Ok(Some(ThingIterator::UniqueEqual(UniqueEqualThingIterator::new(
 				opt,
+				base,
 				io.ix(),
 				io.value(),
 			)?)));
@@ -4,6 +4,7 @@ use crate::idx::ft::docids::{DocId, NO_DOC_ID};
 use crate::idx::ft::termdocs::TermsDocs;
 use crate::idx::ft::{FtIndex, HitsIterator};
 use crate::key;
+use crate::key::data::Base;
 use crate::kvs::Key;
 use crate::sql::statements::DefineIndexStatement;
 use crate::sql::{Array, Thing, Value};
@@ -36,6 +37,7 @@ pub(crate) struct NonUniqueEqualThingIterator {
 impl NonUniqueEqualThingIterator {
 	pub(super) fn new(
 		opt: &Options,
+		base: &Base,
 		ix: &DefineIndexStatement,
 		v: &Value,
 	) -> Result<NonUniqueEqualThingIterator, Error> {
@@ -43,8 +45,8 @@ impl NonUniqueEqualThingIterator {
 		let (beg, end) =
 			key::index::Index::range_all_ids(opt.ns(), opt.db(), &ix.what, &ix.name, &v);
 		Ok(Self {
-			beg,
-			end,
+			beg: base.key(beg),
+			end: base.key(end),
 		})
 	}
 
@@ -70,9 +72,14 @@ pub(crate) struct UniqueEqualThingIterator {
 }
 
 impl UniqueEqualThingIterator {
-	pub(super) fn new(opt: &Options, ix: &DefineIndexStatement, v: &Value) -> Result<Self, Error> {
+	pub(super) fn new(
+		opt: &Options,
+		base: &Base,
+		ix: &DefineIndexStatement,
+		v: &Value,
+	) -> Result<Self, Error> {
 		let v = Array::from(v.clone());
-		let key = key::index::Index::new(opt.ns(), opt.db(), &ix.what, &ix.name, v, None).into();
+		let key = base.key(key::index::Index::new(opt.ns(), opt.db(), &ix.what, &ix.name, v, None));
 		Ok(Self {
 			key: Some(key),
 		})
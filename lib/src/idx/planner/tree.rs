@@ -49,14 +49,19 @@ struct TreeBuilder<'a> {
 impl<'a> TreeBuilder<'a> {
 	async fn find_index(&mut self, i: &Idiom) -> Result<Option<DefineIndexStatement>, Error> {
 		if self.indexes.is_none() {
-			let indexes = self
-				.txn
-				.clone()
-				.lock()
-				.await
-				.all_ix(self.opt.ns(), self.opt.db(), &self.table.0)
-				.await?;
-			self.indexes = Some(indexes);
+			let (ns, db, tb) = (self.opt.ns(), self.opt.db(), &self.table.0);
+			let mut run = self.txn.lock().await;
+			let mut indexes = vec![];
+			for ix in run.all_ix(ns, db, tb).await?.iter() {
+				// An index which is still being built in the background is not used
+				if ix.concurrently
+					&& run.exi(crate::key::index::ib::new(ns, db, tb, &ix.name)).await?
+				{
+					continue;
+				}
+				indexes.push(ix.clone());
+			}
+			self.indexes = Some(indexes.into());
 		}
 		if let Some(indexes) = &self.indexes {
 			for ix in indexes.as_ref() {
@@ -0,0 +1,44 @@
+//! Stores the key prefix for all data within a database, by namespace and database id
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Db {
+	__: u8,
+	_a: u8,
+	pub ns: u32,
+	pub db: u32,
+}
+
+pub fn new(ns: u32, db: u32) -> Db {
+	Db::new(ns, db)
+}
+
+impl Db {
+	pub fn new(ns: u32, db: u32) -> Self {
+		Self {
+			__: b'/',
+			_a: b'&',
+			ns,
+			db,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Db::new(
+			1,
+			2,
+		);
+		let enc = Db::encode(&val).unwrap();
+		assert_eq!(enc, b"/&\0\0\0\x01\0\0\0\x02");
+
+		let dec = Db::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,52 @@
+//! Stores the key prefix for all data within an index, by namespace, database, table and index id
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Ix {
+	__: u8,
+	_a: u8,
+	pub ns: u32,
+	pub db: u32,
+	pub tb: u32,
+	_b: u8,
+	pub ix: u32,
+}
+
+pub fn new(ns: u32, db: u32, tb: u32, ix: u32) -> Ix {
+	Ix::new(ns, db, tb, ix)
+}
+
+impl Ix {
+	pub fn new(ns: u32, db: u32, tb: u32, ix: u32) -> Self {
+		Self {
+			__: b'/',
+			_a: b'&',
+			ns,
+			db,
+			tb,
+			_b: b'+',
+			ix,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Ix::new(
+			1,
+			2,
+			3,
+			4,
+		);
+		let enc = Ix::encode(&val).unwrap();
+		assert_eq!(enc, b"/&\0\0\0\x01\0\0\0\x02\0\0\0\x03+\0\0\0\x04");
+
+		let dec = Ix::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,117 @@
+//! Stores the data of tables and indexes by numeric id
+//!
+//! In the original key format, the records, graph edges and index entries
+//! of a table are stored under a prefix which contains the names of the
+//! namespace, database and table, and of the index. In the compact key
+//! format, these keys are stored under a prefix which contains the numeric
+//! ids assigned to those definitions instead.
+//!
+//! The data keys are always built with the named key types, such as
+//! [`crate::key::thing`], and are then rebased onto the prefix which is used
+//! by the key format of the datastore, using a [`Base`].
+pub mod db;
+pub mod ix;
+pub mod ns;
+pub mod tb;
+
+use crate::kvs::Key;
+use std::ops::Range;
+
+/// The base of the keys which store the data of a table, or of an index
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Base {
+	/// The prefix of the keys in the named key format
+	named: Key,
+	/// The prefix under which the keys are actually stored
+	stored: Key,
+}
+
+impl Base {
+	/// Create a base which stores keys in the named key format
+	pub fn named(named: Key) -> Self {
+		Self {
+			stored: named.clone(),
+			named,
+		}
+	}
+
+	/// Create a base which stores keys under a different prefix
+	pub fn new(named: Key, stored: Key) -> Self {
+		Self {
+			named,
+			stored,
+		}
+	}
+
+	/// Check if keys are stored under a different prefix than their named prefix
+	pub fn is_compact(&self) -> bool {
+		self.named != self.stored
+	}
+
+	/// The prefix under which all of the keys are stored
+	pub fn prefix(&self) -> Key {
+		self.stored.clone()
+	}
+
+	/// Convert a key in the named key format into the key which is stored
+	pub fn key<K>(&self, key: K) -> Key
+	where
+		K: Into<Key>,
+	{
+		Self::rebase(key.into(), &self.named, &self.stored)
+	}
+
+	/// Convert a range of keys in the named key format into the range which is stored
+	pub fn range<K>(&self, rng: Range<K>) -> Range<Key>
+	where
+		K: Into<Key>,
+	{
+		self.key(rng.start)..self.key(rng.end)
+	}
+
+	/// Convert a stored key back into the named key format, so that it can be decoded
+	pub fn name<K>(&self, key: K) -> Key
+	where
+		K: Into<Key>,
+	{
+		Self::rebase(key.into(), &self.stored, &self.named)
+	}
+
+	fn rebase(key: Key, from: &[u8], to: &[u8]) -> Key {
+		if from == to {
+			return key;
+		}
+		match key.strip_prefix(from) {
+			Some(rest) => [to, rest].concat(),
+			None => key,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::Id;
+
+	#[test]
+	fn rebase() {
+		let named = crate::key::table::all::new("testns", "testdb", "testtb").encode().unwrap();
+		let stored = tb::new(1, 2, 3).encode().unwrap();
+		let base = Base::new(named, stored);
+		let key = base.key(crate::key::thing::new("testns", "testdb", "testtb", &Id::from("one")));
+		assert_eq!(key, b"/&\0\0\0\x01\0\0\0\x02\0\0\0\x03*\0\0\0\x01one\0");
+		let key = base.name(key);
+		let key: crate::key::thing::Thing = (&key).into();
+		assert_eq!(key.tb, "testtb");
+		assert_eq!(key.id, Id::from("one"));
+	}
+
+	#[test]
+	fn named() {
+		let named = crate::key::table::all::new("testns", "testdb", "testtb").encode().unwrap();
+		let base = Base::named(named);
+		assert!(!base.is_compact());
+		let key = crate::key::thing::new("testns", "testdb", "testtb", &Id::from("one"));
+		assert_eq!(base.key(key.clone()), Key::from(key));
+	}
+}
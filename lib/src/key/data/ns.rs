@@ -0,0 +1,41 @@
+//! Stores the key prefix for all data within a namespace, by namespace id
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Ns {
+	__: u8,
+	_a: u8,
+	pub ns: u32,
+}
+
+pub fn new(ns: u32) -> Ns {
+	Ns::new(ns)
+}
+
+impl Ns {
+	pub fn new(ns: u32) -> Self {
+		Self {
+			__: b'/',
+			_a: b'&',
+			ns,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Ns::new(
+			1,
+		);
+		let enc = Ns::encode(&val).unwrap();
+		assert_eq!(enc, b"/&\0\0\0\x01");
+
+		let dec = Ns::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
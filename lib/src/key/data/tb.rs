@@ -0,0 +1,47 @@
+//! Stores the key prefix for all data within a table, by namespace, database and table id
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Tb {
+	__: u8,
+	_a: u8,
+	pub ns: u32,
+	pub db: u32,
+	pub tb: u32,
+}
+
+pub fn new(ns: u32, db: u32, tb: u32) -> Tb {
+	Tb::new(ns, db, tb)
+}
+
+impl Tb {
+	pub fn new(ns: u32, db: u32, tb: u32) -> Self {
+		Self {
+			__: b'/',
+			_a: b'&',
+			ns,
+			db,
+			tb,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Tb::new(
+			1,
+			2,
+			3,
+		);
+		let enc = Tb::encode(&val).unwrap();
+		assert_eq!(enc, b"/&\0\0\0\x01\0\0\0\x02\0\0\0\x03");
+
+		let dec = Tb::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,67 @@
+//! Stores a DEFINE JOB config definition
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Jb<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	_d: u8,
+	_e: u8,
+	pub jb: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, jb: &'a str) -> Jb<'a> {
+	Jb::new(ns, db, jb)
+}
+
+pub fn prefix(ns: &str, db: &str) -> Vec<u8> {
+	let mut k = super::all::new(ns, db).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'j', b'b', 0x00]);
+	k
+}
+
+pub fn suffix(ns: &str, db: &str) -> Vec<u8> {
+	let mut k = super::all::new(ns, db).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'j', b'b', 0xff]);
+	k
+}
+
+impl<'a> Jb<'a> {
+	pub fn new(ns: &'a str, db: &'a str, jb: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'!',
+			_d: b'j',
+			_e: b'b',
+			jb,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Jb::new(
+			"testns",
+			"testdb",
+			"testjb",
+		);
+		let enc = Jb::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0!jbtestjb\0");
+
+		let dec = Jb::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,67 @@
+//! Stores the outcome of the last run of a scheduled job
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Jr<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	_d: u8,
+	_e: u8,
+	pub jb: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, jb: &'a str) -> Jr<'a> {
+	Jr::new(ns, db, jb)
+}
+
+pub fn prefix(ns: &str, db: &str) -> Vec<u8> {
+	let mut k = super::all::new(ns, db).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'j', b'r', 0x00]);
+	k
+}
+
+pub fn suffix(ns: &str, db: &str) -> Vec<u8> {
+	let mut k = super::all::new(ns, db).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'j', b'r', 0xff]);
+	k
+}
+
+impl<'a> Jr<'a> {
+	pub fn new(ns: &'a str, db: &'a str, jb: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'!',
+			_d: b'j',
+			_e: b'r',
+			jb,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Jr::new(
+			"testns",
+			"testdb",
+			"testjb",
+		);
+		let enc = Jr::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0!jrtestjb\0");
+
+		let dec = Jr::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
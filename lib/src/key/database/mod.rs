@@ -1,9 +1,14 @@
 pub mod all;
 pub mod az;
 pub mod fc;
+pub mod jb;
+pub mod jr;
 pub mod lg;
 pub mod pa;
+pub mod rn;
 pub mod sc;
 pub mod tb;
+pub mod ti;
 pub mod tk;
+pub mod ts;
 pub mod vs;
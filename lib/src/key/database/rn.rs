@@ -0,0 +1,55 @@
+//! Stores the progress of a RENAME TABLE statement which is moving its data in batches
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Rn<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	_d: u8,
+	_e: u8,
+	pub tb: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str) -> Rn<'a> {
+	Rn::new(ns, db, tb)
+}
+
+impl<'a> Rn<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'!',
+			_d: b'r',
+			_e: b'n',
+			tb,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Rn::new(
+			"testns",
+			"testdb",
+			"testtb",
+		);
+		let enc = Rn::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0!rntesttb\0");
+
+		let dec = Rn::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,52 @@
+//! Stores the next table id within a database
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Ti<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	_d: u8,
+	_e: u8,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str) -> Ti<'a> {
+	Ti::new(ns, db)
+}
+
+impl<'a> Ti<'a> {
+	pub fn new(ns: &'a str, db: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'!',
+			_d: b't',
+			_e: b'i',
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Ti::new(
+			"testns",
+			"testdb",
+		);
+		let enc = Ti::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0!ti");
+
+		let dec = Ti::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -1,37 +1,58 @@
 //! How the keys are structured in the key value store
 ///
 /// crate::key::root::all                /
+/// crate::key::root::ek                 /!ek
+/// crate::key::root::es                 /!es
 /// crate::key::root::hb                 /!hb{ts}/{nd}
+/// crate::key::root::kf                 /!kf
+/// crate::key::root::ld                 /!ld{ld}
 /// crate::key::root::nd                 /!nd{nd}
+/// crate::key::root::ni                 /!ni
 /// crate::key::root::ns                 /!ns{ns}
+/// crate::key::root::rn                 /!rn{ns}
+/// crate::key::root::st                 /!st
 ///
 /// crate::key::node::all                /${nd}
 /// crate::key::node::lq                 /${nd}!lq{lq}{ns}{db}
+/// crate::key::node::nt                 /${nd}!nt{ts}{nt}
 ///
 /// crate::key::namespace::all           /*{ns}
 /// crate::key::namespace::db            /*{ns}!db{db}
+/// crate::key::namespace::di            /*{ns}!di
 /// crate::key::namespace::lg            /*{ns}!lg{lg}
+/// crate::key::namespace::rn            /*{ns}!rn{db}
 /// crate::key::namespace::tk            /*{ns}!tk{tk}
 ///
 /// crate::key::database::all            /*{ns}*{db}
 /// crate::key::database::az             /*{ns}*{db}!az{az}
 /// crate::key::database::fc             /*{ns}*{db}!fn{fc}
+/// crate::key::database::jb             /*{ns}*{db}!jb{jb}
+/// crate::key::database::jr             /*{ns}*{db}!jr{jb}
 /// crate::key::database::lg             /*{ns}*{db}!lg{lg}
 /// crate::key::database::pa             /*{ns}*{db}!pa{pa}
+/// crate::key::database::rn             /*{ns}*{db}!rn{tb}
 /// crate::key::database::sc             /*{ns}*{db}!sc{sc}
 /// crate::key::database::tb             /*{ns}*{db}!tb{tb}
+/// crate::key::database::ti             /*{ns}*{db}!ti
 /// crate::key::database::tk             /*{ns}*{db}!tk{tk}
+/// crate::key::database::ts             /*{ns}*{db}!ts{ts}
 /// crate::key::database::vs             /*{ns}*{db}!vs
 ///
 /// crate::key::scope::all               /*{ns}*{db}±{sc}
 /// crate::key::scope::tk                /*{ns}*{db}±{sc}!tk{tk}
 ///
 /// crate::key::table::all               /*{ns}*{db}*{tb}
+/// crate::key::table::co                /*{ns}*{db}*{tb}!co{co}
+/// crate::key::table::cp                /*{ns}*{db}*{tb}!cp{co}
+/// crate::key::table::er                /*{ns}*{db}*{tb}!er{id}
 /// crate::key::table::ev                /*{ns}*{db}*{tb}!ev{ev}
+/// crate::key::table::ex                /*{ns}*{db}*{tb}!ex{at}{id}
 /// crate::key::table::fd                /*{ns}*{db}*{tb}!fd{fd}
 /// crate::key::table::ft                /*{ns}*{db}*{tb}!ft{ft}
+/// crate::key::table::ii                /*{ns}*{db}*{tb}!ii
 /// crate::key::table::ix                /*{ns}*{db}*{tb}!ix{ix}
 /// crate::key::table::lq                /*{ns}*{db}*{tb}!lq{lq}
+/// crate::key::table::st                /*{ns}*{db}*{tb}!st{st}
 ///
 /// crate::key::index::all               /*{ns}*{db}*{tb}+{ix}
 /// crate::key::index::bc                /*{ns}*{db}*{tb}+{ix}!bc{id}
@@ -45,6 +66,7 @@
 /// crate::key::index::bs                /*{ns}*{db}*{tb}+{ix}!bs
 /// crate::key::index::bt                /*{ns}*{db}*{tb}+{ix}!bt{id}
 /// crate::key::index::bu                /*{ns}*{db}*{tb}+{ix}!bu{id}
+/// crate::key::index::ib                /*{ns}*{db}*{tb}+{ix}!ib
 /// crate::key::index                    /*{ns}*{db}*{tb}+{ix}*{fd}{id}
 ///
 /// crate::key::change                   /*{ns}*{db}#{ts}
@@ -53,7 +75,13 @@
 ///
 /// crate::key::graph                    /*{ns}*{db}*{tb}~{id}{eg}{fk}
 ///
+/// crate::key::data::ns                 /&{ns}
+/// crate::key::data::db                 /&{ns}{db}
+/// crate::key::data::tb                 /&{ns}{db}{tb}
+/// crate::key::data::ix                 /&{ns}{db}{tb}+{ix}
+///
 pub mod change;
+pub mod data;
 pub mod database;
 pub mod debug;
 pub mod graph;
@@ -0,0 +1,47 @@
+//! Stores the next database id within a namespace
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Di<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	_c: u8,
+	_d: u8,
+}
+
+pub fn new(ns: &str) -> Di<'_> {
+	Di::new(ns)
+}
+
+impl<'a> Di<'a> {
+	pub fn new(ns: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'!',
+			_c: b'd',
+			_d: b'i',
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Di::new(
+			"testns",
+		);
+		let enc = Di::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0!di");
+
+		let dec = Di::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -1,4 +1,6 @@
 pub mod all;
 pub mod db;
+pub mod di;
 pub mod lg;
+pub mod rn;
 pub mod tk;
@@ -0,0 +1,50 @@
+//! Stores the progress of a RENAME DATABASE statement which is moving its data in batches
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Rn<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	_c: u8,
+	_d: u8,
+	pub db: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str) -> Rn<'a> {
+	Rn::new(ns, db)
+}
+
+impl<'a> Rn<'a> {
+	pub fn new(ns: &'a str, db: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'!',
+			_c: b'r',
+			_d: b'n',
+			db,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Rn::new(
+			"testns",
+			"testdb",
+		);
+		let enc = Rn::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0!rntestdb\0");
+
+		let dec = Rn::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
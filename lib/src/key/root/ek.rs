@@ -0,0 +1,47 @@
+//! Stores the encryption key check value for the datastore
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Ek {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+}
+
+pub fn new() -> Ek {
+	Ek::new()
+}
+
+impl Default for Ek {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Ek {
+	pub fn new() -> Ek {
+		Ek {
+			__: b'/',
+			_a: b'!',
+			_b: b'e',
+			_c: b'k',
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Ek::new();
+		let enc = Ek::encode(&val).unwrap();
+		assert_eq!(enc, b"/!ek");
+
+		let dec = Ek::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
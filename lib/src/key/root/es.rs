@@ -0,0 +1,47 @@
+//! Stores the salt which the encryption keys of the datastore are derived with
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Es {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+}
+
+pub fn new() -> Es {
+	Es::new()
+}
+
+impl Default for Es {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Es {
+	pub fn new() -> Es {
+		Es {
+			__: b'/',
+			_a: b'!',
+			_b: b'e',
+			_c: b's',
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Es::new();
+		let enc = Es::encode(&val).unwrap();
+		assert_eq!(enc, b"/!es");
+
+		let dec = Es::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,47 @@
+//! Stores the version of the key format used by the datastore
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Kf {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+}
+
+pub fn new() -> Kf {
+	Kf::new()
+}
+
+impl Default for Kf {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Kf {
+	pub fn new() -> Kf {
+		Kf {
+			__: b'/',
+			_a: b'!',
+			_b: b'k',
+			_c: b'f',
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Kf::new();
+		let enc = Kf::encode(&val).unwrap();
+		assert_eq!(enc, b"/!kf");
+
+		let dec = Kf::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
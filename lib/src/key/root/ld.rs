@@ -0,0 +1,57 @@
+//! Stores the lease of the node which runs a cluster-wide background job
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Ld<'a> {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+	pub ld: &'a str,
+}
+
+pub fn new(ld: &str) -> Ld<'_> {
+	Ld::new(ld)
+}
+
+pub fn prefix() -> Vec<u8> {
+	let mut k = super::all::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b'l', b'd', 0x00]);
+	k
+}
+
+pub fn suffix() -> Vec<u8> {
+	let mut k = super::all::new().encode().unwrap();
+	k.extend_from_slice(&[b'!', b'l', b'd', 0xff]);
+	k
+}
+
+impl<'a> Ld<'a> {
+	pub fn new(ld: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'!',
+			_b: b'l',
+			_c: b'd',
+			ld,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Ld::new(
+			"testjob",
+		);
+		let enc = Ld::encode(&val).unwrap();
+		assert_eq!(enc, b"/!ldtestjob\0");
+
+		let dec = Ld::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
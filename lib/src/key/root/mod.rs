@@ -1,4 +1,11 @@
 pub mod all;
+pub mod ek;
+pub mod es;
 pub mod hb;
+pub mod kf;
+pub mod ld;
 pub mod nd;
+pub mod ni;
 pub mod ns;
+pub mod rn;
+pub mod st;
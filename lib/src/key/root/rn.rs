@@ -0,0 +1,45 @@
+//! Stores the progress of a RENAME NAMESPACE statement which is moving its data in batches
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Rn<'a> {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+	pub ns: &'a str,
+}
+
+pub fn new(ns: &str) -> Rn<'_> {
+	Rn::new(ns)
+}
+
+impl<'a> Rn<'a> {
+	pub fn new(ns: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'!',
+			_b: b'r',
+			_c: b'n',
+			ns,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Rn::new(
+			"testns",
+		);
+		let enc = Rn::encode(&val).unwrap();
+		assert_eq!(enc, b"/!rntestns\0");
+
+		let dec = Rn::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,47 @@
+//! Stores whether the storage statistics of the existing tables have been counted
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct St {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+}
+
+pub fn new() -> St {
+	St::new()
+}
+
+impl Default for St {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl St {
+	pub fn new() -> St {
+		St {
+			__: b'/',
+			_a: b'!',
+			_b: b's',
+			_c: b't',
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = St::new();
+		let enc = St::encode(&val).unwrap();
+		assert_eq!(enc, b"/!st");
+
+		let dec = St::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,90 @@
+//! Stores a record in the time-ordered queue of expiring records
+use crate::sql::id::Id;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Ex<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub at: u64,
+	pub id: Id,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, at: u64, id: &Id) -> Ex<'a> {
+	Ex::new(ns, db, tb, at, id.to_owned())
+}
+
+pub fn prefix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = super::all::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(b"!ex");
+	k
+}
+
+/// The end of the range of records which expire before the given time
+pub fn suffix(ns: &str, db: &str, tb: &str, at: u64) -> Vec<u8> {
+	let mut k = prefix(ns, db, tb);
+	k.extend_from_slice(&at.to_be_bytes());
+	k
+}
+
+impl<'a> Ex<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, at: u64, id: Id) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'e',
+			_f: b'x',
+			at,
+			id,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Ex::new(
+			"testns",
+			"testdb",
+			"testtb",
+			1,
+			"testid".into(),
+		);
+		let enc = Ex::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0*testtb\0!ex\0\0\0\0\0\0\0\x01\0\0\0\x01testid\0");
+
+		let dec = Ex::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn range() {
+		use super::*;
+		let beg = prefix("testns", "testdb", "testtb");
+		let end = suffix("testns", "testdb", "testtb", 10);
+		for at in [0, 9] {
+			let key = Ex::new("testns", "testdb", "testtb", at, "testid".into()).encode().unwrap();
+			assert!(beg <= key && key < end);
+		}
+		let key = Ex::new("testns", "testdb", "testtb", 10, "testid".into()).encode().unwrap();
+		assert!(key >= end);
+	}
+}
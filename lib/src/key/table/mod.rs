@@ -1,6 +1,12 @@
 pub mod all;
+pub mod co;
+pub mod cp;
+pub mod er;
 pub mod ev;
+pub mod ex;
 pub mod fd;
 pub mod ft;
+pub mod ii;
 pub mod ix;
 pub mod lq;
+pub mod st;
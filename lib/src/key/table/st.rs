@@ -0,0 +1,91 @@
+//! Stores the storage statistics of a table
+//!
+//! The base statistics are stored under the nil id, and the changes which
+//! have not yet been folded into them are stored under the id of the node
+//! which made them.
+use derive::Key;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct St<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	#[serde(with = "uuid::serde::compact")]
+	pub st: Uuid,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, st: Uuid) -> St<'a> {
+	St::new(ns, db, tb, st)
+}
+
+pub fn prefix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = super::all::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[b'!', b's', b't', 0x00]);
+	k
+}
+
+pub fn suffix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = super::all::new(ns, db, tb).encode().unwrap();
+	// The ids are stored as raw bytes, which may begin with 0xff
+	k.extend_from_slice(b"!st");
+	k.extend_from_slice(&[0xff; 17]);
+	k
+}
+
+impl<'a> St<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, st: Uuid) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b's',
+			_f: b't',
+			st,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = St::new(
+			"testns",
+			"testdb",
+			"testtb",
+			Uuid::from_u128(1),
+		);
+		let enc = St::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0*testtb\0!st\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\x01");
+
+		let dec = St::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn range() {
+		use super::*;
+		let beg = prefix("testns", "testdb", "testtb");
+		let end = suffix("testns", "testdb", "testtb");
+		for id in [u128::MIN, u128::MAX - 1] {
+			let key = St::new("testns", "testdb", "testtb", Uuid::from_u128(id)).encode().unwrap();
+			assert!(beg <= key && key < end);
+		}
+	}
+}
@@ -0,0 +1,238 @@
+//! Portable binary backups of an entire datastore.
+//!
+//! A backup contains every key and value in the datastore, and can be
+//! restored into a datastore which uses any of the storage engines. Each
+//! backup starts with a header identifying the backup format, followed by a
+//! sequence of frames in the following format:
+//!
+//! ```text
+//! [kind: 1 byte][length: u32 LE][payload][crc32 of payload: u32 LE]
+//! ```
+//!
+//! Batch frames contain a bincode encoded list of key-value pairs. The final
+//! frame contains the total number of key-value pairs in the backup, so that
+//! a truncated backup can be detected when it is restored.
+use crate::err::Error;
+use crate::kvs::Key;
+use crate::kvs::Val;
+use crate::vs::try_u128_to_versionstamp;
+use crate::vs::Versionstamp;
+
+/// The bytes which identify the start of a backup
+const MAGIC: &[u8; 16] = b"SURREALDB-BACKUP";
+/// The current version of the backup format
+const VERSION: u16 = 1;
+/// The length of the header at the start of a backup
+const HEADER_LEN: usize = MAGIC.len() + 2;
+/// The length of the kind and length fields at the start of each frame
+const FRAME_HEADER_LEN: usize = 5;
+/// The length of the checksum at the end of each frame
+const FRAME_CHECKSUM_LEN: usize = 4;
+/// A frame containing a batch of key-value pairs
+const KIND_BATCH: u8 = 1;
+/// The final frame, containing the number of key-value pairs in the backup
+const KIND_END: u8 = 2;
+
+/// The point in time up to which a datastore is recovered from a backup
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RestorePoint {
+	/// Recover the changes committed up to and including a versionstamp
+	Versionstamp(Versionstamp),
+	/// Recover the changes committed before a unix timestamp, in seconds
+	Timestamp(u64),
+}
+
+impl RestorePoint {
+	/// Recover up to a versionstamp, as it is displayed by SHOW CHANGES
+	pub fn versionstamp(v: u128) -> Result<Self, Error> {
+		Ok(Self::Versionstamp(try_u128_to_versionstamp(v)?))
+	}
+}
+
+/// A single frame which was read from a backup
+#[derive(Debug)]
+pub(crate) enum Frame {
+	Batch(Vec<(Key, Val)>),
+	End,
+}
+
+/// Encodes a backup as a sequence of frames
+#[derive(Default)]
+pub(crate) struct Writer {
+	/// The number of key-value pairs written so far
+	count: u64,
+}
+
+impl Writer {
+	/// Encode the header which starts a backup
+	pub fn header(&self) -> Vec<u8> {
+		let mut out = Vec::with_capacity(HEADER_LEN);
+		out.extend_from_slice(MAGIC);
+		out.extend_from_slice(&VERSION.to_le_bytes());
+		out
+	}
+
+	/// Encode a batch of key-value pairs as a frame
+	pub fn batch(&mut self, entries: &[(Key, Val)]) -> Result<Vec<u8>, Error> {
+		self.count += entries.len() as u64;
+		Ok(Self::frame(KIND_BATCH, &bincode::serialize(entries)?))
+	}
+
+	/// Encode the frame which ends a backup
+	pub fn end(&self) -> Vec<u8> {
+		Self::frame(KIND_END, &self.count.to_le_bytes())
+	}
+
+	fn frame(kind: u8, data: &[u8]) -> Vec<u8> {
+		let mut out = Vec::with_capacity(FRAME_HEADER_LEN + data.len() + FRAME_CHECKSUM_LEN);
+		out.push(kind);
+		out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+		out.extend_from_slice(data);
+		out.extend_from_slice(&crc32fast::hash(data).to_le_bytes());
+		out
+	}
+}
+
+/// Decodes a backup from chunks of bytes, as they are received
+#[derive(Default)]
+pub(crate) struct Reader {
+	/// The bytes which have not yet been decoded
+	buf: Vec<u8>,
+	/// Whether the header has been read
+	header: bool,
+	/// Whether the final frame has been read
+	done: bool,
+	/// The number of key-value pairs read so far
+	count: u64,
+}
+
+fn invalid(msg: &str) -> Error {
+	Error::Backup(msg.to_owned())
+}
+
+impl Reader {
+	/// Add a chunk of bytes to be decoded
+	pub fn push(&mut self, data: &[u8]) {
+		self.buf.extend_from_slice(data);
+	}
+
+	/// Decode the next complete frame, if one has been received
+	pub fn next(&mut self) -> Result<Option<Frame>, Error> {
+		// Check the header at the start of the backup
+		if !self.header {
+			if self.buf.len() < HEADER_LEN {
+				return Ok(None);
+			}
+			if &self.buf[..MAGIC.len()] != MAGIC {
+				return Err(invalid("The data is not a SurrealDB backup"));
+			}
+			let version = u16::from_le_bytes([self.buf[MAGIC.len()], self.buf[MAGIC.len() + 1]]);
+			if version != VERSION {
+				return Err(Error::Backup(format!(
+					"The backup version {version} is not supported"
+				)));
+			}
+			self.buf.drain(..HEADER_LEN);
+			self.header = true;
+		}
+		// Nothing is allowed after the final frame
+		if self.done {
+			return match self.buf.is_empty() {
+				true => Ok(None),
+				false => Err(invalid("The backup contains unexpected data after the end")),
+			};
+		}
+		// Wait until the entire frame has been received
+		if self.buf.len() < FRAME_HEADER_LEN {
+			return Ok(None);
+		}
+		let len = u32::from_le_bytes(self.buf[1..FRAME_HEADER_LEN].try_into().unwrap()) as usize;
+		if self.buf.len() < FRAME_HEADER_LEN + len + FRAME_CHECKSUM_LEN {
+			return Ok(None);
+		}
+		let kind = self.buf[0];
+		let data = &self.buf[FRAME_HEADER_LEN..FRAME_HEADER_LEN + len];
+		let sum = &self.buf[FRAME_HEADER_LEN + len..FRAME_HEADER_LEN + len + FRAME_CHECKSUM_LEN];
+		if crc32fast::hash(data).to_le_bytes() != sum {
+			return Err(invalid("The backup is corrupted"));
+		}
+		let frame = match kind {
+			KIND_BATCH => {
+				let entries: Vec<(Key, Val)> = bincode::deserialize(data)?;
+				self.count += entries.len() as u64;
+				Frame::Batch(entries)
+			}
+			KIND_END => {
+				let count = data.try_into().ok().map(u64::from_le_bytes);
+				if count != Some(self.count) {
+					return Err(invalid("The backup is missing some of its data"));
+				}
+				self.done = true;
+				Frame::End
+			}
+			_ => return Err(invalid("The backup is corrupted")),
+		};
+		self.buf.drain(..FRAME_HEADER_LEN + len + FRAME_CHECKSUM_LEN);
+		Ok(Some(frame))
+	}
+
+	/// Check that the entire backup has been read
+	pub fn finish(&self) -> Result<(), Error> {
+		match self.done && self.buf.is_empty() {
+			true => Ok(()),
+			false => Err(invalid("The backup is incomplete")),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn backup(entries: &[(Key, Val)]) -> Vec<u8> {
+		let mut w = Writer::default();
+		let mut out = w.header();
+		out.extend(w.batch(entries).unwrap());
+		out.extend(w.end());
+		out
+	}
+
+	fn read(data: &[u8], chunk: usize) -> Result<Vec<(Key, Val)>, Error> {
+		let mut r = Reader::default();
+		let mut res = vec![];
+		for c in data.chunks(chunk) {
+			r.push(c);
+			while let Some(f) = r.next()? {
+				if let Frame::Batch(mut v) = f {
+					res.append(&mut v);
+				}
+			}
+		}
+		r.finish()?;
+		Ok(res)
+	}
+
+	#[test]
+	fn round_trip() {
+		let entries = vec![(b"a".to_vec(), b"1".to_vec()), (b"b".to_vec(), b"2".to_vec())];
+		let data = backup(&entries);
+		// The backup can be decoded regardless of how it is split into chunks
+		for chunk in [1, 3, 7, data.len()] {
+			assert_eq!(read(&data, chunk).unwrap(), entries);
+		}
+	}
+
+	#[test]
+	fn corrupted_backup() {
+		let mut data = backup(&[(b"a".to_vec(), b"1".to_vec())]);
+		data[HEADER_LEN + FRAME_HEADER_LEN + 2] ^= 0x01;
+		assert!(matches!(read(&data, data.len()), Err(Error::Backup(_))));
+	}
+
+	#[test]
+	fn truncated_backup() {
+		let data = backup(&[(b"a".to_vec(), b"1".to_vec())]);
+		assert!(read(&data[..data.len() - 1], data.len()).is_err());
+		assert!(read(b"not a backup at all", 4).is_err());
+	}
+}
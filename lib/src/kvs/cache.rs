@@ -1,10 +1,12 @@
 use crate::kvs::kv::Key;
 use crate::sql::statements::DefineAnalyzerStatement;
+use crate::sql::statements::DefineConsumerStatement;
 use crate::sql::statements::DefineDatabaseStatement;
 use crate::sql::statements::DefineEventStatement;
 use crate::sql::statements::DefineFieldStatement;
 use crate::sql::statements::DefineFunctionStatement;
 use crate::sql::statements::DefineIndexStatement;
+use crate::sql::statements::DefineJobStatement;
 use crate::sql::statements::DefineLoginStatement;
 use crate::sql::statements::DefineNamespaceStatement;
 use crate::sql::statements::DefineParamStatement;
@@ -23,6 +25,7 @@ pub enum Entry {
 	Tb(Arc<DefineTableStatement>),
 	// Multi definitions
 	Azs(Arc<[DefineAnalyzerStatement]>),
+	Cos(Arc<[DefineConsumerStatement]>),
 	Dbs(Arc<[DefineDatabaseStatement]>),
 	Dls(Arc<[DefineLoginStatement]>),
 	Dts(Arc<[DefineTokenStatement]>),
@@ -31,6 +34,7 @@ pub enum Entry {
 	Fds(Arc<[DefineFieldStatement]>),
 	Fts(Arc<[DefineTableStatement]>),
 	Ixs(Arc<[DefineIndexStatement]>),
+	Jbs(Arc<[DefineJobStatement]>),
 	Lvs(Arc<[LiveStatement]>),
 	Nls(Arc<[DefineLoginStatement]>),
 	Nss(Arc<[DefineNamespaceStatement]>),
@@ -57,4 +61,8 @@ impl Cache {
 	pub fn del(&mut self, key: &Key) -> Option<Entry> {
 		self.0.remove(key)
 	}
+	/// Clear all keys from the cache
+	pub fn clear(&mut self) {
+		self.0.clear();
+	}
 }
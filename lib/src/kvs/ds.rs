@@ -1,8 +1,16 @@
+use super::backup;
+use super::backup::RestorePoint;
+use super::encryption::Encryption;
+use super::format;
 use super::tx::Transaction;
 use crate::cf;
+use crate::cnf;
 use crate::ctx::Context;
+use crate::dbs::node::JobRun;
+use crate::dbs::node::Lease;
 use crate::dbs::node::Timestamp;
 use crate::dbs::Attach;
+use crate::dbs::Auth;
 use crate::dbs::Executor;
 use crate::dbs::Notification;
 use crate::dbs::Options;
@@ -11,17 +19,40 @@ use crate::dbs::Session;
 use crate::dbs::Variables;
 use crate::err::Error;
 use crate::key::root::hb::Hb;
+use crate::kvs::Key;
+use crate::kvs::KeyFormat;
+use crate::kvs::Scanner;
+use crate::kvs::Val;
+use crate::kvs::VersionstampOracle;
 use crate::sql;
+use crate::sql::statements::DefineJobStatement;
+use crate::sql::statements::DeleteStatement;
+use crate::sql::statements::LiveStatement;
+use crate::sql::statements::UpdateStatement;
 use crate::sql::Value;
+use crate::sql::{Base, Datetime};
 use crate::sql::{Query, Uuid};
+use crate::sql::{Thing, Values};
 use channel::Receiver;
 use channel::Sender;
+use chrono::{DateTime, TimeZone, Utc};
 use futures::lock::Mutex;
+use futures::Stream;
+use futures::StreamExt;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
 use std::fmt;
+use std::future::Future;
+use std::sync::atomic::AtomicBool;
+#[cfg(test)]
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::instrument;
 use tracing::trace;
+use trice::Instant;
 
 /// Used for cluster logic to move LQ data to LQ cleanup code
 /// Not a stored struct; Used only in this module
@@ -34,21 +65,50 @@ pub struct LqValue {
 	pub lq: Uuid,
 }
 
+/// A run of a scheduled job which this node has claimed
+/// Not a stored struct; Used only in this module
+pub(super) struct DueJob {
+	ns: String,
+	db: String,
+	jb: DefineJobStatement,
+	jr: JobRun,
+	at: DateTime<Utc>,
+}
+
 /// The underlying datastore instance which stores the dataset.
 #[allow(dead_code)]
 pub struct Datastore {
 	// The inner datastore type
 	inner: Inner,
 	// The unique id of this datastore, used in notifications
-	id: Uuid,
+	pub(super) id: Uuid,
 	// Whether this datastore runs in strict mode by default
 	strict: bool,
 	// The maximum duration timeout for running multiple statements in a query
 	query_timeout: Option<Duration>,
 	// The maximum duration timeout for running multiple statements in a transaction
 	transaction_timeout: Option<Duration>,
+	// The number of times a transaction is retried after conflicting with another transaction
+	pub(crate) transaction_retries: u32,
 	// Whether this datastore enables live query notifications to subscribers
 	notification_channel: Option<(Sender<Notification>, Receiver<Notification>)>,
+	// The runs of scheduled jobs which this node has claimed, but not yet run
+	pub(super) jobs: (Sender<DueJob>, Receiver<DueJob>),
+	// The scheduled jobs whose claimed runs this node has not yet recorded
+	pub(super) claims: Mutex<BTreeSet<(String, String, String)>>,
+	// The encryption keys used to encrypt values stored in this datastore
+	encryption: Option<Arc<Encryption>>,
+	// The version of the key format used by the data in this datastore
+	key_format: AtomicU8,
+	// How long this node holds the lease of a cluster-wide background job
+	lease_duration: Duration,
+	// Whether this node has registered itself as a member of the cluster
+	registered: AtomicBool,
+	// The versionstamp oracle which is used for the change feeds
+	versionstamp_oracle: VersionstampOracle,
+	// The number of upcoming commits which fail as if they conflicted with another transaction
+	#[cfg(test)]
+	pub(crate) conflicts: Arc<AtomicU32>,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -59,6 +119,8 @@ pub(super) enum Inner {
 	RocksDB(super::rocksdb::Datastore),
 	#[cfg(feature = "kv-speedb")]
 	SpeeDB(super::speedb::Datastore),
+	#[cfg(feature = "kv-redb")]
+	Redb(super::redb::Datastore),
 	#[cfg(feature = "kv-indxdb")]
 	IndxDB(super::indxdb::Datastore),
 	#[cfg(feature = "kv-tikv")]
@@ -77,6 +139,8 @@ impl fmt::Display for Datastore {
 			Inner::RocksDB(_) => write!(f, "rocksdb"),
 			#[cfg(feature = "kv-speedb")]
 			Inner::SpeeDB(_) => write!(f, "speedb"),
+			#[cfg(feature = "kv-redb")]
+			Inner::Redb(_) => write!(f, "redb"),
 			#[cfg(feature = "kv-indxdb")]
 			Inner::IndxDB(_) => write!(f, "indxdb"),
 			#[cfg(feature = "kv-tikv")]
@@ -104,6 +168,18 @@ impl Datastore {
 	/// # }
 	/// ```
 	///
+	/// Or to create an in-memory store which is persisted to disk:
+	///
+	/// ```rust,no_run
+	/// # use surrealdb::kvs::Datastore;
+	/// # use surrealdb::err::Error;
+	/// # #[tokio::main]
+	/// # async fn main() -> Result<(), Error> {
+	/// let ds = Datastore::new("memory:temp.db").await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	///
 	/// Or to create a file-backed store:
 	///
 	/// ```rust,no_run
@@ -116,6 +192,18 @@ impl Datastore {
 	/// # }
 	/// ```
 	///
+	/// Or to create a store backed by the pure-Rust redb engine:
+	///
+	/// ```rust,no_run
+	/// # use surrealdb::kvs::Datastore;
+	/// # use surrealdb::err::Error;
+	/// # #[tokio::main]
+	/// # async fn main() -> Result<(), Error> {
+	/// let ds = Datastore::new("redb://temp.db").await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	///
 	/// Or to connect to a tikv-backed distributed store:
 	///
 	/// ```rust,no_run
@@ -147,6 +235,20 @@ impl Datastore {
 				#[cfg(not(feature = "kv-mem"))]
 				return Err(Error::Ds("Cannot connect to the `memory` storage engine as it is not enabled in this build of SurrealDB".to_owned()));
 			}
+			// Parse and initiate a durable memory database
+			s if s.starts_with("memory:") => {
+				#[cfg(feature = "kv-mem")]
+				{
+					info!("Starting kvs store at {}", path);
+					let s = s.trim_start_matches("memory://");
+					let s = s.trim_start_matches("memory:");
+					let v = super::mem::Datastore::new_durable(s).await.map(Inner::Mem);
+					info!("Started kvs store at {}", path);
+					v
+				}
+				#[cfg(not(feature = "kv-mem"))]
+				return Err(Error::Ds("Cannot connect to the `memory` storage engine as it is not enabled in this build of SurrealDB".to_owned()));
+			}
 			// Parse and initiate an File database
 			s if s.starts_with("file:") => {
 				#[cfg(feature = "kv-rocksdb")]
@@ -189,6 +291,20 @@ impl Datastore {
 				#[cfg(not(feature = "kv-speedb"))]
 				return Err(Error::Ds("Cannot connect to the `speedb` storage engine as it is not enabled in this build of SurrealDB".to_owned()));
 			}
+			// Parse and initiate a redb database
+			s if s.starts_with("redb:") => {
+				#[cfg(feature = "kv-redb")]
+				{
+					info!("Starting kvs store at {}", path);
+					let s = s.trim_start_matches("redb://");
+					let s = s.trim_start_matches("redb:");
+					let v = super::redb::Datastore::new(s).await.map(Inner::Redb);
+					info!("Started kvs store at {}", path);
+					v
+				}
+				#[cfg(not(feature = "kv-redb"))]
+				return Err(Error::Ds("Cannot connect to the `redb` storage engine as it is not enabled in this build of SurrealDB".to_owned()));
+			}
 			// Parse and initiate an IndxDB database
 			s if s.starts_with("indxdb:") => {
 				#[cfg(feature = "kv-indxdb")]
@@ -238,14 +354,27 @@ impl Datastore {
 			}
 		};
 		// Set the properties on the datastore
-		inner.map(|inner| Self {
+		let ds = inner.map(|inner| Self {
 			id: node_id,
 			inner,
 			strict: false,
 			query_timeout: None,
 			transaction_timeout: None,
+			transaction_retries: 0,
 			notification_channel: None,
-		})
+			jobs: channel::unbounded(),
+			claims: Mutex::new(BTreeSet::new()),
+			encryption: None,
+			key_format: AtomicU8::new(KeyFormat::LATEST as u8),
+			lease_duration: cnf::LEASE_DURATION,
+			registered: AtomicBool::new(false),
+			versionstamp_oracle: VersionstampOracle::default(),
+			#[cfg(test)]
+			conflicts: Arc::new(AtomicU32::new(0)),
+		})?;
+		// Detect the key format used by the data
+		ds.check_key_format().await?;
+		Ok(ds)
 	}
 
 	/// Specify whether this Datastore should run in strict mode
@@ -272,6 +401,58 @@ impl Datastore {
 		self
 	}
 
+	/// Set the number of times a transaction is retried after conflicting with another transaction
+	pub fn with_transaction_retries(mut self, retries: u32) -> Self {
+		self.transaction_retries = retries;
+		self
+	}
+
+	/// Set how long this node holds the lease of a cluster-wide background job
+	///
+	/// The lease is renewed each time the job runs, and another node takes
+	/// over the job once the lease expires, so the duration should be longer
+	/// than the interval at which the datastore is ticked.
+	pub fn with_lease_duration(mut self, duration: Duration) -> Self {
+		self.lease_duration = duration;
+		self
+	}
+
+	/// Set the versionstamp oracle which is used for the change feeds
+	///
+	/// The versionstamps allocated by the storage engines are only monotonic
+	/// when the datastore runs on a single node, or with the TiKV timestamp
+	/// oracle, in the order in which transactions obtain them rather than the
+	/// order in which they commit. A hybrid logical clock is persisted for each
+	/// database, so that its versionstamps are monotonic in commit order across
+	/// every node of a cluster.
+	pub fn with_versionstamp_oracle(mut self, oracle: VersionstampOracle) -> Self {
+		self.versionstamp_oracle = oracle;
+		self
+	}
+
+	/// Encrypt the values stored in this datastore with the specified key
+	///
+	/// The key is checked against the datastore when it is bootstrapped. If a
+	/// previous key is specified, any values which were encrypted with the
+	/// previous key are re-encrypted with the new key during bootstrap. Any
+	/// unencrypted values in an existing datastore are encrypted in the same way.
+	/// The keys are derived with a random salt, which is generated when the
+	/// datastore is first encrypted, and which is stored with the datastore.
+	pub async fn with_encryption_key(
+		mut self,
+		key: Option<&str>,
+		previous: Option<&str>,
+	) -> Result<Self, Error> {
+		self.encryption = match key {
+			Some(key) => {
+				let salt = self.encryption_salt().await?;
+				Some(Arc::new(Encryption::new(key, previous, &salt)?))
+			}
+			None => None,
+		};
+		Ok(self)
+	}
+
 	/// Creates a new datastore instance
 	///
 	/// Use this for clustered environments.
@@ -289,16 +470,223 @@ impl Datastore {
 	// Initialise bootstrap with artificial values, intended for testing
 	pub async fn bootstrap_full(&self, node_id: &Uuid) -> Result<(), Error> {
 		trace!("Bootstrapping {}", self.id);
+		self.check_encryption().await?;
+		if !self.key_format().is_compact() {
+			warn!("The datastore uses an outdated key format, and should be migrated to the latest key format");
+		}
 		let mut tx = self.transaction(true, false).await?;
 		let now = tx.clock();
 		let archived = self.register_remove_and_archive(&mut tx, node_id, now).await?;
 		tx.commit().await?;
+		if node_id == &self.id {
+			self.registered.store(true, Ordering::Release);
+		}
 
 		let mut tx = self.transaction(true, false).await?;
 		self.remove_archived(&mut tx, archived).await?;
 		tx.commit().await
 	}
 
+	// Fetch the salt which encryption keys are derived with, generating it for new datastores
+	async fn encryption_salt(&self) -> Result<Val, Error> {
+		// The salt is stored without transparent encryption
+		let mut tx = self.transaction(true, false).await?;
+		tx.enc = None;
+		let key = crate::key::root::es::new();
+		match tx.get(key.clone()).await? {
+			Some(v) => {
+				tx.cancel().await?;
+				Ok(v)
+			}
+			None => {
+				let salt = Encryption::salt();
+				if let Err(e) = tx.put(key, salt.clone()).await {
+					tx.cancel().await?;
+					return Err(e);
+				}
+				tx.commit().await?;
+				Ok(salt)
+			}
+		}
+	}
+
+	// Check the encryption key, and re-encrypt the datastore if the key has changed
+	async fn check_encryption(&self) -> Result<(), Error> {
+		// Values are checked without transparent encryption
+		let mut tx = self.transaction(true, false).await?;
+		tx.enc = None;
+		let key = crate::key::root::ek::new();
+		let chk = tx.get(key.clone()).await?;
+		let (enc, encrypted) = match (&self.encryption, chk) {
+			// The datastore is not encrypted
+			(None, None) => return tx.cancel().await,
+			// The datastore is encrypted, but no key was specified
+			(None, Some(_)) => {
+				tx.cancel().await?;
+				return Err(Error::EncryptionKeyMissing);
+			}
+			// The datastore is encrypted with the current key
+			(Some(enc), Some(chk)) if enc.is_current(&chk) => {
+				tx.cancel().await?;
+				return enc.decrypt(&chk).map(|_| ());
+			}
+			// The datastore is encrypted with a different key
+			(Some(enc), Some(chk)) if !enc.is_previous(&chk) => {
+				tx.cancel().await?;
+				return Err(Error::EncryptionKeyInvalid);
+			}
+			// The datastore is encrypted with the previous key, or is not yet encrypted
+			(Some(enc), chk) => {
+				tx.cancel().await?;
+				info!("Encrypting the datastore with the specified encryption key");
+				(enc, chk.is_some())
+			}
+		};
+		let chk: Key = key.clone().into();
+		// Re-encrypt all of the values in batches
+		let beg: Key = crate::key::root::all::new().into();
+		let mut scanner = Scanner::new(beg..vec![0xff]);
+		loop {
+			let mut tx = self.transaction(true, false).await?;
+			tx.enc = None;
+			let res = scanner.next_batch(&mut tx).await?;
+			if res.is_empty() {
+				tx.cancel().await?;
+				break;
+			}
+			for (k, v) in res.iter() {
+				// Skip the check value until all values are re-encrypted
+				if *k == chk {
+					continue;
+				}
+				// Skip values which are stored unencrypted
+				if Encryption::is_unencrypted(k) {
+					continue;
+				}
+				// Skip values which have already been re-encrypted
+				if enc.is_current(v) {
+					continue;
+				}
+				let v = match encrypted {
+					true => enc.decrypt(v)?,
+					false => v.clone(),
+				};
+				tx.set(k.clone(), enc.encrypt(&v)?).await?;
+			}
+			tx.commit().await?;
+		}
+		// Store the check value with the current key
+		let mut tx = self.transaction(true, false).await?;
+		tx.enc = None;
+		tx.set(key, enc.encrypt(crate::cnf::SERVER_NAME.as_bytes())?).await?;
+		tx.commit().await
+	}
+
+	/// Retrieve the key format used by the data in this datastore
+	pub fn key_format(&self) -> KeyFormat {
+		KeyFormat::try_from(self.key_format.load(Ordering::Acquire)).unwrap_or_default()
+	}
+
+	// Detect the key format used by the data, recording it for new datastores
+	async fn check_key_format(&self) -> Result<(), Error> {
+		// The key format is stored without transparent encryption
+		let mut tx = self.transaction(true, false).await?;
+		tx.enc = None;
+		let key = crate::key::root::kf::new();
+		let kf = match tx.get(key.clone()).await? {
+			Some(v) => {
+				tx.cancel().await?;
+				KeyFormat::try_from(v.as_slice())?
+			}
+			// Datastores which already contain data use the original key format
+			None => {
+				let beg = crate::key::root::ns::prefix();
+				let end = crate::key::root::ns::suffix();
+				match tx.scan(beg..end, 1).await?.is_empty() {
+					true => {
+						tx.set(key, vec![KeyFormat::LATEST as u8]).await?;
+						tx.commit().await?;
+						KeyFormat::LATEST
+					}
+					false => {
+						tx.cancel().await?;
+						KeyFormat::V1
+					}
+				}
+			}
+		};
+		self.key_format.store(kf as u8, Ordering::Release);
+		Ok(())
+	}
+
+	/// Migrates the data in this datastore to the latest key format
+	///
+	/// Every namespace, database, table and index is first assigned a numeric
+	/// id, after which the data of each table is moved under those ids. The
+	/// data is moved in a separate transaction for each batch of keys, so the
+	/// datastore should not be used while it is being migrated. Other nodes
+	/// only read the key format when they start, so the migration is refused
+	/// while any other node in the cluster is still sending heartbeats. If the
+	/// migration is interrupted, it can safely be run again.
+	pub async fn migrate_key_format(&self) -> Result<(), Error> {
+		// Check if the datastore has already been migrated
+		if self.key_format() == KeyFormat::LATEST {
+			return Ok(());
+		}
+		info!("Migrating the datastore to the latest key format");
+		// Assign numeric ids to all of the definitions
+		let mut tx = self.transaction(true, false).await?;
+		if let Err(e) = self.check_no_other_nodes(&mut tx).await {
+			tx.cancel().await?;
+			return Err(e);
+		}
+		let migrations = format::prepare(&mut tx).await?;
+		tx.commit().await?;
+		// Move the data of each table in batches
+		for mut migration in migrations {
+			loop {
+				let mut tx = self.transaction(true, false).await?;
+				if migration.next_batch(&mut tx).await? == 0 {
+					tx.cancel().await?;
+					break;
+				}
+				tx.commit().await?;
+			}
+		}
+		// Record the new key format
+		self.set_key_format(KeyFormat::LATEST).await?;
+		info!("Migrated the datastore to the latest key format");
+		Ok(())
+	}
+
+	// Ensure that no other node has sent a heartbeat within the lease duration
+	async fn check_no_other_nodes(&self, tx: &mut Transaction) -> Result<(), Error> {
+		let now = tx.clock();
+		let expired = now.value.saturating_sub(self.lease_duration.as_millis() as u64);
+		let ts = Timestamp {
+			value: u64::MAX - 1,
+		};
+		for hb in tx.scan_hb(&ts, u32::MAX).await? {
+			if hb.nd != self.id.0 && hb.hb.value > expired {
+				return Err(Error::Ds(format!(
+					"Unable to migrate the key format while node {} is running",
+					Uuid::from(hb.nd)
+				)));
+			}
+		}
+		Ok(())
+	}
+
+	// Record the key format used by the data in this datastore
+	pub(crate) async fn set_key_format(&self, kf: KeyFormat) -> Result<(), Error> {
+		let mut tx = self.transaction(true, false).await?;
+		tx.enc = None;
+		tx.set(crate::key::root::kf::new(), vec![kf as u8]).await?;
+		tx.commit().await?;
+		self.key_format.store(kf as u8, Ordering::Release);
+		Ok(())
+	}
+
 	// Node registration + "mark" stage of mark-and-sweep gc
 	pub async fn register_remove_and_archive(
 		&self,
@@ -338,6 +726,8 @@ impl Datastore {
 		for hb in hbs {
 			trace!("Deleting node {}", &hb.nd);
 			tx.del_nd(hb.nd).await?;
+			// Drop the notifications nobody will deliver
+			tx.delr_nt(&hb.nd).await?;
 			nodes.push(crate::sql::uuid::Uuid::from(hb.nd));
 		}
 		Ok(nodes)
@@ -424,9 +814,13 @@ impl Datastore {
 		trace!("Archiving lqs and found {} LQ entries for {}", lqs.len(), nd);
 		let mut ret = vec![];
 		for lq in lqs {
-			let lvs = tx.get_lv(lq.ns.as_str(), lq.db.as_str(), lq.tb.as_str(), &lq.lq).await?;
-			let archived_lvs = lvs.clone().archive(this_node_id.clone());
-			tx.putc_lv(&lq.ns, &lq.db, &lq.tb, archived_lvs, Some(lvs)).await?;
+			let key = crate::key::table::lq::new(&lq.ns, &lq.db, &lq.tb, lq.lq.0);
+			let val = tx.get(key.clone()).await?.ok_or(Error::LvNotFound {
+				value: lq.lq.to_string(),
+			})?;
+			let lv = LiveStatement::try_from(&val)?.archive(this_node_id.clone());
+			// The stored value may have been encoded by an older version
+			tx.putc(key, lv.to_vec(), Some(val)).await?;
 			ret.push(lq);
 		}
 		Ok(ret)
@@ -454,8 +848,35 @@ impl Datastore {
 	pub async fn heartbeat(&self) -> Result<(), Error> {
 		let mut tx = self.transaction(true, false).await?;
 		let timestamp = tx.clock();
-		self.heartbeat_full(&mut tx, timestamp, self.id.clone()).await?;
-		tx.commit().await
+		// A node registers itself with its first heartbeat
+		let registered = match tx.get_nd(self.id.0).await {
+			Ok(v) => v.is_some(),
+			Err(e) => {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		};
+		if !registered {
+			// The live queries of a node which was removed while it was
+			// unreachable have been archived, so the node can not rejoin
+			if self.registered.load(Ordering::Acquire) {
+				tx.cancel().await?;
+				return Err(Error::NdRemoved {
+					value: self.id.to_string(),
+				});
+			}
+			if let Err(e) = tx.set_nd(self.id.0).await {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		}
+		if let Err(e) = self.heartbeat_full(&mut tx, timestamp, self.id.clone()).await {
+			tx.cancel().await?;
+			return Err(e);
+		}
+		tx.commit().await?;
+		self.registered.store(true, Ordering::Release);
+		Ok(())
 	}
 
 	// Creates a heartbeat entry for the member indicating to the cluster
@@ -471,6 +892,801 @@ impl Datastore {
 		tx.set_hb(timestamp, node_id.0).await
 	}
 
+	// Removes the nodes which have not sent a heartbeat within the lease duration,
+	// and archives the live queries which were registered on those nodes.
+	async fn remove_expired_nodes(&self) -> Result<(), Error> {
+		let mut tx = self.transaction(true, false).await?;
+		let archived = match self.remove_expired_nodes_impl(&mut tx).await {
+			Ok(archived) => archived,
+			Err(e) => {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		};
+		tx.commit().await?;
+		if archived.is_empty() {
+			return Ok(());
+		}
+		let mut tx = self.transaction(true, false).await?;
+		if let Err(e) = self.remove_archived(&mut tx, archived).await {
+			tx.cancel().await?;
+			return Err(e);
+		}
+		tx.commit().await
+	}
+
+	async fn remove_expired_nodes_impl(&self, tx: &mut Transaction) -> Result<Vec<LqValue>, Error> {
+		let now = tx.clock();
+		let expired = Timestamp {
+			value: now.value.saturating_sub(self.lease_duration.as_millis() as u64),
+		};
+		// Each heartbeat is stored separately, so a node is only dead once all of them are old
+		let hbs = tx
+			.scan_hb(
+				&Timestamp {
+					value: u64::MAX - 1,
+				},
+				u32::MAX,
+			)
+			.await?;
+		let (old, new): (Vec<Hb>, Vec<Hb>) = hbs.into_iter().partition(|hb| hb.hb <= expired);
+		tx.delr_hb(old.clone(), 1000).await?;
+		let mut dead: Vec<Uuid> = vec![];
+		for hb in old {
+			let nd = Uuid::from(hb.nd);
+			if nd == self.id || dead.contains(&nd) || new.iter().any(|v| v.nd == hb.nd) {
+				continue;
+			}
+			trace!("Removing expired node {}", nd);
+			tx.del_nd(hb.nd).await?;
+			// Drop the notifications nobody will deliver
+			tx.delr_nt(&hb.nd).await?;
+			dead.push(nd);
+		}
+		self.archive_dead_lqs(tx, &dead, &self.id).await
+	}
+
+	// Attempts to acquire or renew the lease of a cluster-wide background job,
+	// returning whether this node holds the lease.
+	pub async fn acquire_lease(&self, job: &str) -> Result<bool, Error> {
+		let mut tx = self.transaction(true, false).await?;
+		let timestamp = tx.clock();
+		match self.acquire_lease_full(&mut tx, job, self.id.clone(), timestamp).await {
+			Ok(true) => tx.commit().await.map(|_| true),
+			// Another node holds the lease, or acquired it at the same time
+			Ok(false) | Err(Error::TxConditionNotMet) => tx.cancel().await.map(|_| false),
+			Err(e) => {
+				tx.cancel().await?;
+				Err(e)
+			}
+		}
+	}
+
+	// Attempts to acquire or renew the lease of a cluster-wide background job
+	// for the given node at the given timestamp. Intended for testing.
+	// The lease can be taken over once it has expired, or once the node which
+	// holds it has been removed from the cluster.
+	pub async fn acquire_lease_full(
+		&self,
+		tx: &mut Transaction,
+		job: &str,
+		node_id: Uuid,
+		timestamp: Timestamp,
+	) -> Result<bool, Error> {
+		let lease = tx.get_ld(job).await?;
+		let free = match &lease {
+			None => true,
+			Some(v) if v.nd == node_id.0 || v.expires <= timestamp => true,
+			Some(v) => tx.get_nd(v.nd).await?.is_none(),
+		};
+		if free {
+			let expires = Timestamp {
+				value: timestamp.value + self.lease_duration.as_millis() as u64,
+			};
+			let val = Lease {
+				nd: node_id.0,
+				expires,
+			};
+			tx.putc_ld(job, val, lease).await?;
+		}
+		Ok(free)
+	}
+
+	// Renews the lease of a cluster-wide background job between the batches of
+	// a long running job, failing if the lease has been taken over by another node.
+	pub async fn renew_lease(&self, job: &str) -> Result<(), Error> {
+		match self.acquire_lease(job).await? {
+			true => Ok(()),
+			false => Err(Error::LdNotHeld {
+				value: job.to_owned(),
+			}),
+		}
+	}
+
+	// Releases the lease of a cluster-wide background job if this node holds it,
+	// so that another node can take over the job straight away.
+	pub async fn release_lease(&self, job: &str) -> Result<(), Error> {
+		let mut tx = self.transaction(true, false).await?;
+		match tx.get_ld(job).await? {
+			Some(v) if v.nd == self.id.0 => {
+				tx.del_ld(job).await?;
+				tx.commit().await
+			}
+			_ => tx.cancel().await,
+		}
+	}
+
+	/// Run a cluster-wide background job on a single node of the cluster
+	///
+	/// The job only runs if this node holds, or is able to acquire, the lease
+	/// for the job. The node keeps the lease for as long as it keeps running
+	/// the job, and another healthy node takes over the job once the lease has
+	/// expired, or once this node has been removed from the cluster. Returns
+	/// `None` when the job is being run by another node. A job which runs in
+	/// several batches should call [`Datastore::renew_lease`] between them, so
+	/// that it stops once another node has taken over the job.
+	pub async fn singleton<F, T>(&self, job: &str, run: F) -> Result<Option<T>, Error>
+	where
+		F: Future<Output = Result<T, Error>>,
+	{
+		match self.acquire_lease(job).await? {
+			true => match run.await {
+				Ok(v) => Ok(Some(v)),
+				// Another node took over the job while it was running
+				Err(Error::LdNotHeld {
+					..
+				}) => Ok(None),
+				Err(e) => Err(e),
+			},
+			false => Ok(None),
+		}
+	}
+
+	// Delivers the live query notifications which other nodes have queued
+	// for this node to the local notification channel.
+	// This is intended to be polled at a short interval by the server.
+	pub async fn process_notifications(&self) -> Result<(), Error> {
+		// Check if notifications are enabled
+		let chn = match &self.notification_channel {
+			Some(chn) => chn.0.clone(),
+			None => return Ok(()),
+		};
+		// Read the queued notifications, oldest first
+		let mut tx = self.transaction(false, false).await?;
+		let nts = tx.scan_nt(&self.id.0, 1000).await;
+		tx.cancel().await?;
+		let nts = nts?;
+		if nts.is_empty() {
+			return Ok(());
+		}
+		// Send the notifications to the local subscribers
+		let mut res = Ok(());
+		let mut sent = Vec::with_capacity(nts.len());
+		for (nt, notification) in nts {
+			if let Err(e) = chn.send(notification).await {
+				res = Err(e.into());
+				break;
+			}
+			sent.push(nt);
+		}
+		// Only take the delivered notifications off the queue
+		if !sent.is_empty() {
+			let mut tx = self.transaction(true, false).await?;
+			for nt in sent {
+				if let Err(e) = tx.del_nt(nt).await {
+					tx.cancel().await?;
+					return Err(e);
+				}
+			}
+			tx.commit().await?;
+		}
+		res
+	}
+
+	// Performs the periodic housekeeping of the change feeds, using the
+	// current time in seconds since the unix epoch.
+	// This is intended to be run at a regular interval by the server.
+	pub async fn tick(&self) -> Result<(), Error> {
+		let tx = self.transaction(false, false).await?;
+		let ts = tx.clock().value / 1000;
+		self.tick_at(ts).await
+	}
+
+	// Performs the periodic housekeeping of the change feeds at the given
+	// timestamp in seconds. Intended for testing.
+	// This sends a heartbeat for this node, renews the claims of the scheduled
+	// jobs which this node is running, and then runs each of the cluster-wide
+	// background jobs which this node holds the lease for.
+	// These remove the expired nodes, record the current versionstamp of
+	// every database, delete the expired records, and garbage collect the
+	// change feed entries which are no longer needed. Finally the stored
+	// changes to the storage statistics are merged, the indexes which were
+	// defined to be built in the background are built, and the scheduled
+	// jobs which are due are claimed, to be run by `process_jobs`.
+	pub async fn tick_at(&self, ts: u64) -> Result<(), Error> {
+		self.heartbeat().await?;
+		self.renew_scheduled_job_claims().await?;
+		self.singleton("nodes", self.remove_expired_nodes()).await?;
+		self.singleton("versionstamps", self.save_timestamp_for_versionstamp(ts)).await?;
+		self.singleton("expiry", self.delete_expired_records(ts)).await?;
+		self.singleton("changefeeds", self.garbage_collect_stale_change_feeds(ts)).await?;
+		self.singleton("stats", self.merge_storage_stats()).await?;
+		self.singleton("indexes", self.build_indexes()).await?;
+		self.singleton("jobs", self.claim_scheduled_jobs(ts)).await?;
+		Ok(())
+	}
+
+	// Claims the scheduled jobs of every database which are due by the given
+	// timestamp in seconds. A job which missed several of its scheduled times,
+	// because the ticks are further apart than its schedule, only runs once.
+	async fn claim_scheduled_jobs(&self, ts: u64) -> Result<(), Error> {
+		let Some(at) = Utc.timestamp_opt(ts as i64, 0).single() else {
+			return Ok(());
+		};
+		let mut tx = self.transaction(false, false).await?;
+		let mut jbs = vec![];
+		for ns in tx.all_ns().await?.iter() {
+			for db in tx.all_db(&ns.name).await?.iter() {
+				for jb in tx.all_jb(&ns.name, &db.name).await?.iter() {
+					jbs.push((ns.name.to_raw(), db.name.to_raw(), jb.clone()));
+				}
+			}
+		}
+		tx.cancel().await?;
+		for (ns, db, jb) in jbs {
+			self.renew_lease("jobs").await?;
+			// A job which can not be claimed does not hold up the other jobs
+			match self.claim_scheduled_job(&ns, &db, &jb, at).await {
+				Ok(Some(jr)) => {
+					self.claims.lock().await.insert((ns.clone(), db.clone(), jb.name.to_raw()));
+					let due = DueJob {
+						ns,
+						db,
+						jb,
+						jr,
+						at,
+					};
+					self.jobs.0.send(due).await?;
+				}
+				Ok(None) => {}
+				Err(e) => {
+					warn!("Scheduled job {} in {}/{} could not be claimed: {}", jb.name, ns, db, e)
+				}
+			}
+		}
+		Ok(())
+	}
+
+	// Claims the run of a scheduled job if it is due, by moving the job on to
+	// its next scheduled time, and storing the claim of this node until the
+	// run is recorded. A run which was claimed by a node which did not record
+	// it, once that node has been removed or its claim has expired, is claimed
+	// again. Returns the outcome of the last run if it is due.
+	async fn claim_scheduled_job(
+		&self,
+		ns: &str,
+		db: &str,
+		jb: &DefineJobStatement,
+		at: DateTime<Utc>,
+	) -> Result<Option<JobRun>, Error> {
+		let key = crate::key::database::jr::new(ns, db, &jb.name);
+		let mut tx = self.transaction(true, false).await?;
+		let mut jr = match tx.get_jr(ns, db, &jb.name).await {
+			Ok(jr) => jr.unwrap_or_default(),
+			Err(e) => {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		};
+		let now = tx.clock();
+		// Check if a run which was claimed, but not recorded, was lost
+		let lost = match &jr.claim {
+			None => false,
+			Some(v) if v.nd == self.id.0 => {
+				let run = (ns.to_owned(), db.to_owned(), jb.name.to_raw());
+				!self.claims.lock().await.contains(&run)
+			}
+			Some(v) if v.expires <= now => true,
+			Some(v) => match tx.get_nd(v.nd).await {
+				Ok(nd) => nd.is_none(),
+				Err(e) => {
+					tx.cancel().await?;
+					return Err(e);
+				}
+			},
+		};
+		// The claimed run is still being run
+		if jr.claim.is_some() && !lost {
+			return tx.cancel().await.map(|_| None);
+		}
+		let scheduled = match &jr.next {
+			Some(next) if next.0 > at => {
+				if !lost {
+					return tx.cancel().await.map(|_| None);
+				}
+				false
+			}
+			Some(_) => true,
+			// A newly defined job is scheduled from the first tick after it
+			None => false,
+		};
+		if scheduled || jr.next.is_none() {
+			jr.next = jb.schedule.next_after(at).map(Datetime::from);
+		}
+		let due = lost || scheduled;
+		jr.claim = due.then(|| Lease {
+			nd: self.id.0,
+			expires: Timestamp {
+				value: now.value + self.lease_duration.as_millis() as u64,
+			},
+		});
+		if let Err(e) = tx.set(key, jr.clone()).await {
+			tx.cancel().await?;
+			return Err(e);
+		}
+		tx.commit().await?;
+		Ok(due.then_some(jr))
+	}
+
+	// Renews the claims of the scheduled jobs which this node has claimed,
+	// but not yet recorded, so that they are not claimed by another node.
+	async fn renew_scheduled_job_claims(&self) -> Result<(), Error> {
+		let claims = self.claims.lock().await.clone();
+		if claims.is_empty() {
+			return Ok(());
+		}
+		let mut tx = self.transaction(true, false).await?;
+		let expires = Timestamp {
+			value: tx.clock().value + self.lease_duration.as_millis() as u64,
+		};
+		for (ns, db, jb) in claims {
+			let key = crate::key::database::jr::new(&ns, &db, &jb);
+			let res = match tx.get_jr(&ns, &db, &jb).await {
+				Ok(Some(mut jr)) => match &mut jr.claim {
+					Some(v) if v.nd == self.id.0 => {
+						v.expires = expires.clone();
+						tx.set(key, jr).await
+					}
+					_ => Ok(()),
+				},
+				Ok(None) => Ok(()),
+				Err(e) => Err(e),
+			};
+			if let Err(e) = res {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		}
+		tx.commit().await
+	}
+
+	/// Run the scheduled jobs which this node has claimed
+	///
+	/// The scheduled jobs which are due are claimed by the node agent tick,
+	/// and are run separately so that a long running job does not hold up
+	/// the tick. The claimed jobs are run concurrently, and the outcome of
+	/// each run is recorded with the job. This is intended to be polled at
+	/// a regular interval by the server.
+	pub async fn process_jobs(&self) {
+		let mut runs = vec![];
+		while let Ok(due) = self.jobs.1.try_recv() {
+			runs.push(self.run_scheduled_job(due));
+		}
+		futures::future::join_all(runs).await;
+	}
+
+	// Runs a claimed scheduled job, and records the outcome of the run
+	async fn run_scheduled_job(&self, due: DueJob) {
+		let DueJob {
+			ns,
+			db,
+			jb,
+			mut jr,
+			at,
+		} = due;
+		// Run the job with the identity which it was defined with
+		let sess = match &jb.base {
+			Base::Ns => Session::for_ns(&ns).with_db(&db),
+			Base::Sc(sc) => Session::for_sc(ns.as_str(), db.as_str(), sc.as_str()),
+			_ => Session::for_db(&ns, &db),
+		};
+		let val = Value::Block(Box::new(jb.block.clone()));
+		let now = Instant::now();
+		let res = self.compute(val, &sess, None).await;
+		jr.at = Some(Datetime::from(at));
+		jr.duration = Some(now.elapsed().into());
+		jr.error = res.err().map(|e| e.to_string());
+		if let Some(e) = &jr.error {
+			warn!("Scheduled job {} in {}/{} failed: {}", jb.name, ns, db, e);
+		}
+		if let Err(e) = self.record_scheduled_job(&ns, &db, &jb, jr).await {
+			warn!("Scheduled job {} in {}/{} could not be recorded: {}", jb.name, ns, db, e);
+		}
+		self.claims.lock().await.remove(&(ns, db, jb.name.to_raw()));
+	}
+
+	// Records the outcome of a run of a scheduled job, and releases the claim
+	// of the run, unless the job was redefined or removed while it was running,
+	// or the run was claimed again by another node
+	async fn record_scheduled_job(
+		&self,
+		ns: &str,
+		db: &str,
+		jb: &DefineJobStatement,
+		mut jr: JobRun,
+	) -> Result<(), Error> {
+		let key = crate::key::database::jr::new(ns, db, &jb.name);
+		let mut tx = self.transaction(true, false).await?;
+		match tx.get_jb(ns, db, &jb.name).await {
+			Ok(v) if &v == jb => {}
+			Ok(_)
+			| Err(Error::JbNotFound {
+				..
+			}) => return tx.cancel().await,
+			Err(e) => {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		}
+		match tx.get_jr(ns, db, &jb.name).await {
+			Ok(Some(v)) if v.claim.as_ref().map(|v| v.nd) == Some(self.id.0) => jr.next = v.next,
+			Ok(_) => return tx.cancel().await,
+			Err(e) => {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		}
+		jr.claim = None;
+		if let Err(e) = tx.set(key, jr).await {
+			tx.cancel().await?;
+			return Err(e);
+		}
+		tx.commit().await
+	}
+
+	// Deletes the records which have expired by the given timestamp in seconds,
+	// from every table which has a TTL. The records are deleted with a normal
+	// DELETE statement, so that events, live queries and change feeds see them.
+	async fn delete_expired_records(&self, ts: u64) -> Result<(), Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let mut tbs = vec![];
+		for ns in tx.all_ns().await?.iter() {
+			for db in tx.all_db(&ns.name).await?.iter() {
+				for tb in tx.all_tb(&ns.name, &db.name).await?.iter() {
+					if tb.ttl.is_some() {
+						tbs.push((ns.name.to_raw(), db.name.to_raw(), tb.name.to_raw()));
+					}
+				}
+			}
+		}
+		tx.cancel().await?;
+		for (ns, db, tb) in tbs {
+			self.renew_lease("expiry").await?;
+			while self.delete_expired_records_in(&ns, &db, &tb, ts).await? {
+				self.renew_lease("expiry").await?;
+			}
+		}
+		Ok(())
+	}
+
+	// Deletes a batch of expired records from a table, returning whether there may be more
+	async fn delete_expired_records_in(
+		&self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ts: u64,
+	) -> Result<bool, Error> {
+		let tx = self.transaction(true, false).await?;
+		let txn = Arc::new(Mutex::new(tx));
+		// Buffer the live query notifications until the deletion has been committed
+		let (send, recv) = channel::unbounded();
+		let res = self.delete_expired_records_impl(&txn, send, ns, db, tb, ts).await;
+		let mut tx = txn.lock().await;
+		let more = match res {
+			Ok(more) => more,
+			Err(e) => {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		};
+		tx.commit().await?;
+		if let Some(chn) = &self.notification_channel {
+			while let Ok(v) = recv.try_recv() {
+				chn.0.send(v).await?;
+			}
+		}
+		Ok(more)
+	}
+
+	async fn delete_expired_records_impl(
+		&self,
+		txn: &crate::dbs::Transaction,
+		send: Sender<Notification>,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ts: u64,
+	) -> Result<bool, Error> {
+		const BATCH: u32 = 1000;
+		let ids = txn.lock().await.scan_expired(ns, db, tb, ts, BATCH).await?;
+		if ids.is_empty() {
+			return Ok(false);
+		}
+		// Delete the records with full permissions
+		let opt = Options::default()
+			.with_id(self.id.0)
+			.with_ns(Some(ns.into()))
+			.with_db(Some(db.into()))
+			.with_auth(Arc::new(Auth::Kv))
+			.with_strict(self.strict)
+			.with_expired(true)
+			.new_with_sender(send);
+		let stm = DeleteStatement {
+			what: Values(ids.iter().map(|id| Value::from(Thing::from((tb, id.clone())))).collect()),
+			..DeleteStatement::default()
+		};
+		stm.compute(&Context::default(), &opt, txn, None).await?;
+		let mut tx = txn.lock().await;
+		// Remove any expiry entries which were left without a record
+		for id in ids.iter() {
+			tx.set_expiry(ns, db, tb, id, None).await?;
+		}
+		tx.complete_changes(false).await?;
+		Ok(ids.len() == BATCH as usize)
+	}
+
+	// Builds the indexes which were defined to be built in the background,
+	// each in batches of records which are indexed within their own transaction.
+	async fn build_indexes(&self) -> Result<(), Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let ixs = self.building_indexes(&mut tx).await;
+		tx.cancel().await?;
+		for (ns, db, tb, ix) in ixs? {
+			self.renew_lease("indexes").await?;
+			while self.build_index_in(&ns, &db, &tb, &ix).await? {
+				self.renew_lease("indexes").await?;
+			}
+		}
+		Ok(())
+	}
+
+	// Lists the namespace, database, table, and name of every index which is
+	// being built in the background
+	async fn building_indexes(
+		&self,
+		tx: &mut Transaction,
+	) -> Result<Vec<(String, String, String, String)>, Error> {
+		let mut out = vec![];
+		for (ns, db, tb) in self.all_tables(tx).await? {
+			for ix in tx.all_ix(&ns, &db, &tb).await?.iter() {
+				let key = crate::key::index::ib::new(&ns, &db, &tb, &ix.name);
+				if ix.concurrently && tx.exi(key).await? {
+					out.push((ns.clone(), db.clone(), tb.clone(), ix.name.to_raw()));
+				}
+			}
+		}
+		Ok(out)
+	}
+
+	// Indexes a batch of records of a table, returning whether there may be more
+	async fn build_index_in(&self, ns: &str, db: &str, tb: &str, ix: &str) -> Result<bool, Error> {
+		let tx = self.transaction(true, false).await?;
+		let txn = Arc::new(Mutex::new(tx));
+		let res = self.build_index_impl(&txn, ns, db, tb, ix).await;
+		let mut tx = txn.lock().await;
+		let more = match res {
+			Ok(more) => more,
+			Err(e) => {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		};
+		tx.commit().await?;
+		Ok(more)
+	}
+
+	async fn build_index_impl(
+		&self,
+		txn: &crate::dbs::Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ix: &str,
+	) -> Result<bool, Error> {
+		const BATCH: u32 = 1000;
+		let mut run = txn.lock().await;
+		// The build stores the last record which was indexed
+		let key = crate::key::index::ib::new(ns, db, tb, ix);
+		let Some(last) = run.get(key.clone()).await? else {
+			// The index was built, redefined, or removed meanwhile
+			return Ok(false);
+		};
+		let base = run.tb_base(ns, db, tb).await?;
+		let beg = match last.is_empty() {
+			true => crate::key::thing::prefix(ns, db, tb),
+			false => [last.as_slice(), &[0]].concat(),
+		};
+		let end = crate::key::thing::suffix(ns, db, tb);
+		let res = run.scan(base.range(beg..end), BATCH).await?;
+		// The index is used once every record has been indexed
+		let Some((last, _)) = res.last() else {
+			run.del(key).await?;
+			run.clr(crate::key::table::ix::prefix(ns, db, tb)).await?;
+			return Ok(false);
+		};
+		run.set(key, base.name(last.clone())).await?;
+		drop(run);
+		let what = res.into_iter().map(|(k, _)| {
+			let key = base.name(k);
+			let key: crate::key::thing::Thing = (&key).into();
+			Value::Thing(Thing::from((key.tb.to_owned(), key.id)))
+		});
+		// Rewrite the index data of the records with full permissions
+		let opt = Options::default()
+			.with_id(self.id.0)
+			.with_ns(Some(ns.into()))
+			.with_db(Some(db.into()))
+			.with_auth(Arc::new(Auth::Kv))
+			.with_strict(self.strict)
+			.with_expired(true)
+			.with_force(true)
+			.with_fields(false)
+			.with_events(false)
+			.with_tables(false);
+		let stm = UpdateStatement {
+			what: Values(what.collect()),
+			..UpdateStatement::default()
+		};
+		stm.compute(&Context::default(), &opt, txn, None).await?;
+		txn.lock().await.complete_changes(false).await?;
+		Ok(true)
+	}
+
+	// Merges the stored changes to the storage statistics of every table,
+	// each table within its own transaction. The statistics of the tables
+	// are counted from the stored data first, if they have not been counted.
+	async fn merge_storage_stats(&self) -> Result<(), Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let counted = tx.exi(crate::key::root::st::new()).await;
+		let tbs = self.all_tables(&mut tx).await;
+		tx.cancel().await?;
+		let (counted, tbs) = (counted?, tbs?);
+		match counted {
+			true => {
+				for (ns, db, tb) in tbs.iter() {
+					self.renew_lease("stats").await?;
+					self.merge_table_stats(ns, db, tb).await?;
+				}
+			}
+			false => {
+				let dbs: BTreeSet<_> = tbs.into_iter().map(|(ns, db, _)| (ns, db)).collect();
+				for (ns, db) in dbs.iter() {
+					self.renew_lease("stats").await?;
+					self.count_database_stats(ns, db).await?;
+				}
+			}
+		}
+		if !counted {
+			let mut tx = self.transaction(true, false).await?;
+			tx.set(crate::key::root::st::new(), vec![]).await?;
+			tx.commit().await?;
+		}
+		Ok(())
+	}
+
+	/// Recount the storage statistics of every table from the stored data
+	///
+	/// The statistics are maintained as data is written, so this is only
+	/// needed if they no longer match the stored data. The statistics of data
+	/// which was stored before they were maintained are counted automatically.
+	/// The tables are counted by the next node agent tick, so that the count
+	/// never runs at the same time as the merging of the statistics.
+	pub async fn recount_storage_stats(&self) -> Result<(), Error> {
+		let mut tx = self.transaction(true, false).await?;
+		tx.del(crate::key::root::st::new()).await?;
+		tx.commit().await
+	}
+
+	// Lists the namespace, database, and name of every table
+	async fn all_tables(
+		&self,
+		tx: &mut Transaction,
+	) -> Result<Vec<(String, String, String)>, Error> {
+		let mut out = vec![];
+		for ns in tx.all_ns().await?.iter() {
+			for db in tx.all_db(&ns.name).await?.iter() {
+				for tb in tx.all_tb(&ns.name, &db.name).await?.iter() {
+					out.push((ns.name.to_raw(), db.name.to_raw(), tb.name.to_raw()));
+				}
+			}
+		}
+		Ok(out)
+	}
+
+	// Merges the stored changes to the storage statistics of a table
+	async fn merge_table_stats(&self, ns: &str, db: &str, tb: &str) -> Result<(), Error> {
+		let mut tx = self.transaction(true, false).await?;
+		if let Err(e) = tx.merge_stats(ns, db, tb).await {
+			tx.cancel().await?;
+			return Err(e);
+		}
+		tx.commit().await
+	}
+
+	// Replaces the storage statistics of the tables of a database with a count of
+	// the stored data. The data and the stored changes to the statistics are read
+	// at the same snapshot, so only the changes which the count includes are
+	// replaced, and any changes which are committed in the meantime are kept.
+	async fn count_database_stats(&self, ns: &str, db: &str) -> Result<(), Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let res = async {
+			let mut out = vec![];
+			for (tb, st) in tx.count_stats(ns, db).await? {
+				let (_, keys) = tx.stored_stats(ns, db, &tb).await?;
+				out.push((tb, st, keys));
+			}
+			Ok::<_, Error>(out)
+		}
+		.await;
+		tx.cancel().await?;
+		for (tb, st, keys) in res? {
+			let mut tx = self.transaction(true, false).await?;
+			// Statistics are not kept for tables which have been removed
+			let res = match tx.exi(crate::key::database::tb::new(ns, db, &tb)).await {
+				Ok(true) => tx.replace_stats(ns, db, &tb, keys, st).await,
+				Ok(false) => Ok(()),
+				Err(e) => Err(e),
+			};
+			if let Err(e) = res {
+				tx.cancel().await?;
+				return Err(e);
+			}
+			tx.commit().await?;
+		}
+		Ok(())
+	}
+
+	// Records the current versionstamp of every database at the given timestamp
+	async fn save_timestamp_for_versionstamp(&self, ts: u64) -> Result<(), Error> {
+		let mut tx = self.transaction(true, false).await?;
+		if let Err(e) = self.save_timestamp_for_versionstamp_impl(ts, &mut tx).await {
+			tx.cancel().await?;
+			return Err(e);
+		}
+		tx.commit().await
+	}
+
+	async fn save_timestamp_for_versionstamp_impl(
+		&self,
+		ts: u64,
+		tx: &mut Transaction,
+	) -> Result<(), Error> {
+		let nses = tx.all_ns().await?;
+		for ns in nses.iter() {
+			let dbs = tx.all_db(&ns.name).await?;
+			for db in dbs.iter() {
+				tx.set_timestamp_for_versionstamp(ts, &ns.name, &db.name).await?;
+			}
+		}
+		Ok(())
+	}
+
+	// Deletes the change feed entries which are no longer needed at the given timestamp
+	async fn garbage_collect_stale_change_feeds(&self, ts: u64) -> Result<(), Error> {
+		let mut tx = self.transaction(true, false).await?;
+		if let Err(e) = cf::gc_all_at(&mut tx, ts, None).await {
+			tx.cancel().await?;
+			return Err(e);
+		}
+		// Write the changes to the storage statistics
+		if let Err(e) = tx.complete_changes(false).await {
+			tx.cancel().await?;
+			return Err(e);
+		}
+		tx.commit().await
+	}
+
 	// -----
 	// End cluster helpers, storage functions here
 	// -----
@@ -507,6 +1723,11 @@ impl Datastore {
 				let tx = v.transaction(write, lock).await?;
 				super::tx::Inner::SpeeDB(tx)
 			}
+			#[cfg(feature = "kv-redb")]
+			Inner::Redb(v) => {
+				let tx = v.transaction(write, lock).await?;
+				super::tx::Inner::Redb(tx)
+			}
 			#[cfg(feature = "kv-indxdb")]
 			Inner::IndxDB(v) => {
 				let tx = v.transaction(write, lock).await?;
@@ -531,6 +1752,16 @@ impl Datastore {
 			inner,
 			cache: super::cache::Cache::default(),
 			cf: cf::Writer::new(),
+			enc: self.encryption.clone(),
+			sp: super::savepoint::Savepoints::default(),
+			kf: self.key_format(),
+			st: super::stats::Writer::default(),
+			us: HashMap::new(),
+			vo: self.versionstamp_oracle,
+			ld: self.lease_duration,
+			nd: self.id.0,
+			#[cfg(test)]
+			conflicts: self.conflicts.clone(),
 		})
 	}
 
@@ -711,4 +1942,136 @@ impl Datastore {
 		// Everything ok
 		Ok(())
 	}
+
+	/// Performs a full backup of the entire datastore
+	///
+	/// The backup is written in a portable binary format, which can be
+	/// restored into a datastore using any storage engine. All of the data is
+	/// read from a single snapshot, so the backup is consistent even while the
+	/// datastore is being written to. Values are stored decrypted, so that a
+	/// backup can be restored into a datastore with a different encryption key.
+	#[instrument(skip(self, chn))]
+	pub async fn backup(&self, chn: Sender<Vec<u8>>) -> Result<(), Error> {
+		// Start a new transaction
+		let mut txn = self.transaction(false, false).await?;
+		// The encryption check value and salt are specific to this datastore
+		let chk: Key = crate::key::root::ek::new().into();
+		let salt: Key = crate::key::root::es::new().into();
+		// Write the backup in batches
+		let mut out = backup::Writer::default();
+		chn.send(out.header()).await?;
+		let beg: Key = crate::key::root::all::new().into();
+		let mut scanner = Scanner::new(beg..vec![0xff]);
+		loop {
+			let res = scanner.next_batch(&mut txn).await?;
+			if res.is_empty() {
+				break;
+			}
+			// Write the batch to the backup
+			let res: Vec<_> = res.into_iter().filter(|(k, _)| *k != chk && *k != salt).collect();
+			chn.send(out.batch(&res)?).await?;
+		}
+		chn.send(out.end()).await?;
+		txn.cancel().await
+	}
+
+	/// Restores a full backup of an entire datastore
+	///
+	/// The backup can only be restored into an empty datastore. The data is
+	/// written in a separate transaction for each batch of the backup, so an
+	/// incomplete or corrupted backup leaves the datastore partially restored.
+	#[instrument(skip(self, stream))]
+	pub async fn restore<S, B>(&self, mut stream: S) -> Result<(), Error>
+	where
+		S: Stream<Item = Result<B, Error>> + Unpin,
+		B: AsRef<[u8]>,
+	{
+		// Check that the datastore does not contain any data
+		let mut txn = self.transaction(false, false).await?;
+		let ns =
+			txn.scan(crate::key::root::ns::prefix()..crate::key::root::ns::suffix(), 1).await?;
+		let data = txn.scan(b"/*".to_vec()..b"/+".to_vec(), 1).await?;
+		txn.cancel().await?;
+		if !ns.is_empty() || !data.is_empty() {
+			return Err(Error::Backup(
+				"Unable to restore into a datastore which contains data".into(),
+			));
+		}
+		// Read the backup as it is received
+		let kf: Key = crate::key::root::kf::new().into();
+		let mut has_kf = false;
+		let mut inp = backup::Reader::default();
+		while let Some(chunk) = stream.next().await {
+			inp.push(chunk?.as_ref());
+			while let Some(frame) = inp.next()? {
+				if let backup::Frame::Batch(res) = frame {
+					let mut txn = self.transaction(true, false).await?;
+					for (k, v) in res {
+						has_kf |= k == kf;
+						txn.set(k, v).await?;
+					}
+					txn.commit().await?;
+				}
+			}
+		}
+		inp.finish()?;
+		// Backups taken before the key format was recorded use the original key format
+		match has_kf {
+			true => self.check_key_format().await,
+			false => self.set_key_format(KeyFormat::V1).await,
+		}
+	}
+
+	/// Replays the change feeds from a backup on top of this datastore
+	///
+	/// This recovers a datastore to a point in time. An earlier backup is
+	/// first restored with [`Datastore::restore`], and then the changes
+	/// recorded in the change feeds of a later backup are replayed, up to the
+	/// specified restore point. Only tables with a change feed are recovered,
+	/// and only changes still within the change feed retention period of the
+	/// later backup can be replayed. When recovering to a timestamp, changes
+	/// are replayed up to the last versionstamp recorded before that time.
+	#[instrument(skip(self, stream))]
+	pub async fn replay<S, B>(&self, mut stream: S, until: RestorePoint) -> Result<(), Error>
+	where
+		S: Stream<Item = Result<B, Error>> + Unpin,
+		B: AsRef<[u8]>,
+	{
+		// Read the backup as it is received
+		let mut inp = backup::Reader::default();
+		let mut rep = cf::Replay::new(until);
+		while let Some(chunk) = stream.next().await {
+			inp.push(chunk?.as_ref());
+			while let Some(frame) = inp.next()? {
+				if let backup::Frame::Batch(res) = frame {
+					for (k, v) in res {
+						rep.push(self, &k, v).await?;
+					}
+				}
+			}
+		}
+		inp.finish()?;
+		rep.finish(self).await
+	}
+
+	/// Creates a checkpoint of the datastore in the specified directory
+	///
+	/// A checkpoint is a copy of the entire datastore which can be opened
+	/// directly, and which shares unchanged data files with this datastore
+	/// where possible, so it is much faster to create than a full backup.
+	/// Checkpoints are only supported by the RocksDB and SpeeDB engines.
+	#[instrument(skip(self))]
+	pub async fn checkpoint(&self, path: &str) -> Result<(), Error> {
+		#[allow(unused_variables)]
+		match &self.inner {
+			#[cfg(feature = "kv-rocksdb")]
+			Inner::RocksDB(v) => v.checkpoint(path).await,
+			#[cfg(feature = "kv-speedb")]
+			Inner::SpeeDB(v) => v.checkpoint(path).await,
+			#[allow(unreachable_patterns)]
+			_ => Err(Error::Unimplemented(format!(
+				"Checkpoints are not supported by the {self} storage engine"
+			))),
+		}
+	}
 }
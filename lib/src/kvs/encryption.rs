@@ -0,0 +1,236 @@
+//! Transparent encryption of the values stored in the key-value store.
+//!
+//! Values are encrypted with AES-256-GCM, using a key derived from the
+//! encryption key specified when starting the datastore with Argon2id and a
+//! random salt, which is generated once for each datastore and stored with
+//! it. Keys are stored unencrypted, so that range scans across the keyspace
+//! remain possible.
+//!
+//! Each encrypted value is stored in the following format:
+//!
+//! ```text
+//! [version: 1 byte][key id: 4 bytes][nonce: 12 bytes][ciphertext + tag]
+//! ```
+//!
+//! The key id identifies which encryption key was used to encrypt a value,
+//! which allows values encrypted with a previous key to be detected, and
+//! re-encrypted, when the encryption key is rotated.
+use super::Val;
+use crate::err::Error;
+use aes_gcm::aead::Aead;
+use aes_gcm::Aes256Gcm;
+use aes_gcm::KeyInit;
+use aes_gcm::Nonce;
+use argon2::Argon2;
+use sha2::Digest;
+use sha2::Sha256;
+use std::fmt;
+
+/// The current version of the encrypted value format
+const VERSION: u8 = 1;
+/// The length of the key id which prefixes each value
+const KEY_ID_LEN: usize = 4;
+/// The length of the random nonce used for each value
+const NONCE_LEN: usize = 12;
+/// The length of the header which prefixes each value
+const HEADER_LEN: usize = 1 + KEY_ID_LEN + NONCE_LEN;
+/// The length of the random salt which keys are derived with
+const SALT_LEN: usize = 16;
+
+/// An AEAD cipher derived from a single encryption key
+pub(crate) struct Cipher {
+	id: [u8; KEY_ID_LEN],
+	aead: Aes256Gcm,
+}
+
+impl Cipher {
+	/// Derive a cipher from the specified encryption key and salt
+	pub fn new(key: &str, salt: &[u8]) -> Result<Self, Error> {
+		// Derive a 256 bit key from the encryption key
+		let mut out = [0; 32];
+		Argon2::default()
+			.hash_password_into(key.as_bytes(), salt, &mut out)
+			.map_err(|e| Error::Encryption(format!("Unable to derive the encryption key: {e}")))?;
+		// Derive a key id from the derived key
+		let hash =
+			Sha256::new().chain_update(b"surrealdb:encryption:id").chain_update(out).finalize();
+		let mut id = [0; KEY_ID_LEN];
+		id.copy_from_slice(&hash[..KEY_ID_LEN]);
+		Ok(Self {
+			id,
+			aead: Aes256Gcm::new(&out.into()),
+		})
+	}
+
+	/// Encrypt a value with this cipher
+	fn encrypt(&self, val: &[u8]) -> Result<Val, Error> {
+		let nonce: [u8; NONCE_LEN] = rand::random();
+		let data = self
+			.aead
+			.encrypt(Nonce::from_slice(&nonce), val)
+			.map_err(|_| Error::Encryption("Unable to encrypt a value".to_owned()))?;
+		let mut out = Vec::with_capacity(HEADER_LEN + data.len());
+		out.push(VERSION);
+		out.extend_from_slice(&self.id);
+		out.extend_from_slice(&nonce);
+		out.extend_from_slice(&data);
+		Ok(out)
+	}
+
+	/// Decrypt a value which was encrypted with this cipher
+	fn decrypt(&self, val: &[u8]) -> Result<Val, Error> {
+		let nonce = Nonce::from_slice(&val[1 + KEY_ID_LEN..HEADER_LEN]);
+		self.aead
+			.decrypt(nonce, &val[HEADER_LEN..])
+			.map_err(|_| Error::Encryption("Unable to decrypt a corrupted value".to_owned()))
+	}
+}
+
+/// The encryption configuration for a datastore
+pub(crate) struct Encryption {
+	/// The key used to encrypt and decrypt values
+	current: Cipher,
+	/// The key which values were previously encrypted with
+	previous: Option<Cipher>,
+}
+
+impl fmt::Debug for Encryption {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Encryption").finish_non_exhaustive()
+	}
+}
+
+impl Encryption {
+	/// Derive the ciphers for the specified keys with the salt of a datastore
+	pub fn new(key: &str, previous: Option<&str>, salt: &[u8]) -> Result<Self, Error> {
+		Ok(Self {
+			current: Cipher::new(key, salt)?,
+			previous: previous.map(|key| Cipher::new(key, salt)).transpose()?,
+		})
+	}
+
+	/// Generate a new random salt for a datastore
+	pub fn salt() -> Val {
+		rand::random::<[u8; SALT_LEN]>().to_vec()
+	}
+
+	/// Encrypt a value with the current encryption key
+	pub fn encrypt(&self, val: &[u8]) -> Result<Val, Error> {
+		self.current.encrypt(val)
+	}
+
+	/// Decrypt a value with the key it was encrypted with
+	pub fn decrypt(&self, val: &[u8]) -> Result<Val, Error> {
+		match Self::key_id(val) {
+			Some(id) if id == self.current.id => self.current.decrypt(val),
+			Some(_) => match &self.previous {
+				Some(c) if self.is_previous(val) => c.decrypt(val),
+				_ => Err(Error::EncryptionKeyInvalid),
+			},
+			None => Err(Error::Encryption("The stored value is not encrypted".to_owned())),
+		}
+	}
+
+	/// Check if a value was encrypted with the current encryption key
+	pub fn is_current(&self, val: &[u8]) -> bool {
+		Self::key_id(val) == Some(&self.current.id)
+	}
+
+	/// Check if a value was encrypted with the previous encryption key
+	pub fn is_previous(&self, val: &[u8]) -> bool {
+		match &self.previous {
+			Some(c) => Self::key_id(val) == Some(&c.id),
+			None => false,
+		}
+	}
+
+	/// Check if the value for a key is stored unencrypted
+	///
+	/// The versionstamp counters are maintained directly by the underlying
+	/// datastores, and so the values for these keys are never encrypted. The
+	/// key format and the salt are read before the encryption key is checked,
+	/// and so are never encrypted either.
+	pub fn is_unencrypted(key: &[u8]) -> bool {
+		(key.ends_with(b"!vs") && crate::key::database::vs::Vs::decode(key).is_ok())
+			|| key == b"/!kf"
+			|| key == b"/!es"
+	}
+
+	/// Fetch the id of the key which was used to encrypt a value
+	fn key_id(val: &[u8]) -> Option<&[u8]> {
+		match val.first() {
+			Some(&VERSION) if val.len() >= HEADER_LEN => Some(&val[1..1 + KEY_ID_LEN]),
+			_ => None,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SALT: &[u8] = b"0123456789abcdef";
+
+	#[test]
+	fn encrypt_decrypt() {
+		let enc = Encryption::new("0123456789abcdef", None, SALT).unwrap();
+		let val = enc.encrypt(b"some value").unwrap();
+		assert_ne!(&val[HEADER_LEN..], b"some value");
+		assert!(enc.is_current(&val));
+		assert_eq!(enc.decrypt(&val).unwrap(), b"some value");
+		// Each value is encrypted with a unique nonce
+		assert_ne!(enc.encrypt(b"some value").unwrap(), val);
+	}
+
+	#[test]
+	fn decrypt_with_wrong_key() {
+		let val = Encryption::new("0123456789abcdef", None, SALT)
+			.unwrap()
+			.encrypt(b"some value")
+			.unwrap();
+		let enc = Encryption::new("fedcba9876543210", None, SALT).unwrap();
+		assert!(!enc.is_current(&val));
+		assert!(matches!(enc.decrypt(&val), Err(Error::EncryptionKeyInvalid)));
+	}
+
+	#[test]
+	fn decrypt_with_previous_key() {
+		let val = Encryption::new("0123456789abcdef", None, SALT)
+			.unwrap()
+			.encrypt(b"some value")
+			.unwrap();
+		let enc = Encryption::new("fedcba9876543210", Some("0123456789abcdef"), SALT).unwrap();
+		assert!(enc.is_previous(&val));
+		assert_eq!(enc.decrypt(&val).unwrap(), b"some value");
+		assert!(enc.is_current(&enc.encrypt(b"some value").unwrap()));
+	}
+
+	#[test]
+	fn unencrypted_keys() {
+		let key: Vec<u8> = crate::key::database::vs::new("test", "test").into();
+		assert!(Encryption::is_unencrypted(&key));
+		let key: Vec<u8> = crate::key::database::tb::new("test", "test", "vs").into();
+		assert!(!Encryption::is_unencrypted(&key));
+		let key: Vec<u8> = crate::key::root::kf::new().into();
+		assert!(Encryption::is_unencrypted(&key));
+		let key: Vec<u8> = crate::key::root::es::new().into();
+		assert!(Encryption::is_unencrypted(&key));
+	}
+
+	#[test]
+	fn decrypt_with_different_salt() {
+		let val =
+			Encryption::new("0123456789abcdef", None, SALT).unwrap().encrypt(b"value").unwrap();
+		let enc = Encryption::new("0123456789abcdef", None, &Encryption::salt()).unwrap();
+		assert!(!enc.is_current(&val));
+		assert!(matches!(enc.decrypt(&val), Err(Error::EncryptionKeyInvalid)));
+	}
+
+	#[test]
+	fn decrypt_tampered_value() {
+		let enc = Encryption::new("0123456789abcdef", None, SALT).unwrap();
+		let mut val = enc.encrypt(b"some value").unwrap();
+		*val.last_mut().unwrap() ^= 0x01;
+		assert!(enc.decrypt(&val).is_err());
+	}
+}
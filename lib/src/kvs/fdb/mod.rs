@@ -140,6 +140,7 @@ impl Transaction {
 		};
 		match r {
 			Ok(_r) => {}
+			Err(e) if e.is_retryable() => return Err(Error::TxRetryable),
 			Err(e) => {
 				return Err(Error::Tx(format!("Transaction commit error: {}", e)));
 			}
@@ -462,4 +463,48 @@ impl Transaction {
 		}
 		Ok(res)
 	}
+	/// Retrieve a range of keys from the databases, in reverse order
+	pub async fn scanr<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		// Scan the keys
+		let begin: Vec<u8> = rng.start;
+		let end: Vec<u8> = rng.end;
+		let opt = foundationdb::RangeOption {
+			limit: Some(limit.try_into().unwrap()),
+			reverse: true,
+			..foundationdb::RangeOption::from((begin.as_slice(), end.as_slice()))
+		};
+		let tx = self.tx.lock().await;
+		let tx = tx.as_ref().unwrap();
+		// Assuming the `lock` argument passed to the datastore creation function
+		// is meant for conducting a pessimistic lock on the underlying kv store to
+		// make the transaction serializable, we use the inverse of it to enable the snapshot isolation
+		// on the get request.
+		// See https://apple.github.io/foundationdb/api-c.html#snapshot-reads for more information on how the snapshot get is supposed to work in FDB.
+		let mut stream = tx.get_ranges_keyvalues(opt, self.snapshot());
+		let mut res: Vec<(Key, Val)> = vec![];
+		loop {
+			let x = stream.try_next().await;
+			match x {
+				Ok(Some(v)) => {
+					let x = (Key::from(v.key()), Val::from(v.value()));
+					res.push(x)
+				}
+				Ok(None) => break,
+				Err(e) => return Err(Error::Tx(format!("GetRanges failed: {}", e))),
+			}
+		}
+		Ok(res)
+	}
 }
@@ -0,0 +1,209 @@
+//! The layout of the keys stored in a datastore.
+//!
+//! Datastores created before the compact key format was introduced store the
+//! data of every table under keys which contain the names of the namespace,
+//! database and table. These datastores continue to use the original key
+//! format until they are migrated with [`super::Datastore::migrate_key_format`].
+use crate::err::Error;
+use crate::key::data::Base;
+use crate::kvs::Key;
+use crate::kvs::Scanner;
+use crate::kvs::Transaction;
+use crate::sql::statements::DefineDatabaseStatement;
+use crate::sql::statements::DefineIndexStatement;
+use crate::sql::statements::DefineNamespaceStatement;
+use crate::sql::statements::DefineTableStatement;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+
+/// The version of the key format used by a datastore
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum KeyFormat {
+	/// The data of each table is stored under the names of its namespace, database and table
+	V1 = 1,
+	/// The data of each table is stored under the numeric ids of its namespace, database and table
+	#[default]
+	V2 = 2,
+}
+
+impl KeyFormat {
+	/// The latest key format, which is used for new datastores
+	pub const LATEST: KeyFormat = KeyFormat::V2;
+
+	/// Check if the data of each table is stored under numeric ids
+	pub fn is_compact(&self) -> bool {
+		*self != KeyFormat::V1
+	}
+}
+
+impl TryFrom<u8> for KeyFormat {
+	type Error = Error;
+	fn try_from(v: u8) -> Result<Self, Self::Error> {
+		match v {
+			1 => Ok(KeyFormat::V1),
+			2 => Ok(KeyFormat::V2),
+			v => Err(Error::Ds(format!("Unsupported key format version {v}"))),
+		}
+	}
+}
+
+impl TryFrom<&[u8]> for KeyFormat {
+	type Error = Error;
+	fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+		match v {
+			[v] => KeyFormat::try_from(*v),
+			_ => Err(Error::Ds("Invalid key format version".to_owned())),
+		}
+	}
+}
+
+/// Moves the data of a single table from the original key format into the compact key format
+pub(super) struct Migration {
+	/// The base of the table data
+	tb: Base,
+	/// The prefix of the table data in the original key format
+	named: Key,
+	/// The compact prefix of each index on the table, by index name
+	ix: HashMap<Key, Key>,
+	/// The range of table data which remains to be moved
+	scanner: Scanner,
+}
+
+impl Migration {
+	pub fn new(tb: Base, ix: HashMap<Key, Key>) -> Self {
+		// Records, index entries and graph edges are stored after the table definitions
+		let named = tb.name(tb.prefix());
+		let beg = [named.as_slice(), b"*"].concat();
+		let end = [named.as_slice(), &[0xff]].concat();
+		Self {
+			named,
+			ix,
+			tb,
+			scanner: Scanner::new(beg..end),
+		}
+	}
+
+	/// Move the next batch of keys, returning the number of keys which were moved
+	pub async fn next_batch(&mut self, tx: &mut Transaction) -> Result<usize, Error> {
+		let res = self.scanner.next_batch(tx).await?;
+		for (k, v) in res.iter() {
+			let key = match k[self.named.len()..].split_first() {
+				// The entries of an index are stored under the index id
+				Some((b'+', rest)) => {
+					let Some(pos) = rest.iter().position(|c| *c == 0) else {
+						continue;
+					};
+					match self.ix.get(&rest[..pos]) {
+						Some(ix) => [ix.as_slice(), &rest[pos + 1..]].concat(),
+						None => {
+							warn!(
+								"Skipping the data of an undefined index: {}",
+								crate::key::debug::sprint_key(k)
+							);
+							continue;
+						}
+					}
+				}
+				// Records and graph edges are stored under the table id
+				_ => self.tb.key(k.clone()),
+			};
+			tx.set(key, v.clone()).await?;
+			tx.del(k.clone()).await?;
+		}
+		Ok(res.len())
+	}
+}
+
+/// Assign numeric ids to any definitions which do not have one, and prepare
+/// the migration of the data of every table which has data stored.
+pub(super) async fn prepare(tx: &mut Transaction) -> Result<Vec<Migration>, Error> {
+	let mut out = vec![];
+	for ns in tx.all_ns().await?.iter() {
+		let ns_id = match ns.id {
+			Some(id) => id,
+			None => {
+				let id = tx.ns_id(&ns.name).await?;
+				let val = DefineNamespaceStatement {
+					id: Some(id),
+					..ns.clone()
+				};
+				tx.set(crate::key::root::ns::new(&ns.name), val).await?;
+				id
+			}
+		};
+		for db in tx.all_db(&ns.name).await?.iter() {
+			let db_id = match db.id {
+				Some(id) => id,
+				None => {
+					let id = tx.db_id(&ns.name, &db.name).await?;
+					let val = DefineDatabaseStatement {
+						id: Some(id),
+						..db.clone()
+					};
+					tx.set(crate::key::namespace::db::new(&ns.name, &db.name), val).await?;
+					id
+				}
+			};
+			// Graph edges can be stored for tables which were never defined
+			let mut tbs = tables(tx, &ns.name, &db.name).await?;
+			tbs.extend(tx.all_tb(&ns.name, &db.name).await?.iter().map(|tb| tb.name.to_raw()));
+			for tb in tbs {
+				let def = tx.add_tb(&ns.name, &db.name, &tb, false).await?;
+				let tb_id = match def.id {
+					Some(id) => id,
+					None => {
+						let id = tx.tb_id(&ns.name, &db.name, &tb).await?;
+						let val = DefineTableStatement {
+							id: Some(id),
+							..def
+						};
+						tx.set(crate::key::database::tb::new(&ns.name, &db.name, &tb), val).await?;
+						id
+					}
+				};
+				let mut ix = HashMap::new();
+				for def in tx.all_ix(&ns.name, &db.name, &tb).await?.iter() {
+					let ix_id = match def.id {
+						Some(id) => id,
+						None => {
+							let id = tx.ix_id(&ns.name, &db.name, &tb, &def.name).await?;
+							let key =
+								crate::key::table::ix::new(&ns.name, &db.name, &tb, &def.name);
+							let val = DefineIndexStatement {
+								id: Some(id),
+								..def.clone()
+							};
+							tx.set(key, val).await?;
+							id
+						}
+					};
+					let key = crate::key::data::ix::new(ns_id, db_id, tb_id, ix_id).encode()?;
+					ix.insert(def.name.as_bytes().to_vec(), key);
+				}
+				let named = crate::key::table::all::new(&ns.name, &db.name, &tb).encode()?;
+				let stored = crate::key::data::tb::new(ns_id, db_id, tb_id).encode()?;
+				out.push(Migration::new(Base::new(named, stored), ix));
+			}
+		}
+	}
+	Ok(out)
+}
+
+/// Find the names of every table which has keys stored within a database
+async fn tables(tx: &mut Transaction, ns: &str, db: &str) -> Result<BTreeSet<String>, Error> {
+	let mut out = BTreeSet::new();
+	let pre = [crate::key::database::all::new(ns, db).encode()?.as_slice(), b"*"].concat();
+	let end = [crate::key::database::all::new(ns, db).encode()?.as_slice(), b"+"].concat();
+	let mut beg = pre.clone();
+	// Skip over all of the keys of each table after it has been found
+	while let Some((k, _)) = tx.scan(beg.clone()..end.clone(), 1).await?.pop() {
+		let rest = &k[pre.len()..];
+		let Some(pos) = rest.iter().position(|c| *c == 0) else {
+			break;
+		};
+		out.insert(String::from_utf8_lossy(&rest[..pos]).into_owned());
+		beg = [&k[..pre.len() + pos], &[0x00, 0xff]].concat();
+	}
+	Ok(out)
+}
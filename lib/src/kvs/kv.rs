@@ -1,3 +1,5 @@
+use crate::err::Error;
+
 /// The key part of a key-value pair. An alias for [`Vec<u8>`].
 pub type Key = Vec<u8>;
 
@@ -29,3 +31,18 @@ where
 		self.into_iter().map(|(_, v)| v.into()).collect()
 	}
 }
+
+/// This trait converts a collection of key-value pairs into the desired type,
+/// failing if any of the values can not be decoded
+pub(super) trait TryConvert<T> {
+	fn try_convert(self) -> Result<T, Error>;
+}
+
+impl<T> TryConvert<Vec<T>> for Vec<(Key, Val)>
+where
+	T: TryFrom<Val, Error = Error>,
+{
+	fn try_convert(self) -> Result<Vec<T>, Error> {
+		self.into_iter().map(|(_, v)| v.try_into()).collect()
+	}
+}
@@ -0,0 +1,432 @@
+//! Durable storage for the in-memory datastore.
+//!
+//! A durable in-memory datastore keeps all of its data in memory, but also
+//! persists every committed transaction to an append-only log on disk. The
+//! log is written by a dedicated thread, so that the blocking file operations
+//! never run on the async runtime. When the log grows beyond a configured
+//! size, it is set aside and a new log is started, and a snapshot of the
+//! entire dataset is written in the background from a read transaction. Once
+//! the snapshot is complete, the previous log is removed. On startup, the
+//! snapshot is loaded and the logs are replayed on top of it to recover the
+//! dataset.
+//!
+//! All files consist of a sequence of frames, each of which is a little
+//! endian `u32` length followed by a bincode encoded list of operations.
+use crate::err::Error;
+use crate::kvs::Key;
+use crate::kvs::Val;
+use echodb::Db;
+use flume::Receiver;
+use flume::Sender;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::thread::JoinHandle;
+
+/// The name of the file containing the latest snapshot
+const SNAPSHOT: &str = "snapshot";
+/// The name of the file used while writing a new snapshot
+const SNAPSHOT_TMP: &str = "snapshot.tmp";
+/// The name of the file containing the write-ahead log
+const LOG: &str = "log";
+/// The name of the file containing the log which is being replaced by a snapshot
+const LOG_OLD: &str = "log.old";
+/// The number of entries which are stored in each snapshot frame
+const SNAPSHOT_BATCH_SIZE: usize = 1000;
+
+/// A single write operation which was committed to the datastore
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) enum Op {
+	Set(Key, Val),
+	Del(Key),
+}
+
+/// The on-disk log and snapshot for a durable in-memory datastore
+pub(super) struct Log {
+	/// The directory containing the log and snapshot files
+	dir: PathBuf,
+	/// The write-ahead log file, opened for appending
+	file: File,
+	/// The current size of the write-ahead log in bytes
+	size: u64,
+	/// The size in bytes after which the log is replaced by a snapshot
+	threshold: u64,
+}
+
+fn io(e: std::io::Error) -> Error {
+	Error::Ds(format!("Unable to access the durable memory datastore: {e}"))
+}
+
+impl Log {
+	/// Open the log in the specified directory, returning the recovered operations
+	pub fn open(dir: &Path) -> Result<(Log, Vec<Op>), Error> {
+		fs::create_dir_all(dir).map_err(io)?;
+		let mut ops = Vec::new();
+		// Load the latest snapshot
+		if let Ok(file) = File::open(dir.join(SNAPSHOT)) {
+			let (mut snapshot, len) = Self::read(file).map_err(io)?;
+			if len != fs::metadata(dir.join(SNAPSHOT)).map_err(io)?.len() {
+				return Err(Error::Ds("The durable memory datastore snapshot is corrupted".into()));
+			}
+			ops.append(&mut snapshot);
+		}
+		// Replay the log which was not yet replaced by a snapshot
+		if let Ok(file) = File::open(dir.join(LOG_OLD)) {
+			let (mut log, _) = Self::read(file).map_err(io)?;
+			ops.append(&mut log);
+		}
+		// Replay the write-ahead log
+		let file = OpenOptions::new().create(true).read(true).append(true).open(dir.join(LOG));
+		let file = file.map_err(io)?;
+		let (mut log, size) = Self::read(file.try_clone().map_err(io)?).map_err(io)?;
+		// Remove any partially written transaction at the end of the log
+		if size != file.metadata().map_err(io)?.len() {
+			warn!(
+				"Truncating a partially written transaction from the durable memory datastore log"
+			);
+			file.set_len(size).map_err(io)?;
+			file.sync_all().map_err(io)?;
+		}
+		ops.append(&mut log);
+		// Remove any unfinished snapshot
+		let _ = fs::remove_file(dir.join(SNAPSHOT_TMP));
+		Ok((
+			Log {
+				dir: dir.to_owned(),
+				file,
+				size,
+				threshold: *crate::cnf::MEMORY_SNAPSHOT_THRESHOLD,
+			},
+			ops,
+		))
+	}
+
+	/// Check if the log has grown large enough to be replaced by a snapshot
+	pub fn needs_snapshot(&self) -> bool {
+		self.size >= self.threshold
+	}
+
+	/// Append the operations of a committed transaction to the log
+	pub fn append(&mut self, ops: &[Op]) -> Result<(), Error> {
+		let frame = Self::frame(ops)?;
+		self.file.write_all(&frame).map_err(io)?;
+		self.file.sync_data().map_err(io)?;
+		self.size += frame.len() as u64;
+		Ok(())
+	}
+
+	/// Check if a previous log is waiting to be replaced by a snapshot
+	pub fn rotated(&self) -> bool {
+		self.dir.join(LOG_OLD).exists()
+	}
+
+	/// Set the current log aside until a snapshot replaces it, and start a new log
+	pub fn rotate(&mut self) -> Result<(), Error> {
+		fs::rename(self.dir.join(LOG), self.dir.join(LOG_OLD)).map_err(io)?;
+		let file = OpenOptions::new().create(true).append(true).open(self.dir.join(LOG));
+		self.file = file.map_err(io)?;
+		self.size = 0;
+		Ok(())
+	}
+
+	/// Replace the snapshot with the dataset as seen by a read transaction
+	///
+	/// The transaction must have been started after the log was rotated, so
+	/// that the snapshot contains every change in the previous log, which is
+	/// removed once the snapshot is complete. Changes which are in both the
+	/// snapshot and the current log are simply replayed again on startup.
+	pub fn snapshot(dir: &Path, mut tx: echodb::Tx<Key, Val>) -> Result<(), Error> {
+		let path = dir.join(SNAPSHOT_TMP);
+		let mut file = BufWriter::new(File::create(&path).map_err(io)?);
+		let mut beg = Vec::new();
+		loop {
+			let res = tx.scan(beg..vec![0xff], SNAPSHOT_BATCH_SIZE as u32)?;
+			// Continue after the last key of this batch
+			beg = match res.last() {
+				Some((k, _)) => [k.as_slice(), &[0]].concat(),
+				None => break,
+			};
+			let ops: Vec<Op> = res.into_iter().map(|(k, v)| Op::Set(k, v)).collect();
+			file.write_all(&Self::frame(&ops)?).map_err(io)?;
+		}
+		tx.cancel()?;
+		let file = file.into_inner().map_err(|e| io(e.into_error()))?;
+		file.sync_all().map_err(io)?;
+		// Atomically replace the previous snapshot
+		fs::rename(&path, dir.join(SNAPSHOT)).map_err(io)?;
+		// The previous log is now contained in the snapshot
+		fs::remove_file(dir.join(LOG_OLD)).map_err(io)?;
+		Ok(())
+	}
+
+	/// Encode a list of operations as a single frame
+	fn frame(ops: &[Op]) -> Result<Vec<u8>, Error> {
+		let data = bincode::serialize(ops)?;
+		let mut frame = Vec::with_capacity(4 + data.len());
+		frame.extend_from_slice(&(data.len() as u32).to_le_bytes());
+		frame.extend_from_slice(&data);
+		Ok(frame)
+	}
+
+	/// Read all complete frames from a file, returning the length of the valid data
+	fn read(file: File) -> std::io::Result<(Vec<Op>, u64)> {
+		let total = file.metadata()?.len();
+		let mut file = BufReader::new(file);
+		let mut ops = Vec::new();
+		let mut pos = 0;
+		loop {
+			let mut len = [0; 4];
+			if file.read_exact(&mut len).is_err() {
+				break;
+			}
+			let len = u32::from_le_bytes(len) as u64;
+			if pos + 4 + len > total {
+				break;
+			}
+			let mut data = vec![0; len as usize];
+			if file.read_exact(&mut data).is_err() {
+				break;
+			}
+			match bincode::deserialize::<Vec<Op>>(&data) {
+				Ok(mut v) => ops.append(&mut v),
+				Err(_) => break,
+			}
+			pos += 4 + data.len() as u64;
+		}
+		Ok((ops, pos))
+	}
+}
+
+/// A transaction whose operations are appended to the log, and which is
+/// committed once they are durable, along with the channel for the result
+type Request = (echodb::Tx<Key, Val>, Vec<Op>, Sender<Result<(), Error>>);
+
+/// A handle to the thread which writes the log of a durable in-memory datastore
+///
+/// The in-memory datastore only allows a single write transaction at a time,
+/// and a transaction keeps its lock until it is committed by this thread, so
+/// that the changes are logged in the order in which they are committed. The
+/// log is therefore written one transaction at a time. The thread stops once
+/// the datastore and all of its transactions have been dropped.
+#[derive(Clone)]
+pub(super) struct Writer {
+	db: Arc<Db<Key, Val>>,
+	sender: Sender<Request>,
+}
+
+impl Writer {
+	/// Start the thread which writes the log
+	pub fn spawn(log: Log, db: Arc<Db<Key, Val>>) -> Result<Writer, Error> {
+		let (sender, receiver) = flume::unbounded();
+		let ds = db.clone();
+		thread::Builder::new()
+			.name("memory-log".to_owned())
+			.spawn(move || Self::run(log, ds, receiver))
+			.map_err(io)?;
+		Ok(Writer {
+			db,
+			sender,
+		})
+	}
+
+	/// Append the operations of a transaction to the log, and commit it once they are durable
+	///
+	/// The transaction is handed over to the thread which writes the log, so
+	/// that the log and the dataset stay consistent even if this future is
+	/// dropped before the transaction has been committed.
+	pub async fn commit(&self, tx: &mut echodb::Tx<Key, Val>, ops: Vec<Op>) -> Result<(), Error> {
+		let tx = std::mem::replace(tx, self.db.begin(false).await?);
+		let (sender, receiver) = flume::bounded(1);
+		self.sender.send((tx, ops, sender)).map_err(|_| Self::stopped())?;
+		receiver.recv_async().await.map_err(|_| Self::stopped())?
+	}
+
+	fn stopped() -> Error {
+		Error::Ds("The durable memory datastore log is no longer being written".into())
+	}
+
+	fn run(mut log: Log, db: Arc<Db<Key, Val>>, receiver: Receiver<Request>) {
+		// The thread which is writing a snapshot
+		let mut snapshot = None;
+		// Replace a previous log which was not yet contained in a snapshot
+		let mut rotated = log.rotated();
+		if rotated {
+			snapshot = Self::snapshot(&log, &db);
+		}
+		while let Ok((mut tx, ops, res)) = receiver.recv() {
+			let _ = res.send(log.append(&ops).and_then(|_| Ok(tx.commit()?)));
+			// Check whether the previous log has been replaced by the snapshot
+			if let Some(handle) = snapshot.take() {
+				match handle.is_finished() {
+					false => snapshot = Some(handle),
+					true => match handle.join() {
+						Ok(Ok(())) => rotated = false,
+						Ok(Err(e)) => {
+							error!("Unable to snapshot the durable memory datastore: {e}")
+						}
+						Err(_) => error!("Unable to snapshot the durable memory datastore"),
+					},
+				}
+			}
+			// Rotate the log if it has grown too large, and no snapshot is pending
+			if !rotated && log.needs_snapshot() {
+				match log.rotate() {
+					Ok(()) => {
+						rotated = true;
+						snapshot = Self::snapshot(&log, &db);
+					}
+					Err(e) => error!("Unable to rotate the durable memory datastore log: {e}"),
+				}
+			}
+		}
+		// Wait for a snapshot which is still being written
+		if let Some(handle) = snapshot {
+			let _ = handle.join();
+		}
+	}
+
+	/// Write a snapshot of the current dataset in the background
+	fn snapshot(log: &Log, db: &Db<Key, Val>) -> Option<JoinHandle<Result<(), Error>>> {
+		// Read transactions never wait for the write lock
+		let tx = futures::executor::block_on(db.begin(false));
+		let dir = log.dir.clone();
+		let handle = tx.map_err(Error::from).and_then(|tx| {
+			thread::Builder::new()
+				.name("memory-snapshot".to_owned())
+				.spawn(move || Log::snapshot(&dir, tx))
+				.map_err(io)
+		});
+		match handle {
+			Ok(handle) => Some(handle),
+			Err(e) => {
+				error!("Unable to snapshot the durable memory datastore: {e}");
+				None
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use temp_dir::TempDir;
+
+	fn keys(ops: Vec<Op>) -> Vec<(Key, Option<Val>)> {
+		ops.into_iter()
+			.map(|op| match op {
+				Op::Set(k, v) => (k, Some(v)),
+				Op::Del(k) => (k, None),
+			})
+			.collect()
+	}
+
+	async fn snapshot(dir: &Path, data: Vec<(&[u8], &[u8])>) {
+		let db = echodb::db::new();
+		let mut tx = db.begin(true).await.unwrap();
+		for (k, v) in data {
+			tx.set(k.to_vec(), v.to_vec()).unwrap();
+		}
+		tx.commit().unwrap();
+		Log::snapshot(dir, db.begin(false).await.unwrap()).unwrap();
+	}
+
+	#[tokio::test]
+	async fn snapshot_replaces_log() {
+		let dir = TempDir::new().unwrap();
+		let (mut log, ops) = Log::open(dir.path()).unwrap();
+		assert!(ops.is_empty());
+		log.append(&[Op::Set(b"a".to_vec(), b"1".to_vec())]).unwrap();
+		log.append(&[Op::Del(b"a".to_vec())]).unwrap();
+		log.rotate().unwrap();
+		assert_eq!(log.size, 0);
+		assert!(log.rotated());
+		log.append(&[Op::Set(b"c".to_vec(), b"3".to_vec())]).unwrap();
+		// The snapshot may contain changes which are also in the current log
+		snapshot(dir.path(), vec![(b"b", b"2"), (b"c", b"3")]).await;
+		assert!(!log.rotated());
+		drop(log);
+		// The snapshot is loaded before the log is replayed
+		let (_, ops) = Log::open(dir.path()).unwrap();
+		assert_eq!(
+			keys(ops),
+			vec![
+				(b"b".to_vec(), Some(b"2".to_vec())),
+				(b"c".to_vec(), Some(b"3".to_vec())),
+				(b"c".to_vec(), Some(b"3".to_vec()))
+			]
+		);
+	}
+
+	#[test]
+	fn rotated_log_is_replayed() {
+		let dir = TempDir::new().unwrap();
+		let (mut log, _) = Log::open(dir.path()).unwrap();
+		log.append(&[Op::Set(b"a".to_vec(), b"1".to_vec())]).unwrap();
+		log.rotate().unwrap();
+		log.append(&[Op::Set(b"b".to_vec(), b"2".to_vec())]).unwrap();
+		drop(log);
+		// The previous log is replayed if the snapshot was never written
+		let (log, ops) = Log::open(dir.path()).unwrap();
+		assert!(log.rotated());
+		assert_eq!(
+			keys(ops),
+			vec![(b"a".to_vec(), Some(b"1".to_vec())), (b"b".to_vec(), Some(b"2".to_vec()))]
+		);
+	}
+
+	#[tokio::test]
+	async fn corrupted_snapshot() {
+		let dir = TempDir::new().unwrap();
+		let (mut log, _) = Log::open(dir.path()).unwrap();
+		log.rotate().unwrap();
+		snapshot(dir.path(), vec![(b"b", b"2")]).await;
+		drop(log);
+		let path = dir.path().join(SNAPSHOT);
+		let data = fs::read(&path).unwrap();
+		fs::write(&path, &data[..data.len() - 1]).unwrap();
+		assert!(Log::open(dir.path()).is_err());
+	}
+
+	#[tokio::test]
+	async fn writer_snapshots_in_the_background() {
+		let dir = TempDir::new().unwrap();
+		let (mut log, _) = Log::open(dir.path()).unwrap();
+		// Rotate the log after every transaction
+		log.threshold = 0;
+		let db = Arc::new(echodb::db::new());
+		let writer = Writer::spawn(log, db.clone()).unwrap();
+		for (k, v) in [(b"a", b"1"), (b"b", b"2")] {
+			let mut tx = db.begin(true).await.unwrap();
+			tx.set(k.to_vec(), v.to_vec()).unwrap();
+			let ops = vec![Op::Set(k.to_vec(), v.to_vec())];
+			writer.commit(&mut tx, ops).await.unwrap();
+		}
+		// The transactions were committed by the writer
+		let tx = db.begin(false).await.unwrap();
+		assert_eq!(tx.get(b"b".to_vec()).unwrap(), Some(b"2".to_vec()));
+		drop(writer);
+		// Wait until the snapshot has replaced the previous log
+		for _ in 0..100 {
+			if !dir.path().join(LOG_OLD).exists() {
+				break;
+			}
+			tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+		}
+		let (_, ops) = Log::open(dir.path()).unwrap();
+		let mut keys = keys(ops);
+		keys.dedup();
+		assert_eq!(
+			keys,
+			vec![(b"a".to_vec(), Some(b"1".to_vec())), (b"b".to_vec(), Some(b"2".to_vec()))]
+		);
+	}
+}
@@ -1,13 +1,22 @@
 #![cfg(feature = "kv-mem")]
 
+mod log;
+
+use self::log::Log;
+use self::log::Op;
+use self::log::Writer;
 use crate::err::Error;
 use crate::kvs::Key;
 use crate::kvs::Val;
 use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
 use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
 
 pub struct Datastore {
-	db: echodb::Db<Key, Val>,
+	db: Arc<echodb::Db<Key, Val>>,
+	/// The writer of the on-disk log, if this datastore is durable
+	log: Option<Writer>,
 }
 
 pub struct Transaction {
@@ -17,13 +26,36 @@ pub struct Transaction {
 	rw: bool,
 	/// The distributed datastore transaction
 	tx: echodb::Tx<Key, Val>,
+	/// The writer of the on-disk log, if this datastore is durable
+	log: Option<Writer>,
+	/// The write operations to append to the log on commit
+	ops: Vec<Op>,
 }
 
 impl Datastore {
 	/// Open a new database
 	pub async fn new() -> Result<Datastore, Error> {
 		Ok(Datastore {
-			db: echodb::db::new(),
+			db: Arc::new(echodb::db::new()),
+			log: None,
+		})
+	}
+	/// Open a durable database, persisted to the specified directory
+	pub async fn new_durable(path: &str) -> Result<Datastore, Error> {
+		let db = Arc::new(echodb::db::new());
+		// Recover the dataset from the snapshot and logs
+		let (log, ops) = Log::open(Path::new(path))?;
+		let mut tx = db.begin(true).await?;
+		for op in ops {
+			match op {
+				Op::Set(k, v) => tx.set(k, v)?,
+				Op::Del(k) => tx.del(k)?,
+			}
+		}
+		tx.commit()?;
+		Ok(Datastore {
+			log: Some(Writer::spawn(log, db.clone())?),
+			db,
 		})
 	}
 	/// Start a new transaction
@@ -33,6 +65,8 @@ impl Datastore {
 				ok: false,
 				rw: write,
 				tx,
+				log: self.log.clone(),
+				ops: Vec::new(),
 			}),
 			Err(e) => Err(Error::Tx(e.to_string())),
 		}
@@ -58,7 +92,7 @@ impl Transaction {
 		Ok(())
 	}
 	/// Commit a transaction
-	pub fn commit(&mut self) -> Result<(), Error> {
+	pub async fn commit(&mut self) -> Result<(), Error> {
 		// Check to see if transaction is closed
 		if self.ok {
 			return Err(Error::TxFinished);
@@ -69,7 +103,13 @@ impl Transaction {
 		}
 		// Mark this transaction as done
 		self.ok = true;
-		// Cancel this transaction
+		// Commit this transaction once the changes are durable
+		if let Some(log) = &self.log {
+			if !self.ops.is_empty() {
+				return log.commit(&mut self.tx, std::mem::take(&mut self.ops)).await;
+			}
+		}
+		// Commit this transaction
 		self.tx.commit()?;
 		// Continue
 		Ok(())
@@ -136,7 +176,9 @@ impl Transaction {
 
 		let verbytes = u64_to_versionstamp(ver);
 
+		let op = self.log.as_ref().map(|_| Op::Set(k.clone(), verbytes.to_vec()));
 		self.tx.set(k, verbytes.to_vec())?;
+		self.ops.extend(op);
 		// Return the uint64 representation of the timestamp as the result
 		Ok(verbytes)
 	}
@@ -188,7 +230,10 @@ impl Transaction {
 			return Err(Error::TxReadonly);
 		}
 		// Set the key
-		self.tx.set(key.into(), val.into())?;
+		let (key, val) = (key.into(), val.into());
+		let op = self.log.as_ref().map(|_| Op::Set(key.clone(), val.clone()));
+		self.tx.set(key, val)?;
+		self.ops.extend(op);
 		// Return result
 		Ok(())
 	}
@@ -207,7 +252,10 @@ impl Transaction {
 			return Err(Error::TxReadonly);
 		}
 		// Set the key
-		self.tx.put(key.into(), val.into())?;
+		let (key, val) = (key.into(), val.into());
+		let op = self.log.as_ref().map(|_| Op::Set(key.clone(), val.clone()));
+		self.tx.put(key, val)?;
+		self.ops.extend(op);
 		// Return result
 		Ok(())
 	}
@@ -226,7 +274,10 @@ impl Transaction {
 			return Err(Error::TxReadonly);
 		}
 		// Set the key
-		self.tx.putc(key.into(), val.into(), chk.map(Into::into))?;
+		let (key, val) = (key.into(), val.into());
+		let op = self.log.as_ref().map(|_| Op::Set(key.clone(), val.clone()));
+		self.tx.putc(key, val, chk.map(Into::into))?;
+		self.ops.extend(op);
 		// Return result
 		Ok(())
 	}
@@ -244,7 +295,10 @@ impl Transaction {
 			return Err(Error::TxReadonly);
 		}
 		// Remove the key
-		self.tx.del(key.into())?;
+		let key = key.into();
+		let op = self.log.as_ref().map(|_| Op::Del(key.clone()));
+		self.tx.del(key)?;
+		self.ops.extend(op);
 		// Return result
 		Ok(())
 	}
@@ -263,7 +317,10 @@ impl Transaction {
 			return Err(Error::TxReadonly);
 		}
 		// Remove the key
-		self.tx.delc(key.into(), chk.map(Into::into))?;
+		let key = key.into();
+		let op = self.log.as_ref().map(|_| Op::Del(key.clone()));
+		self.tx.delc(key, chk.map(Into::into))?;
+		self.ops.extend(op);
 		// Return result
 		Ok(())
 	}
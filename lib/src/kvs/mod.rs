@@ -8,24 +8,37 @@
 //! These operations can be processed by the following storage engines:
 //! - `fdb`: [FoundationDB](https://github.com/apple/foundationdb/) a distributed database designed to handle large volumes of structured data across clusters of commodity servers
 //! - `indxdb`: WASM based database to store data in the browser
+//! - `redb`: [redb](https://github.com/cberner/redb) a pure-Rust embedded key-value store, with no native dependencies
 //! - `rocksdb`: [RocksDB](https://github.com/facebook/rocksdb) an embeddable persistent key-value store for fast storage
 //! - `speedb`: [SpeedyDB](https://github.com/speedb-io/speedb) fork of rocksDB making it faster (Redis is using speedb but this is not acid transactions)
 //! - `tikv`: [TiKV](https://github.com/tikv/tikv) a distributed, and transactional key-value database
-//! - `mem`: in-memory database
+//! - `mem`: in-memory database, optionally persisted to disk with a write-ahead log and snapshots
+mod backup;
 mod cache;
 mod ds;
+mod encryption;
 mod fdb;
+mod format;
 mod indxdb;
 mod kv;
 mod mem;
+mod redb;
 mod rocksdb;
+mod savepoint;
+mod scanner;
 mod speedb;
+mod stats;
 mod tikv;
 mod tx;
 
 #[cfg(test)]
 mod tests;
 
+pub use self::backup::RestorePoint;
 pub use self::ds::*;
+pub use self::format::KeyFormat;
 pub use self::kv::*;
+pub use self::scanner::Scanner;
+pub use self::stats::Stats;
 pub use self::tx::*;
+pub use crate::vs::VersionstampOracle;
@@ -0,0 +1,498 @@
+#![cfg(feature = "kv-redb")]
+
+use crate::err::Error;
+use crate::kvs::Key;
+use crate::kvs::Val;
+use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
+use redb::{
+	AccessGuard, ReadOnlyTable, ReadableTable, StorageError, TableDefinition, WriteTransaction,
+};
+use std::ops::Range;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::sync::OwnedMutexGuard;
+
+/// The single table which stores all of the keys in the datastore
+const TABLE: TableDefinition<&[u8], &[u8]> = TableDefinition::new("kv");
+
+/// A single key-value pair which is read from a table
+type RangeEntry<'a> =
+	Result<(AccessGuard<'a, &'static [u8]>, AccessGuard<'a, &'static [u8]>), StorageError>;
+
+#[derive(Clone)]
+pub struct Datastore {
+	db: Arc<redb::Database>,
+	// The lock which ensures that only one write transaction is started at a time,
+	// so that waiting for a write transaction does not block the async runtime
+	lk: Arc<Mutex<()>>,
+}
+
+pub struct Transaction {
+	// Is the transaction complete?
+	ok: bool,
+	// Is the transaction read+write?
+	rw: bool,
+	// The distributed datastore transaction
+	tx: Inner,
+	// The database, which must outlive the transaction
+	_db: Arc<redb::Database>,
+}
+
+enum Inner {
+	// A read-only snapshot of the table
+	Read(ReadOnlyTable<&'static [u8], &'static [u8]>),
+	// A write transaction, and the lock which is held until it completes
+	Write(Option<(Box<WriteTransaction>, OwnedMutexGuard<()>)>),
+}
+
+impl Datastore {
+	/// Open a new database
+	pub async fn new(path: &str) -> Result<Datastore, Error> {
+		let db = redb::Database::create(path)?;
+		// Ensure that the table exists for read transactions
+		let tx = db.begin_write()?;
+		tx.open_table(TABLE)?;
+		tx.commit()?;
+		Ok(Datastore {
+			db: Arc::new(db),
+			lk: Arc::new(Mutex::new(())),
+		})
+	}
+	/// Start a new transaction
+	pub async fn transaction(&self, write: bool, _: bool) -> Result<Transaction, Error> {
+		let tx = match write {
+			true => {
+				let lk = self.lk.clone().lock_owned().await;
+				let tx = self.db.begin_write()?;
+				Inner::Write(Some((Box::new(tx), lk)))
+			}
+			false => {
+				let tx = self.db.begin_read()?;
+				Inner::Read(tx.open_table(TABLE)?)
+			}
+		};
+		Ok(Transaction {
+			ok: false,
+			rw: write,
+			tx,
+			_db: self.db.clone(),
+		})
+	}
+}
+
+impl Transaction {
+	/// Check if closed
+	pub fn closed(&self) -> bool {
+		self.ok
+	}
+	/// Cancel a transaction
+	pub async fn cancel(&mut self) -> Result<(), Error> {
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Mark this transaction as done
+		self.ok = true;
+		// Cancel this transaction
+		if let Inner::Write(tx) = &mut self.tx {
+			if let Some((tx, _lk)) = tx.take() {
+				tx.abort()?;
+			}
+		}
+		// Continue
+		Ok(())
+	}
+	/// Commit a transaction
+	pub async fn commit(&mut self) -> Result<(), Error> {
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.rw {
+			return Err(Error::TxReadonly);
+		}
+		// Mark this transaction as done
+		self.ok = true;
+		// Commit this transaction
+		if let Inner::Write(tx) = &mut self.tx {
+			if let Some((tx, _lk)) = tx.take() {
+				tx.commit()?;
+			}
+		}
+		// Continue
+		Ok(())
+	}
+	/// Check if a key exists
+	pub async fn exi<K>(&mut self, key: K) -> Result<bool, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Check the key
+		let res = self.fetch(&key.into())?.is_some();
+		// Return result
+		Ok(res)
+	}
+	/// Fetch a key from the database
+	pub async fn get<K>(&mut self, key: K) -> Result<Option<Val>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Get the key
+		let res = self.fetch(&key.into())?;
+		// Return result
+		Ok(res)
+	}
+	/// Obtain a new change timestamp for a key
+	/// which is replaced with the current timestamp when the transaction is committed.
+	/// NOTE: This should be called when composing the change feed entries for this transaction,
+	/// which should be done immediately before the transaction commit.
+	/// That is to keep other transactions commit delay(pessimistic) or conflict(optimistic) as less as possible.
+	#[allow(unused)]
+	pub async fn get_timestamp<K>(&mut self, key: K) -> Result<Versionstamp, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Write the timestamp to the "last-write-timestamp" key
+		// to ensure that no other transactions can commit with older timestamps.
+		let k: Key = key.into();
+		let prev = self.fetch(&k)?;
+		let ver = match prev {
+			Some(prev) => {
+				let slice = prev.as_slice();
+				let res: Result<[u8; 10], Error> = match slice.try_into() {
+					Ok(ba) => Ok(ba),
+					Err(e) => Err(Error::Ds(e.to_string())),
+				};
+				let array = res?;
+				let prev = try_to_u64_be(array)?;
+				prev + 1
+			}
+			None => 1,
+		};
+
+		let verbytes = u64_to_versionstamp(ver);
+
+		self.write()?.open_table(TABLE)?.insert(k.as_slice(), verbytes.as_slice())?;
+		// Return the uint64 representation of the timestamp as the result
+		Ok(verbytes)
+	}
+	/// Obtain a new key that is suffixed with the change timestamp
+	pub async fn get_versionstamped_key<K>(
+		&mut self,
+		ts_key: K,
+		prefix: K,
+		suffix: K,
+	) -> Result<Vec<u8>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.rw {
+			return Err(Error::TxReadonly);
+		}
+		let ts = self.get_timestamp(ts_key).await?;
+		let mut k: Vec<u8> = prefix.into();
+		k.append(&mut ts.to_vec());
+		k.append(&mut suffix.into());
+		Ok(k)
+	}
+	/// Insert or update a key in the database
+	pub async fn set<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.rw {
+			return Err(Error::TxReadonly);
+		}
+		// Set the key
+		let (key, val) = (key.into(), val.into());
+		let mut tb = self.write()?.open_table(TABLE)?;
+		tb.insert(key.as_slice(), val.as_slice())?;
+		// Return result
+		Ok(())
+	}
+	/// Insert a key if it doesn't exist in the database
+	pub async fn put<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.rw {
+			return Err(Error::TxReadonly);
+		}
+		// Get the arguments
+		let key = key.into();
+		let val = val.into();
+		// Set the key if empty
+		let mut tb = self.write()?.open_table(TABLE)?;
+		let cur = tb.get(key.as_slice())?.is_some();
+		match cur {
+			false => tb.insert(key.as_slice(), val.as_slice())?,
+			true => return Err(Error::TxKeyAlreadyExists),
+		};
+		// Return result
+		Ok(())
+	}
+	/// Insert a key if it doesn't exist in the database
+	pub async fn putc<K, V>(&mut self, key: K, val: V, chk: Option<V>) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.rw {
+			return Err(Error::TxReadonly);
+		}
+		// Get the arguments
+		let key = key.into();
+		let val = val.into();
+		let chk = chk.map(Into::into);
+		// Set the key if valid
+		let mut tb = self.write()?.open_table(TABLE)?;
+		let cur = tb.get(key.as_slice())?.map(|v| v.value().to_vec());
+		match (cur, chk) {
+			(Some(v), Some(w)) if v == w => tb.insert(key.as_slice(), val.as_slice())?,
+			(None, None) => tb.insert(key.as_slice(), val.as_slice())?,
+			_ => return Err(Error::TxConditionNotMet),
+		};
+		// Return result
+		Ok(())
+	}
+	/// Delete a key
+	pub async fn del<K>(&mut self, key: K) -> Result<(), Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.rw {
+			return Err(Error::TxReadonly);
+		}
+		// Remove the key
+		let key = key.into();
+		let mut tb = self.write()?.open_table(TABLE)?;
+		tb.remove(key.as_slice())?;
+		// Return result
+		Ok(())
+	}
+	/// Delete a key
+	pub async fn delc<K, V>(&mut self, key: K, chk: Option<V>) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.rw {
+			return Err(Error::TxReadonly);
+		}
+		// Get the arguments
+		let key = key.into();
+		let chk = chk.map(Into::into);
+		// Delete the key if valid
+		let mut tb = self.write()?.open_table(TABLE)?;
+		let cur = tb.get(key.as_slice())?.map(|v| v.value().to_vec());
+		match (cur, chk) {
+			(Some(v), Some(w)) if v == w => tb.remove(key.as_slice())?,
+			(None, None) => tb.remove(key.as_slice())?,
+			_ => return Err(Error::TxConditionNotMet),
+		};
+		// Return result
+		Ok(())
+	}
+	/// Retrieve a range of keys from the databases
+	pub async fn scan<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		let rng = rng.start.as_slice()..rng.end.as_slice();
+		// Scan the keys
+		let res = match &self.tx {
+			Inner::Read(tb) => Self::collect(tb.range(rng)?, limit)?,
+			Inner::Write(Some((tx, _))) => {
+				let tb = tx.open_table(TABLE)?;
+				let res = Self::collect(tb.range(rng)?, limit)?;
+				res
+			}
+			Inner::Write(None) => return Err(Error::TxFinished),
+		};
+		// Return result
+		Ok(res)
+	}
+	/// Retrieve a range of keys from the databases, in reverse order
+	pub async fn scanr<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		let rng = rng.start.as_slice()..rng.end.as_slice();
+		// Scan the keys
+		let res = match &self.tx {
+			Inner::Read(tb) => Self::collect(tb.range(rng)?.rev(), limit)?,
+			Inner::Write(Some((tx, _))) => {
+				let tb = tx.open_table(TABLE)?;
+				let res = Self::collect(tb.range(rng)?.rev(), limit)?;
+				res
+			}
+			Inner::Write(None) => return Err(Error::TxFinished),
+		};
+		// Return result
+		Ok(res)
+	}
+	/// Retrieve a range of keys from the databases, without fetching the values
+	pub async fn keys<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<Key>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		let rng = rng.start.as_slice()..rng.end.as_slice();
+		// Scan the keys
+		let res = match &self.tx {
+			Inner::Read(tb) => Self::collect_keys(tb.range(rng)?, limit)?,
+			Inner::Write(Some((tx, _))) => {
+				let tb = tx.open_table(TABLE)?;
+				let res = Self::collect_keys(tb.range(rng)?, limit)?;
+				res
+			}
+			Inner::Write(None) => return Err(Error::TxFinished),
+		};
+		// Return result
+		Ok(res)
+	}
+	/// Retrieve a range of keys from the databases, in reverse order, without fetching the values
+	pub async fn keysr<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<Key>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		let rng = rng.start.as_slice()..rng.end.as_slice();
+		// Scan the keys
+		let res = match &self.tx {
+			Inner::Read(tb) => Self::collect_keys(tb.range(rng)?.rev(), limit)?,
+			Inner::Write(Some((tx, _))) => {
+				let tb = tx.open_table(TABLE)?;
+				let res = Self::collect_keys(tb.range(rng)?.rev(), limit)?;
+				res
+			}
+			Inner::Write(None) => return Err(Error::TxFinished),
+		};
+		// Return result
+		Ok(res)
+	}
+	/// Fetch a key from the current table
+	fn fetch(&self, key: &Key) -> Result<Option<Val>, Error> {
+		let res = match &self.tx {
+			Inner::Read(tb) => tb.get(key.as_slice())?.map(|v| v.value().to_vec()),
+			Inner::Write(Some((tx, _))) => {
+				let tb = tx.open_table(TABLE)?;
+				let res = tb.get(key.as_slice())?.map(|v| v.value().to_vec());
+				res
+			}
+			Inner::Write(None) => return Err(Error::TxFinished),
+		};
+		Ok(res)
+	}
+	/// Fetch the underlying write transaction
+	fn write(&self) -> Result<&WriteTransaction, Error> {
+		match &self.tx {
+			Inner::Write(Some((tx, _))) => Ok(tx),
+			Inner::Write(None) => Err(Error::TxFinished),
+			Inner::Read(_) => Err(Error::TxReadonly),
+		}
+	}
+	/// Collect the entries from a range iterator, up to the specified limit
+	fn collect<'a>(
+		iter: impl Iterator<Item = RangeEntry<'a>>,
+		limit: u32,
+	) -> Result<Vec<(Key, Val)>, Error> {
+		let mut res = vec![];
+		for entry in iter.take(limit as usize) {
+			let (k, v) = entry?;
+			res.push((k.value().to_vec(), v.value().to_vec()));
+		}
+		Ok(res)
+	}
+	/// Collect the keys from a range iterator, up to the specified limit
+	fn collect_keys<'a>(
+		iter: impl Iterator<Item = RangeEntry<'a>>,
+		limit: u32,
+	) -> Result<Vec<Key>, Error> {
+		let mut res = vec![];
+		for entry in iter.take(limit as usize) {
+			let (k, _) = entry?;
+			res.push(k.value().to_vec());
+		}
+		Ok(res)
+	}
+}
@@ -5,6 +5,7 @@ use crate::kvs::Key;
 use crate::kvs::Val;
 use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
 use futures::lock::Mutex;
+use rocksdb::checkpoint::Checkpoint;
 use rocksdb::{OptimisticTransactionDB, OptimisticTransactionOptions, ReadOptions, WriteOptions};
 use std::ops::Range;
 use std::pin::Pin;
@@ -66,6 +67,11 @@ impl Datastore {
 			_db: self.db.clone(),
 		})
 	}
+	/// Create a checkpoint of the database in the specified directory
+	pub async fn checkpoint(&self, path: &str) -> Result<(), Error> {
+		Checkpoint::new(&*self.db)?.create_checkpoint(path)?;
+		Ok(())
+	}
 }
 
 impl Transaction {
@@ -373,4 +379,164 @@ impl Transaction {
 		// Return result
 		Ok(res)
 	}
+	/// Retrieve a range of keys from the databases, in reverse order
+	pub async fn scanr<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Get the transaction
+		let tx = self.tx.lock().await;
+		let tx = tx.as_ref().unwrap();
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		// Create result set
+		let mut res = vec![];
+		// Set the key range
+		let beg = rng.start.as_slice();
+		let end = rng.end.as_slice();
+		// Set the ReadOptions with the snapshot
+		let mut ro = ReadOptions::default();
+		ro.set_snapshot(&tx.snapshot());
+		// Create the iterator
+		let mut iter = tx.raw_iterator_opt(ro);
+		// Seek to the end key
+		iter.seek_for_prev(&rng.end);
+		// Scan the keys in the iterator
+		while iter.valid() {
+			// Check the scan limit
+			if res.len() < limit as usize {
+				// Get the key and value
+				let (k, v) = (iter.key(), iter.value());
+				// Check the key and value
+				if let (Some(k), Some(v)) = (k, v) {
+					// The end of the range is exclusive
+					if k == end {
+						iter.prev();
+						continue;
+					}
+					if k >= beg && k < end {
+						res.push((k.to_vec(), v.to_vec()));
+						iter.prev();
+						continue;
+					}
+				}
+			}
+			// Exit
+			break;
+		}
+		// Return result
+		Ok(res)
+	}
+	/// Retrieve a range of keys from the databases, without fetching the values
+	pub async fn keys<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<Key>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Get the transaction
+		let tx = self.tx.lock().await;
+		let tx = tx.as_ref().unwrap();
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		// Create result set
+		let mut res = vec![];
+		// Set the key range
+		let beg = rng.start.as_slice();
+		let end = rng.end.as_slice();
+		// Set the ReadOptions with the snapshot
+		let mut ro = ReadOptions::default();
+		ro.set_snapshot(&tx.snapshot());
+		// Create the iterator
+		let mut iter = tx.raw_iterator_opt(ro);
+		// Seek to the start key
+		iter.seek(&rng.start);
+		// Scan the keys in the iterator
+		while iter.valid() {
+			// Check the scan limit
+			if res.len() < limit as usize {
+				// Get the key
+				let k = iter.key();
+				// Check the key
+				if let Some(k) = k {
+					if k >= beg && k < end {
+						res.push(k.to_vec());
+						iter.next();
+						continue;
+					}
+				}
+			}
+			// Exit
+			break;
+		}
+		// Return result
+		Ok(res)
+	}
+	/// Retrieve a range of keys from the databases, in reverse order, without fetching the values
+	pub async fn keysr<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<Key>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Get the transaction
+		let tx = self.tx.lock().await;
+		let tx = tx.as_ref().unwrap();
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		// Create result set
+		let mut res = vec![];
+		// Set the key range
+		let beg = rng.start.as_slice();
+		let end = rng.end.as_slice();
+		// Set the ReadOptions with the snapshot
+		let mut ro = ReadOptions::default();
+		ro.set_snapshot(&tx.snapshot());
+		// Create the iterator
+		let mut iter = tx.raw_iterator_opt(ro);
+		// Seek to the end key
+		iter.seek_for_prev(&rng.end);
+		// Scan the keys in the iterator
+		while iter.valid() {
+			// Check the scan limit
+			if res.len() < limit as usize {
+				// Get the key
+				let k = iter.key();
+				// Check the key
+				if let Some(k) = k {
+					// The end of the range is exclusive
+					if k == end {
+						iter.prev();
+						continue;
+					}
+					if k >= beg && k < end {
+						res.push(k.to_vec());
+						iter.prev();
+						continue;
+					}
+				}
+			}
+			// Exit
+			break;
+		}
+		// Return result
+		Ok(res)
+	}
 }
@@ -0,0 +1,214 @@
+//! Savepoints within a transaction.
+//!
+//! While a savepoint is active, writes are not sent to the underlying
+//! datastore, but are buffered in a layer belonging to the most recent
+//! savepoint. Reads check the buffered layers, from the most recent to the
+//! oldest, before falling back to the datastore. Rolling back to a savepoint
+//! discards its layer and every layer above it, and releasing the outermost
+//! savepoint writes the buffered changes to the datastore. Changes whose
+//! keys contain a versionstamp are buffered in the same way, and are only
+//! assigned a versionstamp once they are written to the datastore.
+use crate::cf;
+use crate::err::Error;
+use crate::kvs::stats;
+use crate::kvs::Key;
+use crate::kvs::Val;
+use std::collections::BTreeMap;
+
+/// The buffered changes, where a value of `None` marks a deleted key
+pub(super) type Writes = BTreeMap<Key, Option<Val>>;
+
+/// A buffered change whose key contains a versionstamp, stored as the
+/// versionstamp key, the key prefix, the key suffix, and the value
+pub(super) type Versionstamped = (Key, Key, Key, Val);
+
+/// The changes which were buffered within savepoints
+#[derive(Default)]
+pub(super) struct Changes {
+	/// The changes to keys which are known up front
+	pub writes: Writes,
+	/// The changes to versionstamped keys, in the order they were made
+	pub versionstamped: Vec<Versionstamped>,
+}
+
+impl Changes {
+	fn extend(&mut self, other: Changes) {
+		self.writes.extend(other.writes);
+		self.versionstamped.extend(other.versionstamped);
+	}
+}
+
+struct Savepoint {
+	/// The name of the savepoint
+	name: String,
+	/// The changes made since the savepoint was created
+	changes: Changes,
+	/// The change feed entries as they were when the savepoint was created
+	cf: cf::Writer,
+	/// The changes to the storage statistics as they were when the savepoint was created
+	st: stats::Writer,
+}
+
+#[derive(Default)]
+pub(super) struct Savepoints(Vec<Savepoint>);
+
+impl Savepoints {
+	/// Check if any savepoints are active
+	pub fn is_active(&self) -> bool {
+		!self.0.is_empty()
+	}
+
+	/// Create a new savepoint on top of any existing savepoints
+	pub fn create(&mut self, name: &str, cf: cf::Writer, st: stats::Writer) {
+		self.0.push(Savepoint {
+			name: name.to_owned(),
+			changes: Changes::default(),
+			cf,
+			st,
+		});
+	}
+
+	/// Fetch the buffered change for a key, if it has been changed
+	pub fn get(&self, key: &Key) -> Option<Option<Val>> {
+		self.0.iter().rev().find_map(|sp| sp.changes.writes.get(key)).cloned()
+	}
+
+	/// Buffer a change for a key in the most recent savepoint
+	pub fn set(&mut self, key: Key, val: Option<Val>) {
+		if let Some(sp) = self.0.last_mut() {
+			sp.changes.writes.insert(key, val);
+		}
+	}
+
+	/// Buffer a change for a versionstamped key in the most recent savepoint
+	pub fn set_versionstamped(&mut self, change: Versionstamped) {
+		if let Some(sp) = self.0.last_mut() {
+			sp.changes.versionstamped.push(change);
+		}
+	}
+
+	/// Fetch the buffered changes within a range of keys
+	pub fn range(&self, beg: &Key, end: &Key) -> Writes {
+		let mut out = Writes::new();
+		if beg >= end {
+			return out;
+		}
+		for sp in self.0.iter() {
+			for (k, v) in sp.changes.writes.range(beg.clone()..end.clone()) {
+				out.insert(k.clone(), v.clone());
+			}
+		}
+		out
+	}
+
+	/// Discard the changes made since a savepoint was created, keeping the
+	/// savepoint itself, and return the change feed entries and the changes
+	/// to the storage statistics at that point
+	pub fn rollback(&mut self, name: &str) -> Result<(cf::Writer, stats::Writer), Error> {
+		let pos = self.position(name)?;
+		self.0.truncate(pos + 1);
+		let sp = &mut self.0[pos];
+		sp.changes = Changes::default();
+		Ok((sp.cf.clone(), sp.st.clone()))
+	}
+
+	/// Remove a savepoint, and any savepoints created after it, keeping their
+	/// changes. The changes are returned if they need to be written to the
+	/// datastore, because no savepoints remain.
+	pub fn release(&mut self, name: &str) -> Result<Option<Changes>, Error> {
+		let pos = self.position(name)?;
+		let changes = Self::merge(self.0.drain(pos..));
+		match self.0.last_mut() {
+			Some(sp) => {
+				sp.changes.extend(changes);
+				Ok(None)
+			}
+			None => Ok(Some(changes)),
+		}
+	}
+
+	/// Remove all savepoints, returning all of the buffered changes
+	pub fn take(&mut self) -> Changes {
+		Self::merge(self.0.drain(..))
+	}
+
+	/// Remove all savepoints, discarding all of the buffered changes
+	pub fn clear(&mut self) {
+		self.0.clear();
+	}
+
+	/// Find the most recent savepoint with the specified name
+	fn position(&self, name: &str) -> Result<usize, Error> {
+		self.0.iter().rposition(|sp| sp.name == name).ok_or_else(|| Error::TxSavepointNotFound {
+			name: name.to_owned(),
+		})
+	}
+
+	/// Merge the changes of a number of savepoints, in the order they were created
+	fn merge(savepoints: impl Iterator<Item = Savepoint>) -> Changes {
+		savepoints.fold(Changes::default(), |mut acc, sp| {
+			acc.extend(sp.changes);
+			acc
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn key(k: &str) -> Key {
+		k.as_bytes().to_vec()
+	}
+
+	#[test]
+	fn nested_savepoints() {
+		let mut sp = Savepoints::default();
+		assert!(!sp.is_active());
+		sp.create("one", cf::Writer::new(), stats::Writer::default());
+		sp.set(key("a"), Some(b"1".to_vec()));
+		sp.create("two", cf::Writer::new(), stats::Writer::default());
+		sp.set(key("a"), None);
+		sp.set(key("b"), Some(b"2".to_vec()));
+		// The most recent change is visible
+		assert_eq!(sp.get(&key("a")), Some(None));
+		assert_eq!(sp.range(&key("a"), &key("c")).len(), 2);
+		// Rolling back restores the earlier changes
+		sp.rollback("two").unwrap();
+		assert_eq!(sp.get(&key("a")), Some(Some(b"1".to_vec())));
+		assert_eq!(sp.get(&key("b")), None);
+		// Releasing the inner savepoint keeps its changes
+		sp.set(key("c"), Some(b"3".to_vec()));
+		assert!(sp.release("two").unwrap().is_none());
+		assert_eq!(sp.get(&key("c")), Some(Some(b"3".to_vec())));
+		// Releasing the outer savepoint returns all of the changes
+		let changes = sp.release("one").unwrap().unwrap();
+		assert_eq!(changes.writes.len(), 2);
+		assert!(!sp.is_active());
+	}
+
+	#[test]
+	fn versionstamped_changes() {
+		let change = |v: &str| (key("ts"), key("cf"), key("tb"), v.as_bytes().to_vec());
+		let mut sp = Savepoints::default();
+		sp.create("one", cf::Writer::new(), stats::Writer::default());
+		sp.set_versionstamped(change("1"));
+		sp.create("two", cf::Writer::new(), stats::Writer::default());
+		sp.set_versionstamped(change("2"));
+		// Rolling back discards the later changes
+		sp.rollback("two").unwrap();
+		sp.set_versionstamped(change("3"));
+		// Releasing keeps the changes in the order they were made
+		let changes = sp.release("one").unwrap().unwrap();
+		assert_eq!(changes.versionstamped, vec![change("1"), change("3")]);
+	}
+
+	#[test]
+	fn unknown_savepoint() {
+		let mut sp = Savepoints::default();
+		sp.create("one", cf::Writer::new(), stats::Writer::default());
+		assert!(matches!(sp.rollback("two"), Err(Error::TxSavepointNotFound { .. })));
+		assert!(matches!(sp.release("two"), Err(Error::TxSavepointNotFound { .. })));
+		assert!(sp.is_active());
+	}
+}
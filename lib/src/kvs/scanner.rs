@@ -0,0 +1,133 @@
+//! Streaming range iterators over the keys in a transaction.
+//!
+//! A [`Scanner`] describes a range of keys, along with the direction of
+//! iteration, whether values are required, and how many entries are fetched
+//! from the datastore at a time. It can then be turned into an async stream
+//! which fetches each batch of entries lazily, as it is consumed.
+use crate::err::Error;
+use crate::kvs::Key;
+use crate::kvs::Transaction;
+use crate::kvs::Val;
+use futures::lock::Mutex;
+use futures::stream::Stream;
+use futures::stream::TryStreamExt;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// The default number of entries which are fetched at a time
+const DEFAULT_PREFETCH: u32 = 1000;
+
+#[derive(Clone, Debug)]
+pub struct Scanner {
+	/// The keys which have not yet been fetched
+	rng: Range<Key>,
+	/// Whether the keys are iterated from the greatest to the smallest
+	reverse: bool,
+	/// Whether the values are fetched along with the keys
+	values: bool,
+	/// The number of entries to fetch at a time
+	prefetch: u32,
+	/// Whether all of the entries in the range have been fetched
+	done: bool,
+}
+
+impl Scanner {
+	/// Create a scanner over a range of keys, in ascending order
+	pub fn new<K>(rng: Range<K>) -> Self
+	where
+		K: Into<Key>,
+	{
+		Self {
+			rng: rng.start.into()..rng.end.into(),
+			reverse: false,
+			values: true,
+			prefetch: DEFAULT_PREFETCH,
+			done: false,
+		}
+	}
+
+	/// Create a scanner over all of the keys with a prefix, in ascending order
+	pub fn prefix<K>(prefix: K) -> Self
+	where
+		K: Into<Key>,
+	{
+		let beg: Key = prefix.into();
+		let end = [beg.as_slice(), &[0xff]].concat();
+		Self::new(beg..end)
+	}
+
+	/// Iterate over the keys in descending order
+	pub fn reverse(mut self) -> Self {
+		self.reverse = true;
+		self
+	}
+
+	/// Only fetch the keys, leaving the values empty
+	pub fn keys_only(mut self) -> Self {
+		self.values = false;
+		self
+	}
+
+	/// Set the number of entries which are fetched at a time
+	pub fn prefetch(mut self, size: u32) -> Self {
+		self.prefetch = size.max(1);
+		self
+	}
+
+	/// Fetch the next batch of entries, returning an empty batch once the range is exhausted
+	pub async fn next_batch(&mut self, tx: &mut Transaction) -> Result<Vec<(Key, Val)>, Error> {
+		// Check if the range has been exhausted
+		if self.done || self.rng.start >= self.rng.end {
+			self.done = true;
+			return Ok(vec![]);
+		}
+		// Fetch the next batch of entries
+		let res = tx.scan_range(self.rng.clone(), self.prefetch, self.reverse, self.values).await?;
+		// Check if this was the last batch
+		if res.len() < self.prefetch as usize {
+			self.done = true;
+		}
+		// Move the range past the fetched entries
+		if let Some((k, _)) = res.last() {
+			match self.reverse {
+				true => self.rng.end = k.clone(),
+				false => {
+					let mut k = k.clone();
+					k.push(0x00);
+					self.rng.start = k;
+				}
+			}
+		}
+		Ok(res)
+	}
+
+	/// Stream the entries from a transaction which is borrowed for the life of the stream
+	pub fn stream(
+		self,
+		tx: &mut Transaction,
+	) -> impl Stream<Item = Result<(Key, Val), Error>> + '_ {
+		futures::stream::try_unfold((self, tx), |(mut scanner, tx)| async move {
+			let res = scanner.next_batch(tx).await?;
+			Ok::<_, Error>(match res.is_empty() {
+				true => None,
+				false => Some((futures::stream::iter(res.into_iter().map(Ok)), (scanner, tx))),
+			})
+		})
+		.try_flatten()
+	}
+
+	/// Stream the entries from a shared transaction, which is only locked while fetching a batch
+	pub fn into_stream(
+		self,
+		tx: Arc<Mutex<Transaction>>,
+	) -> impl Stream<Item = Result<(Key, Val), Error>> {
+		futures::stream::try_unfold((self, tx), |(mut scanner, tx)| async move {
+			let res = scanner.next_batch(&mut *tx.lock().await).await?;
+			Ok::<_, Error>(match res.is_empty() {
+				true => None,
+				false => Some((futures::stream::iter(res.into_iter().map(Ok)), (scanner, tx))),
+			})
+		})
+		.try_flatten()
+	}
+}
@@ -0,0 +1,186 @@
+//! The storage statistics of each table.
+//!
+//! Counting the records and bytes stored within a table would require
+//! scanning all of its keys, so the statistics are maintained as the data is
+//! written instead. Each transaction buffers the changes which it makes, and
+//! adds them to a key for each table which belongs to the node which it runs
+//! on when it commits, so that transactions on different nodes never conflict
+//! on a shared counter. The changes stored by each node are periodically
+//! folded into the base statistics of each table by [`super::Datastore::tick`],
+//! which also counts the statistics of any tables which stored data before
+//! they were maintained, or whenever [`super::Datastore::recount_storage_stats`]
+//! is called.
+use crate::sql::object::Object;
+use crate::sql::value::Value;
+use derive::Store;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The storage statistics of a table, or a change to those statistics
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store)]
+#[non_exhaustive]
+pub struct Stats {
+	/// The number of records
+	pub records: i64,
+	/// The size of the keys of the records, in bytes
+	pub keys: i64,
+	/// The size of the values of the records, in bytes
+	pub values: i64,
+	/// The size of the entries of each unique and non-unique index, in bytes
+	pub indexes: BTreeMap<String, i64>,
+	/// The size of the change feed entries, in bytes
+	pub changes: i64,
+}
+
+impl Stats {
+	/// The change caused by writing a record, given the size of its key, and
+	/// the sizes of its previous and new values, if the record exists
+	pub(crate) fn record(key: usize, old: Option<u64>, new: Option<u64>) -> Self {
+		let records = new.is_some() as i64 - old.is_some() as i64;
+		Self {
+			records,
+			keys: records * key as i64,
+			values: new.unwrap_or(0) as i64 - old.unwrap_or(0) as i64,
+			..Self::default()
+		}
+	}
+
+	/// The change caused by writing or deleting the entries of an index
+	pub(crate) fn index(ix: &str, size: i64) -> Self {
+		Self {
+			indexes: BTreeMap::from([(ix.to_owned(), size)]),
+			..Self::default()
+		}
+	}
+
+	/// The change caused by writing or deleting change feed entries
+	pub(crate) fn changes(size: i64) -> Self {
+		Self {
+			changes: size,
+			..Self::default()
+		}
+	}
+
+	/// The total size of the records, index entries and change feed entries, in bytes
+	pub fn storage(&self) -> i64 {
+		self.keys + self.values + self.changes + self.indexes.values().sum::<i64>()
+	}
+
+	/// Check if these statistics are all zero
+	pub fn is_empty(&self) -> bool {
+		*self == Self::default()
+	}
+
+	/// Apply a change to these statistics
+	pub fn add(&mut self, other: &Stats) {
+		self.records += other.records;
+		self.keys += other.keys;
+		self.values += other.values;
+		self.changes += other.changes;
+		for (ix, size) in other.indexes.iter() {
+			let total = self.indexes.entry(ix.clone()).or_default();
+			*total += size;
+			if *total == 0 {
+				self.indexes.remove(ix);
+			}
+		}
+	}
+}
+
+impl From<Stats> for Object {
+	fn from(v: Stats) -> Self {
+		let mut res = Object::default();
+		res.insert("records".to_owned(), v.records.into());
+		res.insert("keys".to_owned(), v.keys.into());
+		res.insert("values".to_owned(), v.values.into());
+		res.insert("changes".to_owned(), v.changes.into());
+		let mut tmp = Object::default();
+		for (ix, size) in v.indexes {
+			tmp.insert(ix, size.into());
+		}
+		res.insert("indexes".to_owned(), tmp.into());
+		res
+	}
+}
+
+impl From<Stats> for Value {
+	fn from(v: Stats) -> Self {
+		Object::from(v).into()
+	}
+}
+
+/// The changes to the statistics of each table which are buffered within a transaction
+#[derive(Clone, Default)]
+pub(super) struct Writer(BTreeMap<(String, String, String), Stats>);
+
+impl Writer {
+	/// Buffer a change to the statistics of a table
+	pub fn update(&mut self, ns: &str, db: &str, tb: &str, st: &Stats) {
+		let key = (ns.to_owned(), db.to_owned(), tb.to_owned());
+		self.0.entry(key).or_default().add(st);
+	}
+
+	/// Fetch the buffered change to the statistics of a table
+	pub fn get(&self, ns: &str, db: &str, tb: &str) -> Option<&Stats> {
+		self.0.get(&(ns.to_owned(), db.to_owned(), tb.to_owned()))
+	}
+
+	/// Fetch the total of the buffered changes within a namespace, or within a single database
+	pub fn total(&self, ns: &str, db: Option<&str>) -> Stats {
+		let mut out = Stats::default();
+		for ((n, d, _), st) in self.0.iter() {
+			if n == ns && db.map_or(true, |db| d == db) {
+				out.add(st);
+			}
+		}
+		out
+	}
+
+	/// Remove all of the buffered changes
+	pub fn take(&mut self) -> BTreeMap<(String, String, String), Stats> {
+		std::mem::take(&mut self.0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn record_changes() {
+		let mut st = Stats::default();
+		st.add(&Stats::record(10, None, Some(100)));
+		st.add(&Stats::record(12, None, Some(50)));
+		st.add(&Stats::record(10, Some(100), Some(80)));
+		assert_eq!(st.records, 2);
+		assert_eq!(st.keys, 22);
+		assert_eq!(st.values, 130);
+		st.add(&Stats::record(12, Some(50), None));
+		assert_eq!(st.records, 1);
+		assert_eq!(st.keys, 10);
+		assert_eq!(st.values, 80);
+	}
+
+	#[test]
+	fn index_changes() {
+		let mut st = Stats::default();
+		st.add(&Stats::index("one", 20));
+		st.add(&Stats::index("two", 30));
+		st.add(&Stats::index("one", -20));
+		assert_eq!(st.indexes, BTreeMap::from([("two".to_owned(), 30)]));
+		st.add(&Stats::index("two", -30));
+		assert!(st.is_empty());
+	}
+
+	#[test]
+	fn buffered_totals() {
+		let mut w = Writer::default();
+		w.update("ns", "one", "person", &Stats::record(10, None, Some(100)));
+		w.update("ns", "one", "company", &Stats::record(10, None, Some(50)));
+		w.update("ns", "two", "person", &Stats::record(10, None, Some(20)));
+		w.update("other", "one", "person", &Stats::record(10, None, Some(10)));
+		assert_eq!(w.total("ns", None).records, 3);
+		assert_eq!(w.total("ns", Some("one")).values, 150);
+		assert_eq!(w.total("ns", Some("three")), Stats::default());
+	}
+}
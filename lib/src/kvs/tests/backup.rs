@@ -0,0 +1,137 @@
+async fn backup_datastore(ds: &Datastore) -> Vec<Vec<u8>> {
+	let (snd, rcv) = channel::unbounded();
+	ds.backup(snd).await.unwrap();
+	let mut res = vec![];
+	while let Ok(v) = rcv.try_recv() {
+		res.push(v);
+	}
+	res
+}
+
+async fn restore_datastore(ds: &Datastore, data: Vec<Vec<u8>>) -> Result<(), crate::err::Error> {
+	ds.restore(futures::stream::iter(data.into_iter().map(Ok::<_, crate::err::Error>))).await
+}
+
+#[tokio::test]
+#[serial]
+async fn backup_and_restore() {
+	let ses = crate::dbs::Session::for_kv().with_ns("test").with_db("test");
+	// Create some data in the source datastore
+	let src = new_ds().await;
+	let sql = "DEFINE TABLE person CHANGEFEED 1h; CREATE person:one SET name = 'Tobie';";
+	for res in src.execute(sql, &ses, None).await.unwrap() {
+		res.result.unwrap();
+	}
+	let data = backup_datastore(&src).await;
+	// Restore the backup into a new datastore
+	let dst = new_ds().await;
+	restore_datastore(&dst, data.clone()).await.unwrap();
+	let sql = "SELECT * FROM person; CREATE person:two SET name = 'Jaime'; SHOW CHANGES FOR TABLE person SINCE 0;";
+	let mut res = dst.execute(sql, &ses, None).await.unwrap();
+	let val = res.remove(0).result.unwrap();
+	assert_eq!(val, crate::sql::value("[{ id: person:one, name: 'Tobie' }]").unwrap());
+	res.remove(0).result.unwrap();
+	// The change feed continues from the restored versionstamp
+	let val = res.remove(0).result.unwrap();
+	assert!(matches!(val, crate::sql::Value::Array(v) if v.len() == 3));
+	// A backup can not be restored into a datastore which contains data
+	let res = restore_datastore(&dst, data).await;
+	assert!(matches!(res, Err(crate::err::Error::Backup(_))));
+}
+
+#[tokio::test]
+#[serial]
+async fn backup_and_restore_incomplete() {
+	let src = new_ds().await;
+	let mut tx = src.transaction(true, false).await.unwrap();
+	tx.set("test", "ok").await.unwrap();
+	tx.commit().await.unwrap();
+	let mut data = backup_datastore(&src).await;
+	// The final frame of the backup is missing
+	data.pop();
+	let dst = new_ds().await;
+	let res = restore_datastore(&dst, data).await;
+	assert!(matches!(res, Err(crate::err::Error::Backup(_))));
+}
+
+#[tokio::test]
+#[serial]
+async fn backup_and_restore_encrypted() {
+	// Create some data in an encrypted datastore
+	let src = new_ds().await.with_encryption_key(Some("0123456789abcdef"), None).await.unwrap();
+	src.bootstrap_full(&crate::sql::Uuid::new_v4()).await.unwrap();
+	let mut tx = src.transaction(true, false).await.unwrap();
+	tx.set("test", "ok").await.unwrap();
+	tx.commit().await.unwrap();
+	let data = backup_datastore(&src).await;
+	// Restore the backup into a datastore with a different key
+	let dst = new_ds().await.with_encryption_key(Some("fedcba9876543210"), None).await.unwrap();
+	dst.bootstrap_full(&crate::sql::Uuid::new_v4()).await.unwrap();
+	restore_datastore(&dst, data).await.unwrap();
+	let mut tx = dst.transaction(false, false).await.unwrap();
+	let val = tx.get("test").await.unwrap();
+	assert!(matches!(val.as_deref(), Some(b"ok")));
+	tx.cancel().await.unwrap();
+	// The datastore can be opened again with its own key
+	dst.bootstrap_full(&crate::sql::Uuid::new_v4()).await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn backup_and_replay() {
+	use crate::kvs::RestorePoint;
+	let ses = crate::dbs::Session::for_kv().with_ns("test").with_db("test");
+	let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap();
+	let now = now.as_secs();
+	// Take a backup of the source datastore
+	let src = new_ds().await;
+	let sql = "DEFINE TABLE person CHANGEFEED 1h; CREATE person:one SET name = 'Tobie';";
+	for res in src.execute(sql, &ses, None).await.unwrap() {
+		res.result.unwrap();
+	}
+	let base = backup_datastore(&src).await;
+	// Make some changes before and after a point in time
+	let sql = "CREATE person:two SET name = 'Jaime';";
+	for res in src.execute(sql, &ses, None).await.unwrap() {
+		res.result.unwrap();
+	}
+	src.tick_at(now).await.unwrap();
+	let sql = "DELETE person:one; CREATE person:three SET name = 'Lizzie';";
+	for res in src.execute(sql, &ses, None).await.unwrap() {
+		res.result.unwrap();
+	}
+	src.tick_at(now + 1).await.unwrap();
+	let later = backup_datastore(&src).await;
+	// Read the versionstamps as they are displayed by SHOW CHANGES
+	let sql = "SHOW CHANGES FOR TABLE person SINCE 0";
+	let mut res = src.execute(sql, &ses, None).await.unwrap();
+	let val = res.remove(0).result.unwrap();
+	let vs = |i: usize| {
+		let vs = val.pick(&[crate::sql::Part::Index(i.into()), "versionstamp".into()]);
+		RestorePoint::versionstamp(u128::try_from(vs).unwrap()).unwrap()
+	};
+	// Recover up to the change which created person:two
+	let dst = new_ds().await;
+	restore_datastore(&dst, base.clone()).await.unwrap();
+	let stream = futures::stream::iter(later.clone().into_iter().map(Ok));
+	dst.replay(stream, vs(2)).await.unwrap();
+	let mut res = dst.execute("SELECT VALUE id FROM person", &ses, None).await.unwrap();
+	let val = res.remove(0).result.unwrap();
+	assert_eq!(val, crate::sql::value("[person:one, person:two]").unwrap());
+	// Recover to the point in time
+	let dst = new_ds().await;
+	restore_datastore(&dst, base.clone()).await.unwrap();
+	let stream = futures::stream::iter(later.clone().into_iter().map(Ok));
+	dst.replay(stream, RestorePoint::Timestamp(now)).await.unwrap();
+	let mut res = dst.execute("SELECT VALUE id FROM person", &ses, None).await.unwrap();
+	let val = res.remove(0).result.unwrap();
+	assert_eq!(val, crate::sql::value("[person:one, person:two]").unwrap());
+	// Recover all of the changes
+	let dst = new_ds().await;
+	restore_datastore(&dst, base).await.unwrap();
+	let stream = futures::stream::iter(later.into_iter().map(Ok));
+	dst.replay(stream, vs(4)).await.unwrap();
+	let mut res = dst.execute("SELECT VALUE id FROM person", &ses, None).await.unwrap();
+	let val = res.remove(0).result.unwrap();
+	assert_eq!(val, crate::sql::value("[person:three, person:two]").unwrap());
+}
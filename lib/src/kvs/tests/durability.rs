@@ -0,0 +1,61 @@
+#[tokio::test]
+#[serial]
+async fn durability_recovers_committed_transactions() {
+	let dir = TempDir::new().unwrap();
+	let path = format!("memory:{}", dir.path().to_string_lossy());
+	// Write some data to a durable datastore
+	let ds = Datastore::new(&path).await.unwrap();
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set("test", "ok").await.unwrap();
+	tx.set("temp", "ok").await.unwrap();
+	tx.commit().await.unwrap();
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.del("temp").await.unwrap();
+	tx.putc("test", "updated", Some("ok")).await.unwrap();
+	tx.commit().await.unwrap();
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set("none", "ok").await.unwrap();
+	tx.cancel().await.unwrap();
+	drop(ds);
+	// Reopen the datastore from disk
+	let ds = Datastore::new(&path).await.unwrap();
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.get("test").await.unwrap();
+	assert!(matches!(val.as_deref(), Some(b"updated")));
+	assert!(!tx.exi("temp").await.unwrap());
+	assert!(!tx.exi("none").await.unwrap());
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn durability_ignores_partially_written_transactions() {
+	let dir = TempDir::new().unwrap();
+	let path = format!("memory:{}", dir.path().to_string_lossy());
+	// Write some data to a durable datastore
+	let ds = Datastore::new(&path).await.unwrap();
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set("test", "ok").await.unwrap();
+	tx.commit().await.unwrap();
+	drop(ds);
+	// Simulate a crash while writing a transaction
+	let log = dir.path().join("log");
+	let mut data = std::fs::read(&log).unwrap();
+	let len = data.len();
+	data.extend_from_within(..len - 1);
+	std::fs::write(&log, data).unwrap();
+	// Reopen the datastore from disk
+	let ds = Datastore::new(&path).await.unwrap();
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	let val = tx.get("test").await.unwrap();
+	assert!(matches!(val.as_deref(), Some(b"ok")));
+	tx.set("temp", "ok").await.unwrap();
+	tx.commit().await.unwrap();
+	drop(ds);
+	// The log continues after the last complete transaction
+	let ds = Datastore::new(&path).await.unwrap();
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	assert!(tx.exi("test").await.unwrap());
+	assert!(tx.exi("temp").await.unwrap());
+	tx.cancel().await.unwrap();
+}
@@ -0,0 +1,170 @@
+#[tokio::test]
+#[serial]
+async fn encryption_round_trip() {
+	// Create a new encrypted datastore
+	let ds = new_ds().await.with_encryption_key(Some("0123456789abcdef"), None).await.unwrap();
+	ds.bootstrap().await.unwrap();
+	// Create a writeable transaction
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set("test", "ok").await.unwrap();
+	tx.commit().await.unwrap();
+	// Values are decrypted transparently
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.get("test").await.unwrap();
+	assert!(matches!(val.as_deref(), Some(b"ok")));
+	let val = tx.scan("test".."tesu", 10).await.unwrap();
+	assert_eq!(val, vec![(b"test".to_vec(), b"ok".to_vec())]);
+	// Values are not stored in plaintext
+	tx.enc = None;
+	let val = tx.get("test").await.unwrap().unwrap();
+	assert_ne!(val, b"ok");
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn encryption_conditional_operations() {
+	// Create a new encrypted datastore
+	let ds = new_ds().await.with_encryption_key(Some("0123456789abcdef"), None).await.unwrap();
+	ds.bootstrap().await.unwrap();
+	// Create a writeable transaction
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.putc("test", "ok", None).await.unwrap();
+	assert!(tx.putc("test", "ko", Some("none")).await.is_err());
+	tx.putc("test", "ko", Some("ok")).await.unwrap();
+	assert!(tx.delc("test", Some("ok")).await.is_err());
+	tx.delc("test", Some("ko")).await.unwrap();
+	assert!(!tx.exi("test").await.unwrap());
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn encryption_key_checked() {
+	// Create a new encrypted datastore
+	let ds = new_ds().await.with_encryption_key(Some("0123456789abcdef"), None).await.unwrap();
+	ds.bootstrap().await.unwrap();
+	// Opening the datastore without a key fails
+	let ds = ds.with_encryption_key(None, None).await.unwrap();
+	assert!(matches!(
+		ds.bootstrap_full(&crate::sql::Uuid::new_v4()).await,
+		Err(crate::err::Error::EncryptionKeyMissing)
+	));
+	// Opening the datastore with the wrong key fails
+	let ds = ds.with_encryption_key(Some("fedcba9876543210"), None).await.unwrap();
+	assert!(matches!(
+		ds.bootstrap_full(&crate::sql::Uuid::new_v4()).await,
+		Err(crate::err::Error::EncryptionKeyInvalid)
+	));
+	// Opening the datastore with the correct key succeeds
+	let ds = ds.with_encryption_key(Some("0123456789abcdef"), None).await.unwrap();
+	ds.bootstrap_full(&crate::sql::Uuid::new_v4()).await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn encryption_key_rotation() {
+	// Create a new encrypted datastore
+	let ds = new_ds().await.with_encryption_key(Some("0123456789abcdef"), None).await.unwrap();
+	ds.bootstrap().await.unwrap();
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set("test", "ok").await.unwrap();
+	tx.commit().await.unwrap();
+	// Rotate the encryption key
+	let ds =
+		ds.with_encryption_key(Some("fedcba9876543210"), Some("0123456789abcdef")).await.unwrap();
+	ds.bootstrap_full(&crate::sql::Uuid::new_v4()).await.unwrap();
+	// Opening the datastore with the old key fails
+	let ds = ds.with_encryption_key(Some("0123456789abcdef"), None).await.unwrap();
+	assert!(matches!(
+		ds.bootstrap_full(&crate::sql::Uuid::new_v4()).await,
+		Err(crate::err::Error::EncryptionKeyInvalid)
+	));
+	// Values are readable with only the new key
+	let ds = ds.with_encryption_key(Some("fedcba9876543210"), None).await.unwrap();
+	ds.bootstrap_full(&crate::sql::Uuid::new_v4()).await.unwrap();
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.get("test").await.unwrap();
+	assert!(matches!(val.as_deref(), Some(b"ok")));
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn encryption_of_existing_datastore() {
+	// Create a new unencrypted datastore
+	let ds = new_ds().await;
+	ds.bootstrap().await.unwrap();
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set("test", "ok").await.unwrap();
+	tx.commit().await.unwrap();
+	// Encrypt the existing datastore
+	let ds = ds.with_encryption_key(Some("0123456789abcdef"), None).await.unwrap();
+	ds.bootstrap_full(&crate::sql::Uuid::new_v4()).await.unwrap();
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.get("test").await.unwrap();
+	assert!(matches!(val.as_deref(), Some(b"ok")));
+	tx.enc = None;
+	let val = tx.get("test").await.unwrap().unwrap();
+	assert_ne!(val, b"ok");
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn encryption_keys_are_salted() {
+	let mut ids = vec![];
+	for _ in 0..2 {
+		// Create a new encrypted datastore with the same key
+		let ds = new_ds().await.with_encryption_key(Some("0123456789abcdef"), None).await.unwrap();
+		ds.bootstrap().await.unwrap();
+		// The salt is stored unencrypted
+		let mut tx = ds.transaction(false, false).await.unwrap();
+		tx.enc = None;
+		let salt = tx.get(crate::key::root::es::new()).await.unwrap().unwrap();
+		assert_eq!(salt.len(), 16);
+		// The key id of the check value identifies the derived key
+		let chk = tx.get(crate::key::root::ek::new()).await.unwrap().unwrap();
+		ids.push(chk[1..5].to_vec());
+		tx.cancel().await.unwrap();
+		// The salt is reused when the datastore is opened again
+		let ds = ds.with_encryption_key(Some("0123456789abcdef"), None).await.unwrap();
+		ds.bootstrap_full(&crate::sql::Uuid::new_v4()).await.unwrap();
+	}
+	// The same key derives a different key for each datastore
+	assert_ne!(ids[0], ids[1]);
+}
+
+#[tokio::test]
+#[serial]
+async fn encryption_rename() {
+	// Create a new encrypted datastore
+	let ds = new_ds().await.with_encryption_key(Some("0123456789abcdef"), None).await.unwrap();
+	ds.bootstrap().await.unwrap();
+	let ses = crate::dbs::Session::for_kv().with_ns("test").with_db("test");
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		CREATE person:one SET name = 'Tobie';
+		RENAME TABLE person TO user;
+		RENAME DATABASE test TO other;
+	";
+	for res in ds.execute(sql, &ses, None).await.unwrap() {
+		res.result.unwrap();
+	}
+	// The versionstamp of the database is still stored unencrypted
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	tx.enc = None;
+	let val = tx.get(crate::key::database::vs::new("test", "other")).await.unwrap().unwrap();
+	assert_eq!(val.len(), 10);
+	tx.cancel().await.unwrap();
+	// The renamed data can be read and written
+	let ses = crate::dbs::Session::for_kv().with_ns("test").with_db("other");
+	let sql = "
+		CREATE user:two SET name = 'Jaime';
+		SELECT VALUE name FROM user;
+	";
+	let mut res = ds.execute(sql, &ses, None).await.unwrap();
+	res.remove(0).result.unwrap();
+	let val = res.remove(0).result.unwrap();
+	assert_eq!(val, crate::sql::value("['Tobie', 'Jaime']").unwrap());
+}
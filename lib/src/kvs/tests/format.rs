@@ -0,0 +1,132 @@
+#[tokio::test]
+#[serial]
+async fn migrate_key_format() {
+	let ses = crate::dbs::Session::for_kv().with_ns("test").with_db("test");
+	// Store some data in the original key format
+	let ds = new_ds().await;
+	ds.set_key_format(crate::kvs::KeyFormat::V1).await.unwrap();
+	let sql = "
+		DEFINE INDEX name ON person FIELDS name UNIQUE;
+		CREATE person:one SET name = 'Tobie';
+		CREATE person:two SET name = 'Jaime';
+		RELATE person:one->knows->person:two;
+	";
+	for res in ds.execute(sql, &ses, None).await.unwrap() {
+		res.result.unwrap();
+	}
+	let key = crate::key::thing::new("test", "test", "person", &"one".into());
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	assert!(tx.exi(key.clone()).await.unwrap());
+	tx.cancel().await.unwrap();
+	// Move the data under the numeric ids
+	ds.migrate_key_format().await.unwrap();
+	assert_eq!(ds.key_format(), crate::kvs::KeyFormat::V2);
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	assert!(!tx.exi(key).await.unwrap());
+	tx.cancel().await.unwrap();
+	// The records, graph edges and index entries were all moved
+	let sql = "
+		SELECT VALUE id FROM person WHERE name = 'Jaime';
+		SELECT VALUE ->knows->person.name FROM person:one;
+		CREATE person:three SET name = 'Tobie';
+	";
+	let mut res = ds.execute(sql, &ses, None).await.unwrap();
+	let val = res.remove(0).result.unwrap();
+	assert_eq!(val, crate::sql::value("[person:two]").unwrap());
+	let val = res.remove(0).result.unwrap();
+	assert_eq!(val, crate::sql::value("[['Jaime']]").unwrap());
+	let res = res.remove(0).result;
+	assert!(matches!(res, Err(crate::err::Error::IndexExists { .. })));
+}
+
+#[tokio::test]
+#[serial]
+async fn rename_table_in_original_key_format() {
+	let ses = crate::dbs::Session::for_kv().with_ns("test").with_db("test");
+	// Store some data in the original key format
+	let ds = new_ds().await;
+	ds.set_key_format(crate::kvs::KeyFormat::V1).await.unwrap();
+	let sql = "
+		DEFINE INDEX name ON person FIELDS name UNIQUE;
+		CREATE person:one SET name = 'Tobie';
+		CREATE person:two SET name = 'Jaime';
+		RELATE person:one->knows->person:two;
+		RENAME TABLE person TO user;
+	";
+	for res in ds.execute(sql, &ses, None).await.unwrap() {
+		res.result.unwrap();
+	}
+	// The data was moved under the new table name
+	let key = crate::key::thing::new("test", "test", "person", &"one".into());
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	assert!(!tx.exi(key).await.unwrap());
+	let key = crate::key::thing::new("test", "test", "user", &"one".into());
+	assert!(tx.exi(key).await.unwrap());
+	tx.cancel().await.unwrap();
+	// The records, graph edges and index entries were all rewritten
+	let sql = "
+		SELECT VALUE id FROM user WHERE name = 'Jaime';
+		SELECT VALUE ->knows->user.name FROM user:one;
+		CREATE user:three SET name = 'Tobie';
+	";
+	let mut res = ds.execute(sql, &ses, None).await.unwrap();
+	let val = res.remove(0).result.unwrap();
+	assert_eq!(val, crate::sql::value("[user:two]").unwrap());
+	let val = res.remove(0).result.unwrap();
+	assert_eq!(val, crate::sql::value("[['Jaime']]").unwrap());
+	let res = res.remove(0).result;
+	assert!(matches!(res, Err(crate::err::Error::IndexExists { .. })));
+}
+
+#[tokio::test]
+#[serial]
+async fn relate_records_of_undefined_tables() {
+	let ses = crate::dbs::Session::for_kv().with_ns("test").with_db("test");
+	let ds = new_ds().await.with_strict_mode(true);
+	let sql = "
+		DEFINE NAMESPACE test;
+		DEFINE DATABASE test;
+		DEFINE TABLE knows;
+		RELATE person:one->knows->person:two;
+		SELECT VALUE out FROM person:one->knows;
+	";
+	let mut res = ds.execute(sql, &ses, None).await.unwrap();
+	let val = res.pop().unwrap().result.unwrap();
+	assert_eq!(val, crate::sql::value("[person:two]").unwrap());
+	for res in res {
+		res.result.unwrap();
+	}
+	// The edges are moved once the table is defined
+	let sql = "
+		DEFINE TABLE person;
+		SELECT VALUE out FROM person:one->knows;
+		SELECT VALUE in FROM person:two<-knows;
+	";
+	let mut res = ds.execute(sql, &ses, None).await.unwrap();
+	res.remove(0).result.unwrap();
+	let val = res.remove(0).result.unwrap();
+	assert_eq!(val, crate::sql::value("[person:two]").unwrap());
+	let val = res.remove(0).result.unwrap();
+	assert_eq!(val, crate::sql::value("[person:one]").unwrap());
+	let key = crate::key::table::all::new("test", "test", "person").encode().unwrap();
+	let beg = [key.as_slice(), b"~"].concat();
+	let end = [key.as_slice(), b"~\xff"].concat();
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	assert!(tx.scan(beg..end, 1).await.unwrap().is_empty());
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn migrate_key_format_while_other_nodes_are_running() {
+	let ds = new_ds().await;
+	ds.set_key_format(crate::kvs::KeyFormat::V1).await.unwrap();
+	// Another node has sent a recent heartbeat
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	let now = tx.clock();
+	tx.set_hb(now, uuid::Uuid::new_v4()).await.unwrap();
+	tx.commit().await.unwrap();
+	let res = ds.migrate_key_format().await;
+	assert!(matches!(res, Err(crate::err::Error::Ds(_))));
+	assert_eq!(ds.key_format(), crate::kvs::KeyFormat::V1);
+}
@@ -0,0 +1,309 @@
+#[tokio::test]
+#[serial]
+async fn lease_is_held_by_a_single_node() {
+	let ds = new_ds().await;
+	let first = crate::sql::uuid::Uuid::new_v4();
+	let second = crate::sql::uuid::Uuid::new_v4();
+
+	// Register both nodes with the cluster
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	ds.register_membership(
+		&mut tx,
+		&first,
+		&Timestamp {
+			value: 1000,
+		},
+	)
+	.await
+	.unwrap();
+	ds.register_membership(
+		&mut tx,
+		&second,
+		&Timestamp {
+			value: 1000,
+		},
+	)
+	.await
+	.unwrap();
+	tx.commit().await.unwrap();
+
+	// The first node acquires the lease
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	let res = ds
+		.acquire_lease_full(
+			&mut tx,
+			"job",
+			first.clone(),
+			Timestamp {
+				value: 1000,
+			},
+		)
+		.await;
+	assert!(res.unwrap());
+	tx.commit().await.unwrap();
+
+	// The second node can not acquire the lease while it is held
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	let res = ds
+		.acquire_lease_full(
+			&mut tx,
+			"job",
+			second.clone(),
+			Timestamp {
+				value: 2000,
+			},
+		)
+		.await;
+	assert!(!res.unwrap());
+	// The first node renews the lease
+	let res = ds
+		.acquire_lease_full(
+			&mut tx,
+			"job",
+			first.clone(),
+			Timestamp {
+				value: 2000,
+			},
+		)
+		.await;
+	assert!(res.unwrap());
+	tx.commit().await.unwrap();
+
+	// The second node takes over the lease once it has expired
+	let expiry = 2000 + crate::cnf::LEASE_DURATION.as_millis() as u64;
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	let res = ds
+		.acquire_lease_full(
+			&mut tx,
+			"job",
+			second.clone(),
+			Timestamp {
+				value: expiry - 1,
+			},
+		)
+		.await;
+	assert!(!res.unwrap());
+	let res = ds
+		.acquire_lease_full(
+			&mut tx,
+			"job",
+			second.clone(),
+			Timestamp {
+				value: expiry,
+			},
+		)
+		.await;
+	assert!(res.unwrap());
+	let res = tx.get_ld("job").await.unwrap().unwrap();
+	assert_eq!(res.nd, second.0);
+	tx.commit().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn lease_fails_over_when_node_is_removed() {
+	let ds = new_ds().await;
+	let first = crate::sql::uuid::Uuid::new_v4();
+	let second = crate::sql::uuid::Uuid::new_v4();
+
+	// The first node acquires the lease
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	ds.register_membership(
+		&mut tx,
+		&first,
+		&Timestamp {
+			value: 1000,
+		},
+	)
+	.await
+	.unwrap();
+	let res = ds
+		.acquire_lease_full(
+			&mut tx,
+			"job",
+			first.clone(),
+			Timestamp {
+				value: 1000,
+			},
+		)
+		.await;
+	assert!(res.unwrap());
+	tx.commit().await.unwrap();
+
+	// The second node takes over the lease once the first node has been removed
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.del_nd(first.0).await.unwrap();
+	let res = ds
+		.acquire_lease_full(
+			&mut tx,
+			"job",
+			second.clone(),
+			Timestamp {
+				value: 2000,
+			},
+		)
+		.await;
+	assert!(res.unwrap());
+	tx.commit().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn singleton_runs_on_the_lease_holder() {
+	let ds = new_ds().await;
+	ds.heartbeat().await.unwrap();
+
+	// This node acquires the lease and runs the job
+	let res = ds.singleton("job", async { Ok(1) }).await.unwrap();
+	assert_eq!(res, Some(1));
+	let res = ds.singleton("job", async { Ok(2) }).await.unwrap();
+	assert_eq!(res, Some(2));
+
+	// Another node can take over the job once the lease has been released
+	ds.release_lease("job").await.unwrap();
+	let other = crate::sql::uuid::Uuid::new_v4();
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	let now = tx.clock();
+	ds.register_membership(&mut tx, &other, &now).await.unwrap();
+	let res = ds.acquire_lease_full(&mut tx, "job", other, now).await;
+	assert!(res.unwrap());
+	tx.commit().await.unwrap();
+
+	// The job is not run while the other node holds the lease
+	let res = ds.singleton("job", async { Ok(3) }).await.unwrap();
+	assert_eq!(res, None);
+}
+
+#[tokio::test]
+#[serial]
+async fn tick_removes_expired_nodes() {
+	let ds = new_ds().await;
+	let other = crate::sql::uuid::Uuid::new_v4();
+
+	// Register another node which has not sent a heartbeat for a long time
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	ds.register_membership(
+		&mut tx,
+		&other,
+		&Timestamp {
+			value: 123,
+		},
+	)
+	.await
+	.unwrap();
+	tx.commit().await.unwrap();
+
+	// The expired node is removed, and this node is registered
+	ds.tick().await.unwrap();
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	assert!(tx.get_nd(other.0).await.unwrap().is_none());
+	let res = tx.scan_cl(100).await.unwrap();
+	assert_eq!(res.len(), 1);
+	assert_eq!(res[0].name, ds.id.0.to_string());
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn singleton_stops_once_the_lease_is_taken_over() {
+	let ds = new_ds().await;
+	ds.heartbeat().await.unwrap();
+	let other = crate::sql::uuid::Uuid::new_v4();
+
+	// Another node takes over the lease while the job is running
+	let res = ds
+		.singleton("job", async {
+			let mut tx = ds.transaction(true, false).await?;
+			let now = Timestamp {
+				value: tx.clock().value + tx.lease_duration().as_millis() as u64,
+			};
+			ds.register_membership(&mut tx, &other, &now).await?;
+			assert!(ds.acquire_lease_full(&mut tx, "job", other.clone(), now).await?);
+			tx.commit().await?;
+			// The job stops at its next batch
+			ds.renew_lease("job").await?;
+			Ok(1)
+		})
+		.await
+		.unwrap();
+	assert_eq!(res, None);
+}
+
+#[tokio::test]
+#[serial]
+async fn heartbeat_fails_once_the_node_was_removed() {
+	let ds = new_ds().await;
+	ds.heartbeat().await.unwrap();
+
+	// Another node removes this node, and archives its live queries
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.del_nd(ds.id.0).await.unwrap();
+	tx.commit().await.unwrap();
+
+	// The node does not rejoin the cluster
+	let res = ds.heartbeat().await;
+	assert!(matches!(res, Err(Error::NdRemoved { .. })), "{res:?}");
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	assert!(tx.get_nd(ds.id.0).await.unwrap().is_none());
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn scheduled_job_run_is_claimed_again_once_lost() {
+	// A time in seconds which is at the start of a minute
+	const START: u64 = 1_699_999_800;
+	let mut ds = new_ds().await;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let sql =
+		"DEFINE JOB rollup SCHEDULE '0 * * * * *' THEN { UPDATE counter:rollup SET runs += 1 }";
+	ds.execute(sql, &ses, None).await.unwrap();
+	async fn runs(ds: &Datastore) -> Value {
+		let ses = Session::for_kv().with_ns("test").with_db("test");
+		let sql = "SELECT VALUE runs FROM counter:rollup";
+		let mut res = ds.execute(sql, &ses, None).await.unwrap();
+		res.remove(0).result.unwrap()
+	}
+
+	// The run is claimed, and the receiver is dropped before the run
+	ds.tick_at(START).await.unwrap();
+	ds.tick_at(START + 60).await.unwrap();
+	ds.jobs = channel::unbounded();
+	ds.claims = Default::default();
+	ds.process_jobs().await;
+	assert_eq!(runs(&ds).await, crate::sql::value("[]").unwrap());
+
+	// The lost run is claimed again by the next tick, and runs once
+	ds.tick_at(START + 61).await.unwrap();
+	ds.process_jobs().await;
+	assert_eq!(runs(&ds).await, crate::sql::value("[1]").unwrap());
+	ds.tick_at(START + 63).await.unwrap();
+	ds.process_jobs().await;
+	assert_eq!(runs(&ds).await, crate::sql::value("[1]").unwrap());
+
+	// A run which is claimed by another node is not claimed again while that node is running it
+	let other = crate::sql::uuid::Uuid::new_v4();
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	let now = tx.clock();
+	ds.register_membership(&mut tx, &other, &now).await.unwrap();
+	let mut jr = tx.get_jr("test", "test", "rollup").await.unwrap().unwrap();
+	jr.claim = Some(crate::dbs::node::Lease {
+		nd: other.0,
+		expires: Timestamp {
+			value: now.value + tx.lease_duration().as_millis() as u64,
+		},
+	});
+	tx.set(crate::key::database::jr::new("test", "test", "rollup"), jr).await.unwrap();
+	tx.commit().await.unwrap();
+	ds.tick_at(START + 120).await.unwrap();
+	ds.process_jobs().await;
+	assert_eq!(runs(&ds).await, crate::sql::value("[1]").unwrap());
+
+	// The run is claimed again once the other node has been removed
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.del_nd(other.0).await.unwrap();
+	tx.commit().await.unwrap();
+	ds.tick_at(START + 121).await.unwrap();
+	ds.process_jobs().await;
+	assert_eq!(runs(&ds).await, crate::sql::value("[2]").unwrap());
+}
@@ -14,11 +14,59 @@ mod mem {
 	}
 
 	include!("helper.rs");
+	include!("backup.rs");
 	include!("cluster_init.rs");
+	include!("encryption.rs");
+	include!("format.rs");
+	include!("ld.rs");
+	include!("nd.rs");
 	include!("lq.rs");
 	include!("nq.rs");
+	include!("nt.rs");
 	include!("raw.rs");
+	include!("retry.rs");
+	include!("savepoint.rs");
+	include!("scanner.rs");
 	include!("snapshot.rs");
+	include!("stats.rs");
+	include!("tb.rs");
+	include!("multireader.rs");
+}
+
+#[cfg(feature = "kv-mem")]
+mod mem_durable {
+
+	use crate::kvs::Datastore;
+	use crate::kvs::Transaction;
+	use serial_test::serial;
+	use temp_dir::TempDir;
+
+	async fn new_ds() -> Datastore {
+		let path = TempDir::new().unwrap().path().to_string_lossy().to_string();
+		Datastore::new(format!("memory:{path}").as_str()).await.unwrap()
+	}
+
+	async fn new_tx(write: bool, lock: bool) -> Transaction {
+		new_ds().await.transaction(write, lock).await.unwrap()
+	}
+
+	include!("helper.rs");
+	include!("backup.rs");
+	include!("cluster_init.rs");
+	include!("durability.rs");
+	include!("encryption.rs");
+	include!("format.rs");
+	include!("ld.rs");
+	include!("nd.rs");
+	include!("lq.rs");
+	include!("nq.rs");
+	include!("nt.rs");
+	include!("raw.rs");
+	include!("retry.rs");
+	include!("savepoint.rs");
+	include!("scanner.rs");
+	include!("snapshot.rs");
+	include!("stats.rs");
 	include!("tb.rs");
 	include!("multireader.rs");
 }
@@ -41,17 +89,64 @@ mod rocksdb {
 	}
 
 	include!("helper.rs");
+	include!("backup.rs");
 	include!("cluster_init.rs");
+	include!("encryption.rs");
+	include!("format.rs");
+	include!("ld.rs");
+	include!("nd.rs");
 	include!("lq.rs");
 	include!("nq.rs");
+	include!("nt.rs");
 	include!("raw.rs");
+	include!("retry.rs");
+	include!("savepoint.rs");
+	include!("scanner.rs");
 	include!("snapshot.rs");
+	include!("stats.rs");
 	include!("tb.rs");
 	include!("multireader.rs");
 	include!("multiwriter_different_keys.rs");
 	include!("multiwriter_same_keys_conflict.rs");
 }
 
+#[cfg(feature = "kv-redb")]
+mod redb {
+
+	use crate::kvs::Datastore;
+	use crate::kvs::Transaction;
+	use serial_test::serial;
+	use temp_dir::TempDir;
+
+	async fn new_ds() -> Datastore {
+		let path = TempDir::new().unwrap().path().to_string_lossy().to_string();
+		Datastore::new(format!("redb:{path}").as_str()).await.unwrap()
+	}
+
+	async fn new_tx(write: bool, lock: bool) -> Transaction {
+		new_ds().await.transaction(write, lock).await.unwrap()
+	}
+
+	include!("helper.rs");
+	include!("backup.rs");
+	include!("cluster_init.rs");
+	include!("encryption.rs");
+	include!("format.rs");
+	include!("ld.rs");
+	include!("nd.rs");
+	include!("lq.rs");
+	include!("nq.rs");
+	include!("nt.rs");
+	include!("raw.rs");
+	include!("retry.rs");
+	include!("savepoint.rs");
+	include!("scanner.rs");
+	include!("snapshot.rs");
+	include!("stats.rs");
+	include!("tb.rs");
+	include!("multireader.rs");
+}
+
 #[cfg(feature = "kv-speedb")]
 mod speedb {
 
@@ -70,11 +165,21 @@ mod speedb {
 	}
 
 	include!("helper.rs");
+	include!("backup.rs");
 	include!("cluster_init.rs");
+	include!("encryption.rs");
+	include!("format.rs");
+	include!("ld.rs");
+	include!("nd.rs");
 	include!("lq.rs");
 	include!("nq.rs");
+	include!("nt.rs");
 	include!("raw.rs");
+	include!("retry.rs");
+	include!("savepoint.rs");
+	include!("scanner.rs");
 	include!("snapshot.rs");
+	include!("stats.rs");
 	include!("tb.rs");
 	include!("multireader.rs");
 	include!("multiwriter_different_keys.rs");
@@ -102,12 +207,22 @@ mod tikv {
 		new_ds().await.transaction(write, lock).await.unwrap()
 	}
 
+	include!("backup.rs");
 	include!("cluster_init.rs");
+	include!("encryption.rs");
+	include!("format.rs");
+	include!("ld.rs");
+	include!("nd.rs");
 	include!("helper.rs");
 	include!("lq.rs");
 	include!("nq.rs");
+	include!("nt.rs");
 	include!("raw.rs");
+	include!("retry.rs");
+	include!("savepoint.rs");
+	include!("scanner.rs");
 	include!("snapshot.rs");
+	include!("stats.rs");
 	include!("tb.rs");
 	include!("multireader.rs");
 	include!("multiwriter_different_keys.rs");
@@ -135,12 +250,22 @@ mod fdb {
 		new_ds().await.transaction(write, lock).await.unwrap()
 	}
 
+	include!("backup.rs");
 	include!("cluster_init.rs");
+	include!("encryption.rs");
+	include!("format.rs");
+	include!("ld.rs");
+	include!("nd.rs");
 	include!("helper.rs");
 	include!("lq.rs");
 	include!("nq.rs");
+	include!("nt.rs");
 	include!("raw.rs");
+	include!("retry.rs");
+	include!("savepoint.rs");
+	include!("scanner.rs");
 	include!("snapshot.rs");
+	include!("stats.rs");
 	include!("tb.rs");
 	include!("multireader.rs");
 	include!("multiwriter_different_keys.rs");
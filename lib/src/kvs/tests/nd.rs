@@ -0,0 +1,115 @@
+#[tokio::test]
+#[serial]
+async fn cluster_info_reports_nodes_and_evicts_them() {
+	let ds = new_ds().await;
+	ds.tick().await.unwrap();
+	let other = crate::sql::uuid::Uuid::new_v4();
+
+	// Register another node, which stopped sending heartbeats long ago
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	ds.register_membership(
+		&mut tx,
+		&other,
+		&Timestamp {
+			value: 1000,
+		},
+	)
+	.await
+	.unwrap();
+	tx.commit().await.unwrap();
+
+	// Register live queries on both nodes, one of which has been archived
+	let archived = sql::Uuid::new_v4();
+	let lqs = [
+		(ds.id.clone(), sql::Uuid::new_v4(), None),
+		(other.clone(), sql::Uuid::new_v4(), None),
+		(other.clone(), archived.clone(), Some(ds.id.clone())),
+	];
+	for (nd, id, archived) in lqs {
+		let lq = LiveStatement {
+			id,
+			node: nd.clone(),
+			expr: Fields(vec![sql::Field::All], false),
+			what: Table(sql::Table::from("person")),
+			cond: None,
+			fetch: None,
+			archived,
+			auth: None,
+			session: None,
+		};
+		let ctx = context::Context::background();
+		let (sender, _) = channel::unbounded();
+		let opt = Options::new()
+			.with_ns(Some("test".into()))
+			.with_db(Some("test".into()))
+			.with_auth(Arc::new(Default::default()))
+			.with_live(true)
+			.with_id(nd.0);
+		let opt = Options::new_with_sender(&opt, sender);
+		let tx = Arc::new(Mutex::new(ds.transaction(true, false).await.unwrap()));
+		lq.compute(&ctx, &opt, &tx, None).await.unwrap();
+		tx.lock().await.commit().await.unwrap();
+	}
+
+	// Both nodes are reported, but only this node is alive
+	let ses = Session::for_kv();
+	let res = &mut ds.execute("INFO FOR CLUSTER", &ses, None).await.unwrap();
+	let tmp = res.remove(0).result.unwrap();
+	let node = tmp.pick(&["nodes".into(), ds.id.0.to_string().as_str().into()]);
+	assert_eq!(node.pick(&["alive".into()]), Value::Bool(true));
+	assert_eq!(node.pick(&["live_queries".into()]), Value::from(1));
+	assert_eq!(node.pick(&["archived".into()]), Value::from(sql::Array::new()));
+	assert!(node.pick(&["heartbeat".into()]).is_datetime());
+	let node = tmp.pick(&["nodes".into(), other.0.to_string().as_str().into()]);
+	assert_eq!(node.pick(&["alive".into()]), Value::Bool(false));
+	assert_eq!(node.pick(&["live_queries".into()]), Value::from(1));
+	assert_eq!(node.pick(&["heartbeat".into()]).to_string(), "'1970-01-01T00:00:01Z'");
+	let val = node.pick(&["archived".into(), sql::Part::Index(0.into())]);
+	assert_eq!(val.pick(&["id".into()]), Value::Uuid(archived));
+	assert_eq!(val.pick(&["tb".into()]), Value::from("person"));
+
+	// A node can not evict itself
+	let sql = format!("REMOVE NODE {}", ds.id);
+	let res = &mut ds.execute(&sql, &ses, None).await.unwrap();
+	let err = res.remove(0).result.unwrap_err();
+	assert!(matches!(err, Error::NdEvictSelf { .. }), "{err:?}");
+
+	// Evicting the other node removes it together with its live queries
+	let sql = format!("REMOVE NODE {other}; INFO FOR CLUSTER; REMOVE NODE {other};");
+	let res = &mut ds.execute(&sql, &ses, None).await.unwrap();
+	assert!(res.remove(0).result.is_ok());
+	let tmp = res.remove(0).result.unwrap();
+	let node = tmp.pick(&["nodes".into(), other.0.to_string().as_str().into()]);
+	assert_eq!(node, Value::None);
+	let err = res.remove(0).result.unwrap_err();
+	assert!(matches!(err, Error::NdNotFound { .. }), "{err:?}");
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	assert_eq!(tx.all_lv("test", "test", "person").await.unwrap().len(), 1);
+	assert!(tx.all_lq(&other.0).await.unwrap().is_empty());
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn cluster_evicts_live_nodes_only_when_forced() {
+	let ds = new_ds().await;
+	ds.tick().await.unwrap();
+	let other = crate::sql::uuid::Uuid::new_v4();
+
+	// Register another node, which is still sending heartbeats
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	let now = tx.clock();
+	ds.register_membership(&mut tx, &other, &now).await.unwrap();
+	tx.commit().await.unwrap();
+
+	// The node is only evicted when forced
+	let ses = Session::for_kv();
+	let sql = format!("REMOVE NODE {other}; REMOVE NODE {other} FORCE;");
+	let res = &mut ds.execute(&sql, &ses, None).await.unwrap();
+	let err = res.remove(0).result.unwrap_err();
+	assert!(matches!(err, Error::NdAlive { .. }), "{err:?}");
+	assert!(res.remove(0).result.is_ok());
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	assert!(tx.get_nd(other.0).await.unwrap().is_none());
+	tx.cancel().await.unwrap();
+}
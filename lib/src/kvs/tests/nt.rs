@@ -0,0 +1,128 @@
+#[tokio::test]
+#[serial]
+async fn remote_live_query_notifications_are_queued() {
+	let ds = new_ds().await.with_notifications();
+	let namespace = "test_namespace";
+	let database = "test_database";
+	let table = "test_table";
+	let remote_node_id = crate::sql::uuid::Uuid::from(Uuid::from_bytes([
+		0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E,
+		0x0F,
+	]));
+	let lv_id = crate::sql::uuid::Uuid::from(Uuid::from_bytes([
+		0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1A, 0x1B, 0x1C, 0x1D, 0x1E,
+		0x1F,
+	]));
+
+	// Register a live query on another node
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	let (_, mut stm) = crate::sql::statements::live::live(
+		format!("LIVE SELECT * FROM {}", table).as_str(),
+	)
+	.unwrap();
+	stm.id = lv_id.clone();
+	stm.node = remote_node_id.clone();
+	tx.putc_lv(namespace, database, table, stm, None).await.unwrap();
+	tx.commit().await.unwrap();
+
+	// Write a record on this node
+	let ses = crate::dbs::Session::for_kv().with_ns(namespace).with_db(database);
+	let res = ds.execute(&format!("CREATE {}:test", table), &ses, None).await.unwrap();
+	assert!(res[0].result.is_ok());
+
+	// The notification is queued for the remote node, and not sent locally
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let res = tx.scan_nt(&remote_node_id.0, 100).await.unwrap();
+	tx.cancel().await.unwrap();
+	assert_eq!(res.len(), 1);
+	assert_eq!(res[0].0.nd, remote_node_id.0);
+	assert_eq!(res[0].1.id, lv_id);
+	assert_eq!(res[0].1.action, crate::dbs::Action::Create);
+	assert!(ds.notifications().unwrap().try_recv().is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn queued_notifications_are_delivered_locally() {
+	let ds = new_ds().await.with_notifications();
+	let lv_id = crate::sql::uuid::Uuid::from(Uuid::from_bytes([
+		0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1A, 0x1B, 0x1C, 0x1D, 0x1E,
+		0x1F,
+	]));
+	let notification = crate::dbs::Notification {
+		id: lv_id,
+		action: crate::dbs::Action::Delete,
+		result: crate::sql::Value::None,
+	};
+
+	// Queue a notification for this node
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set_nt(&ds.id.0, notification.clone()).await.unwrap();
+	tx.commit().await.unwrap();
+
+	// Drain the queue into the local channel
+	ds.process_notifications().await.unwrap();
+	let chn = ds.notifications().unwrap();
+	assert_eq!(chn.try_recv().unwrap(), notification);
+	assert!(chn.try_recv().is_err());
+
+	// The queue is now empty
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let res = tx.scan_nt(&ds.id.0, 100).await.unwrap();
+	tx.cancel().await.unwrap();
+	assert!(res.is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn queued_notifications_are_delivered_in_order() {
+	let ds = new_ds().await.with_notifications();
+	let lv_id = crate::sql::uuid::Uuid::new_v4();
+
+	// Queue notifications within the same millisecond
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	for i in 0..100 {
+		let notification = crate::dbs::Notification {
+			id: lv_id.clone(),
+			action: crate::dbs::Action::Update,
+			result: crate::sql::Value::from(i),
+		};
+		tx.set_nt(&ds.id.0, notification).await.unwrap();
+	}
+	tx.commit().await.unwrap();
+
+	// The notifications are delivered in the order they were queued
+	ds.process_notifications().await.unwrap();
+	let chn = ds.notifications().unwrap();
+	for i in 0..100 {
+		assert_eq!(chn.try_recv().unwrap().result, crate::sql::Value::from(i));
+	}
+	assert!(chn.try_recv().is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn undelivered_notifications_stay_queued() {
+	let ds = new_ds().await.with_notifications();
+	let notification = crate::dbs::Notification {
+		id: crate::sql::uuid::Uuid::new_v4(),
+		action: crate::dbs::Action::Delete,
+		result: crate::sql::Value::None,
+	};
+
+	// Queue a notification for this node
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set_nt(&ds.id.0, notification.clone()).await.unwrap();
+	tx.commit().await.unwrap();
+
+	// The notification can not be sent once the channel is closed
+	ds.notifications().unwrap().close();
+	assert!(ds.process_notifications().await.is_err());
+
+	// The notification is kept in the queue
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let res = tx.scan_nt(&ds.id.0, 100).await.unwrap();
+	tx.cancel().await.unwrap();
+	assert_eq!(res.len(), 1);
+	assert_eq!(res[0].1, notification);
+}
@@ -0,0 +1,33 @@
+#[tokio::test]
+#[serial]
+async fn conflicting_statements_are_retried() {
+	let ds = new_ds().await.with_transaction_retries(5);
+	let ses = crate::dbs::Session::for_kv().with_ns("test").with_db("test");
+	// A statement is retried until it commits
+	ds.conflicts.store(2, std::sync::atomic::Ordering::Release);
+	let sql = "UPDATE counter:one SET count += 1";
+	let mut res = ds.execute(sql, &ses, None).await.unwrap();
+	let res = res.remove(0);
+	assert_eq!(res.retries, 2);
+	assert!(res.result.is_ok());
+	// A transaction is retried from the beginning
+	ds.conflicts.store(1, std::sync::atomic::Ordering::Release);
+	let sql =
+		"BEGIN; UPDATE counter:one SET count += 1; UPDATE counter:one SET count += 1; COMMIT;";
+	let res = ds.execute(sql, &ses, None).await.unwrap();
+	assert_eq!(res.len(), 2);
+	for res in res {
+		assert_eq!(res.retries, 1);
+		assert!(res.result.is_ok());
+	}
+	// A statement fails once it has been retried too many times
+	ds.conflicts.store(6, std::sync::atomic::Ordering::Release);
+	let sql = "UPDATE counter:one SET count += 1";
+	let mut res = ds.execute(sql, &ses, None).await.unwrap();
+	assert!(res.remove(0).result.is_err());
+	// Each change was only applied once
+	let sql = "SELECT VALUE count FROM counter:one";
+	let mut res = ds.execute(sql, &ses, None).await.unwrap();
+	let val = res.remove(0).result.unwrap();
+	assert_eq!(val, crate::sql::value("[3]").unwrap());
+}
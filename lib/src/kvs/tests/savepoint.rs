@@ -0,0 +1,57 @@
+#[tokio::test]
+#[serial]
+async fn savepoint_buffers_writes() {
+	// Create a new datastore
+	let ds = new_ds().await;
+	// Write some data with savepoints
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set("test1", "ok").await.unwrap();
+	tx.savepoint("one");
+	tx.set("test2", "ok").await.unwrap();
+	tx.del("test1").await.unwrap();
+	assert!(tx.get("test1").await.unwrap().is_none());
+	tx.savepoint("two");
+	tx.put("test3", "ok").await.unwrap();
+	assert!(tx.put("test2", "ok").await.is_err());
+	let val = tx.scan("test".."testz", 10).await.unwrap();
+	assert_eq!(val.len(), 2);
+	// Roll back the inner savepoint
+	tx.rollback_to("two").unwrap();
+	assert!(!tx.exi("test3").await.unwrap());
+	let val = tx.scan("test".."testz", 1).await.unwrap();
+	assert_eq!(val, vec![(b"test2".to_vec(), b"ok".to_vec())]);
+	// Release the outer savepoint
+	tx.release("one").await.unwrap();
+	assert!(tx.rollback_to("one").is_err());
+	tx.commit().await.unwrap();
+	// Check the committed data
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.scan("test".."testz", 10).await.unwrap();
+	assert_eq!(val, vec![(b"test2".to_vec(), b"ok".to_vec())]);
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn savepoint_buffers_versionstamped_keys() {
+	// Create a new datastore
+	let ds = new_ds().await;
+	// Write some versionstamped keys with savepoints
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.savepoint("one");
+	tx.set_versionstamped_key("vs", "cf", "one", "ok").await.unwrap();
+	tx.savepoint("two");
+	tx.set_versionstamped_key("vs", "cf", "two", "ok").await.unwrap();
+	// Nothing is written while the savepoints are active
+	let val = tx.scan("cf".."cfz", 10).await.unwrap();
+	assert!(val.is_empty());
+	// Roll back the inner savepoint
+	tx.rollback_to("two").unwrap();
+	tx.commit().await.unwrap();
+	// Check the committed data
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.scan("cf".."cfz", 10).await.unwrap();
+	assert_eq!(val.len(), 1);
+	assert!(val[0].0.ends_with(b"one"));
+	tx.cancel().await.unwrap();
+}
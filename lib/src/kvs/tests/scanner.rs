@@ -0,0 +1,120 @@
+#[tokio::test]
+#[serial]
+async fn scanner_streams_ranges() {
+	use crate::kvs::Scanner;
+	use futures::TryStreamExt;
+	// Create a new datastore
+	let ds = new_ds().await;
+	// Create a writeable transaction
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	for i in 0..25 {
+		tx.set(format!("test{i:02}"), format!("{i}")).await.unwrap();
+	}
+	tx.set("tesu", "other").await.unwrap();
+	tx.commit().await.unwrap();
+	// Stream the range in ascending order
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val: Vec<_> =
+		Scanner::new("test".."tesu").prefetch(10).stream(&mut tx).try_collect().await.unwrap();
+	assert_eq!(val.len(), 25);
+	assert_eq!(val[0], (b"test00".to_vec(), b"0".to_vec()));
+	assert_eq!(val[24], (b"test24".to_vec(), b"24".to_vec()));
+	// Stream the range in descending order
+	let val: Vec<_> = Scanner::new("test".."tesu")
+		.reverse()
+		.prefetch(7)
+		.stream(&mut tx)
+		.try_collect()
+		.await
+		.unwrap();
+	assert_eq!(val.len(), 25);
+	assert_eq!(val[0], (b"test24".to_vec(), b"24".to_vec()));
+	assert_eq!(val[24], (b"test00".to_vec(), b"0".to_vec()));
+	// Stream only the keys
+	let val: Vec<_> =
+		Scanner::new("test05".."test08").keys_only().stream(&mut tx).try_collect().await.unwrap();
+	assert_eq!(
+		val,
+		vec![
+			(b"test05".to_vec(), vec![]),
+			(b"test06".to_vec(), vec![]),
+			(b"test07".to_vec(), vec![]),
+		]
+	);
+	// Stream only the keys, in descending order
+	let val: Vec<_> = Scanner::new("test05".."test08")
+		.keys_only()
+		.reverse()
+		.prefetch(2)
+		.stream(&mut tx)
+		.try_collect()
+		.await
+		.unwrap();
+	assert_eq!(
+		val,
+		vec![
+			(b"test07".to_vec(), vec![]),
+			(b"test06".to_vec(), vec![]),
+			(b"test05".to_vec(), vec![]),
+		]
+	);
+	// Fetch the greatest keys directly
+	let val = tx.scanr("test".."tesu", 2).await.unwrap();
+	assert_eq!(val[0].0, b"test24".to_vec());
+	assert_eq!(val[1].0, b"test23".to_vec());
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn scanner_merges_savepoints_in_reverse() {
+	use crate::kvs::Scanner;
+	use futures::TryStreamExt;
+	// Create a new datastore
+	let ds = new_ds().await;
+	// Create a writeable transaction
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set("test1", "1").await.unwrap();
+	tx.set("test2", "2").await.unwrap();
+	tx.set("test3", "3").await.unwrap();
+	tx.commit().await.unwrap();
+	// Buffer some changes within a savepoint
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.savepoint("one");
+	tx.del("test2").await.unwrap();
+	tx.set("test4", "4").await.unwrap();
+	let val: Vec<_> = Scanner::new("test".."testz")
+		.reverse()
+		.prefetch(1)
+		.stream(&mut tx)
+		.try_collect()
+		.await
+		.unwrap();
+	let keys: Vec<_> = val.into_iter().map(|(k, _)| k).collect();
+	assert_eq!(keys, vec![b"test4".to_vec(), b"test3".to_vec(), b"test1".to_vec()]);
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn scanr_fetches_the_greatest_keys() {
+	// Create a new datastore
+	let ds = new_ds().await;
+	// Create a writeable transaction
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	for i in 0..2500 {
+		tx.set(format!("test{i:04}"), format!("{i}")).await.unwrap();
+	}
+	tx.commit().await.unwrap();
+	// Fetch more keys than are read in a single batch
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.scanr("test".."testz", 1200).await.unwrap();
+	assert_eq!(val.len(), 1200);
+	assert_eq!(val[0], (b"test2499".to_vec(), b"2499".to_vec()));
+	assert_eq!(val[1199], (b"test1300".to_vec(), b"1300".to_vec()));
+	// Fetch fewer keys than are read in a single batch
+	let val = tx.scanr("test".."test0100", 3).await.unwrap();
+	let keys: Vec<_> = val.into_iter().map(|(k, _)| k).collect();
+	assert_eq!(keys, vec![b"test0099".to_vec(), b"test0098".to_vec(), b"test0097".to_vec()]);
+	tx.cancel().await.unwrap();
+}
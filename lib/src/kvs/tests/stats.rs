@@ -0,0 +1,87 @@
+#[tokio::test]
+#[serial]
+async fn count_storage_stats() {
+	let ses = crate::dbs::Session::for_kv().with_ns("test").with_db("test");
+	let ds = new_ds().await;
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		DEFINE INDEX name ON person FIELDS name;
+		CREATE person:one SET name = 'Tobie';
+		CREATE person:two SET name = 'Jaime';
+		UPDATE person:two SET name = 'Jaime', age = 30;
+		DELETE person:one;
+	";
+	for res in ds.execute(sql, &ses, None).await.unwrap() {
+		res.result.unwrap();
+	}
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let st = tx.get_stats("test", "test", "person").await.unwrap();
+	tx.cancel().await.unwrap();
+	assert_eq!(st.records, 1);
+	// The maintained statistics match the stored data
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	assert_eq!(tx.count_stats("test", "test").await.unwrap()["person"], st);
+	// The changes made by each transaction are stored under a single key for the node
+	let (_, keys) = tx.stored_stats("test", "test", "person").await.unwrap();
+	assert_eq!(keys.len(), 1);
+	tx.cancel().await.unwrap();
+	// Remove the statistics, as if the data was stored before they were maintained
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	let (_, keys) = tx.stored_stats("test", "test", "person").await.unwrap();
+	assert!(!keys.is_empty());
+	tx.replace_stats("test", "test", "person", keys, Default::default()).await.unwrap();
+	tx.commit().await.unwrap();
+	// The statistics are counted by the next tick
+	ds.tick().await.unwrap();
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	assert_eq!(tx.get_stats("test", "test", "person").await.unwrap(), st);
+	tx.cancel().await.unwrap();
+	// The statistics can be recounted on request
+	let mut res = ds.execute("CREATE person:three SET name = 'Tobie'", &ses, None).await.unwrap();
+	res.remove(0).result.unwrap();
+	ds.recount_storage_stats().await.unwrap();
+	ds.tick().await.unwrap();
+	ds.tick().await.unwrap();
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let st = tx.get_stats("test", "test", "person").await.unwrap();
+	assert_eq!(st.records, 2);
+	assert_eq!(tx.count_stats("test", "test").await.unwrap()["person"], st);
+	let (_, keys) = tx.stored_stats("test", "test", "person").await.unwrap();
+	assert_eq!(keys.len(), 1);
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn merge_storage_stats() {
+	let ses = crate::dbs::Session::for_kv().with_ns("test").with_db("test");
+	let ds = new_ds().await;
+	// Count the statistics of the tables
+	ds.tick().await.unwrap();
+	for i in 0..5 {
+		let sql = format!("CREATE person:{i} SET name = 'Tobie'");
+		let mut res = ds.execute(&sql, &ses, None).await.unwrap();
+		res.remove(0).result.unwrap();
+	}
+	// The changes are folded into the base statistics of the table by the next tick
+	ds.tick().await.unwrap();
+	let mut res = ds.execute("CREATE person:5 SET name = 'Jaime'", &ses, None).await.unwrap();
+	res.remove(0).result.unwrap();
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let (st, keys) = tx.stored_stats("test", "test", "person").await.unwrap();
+	assert_eq!(st.records, 6);
+	assert_eq!(keys.len(), 2);
+	assert_eq!(tx.count_stats("test", "test").await.unwrap()["person"], st);
+	tx.cancel().await.unwrap();
+	ds.tick().await.unwrap();
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let (st, keys) = tx.stored_stats("test", "test", "person").await.unwrap();
+	assert_eq!(st.records, 6);
+	assert_eq!(
+		keys,
+		vec![crate::key::table::st::new("test", "test", "person", uuid::Uuid::nil())
+			.encode()
+			.unwrap()]
+	);
+	tx.cancel().await.unwrap();
+}
@@ -27,6 +27,8 @@ async fn table_definitions_can_be_scanned() {
 		view: None,
 		permissions: Default::default(),
 		changefeed: None,
+		id: None,
+		ttl: None,
 	};
 	match tx.set(&key, &value).await {
 		Ok(_) => {}
@@ -37,7 +39,7 @@ async fn table_definitions_can_be_scanned() {
 	match tx.scan(tb::prefix(namespace, database)..tb::suffix(namespace, database), 1000).await {
 		Ok(scan) => {
 			assert_eq!(scan.len(), 1);
-			let read = DefineTableStatement::from(&scan[0].1);
+			let read = DefineTableStatement::try_from(&scan[0].1).unwrap();
 			assert_eq!(&read, &value);
 		}
 		Err(e) => panic!("{:?}", e),
@@ -69,6 +71,8 @@ async fn table_definitions_can_be_deleted() {
 		view: None,
 		permissions: Default::default(),
 		changefeed: None,
+		id: None,
+		ttl: None,
 	};
 	match tx.set(&key, &value).await {
 		Ok(_) => {}
@@ -1,30 +1,50 @@
 use super::kv::Add;
 use super::kv::Convert;
+use super::kv::TryConvert;
 use super::Key;
 use super::Val;
 use crate::cf;
 use crate::dbs::node::ClusterMembership;
+use crate::dbs::node::JobRun;
+use crate::dbs::node::Lease;
 use crate::dbs::node::Timestamp;
+use crate::dbs::Notification;
 use crate::err::Error;
+use crate::key::data::Base;
 use crate::kvs::cache::Cache;
 use crate::kvs::cache::Entry;
+use crate::kvs::encryption::Encryption;
+use crate::kvs::format::Migration;
+use crate::kvs::savepoint::Changes;
+use crate::kvs::savepoint::Savepoints;
+use crate::kvs::stats;
+use crate::kvs::KeyFormat;
 use crate::kvs::LqValue;
+use crate::kvs::Scanner;
+use crate::kvs::Stats;
+use crate::kvs::VersionstampOracle;
 use crate::sql;
+use crate::sql::index::Index;
 use crate::sql::paths::EDGE;
 use crate::sql::paths::IN;
 use crate::sql::paths::OUT;
 use crate::sql::thing::Thing;
+use crate::sql::Quota;
 use crate::sql::Strand;
 use crate::sql::Value;
+use crate::vs::HybridLogicalClock;
 use crate::vs::Versionstamp;
 use channel::Sender;
+use futures::TryStreamExt;
 use sql::permission::Permissions;
 use sql::statements::DefineAnalyzerStatement;
+use sql::statements::DefineConsumerStatement;
 use sql::statements::DefineDatabaseStatement;
 use sql::statements::DefineEventStatement;
 use sql::statements::DefineFieldStatement;
 use sql::statements::DefineFunctionStatement;
 use sql::statements::DefineIndexStatement;
+use sql::statements::DefineJobStatement;
 use sql::statements::DefineLoginStatement;
 use sql::statements::DefineNamespaceStatement;
 use sql::statements::DefineParamStatement;
@@ -33,19 +53,39 @@ use sql::statements::DefineTableStatement;
 use sql::statements::DefineTokenStatement;
 use sql::statements::LiveStatement;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::fmt;
 use std::fmt::Debug;
 use std::ops::Range;
+#[cfg(test)]
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
+// The sequence which orders the notifications queued within the same millisecond
+static NOTIFICATION_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
 /// A set of undoable updates and requests against a dataset.
 #[allow(dead_code)]
 pub struct Transaction {
 	pub(super) inner: Inner,
 	pub(super) cache: Cache,
 	pub(super) cf: cf::Writer,
+	pub(super) enc: Option<Arc<Encryption>>,
+	pub(super) sp: Savepoints,
+	pub(super) kf: KeyFormat,
+	pub(super) st: stats::Writer,
+	pub(super) us: HashMap<(String, Option<String>), Stats>,
+	pub(super) vo: VersionstampOracle,
+	pub(super) ld: Duration,
+	pub(super) nd: Uuid,
+	#[cfg(test)]
+	pub(super) conflicts: Arc<AtomicU32>,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -56,6 +96,8 @@ pub(super) enum Inner {
 	RocksDB(super::rocksdb::Transaction),
 	#[cfg(feature = "kv-speedb")]
 	SpeeDB(super::speedb::Transaction),
+	#[cfg(feature = "kv-redb")]
+	Redb(super::redb::Transaction),
 	#[cfg(feature = "kv-indxdb")]
 	IndxDB(super::indxdb::Transaction),
 	#[cfg(feature = "kv-tikv")]
@@ -74,6 +116,8 @@ impl fmt::Display for Transaction {
 			Inner::RocksDB(_) => write!(f, "rocksdb"),
 			#[cfg(feature = "kv-speedb")]
 			Inner::SpeeDB(_) => write!(f, "speedb"),
+			#[cfg(feature = "kv-redb")]
+			Inner::Redb(_) => write!(f, "redb"),
 			#[cfg(feature = "kv-indxdb")]
 			Inner::IndxDB(_) => write!(f, "indxdb"),
 			#[cfg(feature = "kv-tikv")]
@@ -116,6 +160,11 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.closed(),
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.closed(),
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -142,6 +191,8 @@ impl Transaction {
 	pub async fn cancel(&mut self) -> Result<(), Error> {
 		#[cfg(debug_assertions)]
 		trace!("Cancel");
+		// Discard any changes buffered within savepoints
+		self.sp.clear();
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -158,6 +209,11 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.cancel().await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.cancel().await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -184,12 +240,25 @@ impl Transaction {
 	pub async fn commit(&mut self) -> Result<(), Error> {
 		#[cfg(debug_assertions)]
 		trace!("Commit");
+		// Simulate a conflict with another transaction
+		#[cfg(test)]
+		if self
+			.conflicts
+			.fetch_update(Ordering::AcqRel, Ordering::Acquire, |v| v.checked_sub(1))
+			.is_ok()
+		{
+			self.cancel().await?;
+			return Err(Error::TxRetryable);
+		}
+		// Write any changes buffered within savepoints
+		let changes = self.sp.take();
+		self.flush(changes).await?;
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
 				inner: Inner::Mem(v),
 				..
-			} => v.commit(),
+			} => v.commit().await,
 			#[cfg(feature = "kv-rocksdb")]
 			Transaction {
 				inner: Inner::RocksDB(v),
@@ -200,6 +269,11 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.commit().await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.commit().await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -220,6 +294,56 @@ impl Transaction {
 		}
 	}
 
+	/// Create a savepoint within the transaction.
+	///
+	/// Any changes made after this point are buffered until the
+	/// savepoint is released, or the transaction is committed.
+	pub fn savepoint(&mut self, name: &str) {
+		#[cfg(debug_assertions)]
+		trace!("Savepoint {}", name);
+		self.sp.create(name, self.cf.clone(), self.st.clone());
+	}
+
+	/// Roll back a transaction to a savepoint.
+	///
+	/// This reverses all changes made since the savepoint was created,
+	/// while keeping the savepoint itself.
+	pub fn rollback_to(&mut self, name: &str) -> Result<(), Error> {
+		#[cfg(debug_assertions)]
+		trace!("Rollback to {}", name);
+		(self.cf, self.st) = self.sp.rollback(name)?;
+		// Cached definitions may have been changed since the savepoint
+		self.cache.clear();
+		Ok(())
+	}
+
+	/// Release a savepoint.
+	///
+	/// This removes the savepoint, and any savepoints created after it,
+	/// keeping all changes made since the savepoint was created.
+	pub async fn release(&mut self, name: &str) -> Result<(), Error> {
+		#[cfg(debug_assertions)]
+		trace!("Release {}", name);
+		if let Some(changes) = self.sp.release(name)? {
+			self.flush(changes).await?;
+		}
+		Ok(())
+	}
+
+	/// Write changes which were buffered within savepoints to the datastore.
+	async fn flush(&mut self, changes: Changes) -> Result<(), Error> {
+		for (key, val) in changes.writes {
+			match val {
+				Some(val) => self.set(key, val).await?,
+				None => self.del(key).await?,
+			}
+		}
+		for (ts_key, prefix, suffix, val) in changes.versionstamped {
+			self.set_versionstamped_key(ts_key, prefix, suffix, val).await?;
+		}
+		Ok(())
+	}
+
 	/// Delete a key from the datastore.
 	#[allow(unused_variables)]
 	pub async fn del<K>(&mut self, key: K) -> Result<(), Error>
@@ -228,6 +352,11 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Del {:?}", key);
+		// Buffer the change if a savepoint is active
+		if self.sp.is_active() {
+			self.sp.set(key.into(), None);
+			return Ok(());
+		}
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -244,6 +373,11 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.del(key).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.del(key).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -272,6 +406,11 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Exi {:?}", key);
+		// Check the changes buffered within savepoints
+		let key: Key = key.into();
+		if let Some(val) = self.sp.get(&key) {
+			return Ok(val.is_some());
+		}
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -288,6 +427,11 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.exi(key).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.exi(key).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -316,39 +460,54 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Get {:?}", key);
-		match self {
+		// Check the changes buffered within savepoints
+		let key: Key = key.into();
+		if let Some(val) = self.sp.get(&key) {
+			return Ok(val);
+		}
+		let val: Result<Option<Val>, Error> = match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
 				inner: Inner::Mem(v),
 				..
-			} => v.get(key),
+			} => v.get(key.clone()),
 			#[cfg(feature = "kv-rocksdb")]
 			Transaction {
 				inner: Inner::RocksDB(v),
 				..
-			} => v.get(key).await,
+			} => v.get(key.clone()).await,
 			#[cfg(feature = "kv-speedb")]
 			Transaction {
 				inner: Inner::SpeeDB(v),
 				..
-			} => v.get(key).await,
+			} => v.get(key.clone()).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.get(key.clone()).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
 				..
-			} => v.get(key).await,
+			} => v.get(key.clone()).await,
 			#[cfg(feature = "kv-tikv")]
 			Transaction {
 				inner: Inner::TiKV(v),
 				..
-			} => v.get(key).await,
+			} => v.get(key.clone()).await,
 			#[cfg(feature = "kv-fdb")]
 			Transaction {
 				inner: Inner::FoundationDB(v),
 				..
-			} => v.get(key).await,
+			} => v.get(key.clone()).await,
 			#[allow(unreachable_patterns)]
 			_ => unreachable!(),
+		};
+		// Decrypt the value if necessary
+		match val? {
+			Some(val) => Ok(Some(self.decrypt(&key, val)?)),
+			None => Ok(None),
 		}
 	}
 
@@ -361,6 +520,14 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Set {:?} => {:?}", key, val);
+		// Buffer the change if a savepoint is active
+		if self.sp.is_active() {
+			self.sp.set(key.into(), Some(val.into()));
+			return Ok(());
+		}
+		// Encrypt the value if necessary
+		let key: Key = key.into();
+		let val = self.encrypt(&key, val)?;
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -377,6 +544,11 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.set(key, val).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.set(key, val).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -409,6 +581,10 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Get Timestamp {:?}", key);
+		// Allocate the versionstamp with a hybrid logical clock if selected
+		if self.vo == VersionstampOracle::HybridLogicalClock {
+			return self.get_hybrid_timestamp(key.into()).await;
+		}
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -440,11 +616,31 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.get_timestamp(key).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.get_timestamp(key).await,
 			#[allow(unreachable_patterns)]
 			_ => unreachable!(),
 		}
 	}
 
+	/// Obtain a new change timestamp from a hybrid logical clock, which is
+	/// persisted under the key, so that concurrent transactions conflict.
+	async fn get_hybrid_timestamp(&mut self, key: Key) -> Result<Versionstamp, Error> {
+		let prev = match self.get(key.clone()).await? {
+			Some(v) => match <Versionstamp>::try_from(v.as_slice()) {
+				Ok(vs) => Some(vs),
+				Err(e) => return Err(Error::Ds(e.to_string())),
+			},
+			None => None,
+		};
+		let vs = HybridLogicalClock::new(prev).now();
+		self.set(key, vs.to_vec()).await?;
+		Ok(vs)
+	}
+
 	/// Insert or update a key in the datastore.
 	#[allow(unused_variables)]
 	pub async fn set_versionstamped_key<K, V>(
@@ -460,6 +656,24 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Set {:?} <ts> {:?} => {:?}", prefix, suffix, val);
+		// Buffer the change until no savepoints are active, so
+		// that the versionstamp is only allocated once it is kept
+		if self.sp.is_active() {
+			self.sp.set_versionstamped((ts_key.into(), prefix.into(), suffix.into(), val.into()));
+			return Ok(());
+		}
+		// Allocate the versionstamp with a hybrid logical clock if selected
+		if self.vo == VersionstampOracle::HybridLogicalClock {
+			let ts = self.get_hybrid_timestamp(ts_key.into()).await?;
+			let mut k: Vec<u8> = prefix.into();
+			k.extend_from_slice(&ts);
+			k.append(&mut suffix.into());
+			return self.set(k, val).await;
+		}
+		// Encrypt the value if necessary
+		let (ts_key, prefix, suffix): (Key, Key, Key) =
+			(ts_key.into(), prefix.into(), suffix.into());
+		let val = self.encrypt(&prefix, val)?;
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -506,6 +720,14 @@ impl Transaction {
 				let k = v.get_versionstamped_key(ts_key, prefix, suffix).await?;
 				v.set(k, val).await
 			}
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => {
+				let k = v.get_versionstamped_key(ts_key, prefix, suffix).await?;
+				v.set(k, val).await
+			}
 			#[allow(unreachable_patterns)]
 			_ => unreachable!(),
 		}
@@ -520,6 +742,17 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Put {:?} => {:?}", key, val);
+		// Buffer the change if a savepoint is active
+		if self.sp.is_active() {
+			let key: Key = key.into();
+			if self.exi(key.clone()).await? {
+				return Err(Error::TxKeyAlreadyExists);
+			}
+			return self.set(key, val).await;
+		}
+		// Encrypt the value if necessary
+		let key: Key = key.into();
+		let val = self.encrypt(&key, val)?;
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -536,6 +769,11 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.put(key, val).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.put(key, val).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -559,13 +797,116 @@ impl Transaction {
 	/// Retrieve a specific range of keys from the datastore.
 	///
 	/// This function fetches the full range of key-value pairs, in a single request to the underlying datastore.
-	#[allow(unused_variables)]
 	pub async fn scan<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<(Key, Val)>, Error>
 	where
 		K: Into<Key> + Debug,
 	{
 		#[cfg(debug_assertions)]
 		trace!("Scan {:?} - {:?}", rng.start, rng.end);
+		let rng = rng.start.into()..rng.end.into();
+		self.scan_range(rng, limit, false, true).await
+	}
+
+	/// Retrieve a specific range of keys from the datastore, in reverse order.
+	///
+	/// This function fetches the last key-value pairs in the range, starting with the greatest key.
+	pub async fn scanr<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key> + Debug,
+	{
+		#[cfg(debug_assertions)]
+		trace!("Scanr {:?} - {:?}", rng.start, rng.end);
+		let rng = rng.start.into()..rng.end.into();
+		self.scan_range(rng, limit, true, true).await
+	}
+
+	/// Retrieve a specific range of keys from the datastore, in either direction.
+	///
+	/// If values are not required, then the returned values are empty.
+	pub(super) async fn scan_range(
+		&mut self,
+		rng: Range<Key>,
+		limit: u32,
+		reverse: bool,
+		values: bool,
+	) -> Result<Vec<(Key, Val)>, Error> {
+		// Fetch the range directly if no savepoint is active
+		if !self.sp.is_active() {
+			return self.scan_datastore(rng, limit, reverse, values).await;
+		}
+		// Merge the changes buffered within savepoints into the range
+		let (mut beg, mut end) = (rng.start, rng.end);
+		let mut out: Vec<(Key, Val)> = vec![];
+		while out.len() < limit as usize {
+			let res = self.scan_datastore(beg.clone()..end.clone(), limit, reverse, values).await?;
+			let full = res.len() >= limit as usize;
+			// Find the range covered by this batch
+			let (min, max) = match res.last() {
+				Some((k, _)) if full && !reverse => {
+					let mut k = k.clone();
+					k.push(0x00);
+					(beg.clone(), k)
+				}
+				Some((k, _)) if full => (k.clone(), end.clone()),
+				_ => (beg.clone(), end.clone()),
+			};
+			let mut batch: BTreeMap<Key, Val> = res.into_iter().collect();
+			for (k, v) in self.sp.range(&min, &max) {
+				match v {
+					Some(v) if values => batch.insert(k, v),
+					Some(_) => batch.insert(k, vec![]),
+					None => batch.remove(&k),
+				};
+			}
+			match reverse {
+				false => out.extend(batch),
+				true => out.extend(batch.into_iter().rev()),
+			}
+			if !full {
+				break;
+			}
+			match reverse {
+				false => beg = max,
+				true => end = min,
+			}
+		}
+		out.truncate(limit as usize);
+		Ok(out)
+	}
+
+	/// Retrieve a specific range of keys from the underlying datastore, ignoring savepoints.
+	async fn scan_datastore(
+		&mut self,
+		rng: Range<Key>,
+		limit: u32,
+		reverse: bool,
+		values: bool,
+	) -> Result<Vec<(Key, Val)>, Error> {
+		// Fetch only the keys if the values are not needed
+		if !values {
+			let res = self.keys_range(rng, limit, reverse).await?;
+			return Ok(res.into_iter().map(|k| (k, vec![])).collect());
+		}
+		let res = match reverse {
+			false => self.scan_forward(rng, limit).await?,
+			true => self.scan_reverse(rng, limit).await?,
+		};
+		// Decrypt the values if necessary
+		res.into_iter()
+			.map(|(k, v)| {
+				let v = self.decrypt(&k, v)?;
+				Ok((k, v))
+			})
+			.collect()
+	}
+
+	/// Retrieve a specific range of keys from the underlying datastore.
+	#[allow(unused_variables)]
+	async fn scan_forward(
+		&mut self,
+		rng: Range<Key>,
+		limit: u32,
+	) -> Result<Vec<(Key, Val)>, Error> {
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -582,6 +923,11 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.scan(rng, limit).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.scan(rng, limit).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -602,6 +948,201 @@ impl Transaction {
 		}
 	}
 
+	/// Retrieve a specific range of keys from the underlying datastore, in reverse order.
+	#[allow(unused_variables)]
+	async fn scan_reverse(
+		&mut self,
+		rng: Range<Key>,
+		limit: u32,
+	) -> Result<Vec<(Key, Val)>, Error> {
+		// Datastores which can only be iterated forwards keep a window of the greatest keys
+		if !self.reverse_scans() {
+			return self.scan_reverse_window(rng, limit).await;
+		}
+		match self {
+			#[cfg(feature = "kv-rocksdb")]
+			Transaction {
+				inner: Inner::RocksDB(v),
+				..
+			} => v.scanr(rng, limit).await,
+			#[cfg(feature = "kv-speedb")]
+			Transaction {
+				inner: Inner::SpeeDB(v),
+				..
+			} => v.scanr(rng, limit).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.scanr(rng, limit).await,
+			#[cfg(feature = "kv-tikv")]
+			Transaction {
+				inner: Inner::TiKV(v),
+				..
+			} => v.scanr(rng, limit).await,
+			#[cfg(feature = "kv-fdb")]
+			Transaction {
+				inner: Inner::FoundationDB(v),
+				..
+			} => v.scanr(rng, limit).await,
+			#[allow(unreachable_patterns)]
+			_ => unreachable!(),
+		}
+	}
+
+	/// Retrieve a specific range of keys from a datastore which can only be iterated forwards,
+	/// in reverse order.
+	///
+	/// The range is read forwards in batches, and only the last `limit` entries are kept, so
+	/// callers should fetch a large range as a series of small windows, moving the end of the
+	/// range past the entries which have already been returned.
+	async fn scan_reverse_window(
+		&mut self,
+		rng: Range<Key>,
+		limit: u32,
+	) -> Result<Vec<(Key, Val)>, Error> {
+		let size = limit.min(1000);
+		let mut out = VecDeque::with_capacity(size as usize);
+		if limit == 0 {
+			return Ok(vec![]);
+		}
+		let Range {
+			start: mut beg,
+			end,
+		} = rng;
+		// Scan the range in batches, keeping the last key-value pairs
+		while beg < end {
+			let res = self.scan_forward(beg.clone()..end.clone(), size).await?;
+			let done = res.len() < size as usize;
+			if let Some((k, _)) = res.last() {
+				beg = k.clone();
+				beg.push(0x00);
+			}
+			for kv in res {
+				if out.len() == limit as usize {
+					out.pop_front();
+				}
+				out.push_back(kv);
+			}
+			if done {
+				break;
+			}
+		}
+		Ok(out.into_iter().rev().collect())
+	}
+
+	/// Retrieve a specific range of keys from the underlying datastore, without fetching the values.
+	#[allow(unused_variables)]
+	async fn keys_range(
+		&mut self,
+		rng: Range<Key>,
+		limit: u32,
+		reverse: bool,
+	) -> Result<Vec<Key>, Error> {
+		match (self, reverse) {
+			#[cfg(feature = "kv-rocksdb")]
+			(
+				Transaction {
+					inner: Inner::RocksDB(v),
+					..
+				},
+				false,
+			) => v.keys(rng, limit).await,
+			#[cfg(feature = "kv-rocksdb")]
+			(
+				Transaction {
+					inner: Inner::RocksDB(v),
+					..
+				},
+				true,
+			) => v.keysr(rng, limit).await,
+			#[cfg(feature = "kv-speedb")]
+			(
+				Transaction {
+					inner: Inner::SpeeDB(v),
+					..
+				},
+				false,
+			) => v.keys(rng, limit).await,
+			#[cfg(feature = "kv-speedb")]
+			(
+				Transaction {
+					inner: Inner::SpeeDB(v),
+					..
+				},
+				true,
+			) => v.keysr(rng, limit).await,
+			#[cfg(feature = "kv-redb")]
+			(
+				Transaction {
+					inner: Inner::Redb(v),
+					..
+				},
+				false,
+			) => v.keys(rng, limit).await,
+			#[cfg(feature = "kv-redb")]
+			(
+				Transaction {
+					inner: Inner::Redb(v),
+					..
+				},
+				true,
+			) => v.keysr(rng, limit).await,
+			// The other datastores always return the values along with the keys
+			#[allow(unreachable_patterns)]
+			(tx, reverse) => {
+				let res = match reverse {
+					false => tx.scan_forward(rng, limit).await?,
+					true => tx.scan_reverse(rng, limit).await?,
+				};
+				Ok(res.into_iter().map(|(k, _)| k).collect())
+			}
+		}
+	}
+
+	/// Check if the underlying datastore can iterate over a range in reverse order.
+	fn reverse_scans(&self) -> bool {
+		match self {
+			#[cfg(feature = "kv-mem")]
+			Transaction {
+				inner: Inner::Mem(_),
+				..
+			} => false,
+			#[cfg(feature = "kv-rocksdb")]
+			Transaction {
+				inner: Inner::RocksDB(_),
+				..
+			} => true,
+			#[cfg(feature = "kv-speedb")]
+			Transaction {
+				inner: Inner::SpeeDB(_),
+				..
+			} => true,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(_),
+				..
+			} => true,
+			#[cfg(feature = "kv-indxdb")]
+			Transaction {
+				inner: Inner::IndxDB(_),
+				..
+			} => false,
+			#[cfg(feature = "kv-tikv")]
+			Transaction {
+				inner: Inner::TiKV(_),
+				..
+			} => true,
+			#[cfg(feature = "kv-fdb")]
+			Transaction {
+				inner: Inner::FoundationDB(_),
+				..
+			} => true,
+			#[allow(unreachable_patterns)]
+			_ => unreachable!(),
+		}
+	}
+
 	/// Update a key in the datastore if the current value matches a condition.
 	#[allow(unused_variables)]
 	pub async fn putc<K, V>(&mut self, key: K, val: V, chk: Option<V>) -> Result<(), Error>
@@ -611,6 +1152,15 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Putc {:?} if {:?} => {:?}", key, chk, val);
+		// Encrypted or buffered values can not be compared by the underlying datastore
+		if self.enc.is_some() || self.sp.is_active() {
+			let key: Key = key.into();
+			let chk: Option<Val> = chk.map(Into::into);
+			if self.get(key.clone()).await? != chk {
+				return Err(Error::TxConditionNotMet);
+			}
+			return self.set(key, val).await;
+		}
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -627,6 +1177,11 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.putc(key, val, chk).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.putc(key, val, chk).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -656,6 +1211,15 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Delc {:?} if {:?}", key, chk);
+		// Encrypted or buffered values can not be compared by the underlying datastore
+		if self.enc.is_some() || self.sp.is_active() {
+			let key: Key = key.into();
+			let chk: Option<Val> = chk.map(Into::into);
+			if self.get(key.clone()).await? != chk {
+				return Err(Error::TxConditionNotMet);
+			}
+			return self.del(key).await;
+		}
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -672,6 +1236,11 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.delc(key, chk).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.delc(key, chk).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -692,6 +1261,27 @@ impl Transaction {
 		}
 	}
 
+	/// Encrypt a value if encryption is enabled on the datastore, and the
+	/// value for this key is not stored unencrypted.
+	fn encrypt<V>(&self, key: &[u8], val: V) -> Result<Val, Error>
+	where
+		V: Into<Val>,
+	{
+		match &self.enc {
+			Some(enc) if !Encryption::is_unencrypted(key) => enc.encrypt(&val.into()),
+			_ => Ok(val.into()),
+		}
+	}
+
+	/// Decrypt a value if encryption is enabled on the datastore, and the
+	/// value for this key is not stored unencrypted.
+	fn decrypt(&self, key: &[u8], val: Val) -> Result<Val, Error> {
+		match &self.enc {
+			Some(enc) if !Encryption::is_unencrypted(key) => enc.decrypt(&val),
+			_ => Ok(val),
+		}
+	}
+
 	// --------------------------------------------------
 	// Superjacent methods
 	// --------------------------------------------------
@@ -895,6 +1485,37 @@ impl Transaction {
 		Ok(())
 	}
 
+	/// Move all keys with the specified prefix to a different prefix, keeping their values.
+	pub async fn movp<K>(&mut self, from: K, to: K) -> Result<(), Error>
+	where
+		K: Into<Key>,
+	{
+		let from: Key = from.into();
+		let to: Key = to.into();
+		let mut scanner = Scanner::prefix(from.clone());
+		while self.movp_batch(&mut scanner, &from, &to).await? {}
+		Ok(())
+	}
+
+	/// Move the next batch of keys from a scanner over a prefix to a different prefix,
+	/// returning whether any keys were moved.
+	pub async fn movp_batch(
+		&mut self,
+		scanner: &mut Scanner,
+		from: &[u8],
+		to: &[u8],
+	) -> Result<bool, Error> {
+		let res = scanner.next_batch(self).await?;
+		for (k, v) in res.iter() {
+			let key = [to, &k[from.len()..]].concat();
+			self.set(key, v.clone()).await?;
+			self.del(k.clone()).await?;
+		}
+		// Cached definitions may have been stored under the moved keys
+		self.cache.clear();
+		Ok(!res.is_empty())
+	}
+
 	// --------------------------------------------------
 	// Superimposed methods
 	// --------------------------------------------------
@@ -951,7 +1572,7 @@ impl Transaction {
 	pub async fn set_hb(&mut self, timestamp: Timestamp, id: Uuid) -> Result<(), Error> {
 		let key = crate::key::root::hb::Hb::new(timestamp.clone(), id);
 		// We do not need to do a read, we always want to overwrite
-		self.put(
+		self.set(
 			key,
 			ClusterMembership {
 				name: id.to_string(),
@@ -962,12 +1583,72 @@ impl Transaction {
 		Ok(())
 	}
 
+	// Retrieve the lease of a cluster-wide background job
+	pub async fn get_ld(&mut self, job: &str) -> Result<Option<Lease>, Error> {
+		let key = crate::key::root::ld::new(job);
+		Ok(self.get(key).await?.map(Into::into))
+	}
+
+	// Update the lease of a cluster-wide background job, if it has not changed
+	pub async fn putc_ld(
+		&mut self,
+		job: &str,
+		lease: Lease,
+		chk: Option<Lease>,
+	) -> Result<(), Error> {
+		let key = crate::key::root::ld::new(job);
+		self.putc(key, lease, chk).await
+	}
+
+	// Delete the lease of a cluster-wide background job
+	pub async fn del_ld(&mut self, job: &str) -> Result<(), Error> {
+		let key = crate::key::root::ld::new(job);
+		self.del(key).await
+	}
+
 	// Delete a cluster registration entry
 	pub async fn del_nd(&mut self, node: Uuid) -> Result<(), Error> {
 		let key = crate::key::root::nd::Nd::new(node);
 		self.del(key).await
 	}
 
+	// Retrieve the ids of the registered cluster nodes
+	pub async fn scan_nd(&mut self, limit: u32) -> Result<Vec<Uuid>, Error> {
+		let beg = crate::key::root::nd::Nd::prefix();
+		let end = crate::key::root::nd::Nd::suffix();
+		let mut out = vec![];
+		for (k, _) in self.scan(beg..end, limit).await? {
+			out.push(crate::key::root::nd::Nd::decode(&k)?.nd);
+		}
+		Ok(out)
+	}
+
+	// The duration after which a node which has not sent a heartbeat is removed
+	pub(crate) fn lease_duration(&self) -> Duration {
+		self.ld
+	}
+
+	// Forcibly remove a node from the cluster, together with its heartbeats, its queued
+	// notifications and its live queries. Returns whether anything was known about the node.
+	pub async fn evict_nd(&mut self, nd: Uuid) -> Result<bool, Error> {
+		let ts = Timestamp {
+			value: u64::MAX - 1,
+		};
+		let hbs: Vec<_> =
+			self.scan_hb(&ts, u32::MAX).await?.into_iter().filter(|hb| hb.nd == nd).collect();
+		let lqs = self.all_lq(&nd).await?;
+		let found = !hbs.is_empty() || !lqs.is_empty() || self.get_nd(nd).await?.is_some();
+		self.delr_hb(hbs, 1000).await?;
+		self.del_nd(nd).await?;
+		self.delr_nt(&nd).await?;
+		for lq in lqs {
+			let key = crate::key::node::lq::new(lq.nd.0, lq.lq.0, &lq.ns, &lq.db);
+			self.del(key).await?;
+			self.del_lv(&lq.ns, &lq.db, &lq.tb, lq.lq.0).await?;
+		}
+		Ok(found)
+	}
+
 	// Delete the live query notification registry on the table
 	// Return the Table ID
 	pub async fn del_ndlv(&mut self, nd: &Uuid) -> Result<Uuid, Error> {
@@ -1122,6 +1803,42 @@ impl Transaction {
 		Ok(res)
 	}
 
+	// Queue a live query notification for delivery by another node
+	pub async fn set_nt(&mut self, nd: &Uuid, notification: Notification) -> Result<(), Error> {
+		let sq = NOTIFICATION_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+		let key = crate::key::node::nt::new(*nd, self.clock().value, sq, Uuid::new_v4());
+		self.put(key, notification).await
+	}
+
+	// Scan the live query notifications queued for a node, oldest first
+	pub async fn scan_nt(
+		&mut self,
+		nd: &Uuid,
+		limit: u32,
+	) -> Result<Vec<(crate::key::node::nt::Nt, Notification)>, Error> {
+		let beg = crate::key::node::nt::prefix_nd(nd);
+		let end = crate::key::node::nt::suffix_nd(nd);
+		let scanned = self.scan(beg..end, limit).await?;
+		let mut res = vec![];
+		for (key, value) in scanned {
+			let nt = crate::key::node::nt::Nt::decode(key.as_slice())?;
+			res.push((nt, value.into()));
+		}
+		Ok(res)
+	}
+
+	// Delete a live query notification which has been delivered
+	pub async fn del_nt(&mut self, nt: crate::key::node::nt::Nt) -> Result<(), Error> {
+		self.del(nt).await
+	}
+
+	// Delete all the live query notifications queued for a node
+	pub async fn delr_nt(&mut self, nd: &Uuid) -> Result<(), Error> {
+		let beg = crate::key::node::nt::prefix_nd(nd);
+		let end = crate::key::node::nt::suffix_nd(nd);
+		self.delr(beg..end, u32::MAX).await
+	}
+
 	pub async fn putc_lv(
 		&mut self,
 		ns: &str,
@@ -1149,7 +1866,7 @@ impl Transaction {
 			let beg = crate::key::root::ns::prefix();
 			let end = crate::key::root::ns::suffix();
 			let val = self.getr(beg..end, u32::MAX).await?;
-			let val = val.convert().into();
+			let val = val.try_convert()?.into();
 			self.cache.set(key, Entry::Nss(Arc::clone(&val)));
 			val
 		})
@@ -1206,7 +1923,7 @@ impl Transaction {
 			let beg = crate::key::namespace::db::prefix(ns);
 			let end = crate::key::namespace::db::suffix(ns);
 			let val = self.getr(beg..end, u32::MAX).await?;
-			let val = val.convert().into();
+			let val = val.try_convert()?.into();
 			self.cache.set(key, Entry::Dbs(Arc::clone(&val)));
 			val
 		})
@@ -1351,11 +2068,30 @@ impl Transaction {
 		})
 	}
 
-	/// Retrieve all table definitions for a specific database.
-	pub async fn all_tb(
-		&mut self,
-		ns: &str,
-		db: &str,
+	/// Retrieve all job definitions for a specific database.
+	pub async fn all_jb(&mut self, ns: &str, db: &str) -> Result<Arc<[DefineJobStatement]>, Error> {
+		let key = crate::key::database::jb::prefix(ns, db);
+		Ok(if let Some(e) = self.cache.get(&key) {
+			if let Entry::Jbs(v) = e {
+				v
+			} else {
+				unreachable!();
+			}
+		} else {
+			let beg = crate::key::database::jb::prefix(ns, db);
+			let end = crate::key::database::jb::suffix(ns, db);
+			let val = self.getr(beg..end, u32::MAX).await?;
+			let val = val.convert().into();
+			self.cache.set(key, Entry::Jbs(Arc::clone(&val)));
+			val
+		})
+	}
+
+	/// Retrieve all table definitions for a specific database.
+	pub async fn all_tb(
+		&mut self,
+		ns: &str,
+		db: &str,
 	) -> Result<Arc<[DefineTableStatement]>, Error> {
 		let key = crate::key::database::tb::prefix(ns, db);
 		Ok(if let Some(e) = self.cache.get(&key) {
@@ -1368,12 +2104,36 @@ impl Transaction {
 			let beg = crate::key::database::tb::prefix(ns, db);
 			let end = crate::key::database::tb::suffix(ns, db);
 			let val = self.getr(beg..end, u32::MAX).await?;
-			let val = val.convert().into();
+			let val = val.try_convert()?.into();
 			self.cache.set(key, Entry::Tbs(Arc::clone(&val)));
 			val
 		})
 	}
 
+	/// Retrieve all change feed consumer definitions for a specific table.
+	pub async fn all_co(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+	) -> Result<Arc<[DefineConsumerStatement]>, Error> {
+		let key = crate::key::table::co::prefix(ns, db, tb);
+		Ok(if let Some(e) = self.cache.get(&key) {
+			if let Entry::Cos(v) = e {
+				v
+			} else {
+				unreachable!();
+			}
+		} else {
+			let beg = crate::key::table::co::prefix(ns, db, tb);
+			let end = crate::key::table::co::suffix(ns, db, tb);
+			let val = self.getr(beg..end, u32::MAX).await?;
+			let val = val.convert().into();
+			self.cache.set(key, Entry::Cos(Arc::clone(&val)));
+			val
+		})
+	}
+
 	/// Retrieve all event definitions for a specific table.
 	pub async fn all_ev(
 		&mut self,
@@ -1440,7 +2200,7 @@ impl Transaction {
 			let beg = crate::key::table::ix::prefix(ns, db, tb);
 			let end = crate::key::table::ix::suffix(ns, db, tb);
 			let val = self.getr(beg..end, u32::MAX).await?;
-			let val = val.convert().into();
+			let val = val.try_convert()?.into();
 			self.cache.set(key, Entry::Ixs(Arc::clone(&val)));
 			val
 		})
@@ -1464,7 +2224,7 @@ impl Transaction {
 			let beg = crate::key::table::ft::prefix(ns, db, tb);
 			let end = crate::key::table::ft::suffix(ns, db, tb);
 			let val = self.getr(beg..end, u32::MAX).await?;
-			let val = val.convert().into();
+			let val = val.try_convert()?.into();
 			self.cache.set(key, Entry::Fts(Arc::clone(&val)));
 			val
 		})
@@ -1488,7 +2248,7 @@ impl Transaction {
 			let beg = crate::key::table::lq::prefix(ns, db, tb);
 			let end = crate::key::table::lq::suffix(ns, db, tb);
 			let val = self.getr(beg..end, u32::MAX).await?;
-			let val = val.convert().into();
+			let val = val.try_convert()?.into();
 			self.cache.set(key, Entry::Lvs(Arc::clone(&val)));
 			val
 		})
@@ -1546,7 +2306,7 @@ impl Transaction {
 		let val = self.get(key).await?.ok_or(Error::NsNotFound {
 			value: ns.to_owned(),
 		})?;
-		Ok(val.into())
+		val.try_into()
 	}
 
 	/// Retrieve a specific namespace login definition.
@@ -1573,7 +2333,7 @@ impl Transaction {
 		let val = self.get(key).await?.ok_or(Error::DbNotFound {
 			value: db.to_owned(),
 		})?;
-		Ok(val.into())
+		val.try_into()
 	}
 
 	/// Retrieve a specific database login definition.
@@ -1675,7 +2435,7 @@ impl Transaction {
 		let val = self.get(key_enc).await?.ok_or(Error::LvNotFound {
 			value: lv.to_string(),
 		})?;
-		Ok(val.into())
+		val.try_into()
 	}
 
 	/// Retrieve a specific param definition.
@@ -1692,6 +2452,26 @@ impl Transaction {
 		Ok(val.into())
 	}
 
+	/// Retrieve a specific job definition.
+	pub async fn get_jb(
+		&mut self,
+		ns: &str,
+		db: &str,
+		jb: &str,
+	) -> Result<DefineJobStatement, Error> {
+		let key = crate::key::database::jb::new(ns, db, jb);
+		let val = self.get(key).await?.ok_or(Error::JbNotFound {
+			value: jb.to_owned(),
+		})?;
+		Ok(val.into())
+	}
+
+	/// Retrieve the outcome of the last run of a scheduled job.
+	pub async fn get_jr(&mut self, ns: &str, db: &str, jb: &str) -> Result<Option<JobRun>, Error> {
+		let key = crate::key::database::jr::new(ns, db, jb);
+		Ok(self.get(key).await?.map(Into::into))
+	}
+
 	/// Retrieve a specific table definition.
 	pub async fn get_tb(
 		&mut self,
@@ -1703,8 +2483,103 @@ impl Transaction {
 		let val = self.get(key).await?.ok_or(Error::TbNotFound {
 			value: tb.to_owned(),
 		})?;
+		val.try_into()
+	}
+	/// Retrieve a specific change feed consumer definition.
+	pub async fn get_co(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		co: &str,
+	) -> Result<DefineConsumerStatement, Error> {
+		let key = crate::key::table::co::new(ns, db, tb, co);
+		let val = self.get(key).await?.ok_or(Error::CoNotFound {
+			value: co.to_owned(),
+		})?;
 		Ok(val.into())
 	}
+
+	/// Retrieve the versionstamp acknowledged by a change feed consumer.
+	pub async fn get_cp(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		co: &str,
+	) -> Result<Option<Versionstamp>, Error> {
+		let key = crate::key::table::cp::new(ns, db, tb, co);
+		match self.get(key).await? {
+			Some(v) => match v.as_slice().try_into() {
+				Ok(vs) => Ok(Some(vs)),
+				Err(e) => Err(Error::Ds(e.to_string())),
+			},
+			None => Ok(None),
+		}
+	}
+
+	/// Store the versionstamp acknowledged by a change feed consumer.
+	pub async fn set_cp(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		co: &str,
+		vs: Versionstamp,
+	) -> Result<(), Error> {
+		let key = crate::key::table::cp::new(ns, db, tb, co);
+		self.set(key, vs.to_vec()).await
+	}
+
+	/// Record the current database versionstamp against a point in time.
+	pub async fn set_timestamp_for_versionstamp(
+		&mut self,
+		ts: u64,
+		ns: &str,
+		db: &str,
+	) -> Result<Versionstamp, Error> {
+		// This also advances the database versionstamp, so every change
+		// recorded below this versionstamp was committed before the timestamp.
+		let vs = self.get_timestamp(crate::key::database::vs::new(ns, db), false).await?;
+		let key = crate::key::database::ts::new(ns, db, ts);
+		self.set(key, vs.to_vec()).await?;
+		Ok(vs)
+	}
+
+	/// Retrieve the latest database versionstamp recorded at or before a point in time.
+	pub async fn get_versionstamp_from_timestamp(
+		&mut self,
+		ts: u64,
+		ns: &str,
+		db: &str,
+	) -> Result<Option<Versionstamp>, Error> {
+		let beg = crate::key::database::ts::prefix(ns, db);
+		let end = crate::key::database::ts::new(ns, db, ts.saturating_add(1));
+		let val = self.scanr(beg..end.into(), 1).await?;
+		match val.last() {
+			Some((_, v)) => match v.as_slice().try_into() {
+				Ok(vs) => Ok(Some(vs)),
+				Err(e) => Err(Error::Ds(e.to_string())),
+			},
+			None => Ok(None),
+		}
+	}
+
+	/// Retrieve the latest versionstamp which was allocated for a database.
+	pub async fn get_database_versionstamp(
+		&mut self,
+		ns: &str,
+		db: &str,
+	) -> Result<Option<Versionstamp>, Error> {
+		match self.get(crate::key::database::vs::new(ns, db)).await? {
+			Some(v) => match v.as_slice().try_into() {
+				Ok(vs) => Ok(Some(vs)),
+				Err(e) => Err(Error::Ds(e.to_string())),
+			},
+			None => Ok(None),
+		}
+	}
+
 	/// Retrieve a specific analyzer definition.
 	pub async fn get_az(
 		&mut self,
@@ -1730,7 +2605,7 @@ impl Transaction {
 		let val = self.get(key).await?.ok_or(Error::IxNotFound {
 			value: ix.to_owned(),
 		})?;
-		Ok(val.into())
+		val.try_into()
 	}
 	/// Add a namespace with a default configuration, only if we are in dynamic mode.
 	pub async fn add_ns(
@@ -1746,6 +2621,8 @@ impl Transaction {
 					let key = crate::key::root::ns::new(ns);
 					let val = DefineNamespaceStatement {
 						name: ns.to_owned().into(),
+						id: Some(self.ns_id(ns).await?),
+						quota: Default::default(),
 					};
 					self.put(key, &val).await?;
 					Ok(val)
@@ -1775,6 +2652,8 @@ impl Transaction {
 					let val = DefineDatabaseStatement {
 						name: db.to_owned().into(),
 						changefeed: None,
+						id: Some(self.db_id(ns, db).await?),
+						quota: Default::default(),
 					};
 					self.put(key, &val).await?;
 					Ok(val)
@@ -1831,14 +2710,17 @@ impl Transaction {
 				value,
 			}) => match strict {
 				false => {
+					self.check_tb_quota(ns, db).await?;
 					let key = crate::key::database::tb::new(ns, db, tb);
 					let val = DefineTableStatement {
 						name: tb.to_owned().into(),
 						permissions: Permissions::none(),
 						changefeed: None,
+						id: Some(self.tb_id(ns, db, tb).await?),
 						..DefineTableStatement::default()
 					};
 					self.put(key, &val).await?;
+					self.move_tb_data(ns, db, tb).await?;
 					Ok(val)
 				}
 				true => Err(Error::TbNotFound {
@@ -1866,7 +2748,7 @@ impl Transaction {
 			let val = self.get(key.clone()).await?.ok_or(Error::NsNotFound {
 				value: ns.to_owned(),
 			})?;
-			let val: Arc<DefineNamespaceStatement> = Arc::new(val.into());
+			let val: Arc<DefineNamespaceStatement> = Arc::new(val.try_into()?);
 			self.cache.set(key, Entry::Ns(Arc::clone(&val)));
 			val
 		})
@@ -1889,7 +2771,7 @@ impl Transaction {
 			let val = self.get(key.clone()).await?.ok_or(Error::DbNotFound {
 				value: db.to_owned(),
 			})?;
-			let val: Arc<DefineDatabaseStatement> = Arc::new(val.into());
+			let val: Arc<DefineDatabaseStatement> = Arc::new(val.try_into()?);
 			self.cache.set(key, Entry::Db(Arc::clone(&val)));
 			val
 		})
@@ -1913,7 +2795,7 @@ impl Transaction {
 			let val = self.get(key.clone()).await?.ok_or(Error::TbNotFound {
 				value: tb.to_owned(),
 			})?;
-			let val: Arc<DefineTableStatement> = Arc::new(val.into());
+			let val: Arc<DefineTableStatement> = Arc::new(val.try_into()?);
 			self.cache.set(key, Entry::Tb(Arc::clone(&val)));
 			val
 		})
@@ -1933,6 +2815,8 @@ impl Transaction {
 					let key = crate::key::root::ns::new(ns);
 					let val = DefineNamespaceStatement {
 						name: ns.to_owned().into(),
+						id: Some(self.ns_id(ns).await?),
+						quota: Default::default(),
 					};
 					self.put(key, &val).await?;
 					Ok(Arc::new(val))
@@ -1962,6 +2846,8 @@ impl Transaction {
 					let val = DefineDatabaseStatement {
 						name: db.to_owned().into(),
 						changefeed: None,
+						id: Some(self.db_id(ns, db).await?),
+						quota: Default::default(),
 					};
 					self.put(key, &val).await?;
 					Ok(Arc::new(val))
@@ -1988,14 +2874,17 @@ impl Transaction {
 				value,
 			}) => match strict {
 				false => {
+					self.check_tb_quota(ns, db).await?;
 					let key = crate::key::database::tb::new(ns, db, tb);
 					let val = DefineTableStatement {
 						name: tb.to_owned().into(),
 						permissions: Permissions::none(),
 						changefeed: None,
+						id: Some(self.tb_id(ns, db, tb).await?),
 						..DefineTableStatement::default()
 					};
 					self.put(key, &val).await?;
+					self.move_tb_data(ns, db, tb).await?;
 					Ok(Arc::new(val))
 				}
 				true => Err(Error::TbNotFound {
@@ -2028,6 +2917,590 @@ impl Transaction {
 		}
 	}
 
+	// --------------------------------------------------
+	// Key format methods
+	// --------------------------------------------------
+
+	/// Retrieve the key format used by the datastore
+	pub fn key_format(&self) -> KeyFormat {
+		self.kf
+	}
+
+	/// Allocate the next id from a sequence of numeric ids
+	async fn next_id<K>(&mut self, key: K) -> Result<u32, Error>
+	where
+		K: Into<Key>,
+	{
+		let key: Key = key.into();
+		let id = match self.get(key.clone()).await? {
+			Some(v) => match <[u8; 4]>::try_from(v.as_slice()) {
+				Ok(v) => u32::from_be_bytes(v),
+				Err(_) => return Err(Error::Ds("Invalid value for a numeric id sequence".into())),
+			},
+			None => 0,
+		};
+		let next = id
+			.checked_add(1)
+			.ok_or_else(|| Error::Ds("The numeric id sequence has been exhausted".into()))?;
+		self.set(key, next.to_be_bytes().to_vec()).await?;
+		Ok(id)
+	}
+
+	/// Retrieve the numeric id of a namespace, or allocate a new id if it does not have one
+	pub async fn ns_id(&mut self, ns: &str) -> Result<u32, Error> {
+		match self.get_ns(ns).await {
+			Ok(DefineNamespaceStatement {
+				id: Some(id),
+				..
+			}) => Ok(id),
+			Ok(_)
+			| Err(Error::NsNotFound {
+				..
+			}) => self.next_id(crate::key::root::ni::new()).await,
+			Err(e) => Err(e),
+		}
+	}
+
+	/// Retrieve the numeric id of a database, or allocate a new id if it does not have one
+	pub async fn db_id(&mut self, ns: &str, db: &str) -> Result<u32, Error> {
+		match self.get_db(ns, db).await {
+			Ok(DefineDatabaseStatement {
+				id: Some(id),
+				..
+			}) => Ok(id),
+			Ok(_)
+			| Err(Error::DbNotFound {
+				..
+			}) => self.next_id(crate::key::namespace::di::new(ns)).await,
+			Err(e) => Err(e),
+		}
+	}
+
+	/// Retrieve the numeric id of a table, or allocate a new id if it does not have one
+	pub async fn tb_id(&mut self, ns: &str, db: &str, tb: &str) -> Result<u32, Error> {
+		match self.get_tb(ns, db, tb).await {
+			Ok(DefineTableStatement {
+				id: Some(id),
+				..
+			}) => Ok(id),
+			Ok(_)
+			| Err(Error::TbNotFound {
+				..
+			}) => self.next_id(crate::key::database::ti::new(ns, db)).await,
+			Err(e) => Err(e),
+		}
+	}
+
+	/// Retrieve the numeric id of an index, or allocate a new id if it does not have one
+	pub async fn ix_id(&mut self, ns: &str, db: &str, tb: &str, ix: &str) -> Result<u32, Error> {
+		match self.get_ix(ns, db, tb, ix).await {
+			Ok(DefineIndexStatement {
+				id: Some(id),
+				..
+			}) => Ok(id),
+			Ok(_)
+			| Err(Error::IxNotFound {
+				..
+			}) => self.next_id(crate::key::table::ii::new(ns, db, tb)).await,
+			Err(e) => Err(e),
+		}
+	}
+
+	/// Retrieve the base of the keys which store the data of a table
+	pub async fn tb_base(&mut self, ns: &str, db: &str, tb: &str) -> Result<Base, Error> {
+		let named = crate::key::table::all::new(ns, db, tb).encode()?;
+		Ok(match self.tb_ids(ns, db, tb).await? {
+			Some((ns, db, tb)) => Base::new(named, crate::key::data::tb::new(ns, db, tb).encode()?),
+			None => Base::named(named),
+		})
+	}
+
+	/// Move the data which was stored for a table before it was defined under its numeric ids
+	///
+	/// Graph edges can be stored for tables which have not been defined, in
+	/// which case they are stored under the name of the table until the table
+	/// is defined and assigned a numeric id.
+	pub(crate) async fn move_tb_data(&mut self, ns: &str, db: &str, tb: &str) -> Result<(), Error> {
+		let base = self.tb_base(ns, db, tb).await?;
+		if !base.is_compact() {
+			return Ok(());
+		}
+		let mut migration = Migration::new(base, HashMap::new());
+		while migration.next_batch(self).await? > 0 {}
+		Ok(())
+	}
+
+	/// Retrieve the base of the keys which store the entries of an index
+	pub async fn ix_base(
+		&mut self,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+	) -> Result<Base, Error> {
+		let named = crate::key::index::all::new(ns, db, &ix.what, &ix.name).encode()?;
+		Ok(match (self.tb_ids(ns, db, &ix.what).await?, ix.id) {
+			(Some((ns, db, tb)), Some(ix)) => {
+				Base::new(named, crate::key::data::ix::new(ns, db, tb, ix).encode()?)
+			}
+			_ => Base::named(named),
+		})
+	}
+
+	/// Retrieve the numeric ids of a table, when its data is stored in the compact key format
+	async fn tb_ids(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+	) -> Result<Option<(u32, u32, u32)>, Error> {
+		// The data is stored under names in the original key format
+		if !self.kf.is_compact() {
+			return Ok(None);
+		}
+		// Data is never stored for definitions which do not exist
+		let ns_id = match self.get_and_cache_ns(ns).await {
+			Ok(v) => v.id,
+			Err(Error::NsNotFound {
+				..
+			}) => return Ok(None),
+			Err(e) => return Err(e),
+		};
+		let db_id = match self.get_and_cache_db(ns, db).await {
+			Ok(v) => v.id,
+			Err(Error::DbNotFound {
+				..
+			}) => return Ok(None),
+			Err(e) => return Err(e),
+		};
+		let tb_id = match self.get_and_cache_tb(ns, db, tb).await {
+			Ok(v) => v.id,
+			Err(Error::TbNotFound {
+				..
+			}) => return Ok(None),
+			Err(e) => return Err(e),
+		};
+		Ok(match (ns_id, db_id, tb_id) {
+			(Some(ns), Some(db), Some(tb)) => Some((ns, db, tb)),
+			_ => None,
+		})
+	}
+
+	// --------------------------------------------------
+	// Storage statistics methods
+	// --------------------------------------------------
+
+	/// Record a change to the storage statistics of a table, which is written when the transaction commits
+	pub(crate) fn record_stats(&mut self, ns: &str, db: &str, tb: &str, st: Stats) {
+		self.st.update(ns, db, tb, &st)
+	}
+
+	/// Retrieve the storage statistics of a table, including any changes made within this transaction
+	pub async fn get_stats(&mut self, ns: &str, db: &str, tb: &str) -> Result<Stats, Error> {
+		let beg = crate::key::table::st::prefix(ns, db, tb);
+		let end = crate::key::table::st::suffix(ns, db, tb);
+		let mut out = Stats::default();
+		let mut scanner = Scanner::new(beg..end);
+		loop {
+			let res = scanner.next_batch(self).await?;
+			if res.is_empty() {
+				break;
+			}
+			for (_, v) in res {
+				out.add(&v.into());
+			}
+		}
+		if let Some(st) = self.st.get(ns, db, tb) {
+			out.add(st);
+		}
+		Ok(out)
+	}
+
+	/// Reset the storage statistics of an index, after its entries have been removed
+	pub(crate) async fn clear_index_stats(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ix: &str,
+	) -> Result<(), Error> {
+		let st = self.get_stats(ns, db, tb).await?;
+		if let Some(size) = st.indexes.get(ix) {
+			self.record_stats(ns, db, tb, Stats::index(ix, -size));
+		}
+		Ok(())
+	}
+
+	/// Retrieve the stored changes to the storage statistics of a table, and the keys which they are stored under
+	pub async fn stored_stats(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+	) -> Result<(Stats, Vec<Key>), Error> {
+		let beg = crate::key::table::st::prefix(ns, db, tb);
+		let end = crate::key::table::st::suffix(ns, db, tb);
+		let mut out = Stats::default();
+		let mut keys = vec![];
+		let mut scanner = Scanner::new(beg..end);
+		loop {
+			let res = scanner.next_batch(self).await?;
+			if res.is_empty() {
+				break;
+			}
+			for (k, v) in res {
+				out.add(&v.into());
+				keys.push(k);
+			}
+		}
+		Ok((out, keys))
+	}
+
+	/// Replace the given stored statistics of a table with its base statistics
+	pub async fn replace_stats(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		keys: Vec<Key>,
+		st: Stats,
+	) -> Result<(), Error> {
+		for k in keys {
+			self.del(k).await?;
+		}
+		if !st.is_empty() {
+			let key = crate::key::table::st::new(ns, db, tb, Uuid::nil());
+			self.set(key, st).await?;
+		}
+		Ok(())
+	}
+
+	/// Fold the changes to the storage statistics of a table which were stored by each node into its base statistics
+	pub async fn merge_stats(&mut self, ns: &str, db: &str, tb: &str) -> Result<(), Error> {
+		let (st, keys) = self.stored_stats(ns, db, tb).await?;
+		// There is nothing to merge
+		let base: Key = crate::key::table::st::new(ns, db, tb, Uuid::nil()).into();
+		if keys.iter().all(|k| *k == base) {
+			return Ok(());
+		}
+		self.replace_stats(ns, db, tb, keys, st).await
+	}
+
+	/// Count the storage statistics of the tables within a database from the records, index entries, and change feed entries which are stored
+	pub async fn count_stats(
+		&mut self,
+		ns: &str,
+		db: &str,
+	) -> Result<BTreeMap<String, Stats>, Error> {
+		let mut out = BTreeMap::new();
+		for tb in self.all_tb(ns, db).await?.iter() {
+			let st = self.count_table_stats(ns, db, &tb.name).await?;
+			out.insert(tb.name.to_raw(), st);
+		}
+		// Count the change feed entries, which are shared by the tables of the database
+		let beg = crate::key::change::prefix(ns, db);
+		let end = crate::key::change::suffix(ns, db);
+		let mut scanner = Scanner::new(beg..end);
+		loop {
+			let res = scanner.next_batch(self).await?;
+			if res.is_empty() {
+				break;
+			}
+			for (k, v) in res {
+				if let Some(st) = out.get_mut(crate::key::change::Cf::decode(&k)?.tb) {
+					st.add(&Stats::changes((k.len() + v.len()) as i64));
+				}
+			}
+		}
+		Ok(out)
+	}
+
+	/// Count the storage statistics of a table from the records and index entries which are stored
+	async fn count_table_stats(&mut self, ns: &str, db: &str, tb: &str) -> Result<Stats, Error> {
+		let mut out = Stats::default();
+		// Count the records
+		let base = self.tb_base(ns, db, tb).await?;
+		let beg = crate::key::thing::prefix(ns, db, tb);
+		let end = crate::key::thing::suffix(ns, db, tb);
+		let mut scanner = Scanner::new(base.range(beg..end));
+		loop {
+			let res = scanner.next_batch(self).await?;
+			if res.is_empty() {
+				break;
+			}
+			for (k, v) in res {
+				out.add(&Stats::record(k.len(), None, Some(v.len() as u64)));
+			}
+		}
+		// Count the entries of the unique and non-unique indexes
+		for ix in self.all_ix(ns, db, tb).await?.iter() {
+			if !matches!(ix.index, Index::Idx | Index::Uniq) {
+				continue;
+			}
+			let base = self.ix_base(ns, db, ix).await?;
+			let rng = crate::key::index::Index::range(ns, db, tb, &ix.name);
+			let mut scanner = Scanner::new(base.range(rng));
+			loop {
+				let res = scanner.next_batch(self).await?;
+				if res.is_empty() {
+					break;
+				}
+				for (k, v) in res {
+					out.add(&Stats::index(&ix.name, (k.len() + v.len()) as i64));
+				}
+			}
+		}
+		Ok(out)
+	}
+
+	/// Retrieve the total storage statistics of the tables within a database
+	pub async fn db_stats(&mut self, ns: &str, db: &str) -> Result<Stats, Error> {
+		let mut out = Stats::default();
+		for tb in self.all_tb(ns, db).await?.iter() {
+			out.add(&self.get_stats(ns, db, &tb.name).await?);
+		}
+		Ok(out)
+	}
+
+	/// Retrieve the total storage statistics of the tables within a namespace
+	pub async fn ns_stats(&mut self, ns: &str) -> Result<Stats, Error> {
+		let mut out = Stats::default();
+		for db in self.all_db(ns).await?.iter() {
+			out.add(&self.db_stats(ns, &db.name).await?);
+		}
+		Ok(out)
+	}
+
+	/// Count the tables within a namespace, or within a single database
+	pub async fn count_tb(&mut self, ns: &str, db: Option<&str>) -> Result<u64, Error> {
+		match db {
+			Some(db) => Ok(self.all_tb(ns, db).await?.len() as u64),
+			None => {
+				let mut out = 0;
+				for db in self.all_db(ns).await?.iter() {
+					out += self.all_tb(ns, &db.name).await?.len() as u64;
+				}
+				Ok(out)
+			}
+		}
+	}
+
+	/// Check that a change to the storage statistics of a table fits within the quotas of its namespace and database
+	pub(crate) async fn check_quota(
+		&mut self,
+		ns: &str,
+		db: &str,
+		st: &Stats,
+	) -> Result<(), Error> {
+		// Only growth is limited
+		if st.records <= 0 && st.storage() <= 0 {
+			return Ok(());
+		}
+		let quota = self.get_and_cache_ns(ns).await?.quota.clone();
+		if quota.records.is_some() || quota.storage.is_some() {
+			let usage = self.usage(ns, None).await?;
+			Self::check_usage("namespace", ns, &quota, &usage, st)?;
+		}
+		let quota = self.get_and_cache_db(ns, db).await?.quota.clone();
+		if quota.records.is_some() || quota.storage.is_some() {
+			let usage = self.usage(ns, Some(db)).await?;
+			Self::check_usage("database", db, &quota, &usage, st)?;
+		}
+		Ok(())
+	}
+
+	/// Retrieve the total storage statistics of a namespace, or of a single database, including any changes made within this transaction
+	///
+	/// The stored statistics are only read once within each transaction, so
+	/// that checking the quotas does not read the statistics of every table
+	/// for each record which is written.
+	async fn usage(&mut self, ns: &str, db: Option<&str>) -> Result<Stats, Error> {
+		let key = (ns.to_owned(), db.map(str::to_owned));
+		let mut out = match self.us.get(&key) {
+			Some(v) => v.clone(),
+			None => {
+				let dbs = match db {
+					Some(db) => vec![db.to_owned()],
+					None => self.all_db(ns).await?.iter().map(|v| v.name.to_raw()).collect(),
+				};
+				let mut out = Stats::default();
+				for db in dbs.iter() {
+					for tb in self.all_tb(ns, db).await?.iter() {
+						out.add(&self.stored_stats(ns, db, &tb.name).await?.0);
+					}
+				}
+				self.us.insert(key, out.clone());
+				out
+			}
+		};
+		out.add(&self.st.total(ns, db));
+		Ok(out)
+	}
+
+	/// Check that a new table fits within the quotas of its namespace and database
+	pub(crate) async fn check_tb_quota(&mut self, ns: &str, db: &str) -> Result<(), Error> {
+		if let Some(max) = self.get_and_cache_ns(ns).await?.quota.tables {
+			if self.count_tb(ns, None).await? >= max {
+				return Err(Error::QuotaExceeded {
+					level: "namespace".to_owned(),
+					name: ns.to_owned(),
+					limit: format!("{max} tables"),
+				});
+			}
+		}
+		if let Some(max) = self.get_and_cache_db(ns, db).await?.quota.tables {
+			if self.count_tb(ns, Some(db)).await? >= max {
+				return Err(Error::QuotaExceeded {
+					level: "database".to_owned(),
+					name: db.to_owned(),
+					limit: format!("{max} tables"),
+				});
+			}
+		}
+		Ok(())
+	}
+
+	fn check_usage(
+		level: &str,
+		name: &str,
+		quota: &Quota,
+		usage: &Stats,
+		st: &Stats,
+	) -> Result<(), Error> {
+		if let Some(max) = quota.records {
+			if st.records > 0 && usage.records + st.records > max as i64 {
+				return Err(Error::QuotaExceeded {
+					level: level.to_owned(),
+					name: name.to_owned(),
+					limit: format!("{max} records"),
+				});
+			}
+		}
+		if let Some(max) = quota.storage {
+			if st.storage() > 0 && usage.storage() + st.storage() > max as i64 {
+				return Err(Error::QuotaExceeded {
+					level: level.to_owned(),
+					name: name.to_owned(),
+					limit: format!("{max} bytes of storage"),
+				});
+			}
+		}
+		Ok(())
+	}
+
+	/// Add the buffered changes to the storage statistics of each table to the changes stored by this node
+	async fn complete_stats(&mut self) -> Result<(), Error> {
+		for ((ns, db, tb), st) in self.st.take() {
+			if st.is_empty() {
+				continue;
+			}
+			// Statistics are not kept for tables which have been removed
+			if !self.exi(crate::key::database::tb::new(&ns, &db, &tb)).await? {
+				continue;
+			}
+			let key = crate::key::table::st::new(&ns, &db, &tb, self.nd);
+			let mut val: Stats = match self.get(key.clone()).await? {
+				Some(v) => v.into(),
+				None => Stats::default(),
+			};
+			val.add(&st);
+			match val.is_empty() {
+				true => self.del(key).await?,
+				false => self.set(key, val).await?,
+			}
+		}
+		Ok(())
+	}
+
+	// --------------------------------------------------
+	// Record expiry methods
+	// --------------------------------------------------
+
+	/// Retrieve the time in seconds at which a record expires
+	pub(crate) async fn get_expiry(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		id: &sql::Id,
+	) -> Result<Option<u64>, Error> {
+		let key = crate::key::table::er::new(ns, db, tb, id);
+		Ok(self.get(key).await?.and_then(|v| Some(u64::from_be_bytes(v.try_into().ok()?))))
+	}
+
+	/// Check if a record has expired
+	pub(crate) async fn is_expired(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		id: &sql::Id,
+	) -> Result<bool, Error> {
+		Ok(match self.get_expiry(ns, db, tb, id).await? {
+			Some(at) => at <= self.clock().value / 1000,
+			None => false,
+		})
+	}
+
+	/// Check if the records in a table expire
+	pub(crate) async fn tb_expires(&mut self, ns: &str, db: &str, tb: &str) -> Result<bool, Error> {
+		match self.get_and_cache_tb(ns, db, tb).await {
+			Ok(tb) => Ok(tb.ttl.is_some()),
+			Err(Error::TbNotFound {
+				..
+			}) => Ok(false),
+			Err(e) => Err(e),
+		}
+	}
+
+	/// Set or clear the time in seconds at which a record expires
+	pub(crate) async fn set_expiry(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		id: &sql::Id,
+		at: Option<u64>,
+	) -> Result<(), Error> {
+		// Remove the record from its previous place in the queue
+		if let Some(old) = self.get_expiry(ns, db, tb, id).await? {
+			if Some(old) == at {
+				return Ok(());
+			}
+			self.del(crate::key::table::ex::new(ns, db, tb, old, id)).await?;
+		}
+		let key = crate::key::table::er::new(ns, db, tb, id);
+		match at {
+			Some(at) => {
+				self.set(key, at.to_be_bytes().to_vec()).await?;
+				self.set(crate::key::table::ex::new(ns, db, tb, at, id), vec![]).await?;
+			}
+			None => self.del(key).await?,
+		}
+		Ok(())
+	}
+
+	/// Remove the expiry times of all of the records in a table
+	pub(crate) async fn clear_expiry(&mut self, ns: &str, db: &str, tb: &str) -> Result<(), Error> {
+		self.delp(crate::key::table::ex::prefix(ns, db, tb), u32::MAX).await?;
+		self.delp(crate::key::table::er::prefix(ns, db, tb), u32::MAX).await?;
+		Ok(())
+	}
+
+	/// Retrieve the ids of the records which have expired by the given time in seconds
+	pub(crate) async fn scan_expired(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ts: u64,
+		limit: u32,
+	) -> Result<Vec<sql::Id>, Error> {
+		let beg = crate::key::table::ex::prefix(ns, db, tb);
+		let end = crate::key::table::ex::suffix(ns, db, tb, ts.saturating_add(1));
+		let res = self.scan(beg..end, limit).await?;
+		res.iter().map(|(k, _)| Ok(crate::key::table::ex::Ex::decode(k)?.id)).collect()
+	}
+
 	// --------------------------------------------------
 	// Additional methods
 	// --------------------------------------------------
@@ -2138,6 +3611,20 @@ impl Transaction {
 				chn.send(bytes!("")).await?;
 			}
 		}
+		// Output JOBS
+		{
+			let jbs = self.all_jb(ns, db).await?;
+			if !jbs.is_empty() {
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("-- JOBS")).await?;
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("")).await?;
+				for jb in jbs.iter() {
+					chn.send(bytes!(format!("{jb};"))).await?;
+				}
+				chn.send(bytes!("")).await?;
+			}
+		}
 		// Output TABLES
 		{
 			let tbs = self.all_tb(ns, db).await?;
@@ -2192,57 +3679,30 @@ impl Transaction {
 					chn.send(bytes!("-- ------------------------------")).await?;
 					chn.send(bytes!("")).await?;
 					// Fetch records
+					let base = self.tb_base(ns, db, &tb.name).await?;
 					let beg = crate::key::thing::prefix(ns, db, &tb.name);
 					let end = crate::key::thing::suffix(ns, db, &tb.name);
-					let mut nxt: Option<Vec<u8>> = None;
-					loop {
-						let res = match nxt {
-							None => {
-								let min = beg.clone();
-								let max = end.clone();
-								self.scan(min..max, 1000).await?
-							}
-							Some(ref mut beg) => {
-								beg.push(0x00);
-								let min = beg.clone();
-								let max = end.clone();
-								self.scan(min..max, 1000).await?
-							}
-						};
-						if !res.is_empty() {
-							// Get total results
-							let n = res.len();
-							// Exit when settled
-							if n == 0 {
-								break;
+					let stream = Scanner::new(base.range(beg..end)).stream(self);
+					futures::pin_mut!(stream);
+					while let Some((k, v)) = stream.try_next().await? {
+						// Parse the key and the value
+						let k = base.name(k);
+						let k: crate::key::thing::Thing = (&k).into();
+						let v: Value = (&v).into();
+						let t = Thing::from((k.tb, k.id));
+						// Check if this is a graph edge
+						match (v.pick(&*EDGE), v.pick(&*IN), v.pick(&*OUT)) {
+							// This is a graph edge record
+							(Value::Bool(true), Value::Thing(l), Value::Thing(r)) => {
+								let sql = format!("RELATE {l} -> {t} -> {r} CONTENT {v};",);
+								chn.send(bytes!(sql)).await?;
 							}
-							// Loop over results
-							for (i, (k, v)) in res.into_iter().enumerate() {
-								// Ready the next
-								if n == i + 1 {
-									nxt = Some(k.clone());
-								}
-								// Parse the key and the value
-								let k: crate::key::thing::Thing = (&k).into();
-								let v: Value = (&v).into();
-								let t = Thing::from((k.tb, k.id));
-								// Check if this is a graph edge
-								match (v.pick(&*EDGE), v.pick(&*IN), v.pick(&*OUT)) {
-									// This is a graph edge record
-									(Value::Bool(true), Value::Thing(l), Value::Thing(r)) => {
-										let sql = format!("RELATE {l} -> {t} -> {r} CONTENT {v};",);
-										chn.send(bytes!(sql)).await?;
-									}
-									// This is a normal record
-									_ => {
-										let sql = format!("UPDATE {t} CONTENT {v};");
-										chn.send(bytes!(sql)).await?;
-									}
-								}
+							// This is a normal record
+							_ => {
+								let sql = format!("UPDATE {t} CONTENT {v};");
+								chn.send(bytes!(sql)).await?;
 							}
-							continue;
 						}
-						break;
 					}
 					chn.send(bytes!("")).await?;
 				}
@@ -2275,6 +3735,34 @@ impl Transaction {
 		}
 	}
 
+	// record_definition will record a schema change in the changefeed,
+	// if either the database or the affected table has a changefeed enabled.
+	//
+	// Removals must be recorded before the definition is deleted from the
+	// transaction, otherwise the table changefeed can no longer be found.
+	pub(crate) async fn record_definition(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		m: cf::TableMutation,
+	) -> Result<(), Error> {
+		let key = crate::key::namespace::db::new(ns, db);
+		let dbcf = match self.get(key).await? {
+			Some(v) => DefineDatabaseStatement::try_from(v)?.changefeed.is_some(),
+			None => false,
+		};
+		let key = crate::key::database::tb::new(ns, db, tb);
+		let tbcf = match self.get(key).await? {
+			Some(v) => DefineTableStatement::try_from(v)?.changefeed.is_some(),
+			None => false,
+		};
+		if dbcf || tbcf {
+			self.cf.define(ns, db, tb, m)
+		}
+		Ok(())
+	}
+
 	// complete_changes will complete the changefeed recording for the given namespace and database.
 	//
 	// Under the hood, this function calls the transaction's `set_versionstamped_key` for each change.
@@ -2293,9 +3781,12 @@ impl Transaction {
 	// non-FDB backends.
 	pub(crate) async fn complete_changes(&mut self, _lock: bool) -> Result<(), Error> {
 		let changes = self.cf.get();
-		for (tskey, prefix, suffix, v) in changes {
+		for (ck, (tskey, prefix, suffix, v)) in changes {
+			// The stored key contains a 10 byte versionstamp between the prefix and suffix
+			let size = prefix.len() + 10 + suffix.len() + v.len();
+			self.st.update(&ck.ns, &ck.db, &ck.tb, &Stats::changes(size as i64));
 			self.set_versionstamped_key(tskey, prefix, suffix, v).await?
 		}
-		Ok(())
+		self.complete_stats().await
 	}
 }
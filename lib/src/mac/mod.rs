@@ -20,3 +20,86 @@ macro_rules! get_cfg {
 		let $i = || { $( if cfg!($i=$s) { return $s; } );+ "unknown"};
 	)
 }
+
+/// Marks a stored statement which starts with the revision of its layout
+pub(crate) const REVISION_MARKER: u8 = 0xff;
+
+/// Implements the storage conversions for a stored statement which is stored
+/// together with the revision of its layout
+///
+/// The statement is stored as a revision marker and the revision, followed by
+/// the statement itself. Statements which were stored before revisions were
+/// introduced have no marker, and are decoded with the given legacy layout, as
+/// are legacy statements which happen to start with the marker. A statement
+/// which can not be decoded with either layout results in an error.
+macro_rules! impl_store_with_revision {
+	($($name:ident: $legacy:ty),*) => {
+		$(
+			impl $name {
+				/// The revision of the stored layout of this statement
+				pub const REVISION: u8 = 1;
+
+				pub fn to_vec(&self) -> Vec<u8> {
+					self.into()
+				}
+			}
+
+			impl TryFrom<Vec<u8>> for $name {
+				type Error = crate::err::Error;
+				fn try_from(v: Vec<u8>) -> Result<Self, Self::Error> {
+					Self::try_from(&v)
+				}
+			}
+
+			impl From<$name> for Vec<u8> {
+				fn from(v: $name) -> Vec<u8> {
+					Self::from(&v)
+				}
+			}
+
+			impl TryFrom<&Vec<u8>> for $name {
+				type Error = crate::err::Error;
+				fn try_from(v: &Vec<u8>) -> Result<Self, Self::Error> {
+					use bincode::Options;
+					use storekey::decode::Error as DecodeError;
+					let opt = bincode::options()
+						.with_no_limit()
+						.with_little_endian()
+						.with_varint_encoding()
+						.reject_trailing_bytes();
+					// The statement is stored with the current revision
+					if let [crate::mac::REVISION_MARKER, rev, v @ ..] = v.as_slice() {
+						if *rev == Self::REVISION {
+							if let Ok(v) = opt.deserialize::<Self>(v) {
+								return Ok(v);
+							}
+						}
+					}
+					// The statement was stored before revisions were introduced
+					opt.deserialize::<$legacy>(v).map(Self::from).map_err(|e| {
+						crate::err::Error::Decode(DecodeError::Message(format!(
+							"Unable to decode {}: {}",
+							stringify!($name),
+							e
+						)))
+					})
+				}
+			}
+
+			impl From<&$name> for Vec<u8> {
+				fn from(v: &$name) -> Vec<u8> {
+					use bincode::Options;
+					let mut out = vec![crate::mac::REVISION_MARKER, $name::REVISION];
+					bincode::options()
+						.with_no_limit()
+						.with_little_endian()
+						.with_varint_encoding()
+						.reject_trailing_bytes()
+						.serialize_into(&mut out, v)
+						.map(|_| out)
+						.unwrap_or_default()
+				}
+			}
+		)*
+	};
+}
@@ -50,8 +50,10 @@ pub(crate) mod part;
 pub(crate) mod paths;
 pub(crate) mod permission;
 pub(crate) mod query;
+pub(crate) mod quota;
 pub(crate) mod range;
 pub(crate) mod regex;
+pub(crate) mod schedule;
 pub(crate) mod scoring;
 pub(crate) mod script;
 pub(crate) mod special;
@@ -123,8 +125,10 @@ pub use self::part::Part;
 pub use self::permission::Permission;
 pub use self::permission::Permissions;
 pub use self::query::Query;
+pub use self::quota::Quota;
 pub use self::range::Range;
 pub use self::regex::Regex;
+pub use self::schedule::Schedule;
 pub use self::script::Script;
 pub use self::split::Split;
 pub use self::split::Splits;
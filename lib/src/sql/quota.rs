@@ -0,0 +1,169 @@
+use crate::sql::comment::shouldbespace;
+use crate::sql::common::take_u64;
+use crate::sql::error::IResult;
+use crate::sql::object::Object;
+use crate::sql::value::Value;
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{opt, value};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// The resources which a namespace or database is allowed to use
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Hash)]
+pub struct Quota {
+	/// The maximum number of records
+	pub records: Option<u64>,
+	/// The maximum storage size, in bytes
+	pub storage: Option<u64>,
+	/// The maximum number of tables
+	pub tables: Option<u64>,
+}
+
+impl Quota {
+	/// Check if no limits are defined
+	pub fn is_empty(&self) -> bool {
+		self.records.is_none() && self.storage.is_none() && self.tables.is_none()
+	}
+
+	/// Combine the limits of two quotas, preferring the limits of the other quota
+	pub(crate) fn merge(self, other: &Quota) -> Self {
+		Self {
+			records: other.records.or(self.records),
+			storage: other.storage.or(self.storage),
+			tables: other.tables.or(self.tables),
+		}
+	}
+}
+
+impl Display for Quota {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		let mut out = vec![];
+		if let Some(v) = self.records {
+			out.push(format!("MAX RECORDS {v}"));
+		}
+		if let Some(v) = self.storage {
+			out.push(format!("MAX STORAGE {v}"));
+		}
+		if let Some(v) = self.tables {
+			out.push(format!("MAX TABLES {v}"));
+		}
+		f.write_str(&out.join(" "))
+	}
+}
+
+impl From<&Quota> for Value {
+	fn from(v: &Quota) -> Self {
+		let mut res = Object::default();
+		if let Some(v) = v.records {
+			res.insert("records".to_owned(), v.into());
+		}
+		if let Some(v) = v.storage {
+			res.insert("storage".to_owned(), v.into());
+		}
+		if let Some(v) = v.tables {
+			res.insert("tables".to_owned(), v.into());
+		}
+		res.into()
+	}
+}
+
+/// Parses a single limit, such as `MAX RECORDS 1000` or `MAX STORAGE 10GB`
+pub fn quota(i: &str) -> IResult<&str, Quota> {
+	let (i, _) = tag_no_case("MAX")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	alt((records, storage, tables))(i)
+}
+
+fn records(i: &str) -> IResult<&str, Quota> {
+	let (i, _) = tag_no_case("RECORDS")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = take_u64(i)?;
+	Ok((
+		i,
+		Quota {
+			records: Some(v),
+			..Quota::default()
+		},
+	))
+}
+
+fn storage(i: &str) -> IResult<&str, Quota> {
+	let (i, _) = tag_no_case("STORAGE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = take_u64(i)?;
+	let (i, u) = opt(unit)(i)?;
+	Ok((
+		i,
+		Quota {
+			storage: Some(v.saturating_mul(u.unwrap_or(1))),
+			..Quota::default()
+		},
+	))
+}
+
+fn tables(i: &str) -> IResult<&str, Quota> {
+	let (i, _) = tag_no_case("TABLES")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = take_u64(i)?;
+	Ok((
+		i,
+		Quota {
+			tables: Some(v),
+			..Quota::default()
+		},
+	))
+}
+
+/// Parses a binary unit of storage size
+fn unit(i: &str) -> IResult<&str, u64> {
+	alt((
+		value(1 << 40, tag_no_case("TB")),
+		value(1 << 30, tag_no_case("GB")),
+		value(1 << 20, tag_no_case("MB")),
+		value(1 << 10, tag_no_case("KB")),
+		value(1, tag_no_case("B")),
+	))(i)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn quota_records() {
+		let sql = "MAX RECORDS 1000";
+		let res = quota(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("MAX RECORDS 1000", format!("{}", out));
+		assert_eq!(out.records, Some(1000));
+	}
+
+	#[test]
+	fn quota_storage() {
+		let sql = "MAX STORAGE 10GB";
+		let res = quota(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("MAX STORAGE 10737418240", format!("{}", out));
+		assert_eq!(out.storage, Some(10 * 1024 * 1024 * 1024));
+	}
+
+	#[test]
+	fn quota_tables() {
+		let sql = "MAX TABLES 10";
+		let res = quota(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("MAX TABLES 10", format!("{}", out));
+		assert_eq!(out.tables, Some(10));
+	}
+
+	#[test]
+	fn quota_invalid() {
+		let sql = "MAX FIELDS 10";
+		let res = quota(sql);
+		assert!(res.is_err());
+	}
+}
@@ -0,0 +1,199 @@
+use crate::sql::error::Error::Parser;
+use crate::sql::error::IResult;
+use crate::sql::escape::quote_str;
+use crate::sql::strand::strand_raw;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc};
+use nom::Err;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// A cron expression which specifies when a scheduled job runs
+///
+/// The expression consists of six fields, for the seconds, minutes, hours,
+/// days of the month, months, and days of the week, where Sunday is 0 or 7.
+/// Each field is a `*`, a number, a range such as `1-5`, any of these with a
+/// step such as `*/5`, or a comma-separated list of these. An expression with
+/// only five fields omits the seconds, and matches at the start of a minute.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Hash)]
+pub struct Schedule(pub String);
+
+impl Schedule {
+	/// Find the first time after the specified time which matches this schedule
+	pub fn next_after(&self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+		let fields = Fields::parse(&self.0)?;
+		let start = at.with_nanosecond(0)? + Duration::seconds(1);
+		let mut date = start.date_naive();
+		// Every combination of days repeats within 28 years
+		for _ in 0..(366 * 28) {
+			if fields.matches(date) {
+				for h in (0..24).filter(|v| fields.hours & 1 << v != 0) {
+					for m in (0..60).filter(|v| fields.minutes & 1 << v != 0) {
+						for s in (0..60).filter(|v| fields.seconds & 1 << v != 0) {
+							let time = Utc.from_utc_datetime(&date.and_hms_opt(h, m, s)?);
+							if time >= start {
+								return Some(time);
+							}
+						}
+					}
+				}
+			}
+			date = date.succ_opt()?;
+		}
+		None
+	}
+}
+
+impl Display for Schedule {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		f.write_str(&quote_str(&self.0))
+	}
+}
+
+/// The values which each field of a schedule matches, as bit sets
+struct Fields {
+	seconds: u64,
+	minutes: u64,
+	hours: u64,
+	days: u64,
+	months: u64,
+	weekdays: u64,
+	// Whether the days of the month, or the days of the week, are restricted
+	restrict_days: bool,
+	restrict_weekdays: bool,
+}
+
+impl Fields {
+	fn parse(v: &str) -> Option<Self> {
+		let mut v = v.split_whitespace().collect::<Vec<_>>();
+		if v.len() == 5 {
+			v.insert(0, "0");
+		}
+		if v.len() != 6 {
+			return None;
+		}
+		// Sunday can be specified as either 0 or 7
+		let weekdays = Self::field(v[5], 0, 7)?;
+		Some(Self {
+			seconds: Self::field(v[0], 0, 59)?,
+			minutes: Self::field(v[1], 0, 59)?,
+			hours: Self::field(v[2], 0, 23)?,
+			days: Self::field(v[3], 1, 31)?,
+			months: Self::field(v[4], 1, 12)?,
+			weekdays: (weekdays | weekdays >> 7) & 0x7f,
+			restrict_days: !v[3].starts_with('*'),
+			restrict_weekdays: !v[5].starts_with('*'),
+		})
+	}
+
+	fn field(v: &str, min: u32, max: u32) -> Option<u64> {
+		let mut bits = 0;
+		for part in v.split(',') {
+			let (range, step) = match part.split_once('/') {
+				Some((range, step)) => (range, step.parse::<usize>().ok().filter(|v| *v > 0)?),
+				None => (part, 1),
+			};
+			let (lo, hi) = match range.split_once('-') {
+				_ if range == "*" => (min, max),
+				Some((lo, hi)) => (lo.parse().ok()?, hi.parse().ok()?),
+				// A single value with a step repeats until the end of the range
+				None if step > 1 => (range.parse().ok()?, max),
+				None => (range.parse().ok()?, range.parse().ok()?),
+			};
+			if lo < min || hi > max || lo > hi {
+				return None;
+			}
+			for n in (lo..=hi).step_by(step) {
+				bits |= 1 << n;
+			}
+		}
+		Some(bits)
+	}
+
+	fn matches(&self, date: NaiveDate) -> bool {
+		if self.months & 1 << date.month() == 0 {
+			return false;
+		}
+		let day = self.days & 1 << date.day() != 0;
+		let weekday = self.weekdays & 1 << date.weekday().num_days_from_sunday() != 0;
+		// Like cron, a day matches either field when both are restricted
+		match self.restrict_days && self.restrict_weekdays {
+			true => day || weekday,
+			false => day && weekday,
+		}
+	}
+}
+
+pub fn schedule(i: &str) -> IResult<&str, Schedule> {
+	let (o, v) = strand_raw(i)?;
+	match Fields::parse(&v) {
+		Some(_) => Ok((o, Schedule(v))),
+		None => Err(Err::Error(Parser(i))),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	fn time(v: &str) -> DateTime<Utc> {
+		DateTime::parse_from_rfc3339(v).unwrap().with_timezone(&Utc)
+	}
+
+	#[test]
+	fn schedule_every_five_minutes() {
+		let sql = "'0 */5 * * * *'";
+		let res = schedule(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("'0 */5 * * * *'", format!("{}", out));
+		let next = out.next_after(time("2023-08-01T10:02:30Z"));
+		assert_eq!(next, Some(time("2023-08-01T10:05:00Z")));
+		let next = out.next_after(time("2023-08-01T10:05:00Z"));
+		assert_eq!(next, Some(time("2023-08-01T10:10:00Z")));
+		let next = out.next_after(time("2023-08-01T23:59:59Z"));
+		assert_eq!(next, Some(time("2023-08-02T00:00:00Z")));
+	}
+
+	#[test]
+	fn schedule_without_seconds() {
+		let out = schedule("'30 2 1 * *'").unwrap().1;
+		let next = out.next_after(time("2023-08-01T10:00:00Z"));
+		assert_eq!(next, Some(time("2023-09-01T02:30:00Z")));
+	}
+
+	#[test]
+	fn schedule_days_of_the_week() {
+		// Every weekday at noon
+		let out = schedule("'0 0 12 * * 1-5'").unwrap().1;
+		let next = out.next_after(time("2023-08-04T12:00:00Z"));
+		assert_eq!(next, Some(time("2023-08-07T12:00:00Z")));
+		// The 13th of the month, or any Friday
+		let out = schedule("'0 0 0 13 * 5'").unwrap().1;
+		let next = out.next_after(time("2023-08-05T00:00:00Z"));
+		assert_eq!(next, Some(time("2023-08-11T00:00:00Z")));
+		let next = out.next_after(time("2023-08-11T00:00:00Z"));
+		assert_eq!(next, Some(time("2023-08-13T00:00:00Z")));
+		// Sundays, specified as the 7th day of the week
+		let out = schedule("'0 0 0 * * 7'").unwrap().1;
+		let next = out.next_after(time("2023-08-01T00:00:00Z"));
+		assert_eq!(next, Some(time("2023-08-06T00:00:00Z")));
+	}
+
+	#[test]
+	fn schedule_leap_day() {
+		let out = schedule("'0 0 0 29 2 *'").unwrap().1;
+		let next = out.next_after(time("2023-08-01T00:00:00Z"));
+		assert_eq!(next, Some(time("2024-02-29T00:00:00Z")));
+	}
+
+	#[test]
+	fn schedule_invalid() {
+		assert!(schedule("'* * * *'").is_err());
+		assert!(schedule("'60 * * * * *'").is_err());
+		assert!(schedule("'0 0 0 0 * *'").is_err());
+		assert!(schedule("'0 */0 * * * *'").is_err());
+		assert!(schedule("'0 5-1 * * * *'").is_err());
+		assert!(schedule("'0 a * * * *'").is_err());
+	}
+}
@@ -14,6 +14,15 @@ where
 		.serialize(value)
 }
 
+pub fn serialized_size<T: ?Sized + Serialize>(value: &T) -> Result<u64> {
+	bincode::options()
+		.with_no_limit()
+		.with_little_endian()
+		.with_varint_encoding()
+		.reject_trailing_bytes()
+		.serialized_size(value)
+}
+
 pub fn deserialize<'a, T>(bytes: &'a [u8]) -> Result<T>
 where
 	T: Deserialize<'a>,
@@ -7,6 +7,7 @@ use crate::sql::common::colons;
 use crate::sql::error::IResult;
 use crate::sql::fmt::Fmt;
 use crate::sql::fmt::Pretty;
+use crate::sql::statements::acknowledge::{acknowledge, AcknowledgeStatement};
 use crate::sql::statements::analyze::{analyze, AnalyzeStatement};
 use crate::sql::statements::begin::{begin, BeginStatement};
 use crate::sql::statements::cancel::{cancel, CancelStatement};
@@ -22,7 +23,11 @@ use crate::sql::statements::live::{live, LiveStatement};
 use crate::sql::statements::option::{option, OptionStatement};
 use crate::sql::statements::output::{output, OutputStatement};
 use crate::sql::statements::relate::{relate, RelateStatement};
+use crate::sql::statements::release::{release, ReleaseStatement};
 use crate::sql::statements::remove::{remove, RemoveStatement};
+use crate::sql::statements::rename::{rename, RenameStatement};
+use crate::sql::statements::rollback::{rollback, RollbackStatement};
+use crate::sql::statements::savepoint::{savepoint, SavepointStatement};
 use crate::sql::statements::select::{select, SelectStatement};
 use crate::sql::statements::set::{set, SetStatement};
 use crate::sql::statements::show::{show, ShowStatement};
@@ -76,6 +81,7 @@ pub fn statements(i: &str) -> IResult<&str, Statements> {
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
 pub enum Statement {
+	Acknowledge(AcknowledgeStatement),
 	Analyze(AnalyzeStatement),
 	Begin(BeginStatement),
 	Cancel(CancelStatement),
@@ -98,6 +104,10 @@ pub enum Statement {
 	Sleep(SleepStatement),
 	Update(UpdateStatement),
 	Use(UseStatement),
+	Savepoint(SavepointStatement),
+	Rollback(RollbackStatement),
+	Release(ReleaseStatement),
+	Rename(RenameStatement),
 }
 
 impl Statement {
@@ -116,6 +126,7 @@ impl Statement {
 	/// Check if we require a writeable transaction
 	pub(crate) fn writeable(&self) -> bool {
 		match self {
+			Self::Acknowledge(_) => true,
 			Self::Analyze(_) => false,
 			Self::Create(v) => v.writeable(),
 			Self::Define(_) => true,
@@ -135,6 +146,7 @@ impl Statement {
 			Self::Sleep(_) => false,
 			Self::Update(v) => v.writeable(),
 			Self::Use(_) => false,
+			Self::Rename(_) => true,
 			_ => unreachable!(),
 		}
 	}
@@ -147,6 +159,7 @@ impl Statement {
 		doc: Option<&CursorDoc<'_>>,
 	) -> Result<Value, Error> {
 		match self {
+			Self::Acknowledge(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Analyze(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Create(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Delete(v) => v.compute(ctx, opt, txn, doc).await,
@@ -164,6 +177,7 @@ impl Statement {
 			Self::Show(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Sleep(v) => v.compute(ctx, opt, doc).await,
 			Self::Update(v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Rename(v) => v.compute(ctx, opt, txn, doc).await,
 			_ => unreachable!(),
 		}
 	}
@@ -172,6 +186,7 @@ impl Statement {
 impl Display for Statement {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
 		match self {
+			Self::Acknowledge(v) => write!(Pretty::from(f), "{v}"),
 			Self::Analyze(v) => write!(Pretty::from(f), "{v}"),
 			Self::Begin(v) => write!(Pretty::from(f), "{v}"),
 			Self::Cancel(v) => write!(Pretty::from(f), "{v}"),
@@ -194,6 +209,10 @@ impl Display for Statement {
 			Self::Sleep(v) => write!(Pretty::from(f), "{v}"),
 			Self::Update(v) => write!(Pretty::from(f), "{v}"),
 			Self::Use(v) => write!(Pretty::from(f), "{v}"),
+			Self::Savepoint(v) => write!(Pretty::from(f), "{v}"),
+			Self::Rollback(v) => write!(Pretty::from(f), "{v}"),
+			Self::Release(v) => write!(Pretty::from(f), "{v}"),
+			Self::Rename(v) => write!(Pretty::from(f), "{v}"),
 		}
 	}
 }
@@ -202,6 +221,7 @@ pub fn statement(i: &str) -> IResult<&str, Statement> {
 	delimited(
 		mightbespace,
 		alt((
+			map(acknowledge, Statement::Acknowledge),
 			map(analyze, Statement::Analyze),
 			map(begin, Statement::Begin),
 			map(cancel, Statement::Cancel),
@@ -221,8 +241,15 @@ pub fn statement(i: &str) -> IResult<&str, Statement> {
 			map(select, Statement::Select),
 			map(set, Statement::Set),
 			map(show, Statement::Show),
-			map(sleep, Statement::Sleep),
-			alt((map(update, Statement::Update), map(yuse, Statement::Use))),
+			alt((
+				map(sleep, Statement::Sleep),
+				map(update, Statement::Update),
+				map(yuse, Statement::Use),
+				map(savepoint, Statement::Savepoint),
+				map(rollback, Statement::Rollback),
+				map(release, Statement::Release),
+				map(rename, Statement::Rename),
+			)),
 		)),
 		mightbespace,
 	)(i)
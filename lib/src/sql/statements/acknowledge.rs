@@ -0,0 +1,114 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::{Level, Transaction};
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::value::Value;
+use derive::Store;
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::character::complete::u128;
+use nom::combinator::{opt, verify};
+use nom::sequence::tuple;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// AcknowledgeStatement is used to commit the change feed position of a
+// consumer, so that acknowledged changes can be garbage collected.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+pub struct AcknowledgeStatement {
+	pub name: Ident,
+	pub what: Ident,
+	pub at: u128,
+}
+
+impl AcknowledgeStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Selected DB?
+		opt.needs(Level::Db)?;
+		// Allowed to run?
+		opt.check(Level::Db)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Check that the consumer exists
+		run.get_co(opt.ns(), opt.db(), &self.what, &self.name).await?;
+		// Store the acknowledged position
+		let vs = crate::vs::try_u128_to_versionstamp(self.at)?;
+		run.set_cp(opt.ns(), opt.db(), &self.what, &self.name, vs).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl fmt::Display for AcknowledgeStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "ACKNOWLEDGE CONSUMER {} ON {} AT {}", self.name, self.what, self.at)
+	}
+}
+
+pub fn acknowledge(i: &str) -> IResult<&str, AcknowledgeStatement> {
+	let (i, _) = alt((tag_no_case("ACKNOWLEDGE"), tag_no_case("ACK")))(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("CONSUMER")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, name) = ident(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("ON")(i)?;
+	let (i, _) = opt(tuple((shouldbespace, tag_no_case("TABLE"))))(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, what) = ident(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("AT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	// Versionstamps are at most 80 bits long
+	let (i, at) = verify(u128, |v| v >> 80 == 0)(i)?;
+	Ok((
+		i,
+		AcknowledgeStatement {
+			name,
+			what,
+			at,
+		},
+	))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn acknowledge_statement() {
+		let sql = "ACKNOWLEDGE CONSUMER replica ON person AT 131072";
+		let res = acknowledge(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("ACKNOWLEDGE CONSUMER replica ON person AT 131072", format!("{}", out))
+	}
+
+	#[test]
+	fn acknowledge_statement_short() {
+		let sql = "ACK CONSUMER replica ON TABLE person AT 0";
+		let res = acknowledge(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("ACKNOWLEDGE CONSUMER replica ON person AT 0", format!("{}", out))
+	}
+
+	#[test]
+	fn acknowledge_statement_invalid_versionstamp() {
+		let sql = "ACKNOWLEDGE CONSUMER replica ON person AT 1208925819614629174706176";
+		let res = acknowledge(sql);
+		assert!(res.is_err());
+	}
+}
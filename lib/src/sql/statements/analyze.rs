@@ -41,7 +41,8 @@ impl AnalyzeStatement {
 				let mut run = txn.lock().await;
 				// Read the index
 				let ix = run.get_ix(opt.ns(), opt.db(), tb.as_str(), idx.as_str()).await?;
-				let ikb = IndexKeyBase::new(opt, &ix);
+				let base = run.ix_base(opt.ns(), opt.db(), &ix).await?;
+				let ikb = IndexKeyBase::new(opt, &ix, base);
 
 				// Index operation dispatching
 				let stats = match &ix.index {
@@ -1,3 +1,4 @@
+use crate::cf::TableMutation;
 use crate::ctx::Context;
 use crate::dbs::Options;
 use crate::dbs::{Level, Transaction};
@@ -21,6 +22,8 @@ use crate::sql::idiom::{Idiom, Idioms};
 use crate::sql::index::Index;
 use crate::sql::kind::{kind, Kind};
 use crate::sql::permission::{permissions, Permissions};
+use crate::sql::quota::{quota, Quota};
+use crate::sql::schedule::{schedule, Schedule};
 use crate::sql::statements::UpdateStatement;
 use crate::sql::strand::strand_raw;
 use crate::sql::tokenizer::{tokenizers, Tokenizer};
@@ -44,6 +47,13 @@ use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Write};
 
+impl_store_with_revision!(
+	DefineNamespaceStatement: DefineNamespaceStatementLegacy,
+	DefineDatabaseStatement: DefineDatabaseStatementLegacy,
+	DefineTableStatement: DefineTableStatementLegacy,
+	DefineIndexStatement: DefineIndexStatementLegacy
+);
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
 pub enum DefineStatement {
 	Namespace(DefineNamespaceStatement),
@@ -54,10 +64,12 @@ pub enum DefineStatement {
 	Token(DefineTokenStatement),
 	Scope(DefineScopeStatement),
 	Param(DefineParamStatement),
+	Job(DefineJobStatement),
 	Table(DefineTableStatement),
 	Event(DefineEventStatement),
 	Field(DefineFieldStatement),
 	Index(DefineIndexStatement),
+	Consumer(DefineConsumerStatement),
 }
 
 impl DefineStatement {
@@ -77,11 +89,13 @@ impl DefineStatement {
 			Self::Token(ref v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Scope(ref v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Param(ref v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Job(ref v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Table(ref v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Event(ref v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Field(ref v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Index(ref v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Analyzer(ref v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Consumer(ref v) => v.compute(ctx, opt, txn, doc).await,
 		}
 	}
 }
@@ -96,11 +110,13 @@ impl Display for DefineStatement {
 			Self::Token(v) => Display::fmt(v, f),
 			Self::Scope(v) => Display::fmt(v, f),
 			Self::Param(v) => Display::fmt(v, f),
+			Self::Job(v) => Display::fmt(v, f),
 			Self::Table(v) => Display::fmt(v, f),
 			Self::Event(v) => Display::fmt(v, f),
 			Self::Field(v) => Display::fmt(v, f),
 			Self::Index(v) => Display::fmt(v, f),
 			Self::Analyzer(v) => Display::fmt(v, f),
+			Self::Consumer(v) => Display::fmt(v, f),
 		}
 	}
 }
@@ -114,11 +130,13 @@ pub fn define(i: &str) -> IResult<&str, DefineStatement> {
 		map(token, DefineStatement::Token),
 		map(scope, DefineStatement::Scope),
 		map(param, DefineStatement::Param),
+		map(job, DefineStatement::Job),
 		map(table, DefineStatement::Table),
 		map(event, DefineStatement::Event),
 		map(field, DefineStatement::Field),
 		map(index, DefineStatement::Index),
 		map(analyzer, DefineStatement::Analyzer),
+		map(consumer, DefineStatement::Consumer),
 	))(i)
 }
 
@@ -126,9 +144,28 @@ pub fn define(i: &str) -> IResult<&str, DefineStatement> {
 // --------------------------------------------------
 // --------------------------------------------------
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Hash)]
 pub struct DefineNamespaceStatement {
 	pub name: Ident,
+	/// The numeric id under which the data is stored
+	pub id: Option<u32>,
+	/// The resources which the namespace is allowed to use
+	pub quota: Quota,
+}
+
+/// The layout of a namespace definition stored before revisions were introduced
+#[derive(Deserialize)]
+struct DefineNamespaceStatementLegacy {
+	name: Ident,
+}
+
+impl From<DefineNamespaceStatementLegacy> for DefineNamespaceStatement {
+	fn from(v: DefineNamespaceStatementLegacy) -> Self {
+		Self {
+			name: v.name,
+			..Default::default()
+		}
+	}
 }
 
 impl DefineNamespaceStatement {
@@ -148,7 +185,12 @@ impl DefineNamespaceStatement {
 		let key = crate::key::root::ns::new(&self.name);
 		// Claim transaction
 		let mut run = txn.lock().await;
-		run.set(key, self).await?;
+		// Keep the numeric id of an existing namespace
+		let val = DefineNamespaceStatement {
+			id: Some(run.ns_id(&self.name).await?),
+			..self.clone()
+		};
+		run.set(key, val).await?;
 		// Ok all good
 		Ok(Value::None)
 	}
@@ -156,7 +198,11 @@ impl DefineNamespaceStatement {
 
 impl Display for DefineNamespaceStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "DEFINE NAMESPACE {}", self.name)
+		write!(f, "DEFINE NAMESPACE {}", self.name)?;
+		if !self.quota.is_empty() {
+			write!(f, " {}", self.quota)?;
+		}
+		Ok(())
 	}
 }
 
@@ -166,22 +212,51 @@ fn namespace(i: &str) -> IResult<&str, DefineNamespaceStatement> {
 	let (i, _) = alt((tag_no_case("NS"), tag_no_case("NAMESPACE")))(i)?;
 	let (i, _) = shouldbespace(i)?;
 	let (i, name) = ident(i)?;
+	let (i, opts) = many0(namespace_quota)(i)?;
 	Ok((
 		i,
 		DefineNamespaceStatement {
 			name,
+			id: None,
+			quota: opts.iter().fold(Quota::default(), |acc, v| acc.merge(v)),
 		},
 	))
 }
 
+fn namespace_quota(i: &str) -> IResult<&str, Quota> {
+	let (i, _) = shouldbespace(i)?;
+	quota(i)
+}
+
 // --------------------------------------------------
 // --------------------------------------------------
 // --------------------------------------------------
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Hash)]
 pub struct DefineDatabaseStatement {
 	pub name: Ident,
 	pub changefeed: Option<ChangeFeed>,
+	/// The numeric id under which the data is stored
+	pub id: Option<u32>,
+	/// The resources which the database is allowed to use
+	pub quota: Quota,
+}
+
+/// The layout of a database definition stored before revisions were introduced
+#[derive(Deserialize)]
+struct DefineDatabaseStatementLegacy {
+	name: Ident,
+	changefeed: Option<ChangeFeed>,
+}
+
+impl From<DefineDatabaseStatementLegacy> for DefineDatabaseStatement {
+	fn from(v: DefineDatabaseStatementLegacy) -> Self {
+		Self {
+			name: v.name,
+			changefeed: v.changefeed,
+			..Default::default()
+		}
+	}
 }
 
 impl DefineDatabaseStatement {
@@ -202,7 +277,12 @@ impl DefineDatabaseStatement {
 		// Process the statement
 		let key = crate::key::namespace::db::new(opt.ns(), &self.name);
 		run.add_ns(opt.ns(), opt.strict).await?;
-		run.set(key, self).await?;
+		// Keep the numeric id of an existing database
+		let val = DefineDatabaseStatement {
+			id: Some(run.db_id(opt.ns(), &self.name).await?),
+			..self.clone()
+		};
+		run.set(key, val).await?;
 		// Ok all good
 		Ok(Value::None)
 	}
@@ -214,6 +294,9 @@ impl Display for DefineDatabaseStatement {
 		if let Some(ref cf) = self.changefeed {
 			write!(f, " CHANGEFEED {}", crate::sql::duration::Duration(cf.expiry))?;
 		}
+		if !self.quota.is_empty() {
+			write!(f, " {}", self.quota)?;
+		}
 		Ok(())
 	}
 }
@@ -229,12 +312,15 @@ fn database(i: &str) -> IResult<&str, DefineDatabaseStatement> {
 		i,
 		DefineDatabaseStatement {
 			name,
-			changefeed: opts
-				.iter()
-				.map(|x| match x {
-					DefineDatabaseOption::ChangeFeed(ref v) => v.to_owned(),
-				})
-				.next(),
+			changefeed: opts.iter().find_map(|x| match x {
+				DefineDatabaseOption::ChangeFeed(ref v) => Some(v.to_owned()),
+				_ => None,
+			}),
+			id: None,
+			quota: opts.iter().fold(Quota::default(), |acc, x| match x {
+				DefineDatabaseOption::Quota(ref v) => acc.merge(v),
+				_ => acc,
+			}),
 		},
 	))
 }
@@ -245,13 +331,20 @@ fn database_changefeed(i: &str) -> IResult<&str, DefineDatabaseOption> {
 	Ok((i, DefineDatabaseOption::ChangeFeed(v)))
 }
 
+fn database_quota(i: &str) -> IResult<&str, DefineDatabaseOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = quota(i)?;
+	Ok((i, DefineDatabaseOption::Quota(v)))
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
 pub enum DefineDatabaseOption {
 	ChangeFeed(ChangeFeed),
+	Quota(Quota),
 }
 
 fn database_opts(i: &str) -> IResult<&str, DefineDatabaseOption> {
-	database_changefeed(i)
+	alt((database_changefeed, database_quota))(i)
 }
 
 // --------------------------------------------------
@@ -369,7 +462,7 @@ impl DefineAnalyzerStatement {
 		run.set(key, self).await?;
 		// Release the transaction
 		drop(run); // Do we really need this?
-		   // Ok all good
+			 // Ok all good
 		Ok(Value::None)
 	}
 }
@@ -830,6 +923,94 @@ fn param(i: &str) -> IResult<&str, DefineParamStatement> {
 // --------------------------------------------------
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+pub struct DefineJobStatement {
+	pub name: Ident,
+	pub schedule: Schedule,
+	pub base: Base,
+	pub block: Block,
+}
+
+impl DefineJobStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Selected DB?
+		opt.needs(Level::Db)?;
+		// Allowed to run?
+		match self.base {
+			Base::Ns => opt.check(Level::Ns)?,
+			_ => opt.check(Level::Db)?,
+		}
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Process the statement
+		let key = crate::key::database::jb::new(opt.ns(), opt.db(), &self.name);
+		run.add_ns(opt.ns(), opt.strict).await?;
+		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
+		run.set(key, self).await?;
+		// Schedule the job again from the next tick
+		let key = crate::key::database::jr::new(opt.ns(), opt.db(), &self.name);
+		run.del(key).await?;
+		// Clear the cache
+		let key = crate::key::database::jb::prefix(opt.ns(), opt.db());
+		run.clr(key).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl Display for DefineJobStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"DEFINE JOB {} SCHEDULE {} AS {} THEN {}",
+			self.name, self.schedule, self.base, self.block
+		)
+	}
+}
+
+fn job(i: &str) -> IResult<&str, DefineJobStatement> {
+	let (i, _) = tag_no_case("DEFINE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("JOB")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, name) = ident(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("SCHEDULE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, schedule) = schedule(i)?;
+	let (i, base) = opt(|i| {
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = tag_no_case("AS")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, v) = base_or_scope(i)?;
+		Ok((i, v))
+	})(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("THEN")(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, block) = block(i)?;
+	Ok((
+		i,
+		DefineJobStatement {
+			name,
+			schedule,
+			base: base.unwrap_or(Base::Db),
+			block,
+		},
+	))
+}
+
+// --------------------------------------------------
+// --------------------------------------------------
+// --------------------------------------------------
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Hash)]
 pub struct DefineTableStatement {
 	pub name: Ident,
 	pub drop: bool,
@@ -837,6 +1018,35 @@ pub struct DefineTableStatement {
 	pub view: Option<View>,
 	pub permissions: Permissions,
 	pub changefeed: Option<ChangeFeed>,
+	/// The numeric id under which the data is stored
+	pub id: Option<u32>,
+	/// How long records are kept after they were last written
+	pub ttl: Option<Duration>,
+}
+
+/// The layout of a table definition stored before revisions were introduced
+#[derive(Deserialize)]
+struct DefineTableStatementLegacy {
+	name: Ident,
+	drop: bool,
+	full: bool,
+	view: Option<View>,
+	permissions: Permissions,
+	changefeed: Option<ChangeFeed>,
+}
+
+impl From<DefineTableStatementLegacy> for DefineTableStatement {
+	fn from(v: DefineTableStatementLegacy) -> Self {
+		Self {
+			name: v.name,
+			drop: v.drop,
+			full: v.full,
+			view: v.view,
+			permissions: v.permissions,
+			changefeed: v.changefeed,
+			..Default::default()
+		}
+	}
 }
 
 impl DefineTableStatement {
@@ -857,17 +1067,50 @@ impl DefineTableStatement {
 		let key = crate::key::database::tb::new(opt.ns(), opt.db(), &self.name);
 		run.add_ns(opt.ns(), opt.strict).await?;
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
-		run.set(key, self).await?;
+		// Check the quotas when creating a new table
+		let exists = match run.get_tb(opt.ns(), opt.db(), &self.name).await {
+			// Records no longer expire once the table has no TTL
+			Ok(tb) if tb.ttl.is_some() && self.ttl.is_none() => {
+				run.clear_expiry(opt.ns(), opt.db(), &self.name).await?;
+				true
+			}
+			Ok(_) => true,
+			Err(Error::TbNotFound {
+				..
+			}) => {
+				run.check_tb_quota(opt.ns(), opt.db()).await?;
+				false
+			}
+			Err(e) => return Err(e),
+		};
+		// Keep the numeric id of an existing table
+		let val = DefineTableStatement {
+			id: Some(run.tb_id(opt.ns(), opt.db(), &self.name).await?),
+			..self.clone()
+		};
+		run.set(key.clone(), &val).await?;
+		run.clr(key).await?;
+		// Move any graph edges which were stored before the table was defined
+		if !exists {
+			run.move_tb_data(opt.ns(), opt.db(), &self.name).await?;
+		}
+		// Record the change in the changefeed
+		let cf = TableMutation::DefineTable(val.clone());
+		run.record_definition(opt.ns(), opt.db(), &self.name, cf).await?;
 		// Check if table is a view
 		if let Some(view) = &self.view {
 			// Remove the table data
+			let base = run.tb_base(opt.ns(), opt.db(), &self.name).await?;
+			if base.is_compact() {
+				run.delp(base.prefix(), u32::MAX).await?;
+			}
 			let key = crate::key::table::all::new(opt.ns(), opt.db(), &self.name);
 			run.delp(key, u32::MAX).await?;
 			// Process each foreign table
 			for v in view.what.0.iter() {
 				// Save the view config
 				let key = crate::key::table::ft::new(opt.ns(), opt.db(), v, &self.name);
-				run.set(key, self).await?;
+				run.set(key, &val).await?;
 				// Clear the cache
 				let key = crate::key::table::ft::prefix(opt.ns(), opt.db(), v);
 				run.clr(key).await?;
@@ -923,6 +1166,9 @@ impl Display for DefineTableStatement {
 		if let Some(ref cf) = self.changefeed {
 			write!(f, " CHANGEFEED {}", crate::sql::duration::Duration(cf.expiry))?;
 		}
+		if let Some(ref v) = self.ttl {
+			write!(f, " TTL {v}")?;
+		}
 		Ok(())
 	}
 }
@@ -968,6 +1214,11 @@ fn table(i: &str) -> IResult<&str, DefineTableStatement> {
 				DefineTableOption::ChangeFeed(ref v) => Some(v.to_owned()),
 				_ => None,
 			}),
+			id: None,
+			ttl: opts.iter().find_map(|x| match x {
+				DefineTableOption::Ttl(ref v) => Some(v.to_owned()),
+				_ => None,
+			}),
 		},
 	))
 }
@@ -980,6 +1231,7 @@ pub enum DefineTableOption {
 	Schemafull,
 	Permissions(Permissions),
 	ChangeFeed(ChangeFeed),
+	Ttl(Duration),
 }
 
 fn table_opts(i: &str) -> IResult<&str, DefineTableOption> {
@@ -990,6 +1242,7 @@ fn table_opts(i: &str) -> IResult<&str, DefineTableOption> {
 		table_schemafull,
 		table_permissions,
 		table_changefeed,
+		table_ttl,
 	))(i)
 }
 
@@ -1005,6 +1258,14 @@ fn table_changefeed(i: &str) -> IResult<&str, DefineTableOption> {
 	Ok((i, DefineTableOption::ChangeFeed(v)))
 }
 
+fn table_ttl(i: &str) -> IResult<&str, DefineTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("TTL")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = duration(i)?;
+	Ok((i, DefineTableOption::Ttl(v)))
+}
+
 fn table_view(i: &str) -> IResult<&str, DefineTableOption> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, v) = view(i)?;
@@ -1062,6 +1323,9 @@ impl DefineEventStatement {
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
 		run.add_tb(opt.ns(), opt.db(), &self.what, opt.strict).await?;
 		run.set(key, self).await?;
+		// Record the change in the changefeed
+		let cf = TableMutation::DefineEvent(self.clone());
+		run.record_definition(opt.ns(), opt.db(), &self.what, cf).await?;
 		// Clear the cache
 		let key = crate::key::table::ev::prefix(opt.ns(), opt.db(), &self.what);
 		run.clr(key).await?;
@@ -1114,6 +1378,90 @@ fn event(i: &str) -> IResult<&str, DefineEventStatement> {
 // --------------------------------------------------
 // --------------------------------------------------
 
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+pub struct DefineConsumerStatement {
+	pub name: Ident,
+	pub what: Ident,
+	pub maxlag: Option<Duration>,
+}
+
+impl DefineConsumerStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Selected DB?
+		opt.needs(Level::Db)?;
+		// Allowed to run?
+		opt.check(Level::Db)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Process the statement
+		let key = crate::key::table::co::new(opt.ns(), opt.db(), &self.what, &self.name);
+		run.add_ns(opt.ns(), opt.strict).await?;
+		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
+		let tb = run.add_tb(opt.ns(), opt.db(), &self.what, opt.strict).await?;
+		// Check the table has a change feed
+		if tb.changefeed.is_none() {
+			return Err(Error::CfNotEnabled {
+				value: self.what.to_string(),
+			});
+		}
+		run.set(key, self).await?;
+		// Clear the cache
+		let key = crate::key::table::co::prefix(opt.ns(), opt.db(), &self.what);
+		run.clr(key).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl Display for DefineConsumerStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "DEFINE CONSUMER {} ON {}", self.name, self.what)?;
+		if let Some(ref v) = self.maxlag {
+			write!(f, " MAXLAG {v}")?
+		}
+		Ok(())
+	}
+}
+
+fn consumer(i: &str) -> IResult<&str, DefineConsumerStatement> {
+	let (i, _) = tag_no_case("DEFINE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("CONSUMER")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, name) = ident(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("ON")(i)?;
+	let (i, _) = opt(tuple((shouldbespace, tag_no_case("TABLE"))))(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, what) = ident(i)?;
+	let (i, maxlag) = opt(|i| {
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = tag_no_case("MAXLAG")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, v) = duration(i)?;
+		Ok((i, v))
+	})(i)?;
+	Ok((
+		i,
+		DefineConsumerStatement {
+			name,
+			what,
+			maxlag,
+		},
+	))
+}
+
+// --------------------------------------------------
+// --------------------------------------------------
+// --------------------------------------------------
+
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
 pub struct DefineFieldStatement {
 	pub name: Idiom,
@@ -1147,6 +1495,9 @@ impl DefineFieldStatement {
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
 		run.add_tb(opt.ns(), opt.db(), &self.what, opt.strict).await?;
 		run.set(key, self).await?;
+		// Record the change in the changefeed
+		let cf = TableMutation::DefineField(self.clone());
+		run.record_definition(opt.ns(), opt.db(), &self.what, cf).await?;
 		// Clear the cache
 		let key = crate::key::table::fd::prefix(opt.ns(), opt.db(), &self.what);
 		run.clr(key).await?;
@@ -1277,12 +1628,37 @@ fn field_permissions(i: &str) -> IResult<&str, DefineFieldOption> {
 // --------------------------------------------------
 // --------------------------------------------------
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Hash)]
 pub struct DefineIndexStatement {
 	pub name: Ident,
 	pub what: Ident,
 	pub cols: Idioms,
 	pub index: Index,
+	/// The numeric id under which the data is stored
+	pub id: Option<u32>,
+	/// Whether the index is built in the background
+	pub concurrently: bool,
+}
+
+/// The layout of an index definition stored before revisions were introduced
+#[derive(Deserialize)]
+struct DefineIndexStatementLegacy {
+	name: Ident,
+	what: Ident,
+	cols: Idioms,
+	index: Index,
+}
+
+impl From<DefineIndexStatementLegacy> for DefineIndexStatement {
+	fn from(v: DefineIndexStatementLegacy) -> Self {
+		Self {
+			name: v.name,
+			what: v.what,
+			cols: v.cols,
+			index: v.index,
+			..Default::default()
+		}
+	}
 }
 
 impl DefineIndexStatement {
@@ -1305,13 +1681,32 @@ impl DefineIndexStatement {
 		run.add_ns(opt.ns(), opt.strict).await?;
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
 		run.add_tb(opt.ns(), opt.db(), &self.what, opt.strict).await?;
-		run.set(key, self).await?;
+		// Keep the numeric id of an existing index
+		let val = DefineIndexStatement {
+			id: Some(run.ix_id(opt.ns(), opt.db(), &self.what, &self.name).await?),
+			..self.clone()
+		};
+		run.set(key, &val).await?;
+		// Record the change in the changefeed
+		let cf = TableMutation::DefineIndex(val.clone());
+		run.record_definition(opt.ns(), opt.db(), &self.what, cf).await?;
 		// Remove the index data
+		let base = run.ix_base(opt.ns(), opt.db(), &val).await?;
+		if base.is_compact() {
+			run.delp(base.prefix(), u32::MAX).await?;
+		}
 		let key = crate::key::index::all::new(opt.ns(), opt.db(), &self.what, &self.name);
 		run.delp(key, u32::MAX).await?;
+		run.clear_index_stats(opt.ns(), opt.db(), &self.what, &self.name).await?;
 		// Clear the cache
 		let key = crate::key::table::ix::prefix(opt.ns(), opt.db(), &self.what);
 		run.clr(key).await?;
+		// Leave the index data to be built by the node agent
+		if self.concurrently {
+			let key = crate::key::index::ib::new(opt.ns(), opt.db(), &self.what, &self.name);
+			run.set(key, vec![]).await?;
+			return Ok(Value::None);
+		}
 		// Release the transaction
 		drop(run);
 		// Force queries to run
@@ -1339,6 +1734,9 @@ impl Display for DefineIndexStatement {
 		if Index::Idx != self.index {
 			write!(f, " {}", self.index)?;
 		}
+		if self.concurrently {
+			write!(f, " CONCURRENTLY")?;
+		}
 		Ok(())
 	}
 }
@@ -1360,6 +1758,7 @@ fn index(i: &str) -> IResult<&str, DefineIndexStatement> {
 	let (i, cols) = idiom::locals(i)?;
 	let (i, _) = mightbespace(i)?;
 	let (i, index) = index::index(i)?;
+	let (i, concurrently) = opt(tuple((mightbespace, tag_no_case("CONCURRENTLY"))))(i)?;
 	Ok((
 		i,
 		DefineIndexStatement {
@@ -1367,6 +1766,8 @@ fn index(i: &str) -> IResult<&str, DefineIndexStatement> {
 			what,
 			cols,
 			index,
+			id: None,
+			concurrently: concurrently.is_some(),
 		},
 	))
 }
@@ -1376,13 +1777,103 @@ mod tests {
 	use super::*;
 	use crate::sql::scoring::Scoring;
 	use crate::sql::Part;
+	use bincode::Options;
+
+	/// Encodes a definition as it was stored before revisions were introduced
+	fn legacy<T: Serialize>(v: &T) -> Vec<u8> {
+		bincode::options()
+			.with_no_limit()
+			.with_little_endian()
+			.with_varint_encoding()
+			.reject_trailing_bytes()
+			.serialize(v)
+			.unwrap()
+	}
 
 	#[test]
 	fn check_define_serialize() {
 		let stm = DefineStatement::Namespace(DefineNamespaceStatement {
 			name: Ident::from("test"),
+			id: None,
+			quota: Quota::default(),
 		});
-		assert_eq!(6, stm.to_vec().len());
+		assert_eq!(10, stm.to_vec().len());
+	}
+
+	#[test]
+	fn check_define_deserialize_without_id() {
+		let stm = DefineTableStatement {
+			name: Ident::from("test"),
+			id: Some(7),
+			..Default::default()
+		};
+		let val = stm.to_vec();
+		assert_eq!(val[..2], [crate::mac::REVISION_MARKER, DefineTableStatement::REVISION]);
+		assert_eq!(stm, DefineTableStatement::try_from(&val).unwrap());
+		// Definitions stored before revisions were introduced have no id or ttl
+		let val =
+			legacy(&(&stm.name, stm.drop, stm.full, &stm.view, &stm.permissions, &stm.changefeed));
+		let stm = DefineTableStatement::try_from(&val).unwrap();
+		assert_eq!(stm.name, Ident::from("test"));
+		assert_eq!(stm.id, None);
+		assert_eq!(stm.ttl, None);
+	}
+
+	#[test]
+	fn check_define_deserialize_without_quota() {
+		let stm = DefineNamespaceStatement {
+			name: Ident::from("test"),
+			id: Some(7),
+			quota: Quota {
+				records: Some(10),
+				..Default::default()
+			},
+		};
+		let val = stm.to_vec();
+		assert_eq!(stm, DefineNamespaceStatement::try_from(&val).unwrap());
+		// Definitions stored before revisions were introduced have no id or quota
+		let val = legacy(&(&stm.name,));
+		let stm = DefineNamespaceStatement::try_from(&val).unwrap();
+		assert_eq!(stm.name, Ident::from("test"));
+		assert_eq!(stm.id, None);
+		assert_eq!(stm.quota, Quota::default());
+	}
+
+	#[test]
+	fn check_define_deserialize_invalid() {
+		let stm = DefineDatabaseStatement {
+			name: Ident::from("test"),
+			..Default::default()
+		};
+		let mut val = stm.to_vec();
+		// Definitions with an unknown revision are not decoded
+		val[1] = DefineDatabaseStatement::REVISION + 1;
+		let res = DefineDatabaseStatement::try_from(&val);
+		assert!(matches!(res, Err(Error::Decode(_))));
+		// Truncated definitions are not decoded
+		let mut val = stm.to_vec();
+		val.truncate(val.len() - 1);
+		let res = DefineDatabaseStatement::try_from(&val);
+		assert!(matches!(res, Err(Error::Decode(_))));
+	}
+
+	#[test]
+	fn check_define_database_with_quota() {
+		let sql = "DEFINE DATABASE test CHANGEFEED 1h MAX RECORDS 100 MAX STORAGE 1mb";
+		let (_, stm) = database(sql).unwrap();
+		assert_eq!(
+			stm.quota,
+			Quota {
+				records: Some(100),
+				storage: Some(1024 * 1024),
+				tables: None,
+			}
+		);
+		assert!(stm.changefeed.is_some());
+		assert_eq!(
+			stm.to_string(),
+			"DEFINE DATABASE test CHANGEFEED 1h MAX RECORDS 100 MAX STORAGE 1048576"
+		);
 	}
 
 	#[test]
@@ -1396,6 +1887,8 @@ mod tests {
 				what: Ident("my_table".to_string()),
 				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("my_col".to_string()))])]),
 				index: Index::Idx,
+				id: None,
+				concurrently: false,
 			}
 		);
 		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS my_col");
@@ -1412,11 +1905,30 @@ mod tests {
 				what: Ident("my_table".to_string()),
 				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("my_col".to_string()))])]),
 				index: Index::Uniq,
+				id: None,
+				concurrently: false,
 			}
 		);
 		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS my_col UNIQUE");
 	}
 
+	#[test]
+	fn check_create_index_concurrently() {
+		let sql = "DEFINE INDEX my_index ON TABLE my_table COLUMNS my_col UNIQUE CONCURRENTLY";
+		let (_, idx) = index(sql).unwrap();
+		assert!(idx.concurrently);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS my_col UNIQUE CONCURRENTLY"
+		);
+		assert_eq!(idx, DefineIndexStatement::try_from(&idx.to_vec()).unwrap());
+		// Definitions stored before revisions were introduced are not built in the background
+		let val = legacy(&(&idx.name, &idx.what, &idx.cols, &idx.index));
+		let stm = DefineIndexStatement::try_from(&val).unwrap();
+		assert_eq!(stm.index, Index::Uniq);
+		assert!(!stm.concurrently);
+	}
+
 	#[test]
 	fn check_create_search_index_with_highlights() {
 		let sql = "DEFINE INDEX my_index ON TABLE my_table COLUMNS my_col SEARCH ANALYZER my_analyzer BM25(1.2,0.75) ORDER 1000 HIGHLIGHTS";
@@ -1436,6 +1948,8 @@ mod tests {
 					},
 					order: 1000
 				},
+				id: None,
+				concurrently: false,
 			}
 		);
 		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS my_col SEARCH ANALYZER my_analyzer BM25(1.2,0.75) ORDER 1000 HIGHLIGHTS");
@@ -1458,6 +1972,8 @@ mod tests {
 					sc: Scoring::Vs,
 					order: 100
 				},
+				id: None,
+				concurrently: false,
 			}
 		);
 		assert_eq!(
@@ -1491,4 +2007,59 @@ mod tests {
 		let deserializled = DefineTableStatement::try_from(&serialized).unwrap();
 		assert_eq!(out, deserializled);
 	}
+
+	#[test]
+	fn define_table_with_ttl() {
+		let sql = "DEFINE TABLE session SCHEMALESS CHANGEFEED 1h TTL 30m";
+		let res = table(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out.ttl, Some(Duration::from_mins(30)));
+
+		let serialized = out.to_vec();
+		let deserializled = DefineTableStatement::try_from(&serialized).unwrap();
+		assert_eq!(out, deserializled);
+	}
+
+	#[test]
+	fn define_consumer_with_maxlag() {
+		let sql = "DEFINE CONSUMER replica ON person MAXLAG 1d";
+		let res = consumer(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+
+		let serialized = out.to_vec();
+		let deserializled = DefineConsumerStatement::from(&serialized);
+		assert_eq!(out, deserializled);
+	}
+
+	#[test]
+	fn define_job() {
+		let sql =
+			"DEFINE JOB rollup SCHEDULE '0 */5 * * * *' AS SCOPE account THEN { CREATE rollup; }";
+		let res = job(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out.base, Base::Sc(Ident::from("account")));
+
+		let serialized = out.to_vec();
+		let deserializled = DefineJobStatement::from(&serialized);
+		assert_eq!(out, deserializled);
+	}
+
+	#[test]
+	fn define_job_as_database() {
+		let sql = "DEFINE JOB rollup SCHEDULE '*/5 * * * *' THEN { CREATE rollup; }";
+		let res = job(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(
+			"DEFINE JOB rollup SCHEDULE '*/5 * * * *' AS DATABASE THEN { CREATE rollup; }",
+			format!("{}", out)
+		);
+		assert!(job("DEFINE JOB rollup SCHEDULE '*/5 * *' THEN { CREATE rollup; }").is_err());
+	}
 }
@@ -1,17 +1,29 @@
 use crate::ctx::Context;
+use crate::dbs::node::Timestamp;
 use crate::dbs::Options;
 use crate::dbs::{Level, Transaction};
 use crate::doc::CursorDoc;
 use crate::err::Error;
+use crate::idx::btree::store::BTreeStoreType;
+use crate::idx::ft::FtIndex;
+use crate::idx::IndexKeyBase;
+use crate::kvs;
+use crate::kvs::Stats;
 use crate::sql::comment::shouldbespace;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::index::Index;
 use crate::sql::object::Object;
+use crate::sql::quota::Quota;
 use crate::sql::value::Value;
+use chrono::{TimeZone, Utc};
 use derive::Store;
 use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
+use nom::combinator::opt;
+use nom::sequence::preceded;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
@@ -21,6 +33,10 @@ pub enum InfoStatement {
 	Db,
 	Sc(Ident),
 	Tb(Ident),
+	DbStats,
+	TbStats(Ident),
+	NsStats,
+	Cl,
 }
 
 impl InfoStatement {
@@ -115,6 +131,28 @@ impl InfoStatement {
 					tmp.insert(v.name.to_string(), v.to_string().into());
 				}
 				res.insert("params".to_owned(), tmp.into());
+				// Process the jobs
+				let mut tmp = Object::default();
+				let mut runs = Object::default();
+				for v in run.all_jb(opt.ns(), opt.db()).await?.iter() {
+					tmp.insert(v.name.to_string(), v.to_string().into());
+					if let Some(jr) = run.get_jr(opt.ns(), opt.db(), &v.name).await? {
+						let mut val = Object::default();
+						val.insert("at".to_owned(), jr.at.map(Value::from).unwrap_or_default());
+						val.insert(
+							"duration".to_owned(),
+							jr.duration.map(Value::from).unwrap_or_default(),
+						);
+						val.insert(
+							"error".to_owned(),
+							jr.error.map(Value::from).unwrap_or_default(),
+						);
+						val.insert("next".to_owned(), jr.next.map(Value::from).unwrap_or_default());
+						runs.insert(v.name.to_string(), val.into());
+					}
+				}
+				res.insert("jobs".to_owned(), tmp.into());
+				res.insert("runs".to_owned(), runs.into());
 				// Process the scopes
 				let mut tmp = Object::default();
 				for v in run.all_sc(opt.ns(), opt.db()).await?.iter() {
@@ -163,6 +201,12 @@ impl InfoStatement {
 				let mut run = txn.lock().await;
 				// Create the result set
 				let mut res = Object::default();
+				// Process the consumers
+				let mut tmp = Object::default();
+				for v in run.all_co(opt.ns(), opt.db(), tb).await?.iter() {
+					tmp.insert(v.name.to_string(), v.to_string().into());
+				}
+				res.insert("consumers".to_owned(), tmp.into());
 				// Process the events
 				let mut tmp = Object::default();
 				for v in run.all_ev(opt.ns(), opt.db(), tb).await?.iter() {
@@ -190,7 +234,178 @@ impl InfoStatement {
 				// Ok all good
 				Value::from(res).ok()
 			}
+			InfoStatement::DbStats => {
+				// Selected DB?
+				opt.needs(Level::Db)?;
+				// Allowed to run?
+				opt.check(Level::Db)?;
+				// Claim transaction
+				let mut run = txn.lock().await;
+				// Process the tables
+				let mut all = Stats::default();
+				let mut tmp = Object::default();
+				for v in run.all_tb(opt.ns(), opt.db()).await?.iter() {
+					let st = Self::stats(&mut run, opt, &v.name).await?;
+					all.add(&st);
+					tmp.insert(v.name.to_string(), st.into());
+				}
+				// Create the result set
+				let indexes = all.indexes.values().sum::<i64>().max(0);
+				let storage = all.storage().max(0);
+				let mut res = Object::from(all);
+				res.insert("indexes".to_owned(), indexes.into());
+				res.insert("storage".to_owned(), storage.into());
+				res.insert("tables".to_owned(), tmp.into());
+				// Process the quota
+				let quota = match run.get_db(opt.ns(), opt.db()).await {
+					Ok(v) => v.quota,
+					Err(Error::DbNotFound {
+						..
+					}) => Quota::default(),
+					Err(e) => return Err(e),
+				};
+				res.insert("quota".to_owned(), Value::from(&quota));
+				// Ok all good
+				Value::from(res).ok()
+			}
+			InfoStatement::NsStats => {
+				// Selected NS?
+				opt.needs(Level::Ns)?;
+				// Allowed to run?
+				opt.check(Level::Ns)?;
+				// Claim transaction
+				let mut run = txn.lock().await;
+				// Process the databases
+				let mut tmp = Object::default();
+				for v in run.all_db(opt.ns()).await?.iter() {
+					let st = run.db_stats(opt.ns(), &v.name).await?;
+					let tables = run.count_tb(opt.ns(), Some(&v.name)).await?;
+					let mut val = Object::default();
+					val.insert("records".to_owned(), st.records.max(0).into());
+					val.insert("storage".to_owned(), st.storage().max(0).into());
+					val.insert("tables".to_owned(), tables.into());
+					val.insert("quota".to_owned(), Value::from(&v.quota));
+					tmp.insert(v.name.to_string(), val.into());
+				}
+				// Create the result set
+				let st = run.ns_stats(opt.ns()).await?;
+				let tables = run.count_tb(opt.ns(), None).await?;
+				let quota = match run.get_ns(opt.ns()).await {
+					Ok(v) => v.quota,
+					Err(Error::NsNotFound {
+						..
+					}) => Quota::default(),
+					Err(e) => return Err(e),
+				};
+				let mut res = Object::default();
+				res.insert("records".to_owned(), st.records.max(0).into());
+				res.insert("storage".to_owned(), st.storage().max(0).into());
+				res.insert("tables".to_owned(), tables.into());
+				res.insert("quota".to_owned(), Value::from(&quota));
+				res.insert("databases".to_owned(), tmp.into());
+				// Ok all good
+				Value::from(res).ok()
+			}
+			InfoStatement::TbStats(tb) => {
+				// Selected DB?
+				opt.needs(Level::Db)?;
+				// Allowed to run?
+				opt.check(Level::Db)?;
+				// Claim transaction
+				let mut run = txn.lock().await;
+				// Process the statistics
+				let st = Self::stats(&mut run, opt, tb).await?;
+				// Ok all good
+				Value::from(st).ok()
+			}
+			InfoStatement::Cl => {
+				// No need for NS/DB
+				opt.needs(Level::Kv)?;
+				// Allowed to run?
+				opt.check(Level::Kv)?;
+				// Claim transaction
+				let mut run = txn.lock().await;
+				// Find the last heartbeat of each node
+				let ts = Timestamp {
+					value: u64::MAX - 1,
+				};
+				let mut hbs = BTreeMap::new();
+				for hb in run.scan_hb(&ts, u32::MAX).await? {
+					hbs.insert(hb.nd, hb.hb.value);
+				}
+				// Nodes are removed once they have not sent a heartbeat for the lease duration
+				let expired =
+					run.clock().value.saturating_sub(run.lease_duration().as_millis() as u64);
+				let nds = run.scan_nd(u32::MAX).await?;
+				let mut ids = nds.iter().copied().collect::<BTreeSet<_>>();
+				ids.extend(hbs.keys());
+				// Process the nodes
+				let mut tmp = Object::default();
+				for nd in ids {
+					let hb = hbs.get(&nd).copied();
+					let mut live = 0;
+					let mut archived = vec![];
+					for lq in run.all_lq(&nd).await? {
+						match run.get_lv(&lq.ns, &lq.db, &lq.tb, &lq.lq).await {
+							Ok(lv) if lv.archived.is_some() => {
+								let mut val = Object::default();
+								val.insert("id".to_owned(), lq.lq.into());
+								val.insert("ns".to_owned(), lq.ns.into());
+								val.insert("db".to_owned(), lq.db.into());
+								val.insert("tb".to_owned(), lq.tb.into());
+								archived.push(Value::from(val));
+							}
+							Ok(_) => live += 1,
+							Err(Error::LvNotFound {
+								..
+							}) => {}
+							Err(e) => return Err(e),
+						}
+					}
+					let alive = nds.contains(&nd) && hb.map_or(false, |v| v > expired);
+					let hb = hb.and_then(|v| Utc.timestamp_millis_opt(v as i64).single());
+					let mut val = Object::default();
+					val.insert("heartbeat".to_owned(), hb.map(Value::from).unwrap_or_default());
+					val.insert("alive".to_owned(), alive.into());
+					val.insert("live_queries".to_owned(), live.into());
+					val.insert("archived".to_owned(), archived.into());
+					tmp.insert(nd.to_string(), val.into());
+				}
+				// Create the result set
+				let mut res = Object::default();
+				res.insert("nodes".to_owned(), tmp.into());
+				// Ok all good
+				Value::from(res).ok()
+			}
+		}
+	}
+
+	/// Fetch the storage statistics of a table, and the size of each of its indexes
+	async fn stats(run: &mut kvs::Transaction, opt: &Options, tb: &str) -> Result<Stats, Error> {
+		let mut st = run.get_stats(opt.ns(), opt.db(), tb).await?;
+		let mut indexes = BTreeMap::new();
+		for ix in run.all_ix(opt.ns(), opt.db(), tb).await?.iter() {
+			let size = match &ix.index {
+				// The size of full-text indexes is read from their trees
+				Index::Search {
+					az,
+					order,
+					sc,
+					hl,
+				} => {
+					let az = run.get_az(opt.ns(), opt.db(), az.as_str()).await?;
+					let base = run.ix_base(opt.ns(), opt.db(), ix).await?;
+					let ikb = IndexKeyBase::new(opt, ix, base);
+					let ft = FtIndex::new(run, az, ikb, *order, sc, *hl, BTreeStoreType::Traversal)
+						.await?;
+					ft.statistics(run).await?.total_size() as i64
+				}
+				_ => st.indexes.get(ix.name.as_str()).copied().unwrap_or(0),
+			};
+			indexes.insert(ix.name.to_raw(), size);
 		}
+		st.indexes = indexes;
+		Ok(st)
 	}
 }
 
@@ -202,6 +417,10 @@ impl fmt::Display for InfoStatement {
 			Self::Db => f.write_str("INFO FOR DATABASE"),
 			Self::Sc(ref s) => write!(f, "INFO FOR SCOPE {s}"),
 			Self::Tb(ref t) => write!(f, "INFO FOR TABLE {t}"),
+			Self::NsStats => f.write_str("INFO FOR NAMESPACE STATS"),
+			Self::DbStats => f.write_str("INFO FOR DATABASE STATS"),
+			Self::TbStats(ref t) => write!(f, "INFO FOR TABLE {t} STATS"),
+			Self::Cl => f.write_str("INFO FOR CLUSTER"),
 		}
 	}
 }
@@ -211,7 +430,7 @@ pub fn info(i: &str) -> IResult<&str, InfoStatement> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = tag_no_case("FOR")(i)?;
 	let (i, _) = shouldbespace(i)?;
-	alt((kv, ns, db, sc, tb))(i)
+	alt((kv, ns, db, sc, tb, cl))(i)
 }
 
 fn kv(i: &str) -> IResult<&str, InfoStatement> {
@@ -219,14 +438,27 @@ fn kv(i: &str) -> IResult<&str, InfoStatement> {
 	Ok((i, InfoStatement::Kv))
 }
 
+fn cl(i: &str) -> IResult<&str, InfoStatement> {
+	let (i, _) = tag_no_case("CLUSTER")(i)?;
+	Ok((i, InfoStatement::Cl))
+}
+
 fn ns(i: &str) -> IResult<&str, InfoStatement> {
 	let (i, _) = alt((tag_no_case("NAMESPACE"), tag_no_case("NS")))(i)?;
-	Ok((i, InfoStatement::Ns))
+	let (i, stats) = opt(preceded(shouldbespace, tag_no_case("STATS")))(i)?;
+	match stats {
+		Some(_) => Ok((i, InfoStatement::NsStats)),
+		None => Ok((i, InfoStatement::Ns)),
+	}
 }
 
 fn db(i: &str) -> IResult<&str, InfoStatement> {
 	let (i, _) = alt((tag_no_case("DATABASE"), tag_no_case("DB")))(i)?;
-	Ok((i, InfoStatement::Db))
+	let (i, stats) = opt(preceded(shouldbespace, tag_no_case("STATS")))(i)?;
+	match stats {
+		Some(_) => Ok((i, InfoStatement::DbStats)),
+		None => Ok((i, InfoStatement::Db)),
+	}
 }
 
 fn sc(i: &str) -> IResult<&str, InfoStatement> {
@@ -240,7 +472,11 @@ fn tb(i: &str) -> IResult<&str, InfoStatement> {
 	let (i, _) = alt((tag_no_case("TABLE"), tag_no_case("TB")))(i)?;
 	let (i, _) = shouldbespace(i)?;
 	let (i, table) = ident(i)?;
-	Ok((i, InfoStatement::Tb(table)))
+	let (i, stats) = opt(preceded(shouldbespace, tag_no_case("STATS")))(i)?;
+	match stats {
+		Some(_) => Ok((i, InfoStatement::TbStats(table))),
+		None => Ok((i, InfoStatement::Tb(table))),
+	}
 }
 
 #[cfg(test)]
@@ -258,6 +494,16 @@ mod tests {
 		assert_eq!("INFO FOR NAMESPACE", format!("{}", out));
 	}
 
+	#[test]
+	fn info_query_ns_stats() {
+		let sql = "INFO FOR NS STATS";
+		let res = info(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(out, InfoStatement::NsStats);
+		assert_eq!("INFO FOR NAMESPACE STATS", format!("{}", out));
+	}
+
 	#[test]
 	fn info_query_db() {
 		let sql = "INFO FOR DATABASE";
@@ -287,4 +533,34 @@ mod tests {
 		assert_eq!(out, InfoStatement::Tb(Ident::from("test")));
 		assert_eq!("INFO FOR TABLE test", format!("{}", out));
 	}
+
+	#[test]
+	fn info_query_db_stats() {
+		let sql = "INFO FOR DB STATS";
+		let res = info(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(out, InfoStatement::DbStats);
+		assert_eq!("INFO FOR DATABASE STATS", format!("{}", out));
+	}
+
+	#[test]
+	fn info_query_tb_stats() {
+		let sql = "INFO FOR TABLE test STATS";
+		let res = info(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(out, InfoStatement::TbStats(Ident::from("test")));
+		assert_eq!("INFO FOR TABLE test STATS", format!("{}", out));
+	}
+
+	#[test]
+	fn info_query_cluster() {
+		let sql = "INFO FOR CLUSTER";
+		let res = info(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(out, InfoStatement::Cl);
+		assert_eq!("INFO FOR CLUSTER", format!("{}", out));
+	}
 }
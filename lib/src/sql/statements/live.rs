@@ -1,4 +1,5 @@
 use crate::ctx::Context;
+use crate::dbs::Auth;
 use crate::dbs::Options;
 use crate::dbs::{Level, Transaction};
 use crate::doc::CursorDoc;
@@ -12,7 +13,6 @@ use crate::sql::param::param;
 use crate::sql::table::table;
 use crate::sql::value::Value;
 use crate::sql::Uuid;
-use derive::Store;
 use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
 use nom::combinator::map;
@@ -21,7 +21,7 @@ use nom::sequence::preceded;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Hash)]
 pub struct LiveStatement {
 	pub id: Uuid,
 	pub node: Uuid,
@@ -34,8 +34,44 @@ pub struct LiveStatement {
 
 	// When a live query is archived, this should be the node ID that archived the query.
 	pub archived: Option<Uuid>,
+	// The authentication of the session which registered the live query.
+	// Live queries which were registered before this was stored have no
+	// session, and are checked with the permissions of the writing session.
+	pub auth: Option<Auth>,
+	// The session data of the session which registered the live query.
+	pub session: Option<Value>,
 }
 
+/// The layout of a live query stored before revisions were introduced
+#[derive(Deserialize)]
+struct LiveStatementLegacy {
+	id: Uuid,
+	node: Uuid,
+	expr: Fields,
+	what: Value,
+	cond: Option<Cond>,
+	fetch: Option<Fetchs>,
+	archived: Option<Uuid>,
+}
+
+impl From<LiveStatementLegacy> for LiveStatement {
+	fn from(v: LiveStatementLegacy) -> Self {
+		Self {
+			id: v.id,
+			node: v.node,
+			expr: v.expr,
+			what: v.what,
+			cond: v.cond,
+			fetch: v.fetch,
+			archived: v.archived,
+			auth: None,
+			session: None,
+		}
+	}
+}
+
+impl_store_with_revision!(LiveStatement: LiveStatementLegacy);
+
 impl LiveStatement {
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
@@ -63,6 +99,9 @@ impl LiveStatement {
 					trace!("No ID for live query {:?}, error={:?}", stm, e)
 				}
 				stm.node = Uuid(opt.id()?);
+				// Store the session which registered the live query
+				stm.auth = Some(opt.auth.as_ref().clone());
+				stm.session = ctx.value("session").cloned();
 				// Insert the node live query
 				let key = crate::key::node::lq::new(opt.id()?, self.id.0, opt.ns(), opt.db());
 				run.putc(key, tb.as_str(), None).await?;
@@ -88,7 +127,10 @@ impl LiveStatement {
 
 impl fmt::Display for LiveStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "LIVE SELECT {} FROM {}", self.expr, self.what)?;
+		match self.expr.is_empty() {
+			true => write!(f, "LIVE SELECT DIFF FROM {}", self.what)?,
+			false => write!(f, "LIVE SELECT {} FROM {}", self.expr, self.what)?,
+		}
 		if let Some(ref v) = self.cond {
 			write!(f, " {v}")?
 		}
@@ -119,6 +161,63 @@ pub fn live(i: &str) -> IResult<&str, LiveStatement> {
 			cond,
 			fetch,
 			archived: None,
+			auth: None,
+			session: None,
 		},
 	))
 }
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn live_select() {
+		let sql = "LIVE SELECT * FROM person WHERE age > 18";
+		let res = live(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out))
+	}
+
+	#[test]
+	fn live_select_stored_without_session() {
+		let (_, mut stm) = live("LIVE SELECT * FROM person").unwrap();
+		stm.id = Uuid::from(uuid::Uuid::from_u128(1));
+		stm.node = Uuid::from(uuid::Uuid::from_u128(2));
+		// A live query which was stored before the session was recorded
+		#[rustfmt::skip]
+		let val = vec![
+			0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+			0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2,
+			1, 0, 0, 15, 6, 112, 101, 114, 115, 111, 110, 0, 0, 0,
+		];
+		let out = LiveStatement::try_from(&val).unwrap();
+		assert_eq!(out, stm);
+		assert_eq!(out.auth, None);
+		assert_eq!(out.session, None);
+		// A live query whose id happens to start with the revision marker
+		let mut val = val;
+		val[..2].copy_from_slice(&[crate::mac::REVISION_MARKER, LiveStatement::REVISION]);
+		let out = LiveStatement::try_from(&val).unwrap();
+		assert_eq!(out.id.as_bytes()[..2], val[..2]);
+		assert_eq!(out.node, stm.node);
+		// A live query which is stored now roundtrips with its session
+		let stm = LiveStatement {
+			session: Some(Value::from("session")),
+			..stm
+		};
+		assert_eq!(stm, LiveStatement::try_from(&stm.to_vec()).unwrap());
+	}
+
+	#[test]
+	fn live_select_diff() {
+		let sql = "LIVE SELECT DIFF FROM person WHERE age > 18";
+		let res = live(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert!(out.expr.is_empty());
+		assert_eq!(sql, format!("{}", out))
+	}
+}
@@ -1,3 +1,4 @@
+pub(crate) mod acknowledge;
 pub(crate) mod analyze;
 pub(crate) mod begin;
 pub(crate) mod cancel;
@@ -13,7 +14,11 @@ pub(crate) mod live;
 pub(crate) mod option;
 pub(crate) mod output;
 pub(crate) mod relate;
+pub(crate) mod release;
 pub(crate) mod remove;
+pub(crate) mod rename;
+pub(crate) mod rollback;
+pub(crate) mod savepoint;
 pub(crate) mod select;
 pub(crate) mod set;
 pub(crate) mod show;
@@ -21,6 +26,7 @@ pub(crate) mod sleep;
 pub(crate) mod update;
 pub(crate) mod yuse;
 
+pub use self::acknowledge::AcknowledgeStatement;
 pub use self::begin::BeginStatement;
 pub use self::cancel::CancelStatement;
 pub use self::commit::CommitStatement;
@@ -34,17 +40,22 @@ pub use self::live::LiveStatement;
 pub use self::option::OptionStatement;
 pub use self::output::OutputStatement;
 pub use self::relate::RelateStatement;
+pub use self::release::ReleaseStatement;
+pub use self::rollback::RollbackStatement;
+pub use self::savepoint::SavepointStatement;
 pub use self::select::SelectStatement;
 pub use self::set::SetStatement;
 pub use self::update::UpdateStatement;
 pub use self::yuse::UseStatement;
 
 pub use self::define::DefineAnalyzerStatement;
+pub use self::define::DefineConsumerStatement;
 pub use self::define::DefineDatabaseStatement;
 pub use self::define::DefineEventStatement;
 pub use self::define::DefineFieldStatement;
 pub use self::define::DefineFunctionStatement;
 pub use self::define::DefineIndexStatement;
+pub use self::define::DefineJobStatement;
 pub use self::define::DefineLoginStatement;
 pub use self::define::DefineNamespaceStatement;
 pub use self::define::DefineParamStatement;
@@ -53,15 +64,23 @@ pub use self::define::DefineStatement;
 pub use self::define::DefineTableStatement;
 pub use self::define::DefineTokenStatement;
 
+pub use self::remove::RemoveConsumerStatement;
 pub use self::remove::RemoveDatabaseStatement;
 pub use self::remove::RemoveEventStatement;
 pub use self::remove::RemoveFieldStatement;
 pub use self::remove::RemoveFunctionStatement;
 pub use self::remove::RemoveIndexStatement;
+pub use self::remove::RemoveJobStatement;
 pub use self::remove::RemoveLoginStatement;
 pub use self::remove::RemoveNamespaceStatement;
+pub use self::remove::RemoveNodeStatement;
 pub use self::remove::RemoveParamStatement;
 pub use self::remove::RemoveScopeStatement;
 pub use self::remove::RemoveStatement;
 pub use self::remove::RemoveTableStatement;
 pub use self::remove::RemoveTokenStatement;
+
+pub use self::rename::RenameDatabaseStatement;
+pub use self::rename::RenameNamespaceStatement;
+pub use self::rename::RenameStatement;
+pub use self::rename::RenameTableStatement;
@@ -1,4 +1,6 @@
+use crate::cf::TableMutation;
 use crate::ctx::Context;
+use crate::dbs::node::Timestamp;
 use crate::dbs::Options;
 use crate::dbs::{Level, Transaction};
 use crate::doc::CursorDoc;
@@ -10,6 +12,8 @@ use crate::sql::ident;
 use crate::sql::ident::{ident, Ident};
 use crate::sql::idiom;
 use crate::sql::idiom::Idiom;
+use crate::sql::statements::DefineNamespaceStatement;
+use crate::sql::uuid::{uuid, Uuid};
 use crate::sql::value::Value;
 use derive::Store;
 use nom::branch::alt;
@@ -25,16 +29,19 @@ use std::fmt::{self, Display, Formatter};
 pub enum RemoveStatement {
 	Namespace(RemoveNamespaceStatement),
 	Database(RemoveDatabaseStatement),
+	Node(RemoveNodeStatement),
 	Function(RemoveFunctionStatement),
 	Analyzer(RemoveAnalyzerStatement),
 	Login(RemoveLoginStatement),
 	Token(RemoveTokenStatement),
 	Scope(RemoveScopeStatement),
 	Param(RemoveParamStatement),
+	Job(RemoveJobStatement),
 	Table(RemoveTableStatement),
 	Event(RemoveEventStatement),
 	Field(RemoveFieldStatement),
 	Index(RemoveIndexStatement),
+	Consumer(RemoveConsumerStatement),
 }
 
 impl RemoveStatement {
@@ -49,16 +56,19 @@ impl RemoveStatement {
 		match self {
 			Self::Namespace(ref v) => v.compute(ctx, opt, txn).await,
 			Self::Database(ref v) => v.compute(ctx, opt, txn).await,
+			Self::Node(ref v) => v.compute(ctx, opt, txn).await,
 			Self::Function(ref v) => v.compute(ctx, opt, txn).await,
 			Self::Login(ref v) => v.compute(ctx, opt, txn).await,
 			Self::Token(ref v) => v.compute(ctx, opt, txn).await,
 			Self::Scope(ref v) => v.compute(ctx, opt, txn).await,
 			Self::Param(ref v) => v.compute(ctx, opt, txn).await,
+			Self::Job(ref v) => v.compute(ctx, opt, txn).await,
 			Self::Table(ref v) => v.compute(ctx, opt, txn).await,
 			Self::Event(ref v) => v.compute(ctx, opt, txn).await,
 			Self::Field(ref v) => v.compute(ctx, opt, txn).await,
 			Self::Index(ref v) => v.compute(ctx, opt, txn).await,
 			Self::Analyzer(ref v) => v.compute(ctx, opt, txn).await,
+			Self::Consumer(ref v) => v.compute(ctx, opt, txn).await,
 		}
 	}
 }
@@ -68,16 +78,19 @@ impl Display for RemoveStatement {
 		match self {
 			Self::Namespace(v) => Display::fmt(v, f),
 			Self::Database(v) => Display::fmt(v, f),
+			Self::Node(v) => Display::fmt(v, f),
 			Self::Function(v) => Display::fmt(v, f),
 			Self::Login(v) => Display::fmt(v, f),
 			Self::Token(v) => Display::fmt(v, f),
 			Self::Scope(v) => Display::fmt(v, f),
 			Self::Param(v) => Display::fmt(v, f),
+			Self::Job(v) => Display::fmt(v, f),
 			Self::Table(v) => Display::fmt(v, f),
 			Self::Event(v) => Display::fmt(v, f),
 			Self::Field(v) => Display::fmt(v, f),
 			Self::Index(v) => Display::fmt(v, f),
 			Self::Analyzer(v) => Display::fmt(v, f),
+			Self::Consumer(v) => Display::fmt(v, f),
 		}
 	}
 }
@@ -86,16 +99,19 @@ pub fn remove(i: &str) -> IResult<&str, RemoveStatement> {
 	alt((
 		map(namespace, RemoveStatement::Namespace),
 		map(database, RemoveStatement::Database),
+		map(node, RemoveStatement::Node),
 		map(function, RemoveStatement::Function),
 		map(login, RemoveStatement::Login),
 		map(token, RemoveStatement::Token),
 		map(scope, RemoveStatement::Scope),
 		map(param, RemoveStatement::Param),
+		map(job, RemoveStatement::Job),
 		map(table, RemoveStatement::Table),
 		map(event, RemoveStatement::Event),
 		map(field, RemoveStatement::Field),
 		map(index, RemoveStatement::Index),
 		map(analyzer, RemoveStatement::Analyzer),
+		map(consumer, RemoveStatement::Consumer),
 	))(i)
 }
 
@@ -122,9 +138,22 @@ impl RemoveNamespaceStatement {
 		opt.check(Level::Kv)?;
 		// Claim transaction
 		let mut run = txn.lock().await;
+		// Delete the resource data stored under the numeric id
+		match run.get_ns(&self.name).await {
+			Ok(DefineNamespaceStatement {
+				id: Some(id),
+				..
+			}) => run.delp(crate::key::data::ns::new(id), u32::MAX).await?,
+			Ok(_)
+			| Err(Error::NsNotFound {
+				..
+			}) => (),
+			Err(e) => return Err(e),
+		}
 		// Delete the definition
 		let key = crate::key::root::ns::new(&self.name);
-		run.del(key).await?;
+		run.del(key.clone()).await?;
+		run.clr(key).await?;
 		// Delete the resource data
 		let key = crate::key::namespace::all::new(&self.name);
 		run.delp(key, u32::MAX).await?;
@@ -157,6 +186,86 @@ fn namespace(i: &str) -> IResult<&str, RemoveNamespaceStatement> {
 // --------------------------------------------------
 // --------------------------------------------------
 
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+pub struct RemoveNodeStatement {
+	pub id: Uuid,
+	pub force: bool,
+}
+
+impl RemoveNodeStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Value, Error> {
+		// No need for NS/DB
+		opt.needs(Level::Kv)?;
+		// Allowed to run?
+		opt.check(Level::Kv)?;
+		// The node which runs this statement would stop once it has been removed
+		if opt.id()? == self.id.0 {
+			return Err(Error::NdEvictSelf {
+				value: self.id.0.to_string(),
+			});
+		}
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// A node which is still sending heartbeats stops once it has been removed
+		if !self.force {
+			let ts = Timestamp {
+				value: u64::MAX - 1,
+			};
+			let expired = run.clock().value.saturating_sub(run.lease_duration().as_millis() as u64);
+			for hb in run.scan_hb(&ts, u32::MAX).await? {
+				if hb.nd == self.id.0 && hb.hb.value > expired {
+					return Err(Error::NdAlive {
+						value: self.id.0.to_string(),
+					});
+				}
+			}
+		}
+		// Evict the node and its live queries
+		match run.evict_nd(self.id.0).await? {
+			true => Ok(Value::None),
+			false => Err(Error::NdNotFound {
+				value: self.id.0.to_string(),
+			}),
+		}
+	}
+}
+
+impl Display for RemoveNodeStatement {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "REMOVE NODE {}", self.id)?;
+		if self.force {
+			write!(f, " FORCE")?;
+		}
+		Ok(())
+	}
+}
+
+fn node(i: &str) -> IResult<&str, RemoveNodeStatement> {
+	let (i, _) = tag_no_case("REMOVE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("NODE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, id) = uuid(i)?;
+	let (i, force) = opt(tuple((shouldbespace, tag_no_case("FORCE"))))(i)?;
+	Ok((
+		i,
+		RemoveNodeStatement {
+			id,
+			force: force.is_some(),
+		},
+	))
+}
+
+// --------------------------------------------------
+// --------------------------------------------------
+// --------------------------------------------------
+
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
 pub struct RemoveDatabaseStatement {
 	pub name: Ident,
@@ -176,9 +285,28 @@ impl RemoveDatabaseStatement {
 		opt.check(Level::Ns)?;
 		// Claim transaction
 		let mut run = txn.lock().await;
+		// Delete the resource data stored under the numeric ids
+		let ns = match run.get_ns(opt.ns()).await {
+			Ok(v) => v.id,
+			Err(Error::NsNotFound {
+				..
+			}) => None,
+			Err(e) => return Err(e),
+		};
+		let db = match run.get_db(opt.ns(), &self.name).await {
+			Ok(v) => v.id,
+			Err(Error::DbNotFound {
+				..
+			}) => None,
+			Err(e) => return Err(e),
+		};
+		if let (Some(ns), Some(db)) = (ns, db) {
+			run.delp(crate::key::data::db::new(ns, db), u32::MAX).await?;
+		}
 		// Delete the definition
 		let key = crate::key::namespace::db::new(opt.ns(), &self.name);
-		run.del(key).await?;
+		run.del(key.clone()).await?;
+		run.clr(key).await?;
 		// Delete the resource data
 		let key = crate::key::database::all::new(opt.ns(), &self.name);
 		run.delp(key, u32::MAX).await?;
@@ -590,6 +718,63 @@ fn param(i: &str) -> IResult<&str, RemoveParamStatement> {
 // --------------------------------------------------
 // --------------------------------------------------
 
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+pub struct RemoveJobStatement {
+	pub name: Ident,
+}
+
+impl RemoveJobStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Value, Error> {
+		// Selected DB?
+		opt.needs(Level::Db)?;
+		// Allowed to run?
+		opt.check(Level::Db)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Delete the definition
+		let key = crate::key::database::jb::new(opt.ns(), opt.db(), &self.name);
+		run.del(key).await?;
+		// Delete the outcome of the last run
+		let key = crate::key::database::jr::new(opt.ns(), opt.db(), &self.name);
+		run.del(key).await?;
+		// Clear the cache
+		let key = crate::key::database::jb::prefix(opt.ns(), opt.db());
+		run.clr(key).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl Display for RemoveJobStatement {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "REMOVE JOB {}", self.name)
+	}
+}
+
+fn job(i: &str) -> IResult<&str, RemoveJobStatement> {
+	let (i, _) = tag_no_case("REMOVE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("JOB")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, name) = ident(i)?;
+	Ok((
+		i,
+		RemoveJobStatement {
+			name,
+		},
+	))
+}
+
+// --------------------------------------------------
+// --------------------------------------------------
+// --------------------------------------------------
+
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
 pub struct RemoveTableStatement {
 	pub name: Ident,
@@ -609,9 +794,18 @@ impl RemoveTableStatement {
 		opt.check(Level::Db)?;
 		// Claim transaction
 		let mut run = txn.lock().await;
+		// Record the change in the changefeed
+		let cf = TableMutation::RemoveTable(self.clone());
+		run.record_definition(opt.ns(), opt.db(), &self.name, cf).await?;
+		// Remove the resource data stored under the numeric ids
+		let base = run.tb_base(opt.ns(), opt.db(), &self.name).await?;
+		if base.is_compact() {
+			run.delp(base.prefix(), u32::MAX).await?;
+		}
 		// Delete the definition
 		let key = crate::key::database::tb::new(opt.ns(), opt.db(), &self.name);
-		run.del(key).await?;
+		run.del(key.clone()).await?;
+		run.clr(key).await?;
 		// Remove the resource data
 		let key = crate::key::table::all::new(opt.ns(), opt.db(), &self.name);
 		run.delp(key, u32::MAX).await?;
@@ -664,6 +858,9 @@ impl RemoveEventStatement {
 		opt.check(Level::Db)?;
 		// Claim transaction
 		let mut run = txn.lock().await;
+		// Record the change in the changefeed
+		let cf = TableMutation::RemoveEvent(self.clone());
+		run.record_definition(opt.ns(), opt.db(), &self.what, cf).await?;
 		// Delete the definition
 		let key = crate::key::table::ev::new(opt.ns(), opt.db(), &self.what, &self.name);
 		run.del(key).await?;
@@ -725,6 +922,9 @@ impl RemoveFieldStatement {
 		opt.check(Level::Db)?;
 		// Claim transaction
 		let mut run = txn.lock().await;
+		// Record the change in the changefeed
+		let cf = TableMutation::RemoveField(self.clone());
+		run.record_definition(opt.ns(), opt.db(), &self.what, cf).await?;
 		// Delete the definition
 		let fd = self.name.to_string();
 		let key = crate::key::table::fd::new(opt.ns(), opt.db(), &self.what, &fd);
@@ -787,12 +987,29 @@ impl RemoveIndexStatement {
 		opt.check(Level::Db)?;
 		// Claim transaction
 		let mut run = txn.lock().await;
+		// Record the change in the changefeed
+		let cf = TableMutation::RemoveIndex(self.clone());
+		run.record_definition(opt.ns(), opt.db(), &self.what, cf).await?;
+		// Remove the index data stored under the numeric ids
+		match run.get_ix(opt.ns(), opt.db(), &self.what, &self.name).await {
+			Ok(ix) => {
+				let base = run.ix_base(opt.ns(), opt.db(), &ix).await?;
+				if base.is_compact() {
+					run.delp(base.prefix(), u32::MAX).await?;
+				}
+			}
+			Err(Error::IxNotFound {
+				..
+			}) => (),
+			Err(e) => return Err(e),
+		}
 		// Delete the definition
 		let key = crate::key::table::ix::new(opt.ns(), opt.db(), &self.what, &self.name);
 		run.del(key).await?;
 		// Remove the index data
 		let key = crate::key::index::all::new(opt.ns(), opt.db(), &self.what, &self.name);
 		run.delp(key, u32::MAX).await?;
+		run.clear_index_stats(opt.ns(), opt.db(), &self.what, &self.name).await?;
 		// Clear the cache
 		let key = crate::key::table::ix::prefix(opt.ns(), opt.db(), &self.what);
 		run.clr(key).await?;
@@ -827,6 +1044,70 @@ fn index(i: &str) -> IResult<&str, RemoveIndexStatement> {
 	))
 }
 
+// --------------------------------------------------
+// --------------------------------------------------
+// --------------------------------------------------
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+pub struct RemoveConsumerStatement {
+	pub name: Ident,
+	pub what: Ident,
+}
+
+impl RemoveConsumerStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Value, Error> {
+		// Selected DB?
+		opt.needs(Level::Db)?;
+		// Allowed to run?
+		opt.check(Level::Db)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Delete the definition
+		let key = crate::key::table::co::new(opt.ns(), opt.db(), &self.what, &self.name);
+		run.del(key).await?;
+		// Delete the acknowledged position
+		let key = crate::key::table::cp::new(opt.ns(), opt.db(), &self.what, &self.name);
+		run.del(key).await?;
+		// Clear the cache
+		let key = crate::key::table::co::prefix(opt.ns(), opt.db(), &self.what);
+		run.clr(key).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl Display for RemoveConsumerStatement {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "REMOVE CONSUMER {} ON {}", self.name, self.what)
+	}
+}
+
+fn consumer(i: &str) -> IResult<&str, RemoveConsumerStatement> {
+	let (i, _) = tag_no_case("REMOVE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("CONSUMER")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, name) = ident(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("ON")(i)?;
+	let (i, _) = opt(tuple((shouldbespace, tag_no_case("TABLE"))))(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, what) = ident(i)?;
+	Ok((
+		i,
+		RemoveConsumerStatement {
+			name,
+			what,
+		},
+	))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -839,4 +1120,17 @@ mod tests {
 		});
 		assert_eq!(6, stm.to_vec().len());
 	}
+
+	#[test]
+	fn remove_node() {
+		let sql = "REMOVE NODE 'e72bee20-f49b-11ec-b939-0242ac120002'";
+		let res = remove(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert!(remove("REMOVE NODE 'e72bee20'").is_err());
+		let sql = "REMOVE NODE 'e72bee20-f49b-11ec-b939-0242ac120002' FORCE";
+		let (_, out) = remove(sql).unwrap();
+		assert_eq!(sql, format!("{}", out));
+	}
 }
@@ -0,0 +1,1260 @@
+use crate::cf::{TableMutation, TableMutations};
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::{Level, Transaction};
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::kvs::Datastore;
+use crate::kvs::Key;
+use crate::kvs::Scanner;
+use crate::kvs::Stats;
+use crate::sql::comment::shouldbespace;
+use crate::sql::dir::Dir;
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::paths::{ID, IN, OUT};
+use crate::sql::statements::{
+	DefineDatabaseStatement, DefineNamespaceStatement, DefineTableStatement, UpdateStatement,
+};
+use crate::sql::table::Table;
+use crate::sql::thing::Thing;
+use crate::sql::value::{Value, Values};
+use derive::Store;
+use futures::lock::Mutex;
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::map;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+pub enum RenameStatement {
+	Namespace(RenameNamespaceStatement),
+	Database(RenameDatabaseStatement),
+	Table(RenameTableStatement),
+}
+
+impl RenameStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		match self {
+			Self::Namespace(ref v) => v.compute(ctx, opt, txn).await,
+			Self::Database(ref v) => v.compute(ctx, opt, txn).await,
+			Self::Table(ref v) => v.compute(ctx, opt, txn, doc).await,
+		}
+	}
+
+	/// Process this type outside of a transaction, moving the data in batches
+	/// which are each committed in a separate transaction
+	pub(crate) async fn compute_batched(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		kvs: &Datastore,
+	) -> Result<Value, Error> {
+		// In the compact key format the data is stored under numeric ids, which
+		// are kept by the rename, so the rename is run in a single transaction
+		if kvs.key_format().is_compact() {
+			let txn: Transaction = Arc::new(Mutex::new(kvs.transaction(true, false).await?));
+			let res = self.compute(ctx, opt, &txn, None).await;
+			let mut run = txn.lock().await;
+			let res = match res {
+				Ok(v) => run.complete_changes(false).await.map(|_| v),
+				Err(e) => Err(e),
+			};
+			return match res {
+				Ok(v) => {
+					run.commit().await?;
+					Ok(v)
+				}
+				Err(e) => {
+					run.cancel().await?;
+					Err(e)
+				}
+			};
+		}
+		match self {
+			Self::Namespace(ref v) => v.compute_batched(opt, kvs).await,
+			Self::Database(ref v) => v.compute_batched(opt, kvs).await,
+			Self::Table(ref v) => v.compute_batched(ctx, opt, kvs).await,
+		}
+	}
+}
+
+impl Display for RenameStatement {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Self::Namespace(v) => Display::fmt(v, f),
+			Self::Database(v) => Display::fmt(v, f),
+			Self::Table(v) => Display::fmt(v, f),
+		}
+	}
+}
+
+pub fn rename(i: &str) -> IResult<&str, RenameStatement> {
+	alt((
+		map(namespace, RenameStatement::Namespace),
+		map(database, RenameStatement::Database),
+		map(table, RenameStatement::Table),
+	))(i)
+}
+
+/// Move the keys under a prefix to another prefix, committing each batch in a separate transaction
+async fn movp_batched(kvs: &Datastore, from: Key, to: Key) -> Result<(), Error> {
+	let mut scanner = Scanner::prefix(from.clone());
+	loop {
+		let mut tx = kvs.transaction(true, false).await?;
+		match tx.movp_batch(&mut scanner, &from, &to).await {
+			Ok(true) => tx.commit().await?,
+			Ok(false) => return tx.cancel().await,
+			Err(e) => {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		}
+	}
+}
+
+/// Remove the progress of a rename which has completed
+async fn finish(kvs: &Datastore, key: Key) -> Result<(), Error> {
+	let mut tx = kvs.transaction(true, false).await?;
+	match tx.del(key).await {
+		Ok(_) => tx.commit().await,
+		Err(e) => {
+			tx.cancel().await?;
+			Err(e)
+		}
+	}
+}
+
+/// The progress of a rename which moves its data in batches
+///
+/// This is stored under the current name in the same transaction which moves
+/// the definition, and is updated whenever the rename moves on to another step,
+/// so that an interrupted rename can be resumed by running the statement again.
+#[derive(Debug, Serialize, Deserialize)]
+struct Progress {
+	/// The new name
+	to: String,
+	/// The step which is in progress
+	step: u8,
+}
+
+impl Progress {
+	/// Fetch the step of an interrupted rename, which must be renaming to the same name
+	async fn get(
+		run: &mut crate::kvs::Transaction,
+		key: Key,
+		name: &Ident,
+		to: &Ident,
+	) -> Result<Option<u8>, Error> {
+		let val: Progress = match run.get(key).await? {
+			Some(v) => bincode::deserialize(&v)?,
+			None => return Ok(None),
+		};
+		match val.to == to.as_str() {
+			true => Ok(Some(val.step)),
+			false => Err(Error::RenameIncomplete {
+				name: name.to_raw(),
+				to: val.to,
+			}),
+		}
+	}
+
+	/// Store the step which is in progress
+	async fn set(
+		run: &mut crate::kvs::Transaction,
+		key: Key,
+		to: &Ident,
+		step: u8,
+	) -> Result<(), Error> {
+		let val = bincode::serialize(&Progress {
+			to: to.to_raw(),
+			step,
+		})?;
+		run.set(key, val).await
+	}
+}
+
+fn names(i: &str) -> IResult<&str, (Ident, Ident)> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, name) = ident(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("TO")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, to) = ident(i)?;
+	Ok((i, (name, to)))
+}
+
+// --------------------------------------------------
+// --------------------------------------------------
+// --------------------------------------------------
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+pub struct RenameNamespaceStatement {
+	pub name: Ident,
+	pub to: Ident,
+}
+
+impl RenameNamespaceStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Value, Error> {
+		// No need for NS/DB
+		opt.needs(Level::Kv)?;
+		// Allowed to run?
+		opt.check(Level::Kv)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Move the definition
+		self.definition(&mut run).await?;
+		// Move the resource data, which only includes the definitions in the compact key format
+		let (from, to) = self.prefixes()?;
+		run.movp(from, to).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+
+	/// Process this type outside of a transaction, moving the resource data in batches
+	pub(crate) async fn compute_batched(
+		&self,
+		opt: &Options,
+		kvs: &Datastore,
+	) -> Result<Value, Error> {
+		// No need for NS/DB
+		opt.needs(Level::Kv)?;
+		// Allowed to run?
+		opt.check(Level::Kv)?;
+		// Move the definition, unless an interrupted rename is being resumed
+		let key = crate::key::root::rn::new(&self.name).encode()?;
+		let mut tx = kvs.transaction(true, false).await?;
+		match self.begin(&mut tx, &key).await {
+			Ok(_) => tx.commit().await?,
+			Err(e) => {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		}
+		// Move the resource data
+		let (from, to) = self.prefixes()?;
+		movp_batched(kvs, from, to).await?;
+		// The rename has completed
+		finish(kvs, key).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+
+	/// Move the definition and store the progress of the rename, unless the
+	/// progress of an interrupted rename is already stored
+	async fn begin(&self, run: &mut crate::kvs::Transaction, key: &Key) -> Result<(), Error> {
+		if Progress::get(run, key.clone(), &self.name, &self.to).await?.is_none() {
+			self.definition(run).await?;
+			Progress::set(run, key.clone(), &self.to, 0).await?;
+		}
+		Ok(())
+	}
+
+	/// The prefixes of the resource data under the current and the new name
+	fn prefixes(&self) -> Result<(Key, Key), Error> {
+		let from = crate::key::namespace::all::new(&self.name).encode()?;
+		let to = crate::key::namespace::all::new(&self.to).encode()?;
+		Ok((from, to))
+	}
+
+	/// Move the definition to the new name, keeping its numeric id
+	async fn definition(&self, run: &mut crate::kvs::Transaction) -> Result<(), Error> {
+		// Check that the namespace exists
+		let def = run.get_ns(&self.name).await?;
+		// Check that the new name is not in use
+		match run.get_ns(&self.to).await {
+			Ok(_) => {
+				return Err(Error::NsAlreadyExists {
+					value: self.to.to_raw(),
+				})
+			}
+			Err(Error::NsNotFound {
+				..
+			}) => (),
+			Err(e) => return Err(e),
+		}
+		// Move the definition, keeping its numeric id
+		run.del(crate::key::root::ns::new(&self.name)).await?;
+		let val = DefineNamespaceStatement {
+			name: self.to.clone(),
+			..def
+		};
+		run.set(crate::key::root::ns::new(&self.to), val).await
+	}
+}
+
+impl Display for RenameNamespaceStatement {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "RENAME NAMESPACE {} TO {}", self.name, self.to)
+	}
+}
+
+fn namespace(i: &str) -> IResult<&str, RenameNamespaceStatement> {
+	let (i, _) = tag_no_case("RENAME")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = alt((tag_no_case("NS"), tag_no_case("NAMESPACE")))(i)?;
+	let (i, (name, to)) = names(i)?;
+	Ok((
+		i,
+		RenameNamespaceStatement {
+			name,
+			to,
+		},
+	))
+}
+
+// --------------------------------------------------
+// --------------------------------------------------
+// --------------------------------------------------
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+pub struct RenameDatabaseStatement {
+	pub name: Ident,
+	pub to: Ident,
+}
+
+impl RenameDatabaseStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Value, Error> {
+		// Selected NS?
+		opt.needs(Level::Ns)?;
+		// Allowed to run?
+		opt.check(Level::Ns)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Move the definition
+		self.definition(&mut run, opt.ns()).await?;
+		// Move the resource data, which only includes the definitions in the compact key format
+		let (from, to) = self.prefixes(opt.ns())?;
+		run.movp(from, to).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+
+	/// Process this type outside of a transaction, moving the resource data in batches
+	pub(crate) async fn compute_batched(
+		&self,
+		opt: &Options,
+		kvs: &Datastore,
+	) -> Result<Value, Error> {
+		// Selected NS?
+		opt.needs(Level::Ns)?;
+		// Allowed to run?
+		opt.check(Level::Ns)?;
+		// Move the definition, unless an interrupted rename is being resumed
+		let key = crate::key::namespace::rn::new(opt.ns(), &self.name).encode()?;
+		let mut tx = kvs.transaction(true, false).await?;
+		match self.begin(&mut tx, opt.ns(), &key).await {
+			Ok(_) => tx.commit().await?,
+			Err(e) => {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		}
+		// Move the resource data
+		let (from, to) = self.prefixes(opt.ns())?;
+		movp_batched(kvs, from, to).await?;
+		// The rename has completed
+		finish(kvs, key).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+
+	/// Move the definition and store the progress of the rename, unless the
+	/// progress of an interrupted rename is already stored
+	async fn begin(
+		&self,
+		run: &mut crate::kvs::Transaction,
+		ns: &str,
+		key: &Key,
+	) -> Result<(), Error> {
+		if Progress::get(run, key.clone(), &self.name, &self.to).await?.is_none() {
+			self.definition(run, ns).await?;
+			Progress::set(run, key.clone(), &self.to, 0).await?;
+		}
+		Ok(())
+	}
+
+	/// The prefixes of the resource data under the current and the new name
+	fn prefixes(&self, ns: &str) -> Result<(Key, Key), Error> {
+		let from = crate::key::database::all::new(ns, &self.name).encode()?;
+		let to = crate::key::database::all::new(ns, &self.to).encode()?;
+		Ok((from, to))
+	}
+
+	/// Move the definition to the new name, keeping its numeric id
+	async fn definition(&self, run: &mut crate::kvs::Transaction, ns: &str) -> Result<(), Error> {
+		// Check that the database exists
+		let def = run.get_db(ns, &self.name).await?;
+		// Check that the new name is not in use
+		match run.get_db(ns, &self.to).await {
+			Ok(_) => {
+				return Err(Error::DbAlreadyExists {
+					value: self.to.to_raw(),
+				})
+			}
+			Err(Error::DbNotFound {
+				..
+			}) => (),
+			Err(e) => return Err(e),
+		}
+		// Move the definition, keeping its numeric id
+		run.del(crate::key::namespace::db::new(ns, &self.name)).await?;
+		let val = DefineDatabaseStatement {
+			name: self.to.clone(),
+			..def
+		};
+		run.set(crate::key::namespace::db::new(ns, &self.to), val).await
+	}
+}
+
+impl Display for RenameDatabaseStatement {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "RENAME DATABASE {} TO {}", self.name, self.to)
+	}
+}
+
+fn database(i: &str) -> IResult<&str, RenameDatabaseStatement> {
+	let (i, _) = tag_no_case("RENAME")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = alt((tag_no_case("DB"), tag_no_case("DATABASE")))(i)?;
+	let (i, (name, to)) = names(i)?;
+	Ok((
+		i,
+		RenameDatabaseStatement {
+			name,
+			to,
+		},
+	))
+}
+
+// --------------------------------------------------
+// --------------------------------------------------
+// --------------------------------------------------
+
+/// Renames a table, along with its definitions, records, graph edges and change feed entries
+///
+/// The record ids stored in the `id`, `in` and `out` fields of the records of
+/// the table, and of the records related to them, are pointed at the new table
+/// name. Record links to the table which are stored in any other fields are not
+/// rewritten. When the statement is not run within a transaction, and the
+/// datastore uses the original key format, the data is rewritten in batches
+/// which are each committed separately, and so the table should not be used
+/// until the statement has completed. If the rename is interrupted, then it is
+/// resumed by running the same statement again.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
+pub struct RenameTableStatement {
+	pub name: Ident,
+	pub to: Ident,
+}
+
+/// The data of a renamed table which remains to be rewritten
+enum Step {
+	/// The index data under each of these prefixes is removed
+	Clear(Vec<Key>),
+	/// The keys stored under the table name are moved under the new name
+	Move(Scanner),
+	/// The definitions stored on the table are pointed at the new name
+	Definitions,
+	/// The record ids stored within the records are rewritten
+	Records(Scanner),
+	/// The graph edges of the table, and the edges which point back to them, are rewritten
+	Edges(Scanner),
+	/// The change feed entries of the table are rewritten
+	Changes(Scanner),
+	/// All of the data has been rewritten
+	Done,
+}
+
+impl Step {
+	/// The position of this step, which is stored as the progress of the rename
+	fn index(&self) -> u8 {
+		match self {
+			Self::Clear(_) => 0,
+			Self::Move(_) => 1,
+			Self::Definitions => 2,
+			Self::Records(_) => 3,
+			Self::Edges(_) => 4,
+			Self::Changes(_) => 5,
+			Self::Done => 6,
+		}
+	}
+}
+
+impl RenameTableStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Selected DB?
+		opt.needs(Level::Db)?;
+		// Allowed to run?
+		opt.check(Level::Db)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Move the definition
+		let (ns, db) = (opt.ns(), opt.db());
+		let ixs = self.definition(&mut run, ns, db).await?;
+		let reindex = !ixs.is_empty();
+		// Rewrite all of the data
+		let mut step = Step::Clear(ixs);
+		while self.next_batch(&mut run, ns, db, &mut step).await? {}
+		// Release the transaction
+		drop(run);
+		// Rebuild the index data
+		if reindex {
+			let what = Values(vec![Value::Table(self.to.clone().into())]);
+			self.reindex(ctx, opt, txn, doc, what).await?;
+		}
+		// Ok all good
+		Ok(Value::None)
+	}
+
+	/// Process this type outside of a transaction, rewriting the data in batches
+	pub(crate) async fn compute_batched(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		kvs: &Datastore,
+	) -> Result<Value, Error> {
+		// Selected DB?
+		opt.needs(Level::Db)?;
+		// Allowed to run?
+		opt.check(Level::Db)?;
+		// Move the definition, unless an interrupted rename is being resumed
+		let (ns, db) = (opt.ns(), opt.db());
+		let key = crate::key::database::rn::new(ns, db, &self.name).encode()?;
+		let mut tx = kvs.transaction(true, false).await?;
+		let mut step = match self.begin(&mut tx, ns, db, &key).await {
+			Ok(step) => {
+				tx.commit().await?;
+				step
+			}
+			Err(e) => {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		};
+		// Rewrite the data in batches
+		while self.batch(kvs, ns, db, &key, &mut step).await? {}
+		// Rebuild the index data in batches of records
+		self.reindex_batched(ctx, opt, kvs, ns, db).await?;
+		// The rename has completed
+		finish(kvs, key).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+
+	/// Move the definition and store the progress of the rename, or fetch the
+	/// progress of an interrupted rename, returning the step to continue from
+	async fn begin(
+		&self,
+		run: &mut crate::kvs::Transaction,
+		ns: &str,
+		db: &str,
+		key: &Key,
+	) -> Result<Step, Error> {
+		match Progress::get(run, key.clone(), &self.name, &self.to).await? {
+			Some(index) => self.step(run, ns, db, index).await,
+			None => {
+				let ixs = self.definition(run, ns, db).await?;
+				Progress::set(run, key.clone(), &self.to, 0).await?;
+				Ok(Step::Clear(ixs))
+			}
+		}
+	}
+
+	/// Rewrite the next batch of data in a separate transaction, storing the progress
+	/// of the rename whenever it moves on to another step, and returning whether any
+	/// data remains to be rewritten
+	async fn batch(
+		&self,
+		kvs: &Datastore,
+		ns: &str,
+		db: &str,
+		key: &Key,
+		step: &mut Step,
+	) -> Result<bool, Error> {
+		let mut tx = kvs.transaction(true, false).await?;
+		let index = step.index();
+		let res = match self.next_batch(&mut tx, ns, db, step).await {
+			Ok(more) if step.index() != index => {
+				Progress::set(&mut tx, key.clone(), &self.to, step.index()).await.map(|_| more)
+			}
+			res => res,
+		};
+		match res {
+			Ok(true) => {
+				tx.commit().await?;
+				Ok(true)
+			}
+			Ok(false) => {
+				tx.cancel().await?;
+				Ok(false)
+			}
+			Err(e) => {
+				tx.cancel().await?;
+				Err(e)
+			}
+		}
+	}
+
+	/// Rebuild the index data of the table in batches of records, which are each
+	/// committed in a separate transaction
+	async fn reindex_batched(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		kvs: &Datastore,
+		ns: &str,
+		db: &str,
+	) -> Result<(), Error> {
+		let (reindex, base) = {
+			let mut tx = kvs.transaction(false, false).await?;
+			let ixs = tx.all_ix(ns, db, &self.to).await;
+			let base = tx.tb_base(ns, db, &self.to).await;
+			tx.cancel().await?;
+			(!ixs?.is_empty(), base?)
+		};
+		if !reindex {
+			return Ok(());
+		}
+		let beg = crate::key::thing::prefix(ns, db, &self.to);
+		let end = crate::key::thing::suffix(ns, db, &self.to);
+		let mut scanner = Scanner::new(base.range(beg..end)).keys_only();
+		loop {
+			let txn: Transaction = Arc::new(Mutex::new(kvs.transaction(true, false).await?));
+			let res = scanner.next_batch(&mut *txn.lock().await).await;
+			let res = match res {
+				Ok(res) if res.is_empty() => Ok(false),
+				Ok(res) => {
+					let what = res.into_iter().map(|(k, _)| {
+						let key = base.name(k);
+						let key: crate::key::thing::Thing = (&key).into();
+						Value::Thing(Thing::from((key.tb.to_owned(), key.id)))
+					});
+					self.reindex(ctx, opt, &txn, None, Values(what.collect())).await.map(|_| true)
+				}
+				Err(e) => Err(e),
+			};
+			let mut run = txn.lock().await;
+			match res {
+				Ok(true) => run.commit().await?,
+				Ok(false) => {
+					run.cancel().await?;
+					break;
+				}
+				Err(e) => {
+					run.cancel().await?;
+					return Err(e);
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Move the definition to the new name, keeping its numeric id, and return the
+	/// prefixes of the index data, which is rebuilt once the table has been renamed
+	async fn definition(
+		&self,
+		run: &mut crate::kvs::Transaction,
+		ns: &str,
+		db: &str,
+	) -> Result<Vec<Key>, Error> {
+		// Check that the table exists
+		let def = run.get_tb(ns, db, &self.name).await?;
+		// Check that the new name is not in use
+		match run.get_tb(ns, db, &self.to).await {
+			Ok(_) => {
+				return Err(Error::TbAlreadyExists {
+					value: self.to.to_raw(),
+				})
+			}
+			Err(Error::TbNotFound {
+				..
+			}) => (),
+			Err(e) => return Err(e),
+		}
+		// Find the index data, which is rebuilt once the table has been renamed
+		let ixs = self.indexes(run, ns, db).await?;
+		// Move the definition, keeping its numeric id
+		run.del(crate::key::database::tb::new(ns, db, &self.name)).await?;
+		let val = DefineTableStatement {
+			name: self.to.clone(),
+			..def
+		};
+		run.set(crate::key::database::tb::new(ns, db, &self.to), &val).await?;
+		// Point the tables which this view is built from at the new name
+		if let Some(view) = &val.view {
+			for v in view.what.0.iter() {
+				run.del(crate::key::table::ft::new(ns, db, v, &self.name)).await?;
+				run.set(crate::key::table::ft::new(ns, db, v, &self.to), &val).await?;
+			}
+		}
+		Ok(ixs)
+	}
+
+	/// The prefixes of the index data of the table, while the index definitions
+	/// are stored under the current table name
+	async fn indexes(
+		&self,
+		run: &mut crate::kvs::Transaction,
+		ns: &str,
+		db: &str,
+	) -> Result<Vec<Key>, Error> {
+		let mut ixs = vec![];
+		for ix in run.all_ix(ns, db, &self.name).await?.iter() {
+			let base = run.ix_base(ns, db, ix).await?;
+			if base.is_compact() {
+				ixs.push(base.prefix());
+			}
+			ixs.push(crate::key::index::all::new(ns, db, &self.name, &ix.name).encode()?);
+		}
+		Ok(ixs)
+	}
+
+	/// The start of a step of the rename, given its position
+	async fn step(
+		&self,
+		run: &mut crate::kvs::Transaction,
+		ns: &str,
+		db: &str,
+		index: u8,
+	) -> Result<Step, Error> {
+		Ok(match index {
+			0 => Step::Clear(self.indexes(run, ns, db).await?),
+			1 => Step::Move(Scanner::prefix(crate::key::table::all::new(ns, db, &self.name))),
+			2 => Step::Definitions,
+			3 => {
+				let base = run.tb_base(ns, db, &self.to).await?;
+				let beg = crate::key::thing::prefix(ns, db, &self.to);
+				let end = crate::key::thing::suffix(ns, db, &self.to);
+				Step::Records(Scanner::new(base.range(beg..end)))
+			}
+			4 => {
+				let base = run.tb_base(ns, db, &self.to).await?;
+				let named = crate::key::table::all::new(ns, db, &self.to).encode()?;
+				let beg = [named.as_slice(), b"~"].concat();
+				let end = [named.as_slice(), b"~\xff"].concat();
+				Step::Edges(Scanner::new(base.range(beg..end)).keys_only())
+			}
+			5 => {
+				let beg = crate::key::change::prefix(ns, db);
+				let end = crate::key::change::suffix(ns, db);
+				Step::Changes(Scanner::new(beg..end))
+			}
+			_ => Step::Done,
+		})
+	}
+
+	/// Rewrite the next batch of data, returning whether any data remains to be rewritten
+	async fn next_batch(
+		&self,
+		run: &mut crate::kvs::Transaction,
+		ns: &str,
+		db: &str,
+		step: &mut Step,
+	) -> Result<bool, Error> {
+		match step {
+			Step::Clear(ixs) => match ixs.last() {
+				Some(prefix) => {
+					let res = Scanner::prefix(prefix.clone()).keys_only().next_batch(run).await?;
+					if res.is_empty() {
+						ixs.pop();
+					}
+					for (k, _) in res.into_iter() {
+						run.del(k).await?;
+					}
+				}
+				None => *step = self.step(run, ns, db, 1).await?,
+			},
+			// The resource data only includes the definitions in the compact key format
+			Step::Move(scanner) => {
+				let from = crate::key::table::all::new(ns, db, &self.name).encode()?;
+				let to = crate::key::table::all::new(ns, db, &self.to).encode()?;
+				if !run.movp_batch(scanner, &from, &to).await? {
+					*step = self.step(run, ns, db, 2).await?;
+				}
+			}
+			Step::Definitions => {
+				// Point the moved definitions at the new name
+				self.definitions(run, ns, db).await?;
+				// The moved index statistics no longer match the removed index data
+				for ix in run.all_ix(ns, db, &self.to).await?.iter() {
+					run.clear_index_stats(ns, db, &self.to, &ix.name).await?;
+				}
+				*step = self.step(run, ns, db, 3).await?;
+			}
+			Step::Records(scanner) => {
+				if !self.records(run, ns, db, scanner).await? {
+					*step = self.step(run, ns, db, 4).await?;
+				}
+			}
+			Step::Edges(scanner) => {
+				if !self.edges(run, ns, db, scanner).await? {
+					*step = self.step(run, ns, db, 5).await?;
+				}
+			}
+			Step::Changes(scanner) => {
+				if !self.changes(run, ns, db, scanner).await? {
+					*step = Step::Done;
+				}
+			}
+			Step::Done => return Ok(false),
+		}
+		Ok(true)
+	}
+
+	/// Rebuild the index data of the records
+	async fn reindex(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+		what: Values,
+	) -> Result<(), Error> {
+		// Force queries to run
+		let opt = &opt.new_with_force(true);
+		// Don't process field queries
+		let opt = &opt.new_with_fields(false);
+		// Don't process event queries
+		let opt = &opt.new_with_events(false);
+		// Don't process table queries
+		let opt = &opt.new_with_tables(false);
+		// Update the index data
+		let stm = UpdateStatement {
+			what,
+			..UpdateStatement::default()
+		};
+		stm.compute(ctx, opt, txn, doc).await?;
+		Ok(())
+	}
+
+	/// Point the definitions which are stored on the table at the new table name
+	async fn definitions(
+		&self,
+		run: &mut crate::kvs::Transaction,
+		ns: &str,
+		db: &str,
+	) -> Result<(), Error> {
+		for fd in run.all_fd(ns, db, &self.to).await?.iter() {
+			let name = fd.name.to_string();
+			let key = crate::key::table::fd::new(ns, db, &self.to, &name);
+			let mut val = fd.clone();
+			val.what = self.to.clone();
+			run.set(key, val).await?;
+		}
+		for ev in run.all_ev(ns, db, &self.to).await?.iter() {
+			let key = crate::key::table::ev::new(ns, db, &self.to, &ev.name);
+			let mut val = ev.clone();
+			val.what = self.to.clone();
+			run.set(key, val).await?;
+		}
+		for ix in run.all_ix(ns, db, &self.to).await?.iter() {
+			let key = crate::key::table::ix::new(ns, db, &self.to, &ix.name);
+			let mut val = ix.clone();
+			val.what = self.to.clone();
+			run.set(key, val).await?;
+		}
+		for co in run.all_co(ns, db, &self.to).await?.iter() {
+			let key = crate::key::table::co::new(ns, db, &self.to, &co.name);
+			let mut val = co.clone();
+			val.what = self.to.clone();
+			run.set(key, val).await?;
+		}
+		// The views built from this table are stored with the view definitions
+		for ft in run.all_ft(ns, db, &self.to).await?.iter() {
+			let mut val = ft.clone();
+			if let Some(view) = &mut val.view {
+				for v in view.what.0.iter_mut() {
+					if v.0 == self.name.0 {
+						*v = Table::from(self.to.to_raw());
+					}
+				}
+			}
+			run.set(crate::key::table::ft::new(ns, db, &self.to, &ft.name), &val).await?;
+			run.set(crate::key::database::tb::new(ns, db, &ft.name), &val).await?;
+		}
+		// Point the live queries on this table at the new table name
+		for lv in run.all_lv(ns, db, &self.to).await?.iter() {
+			let mut val = lv.clone();
+			if let Value::Table(v) = &mut val.what {
+				if v.0 == self.name.0 {
+					*v = Table::from(self.to.to_raw());
+				}
+			}
+			run.set(crate::key::table::lq::new(ns, db, &self.to, lv.id.0), val).await?;
+			let key = crate::key::node::lq::new(lv.node.0, lv.id.0, ns, db);
+			run.set(key, self.to.as_str()).await?;
+		}
+		// Clear the cached definitions
+		run.clr(crate::key::table::fd::prefix(ns, db, &self.to)).await?;
+		run.clr(crate::key::table::ev::prefix(ns, db, &self.to)).await?;
+		run.clr(crate::key::table::ix::prefix(ns, db, &self.to)).await?;
+		run.clr(crate::key::table::co::prefix(ns, db, &self.to)).await?;
+		run.clr(crate::key::table::ft::prefix(ns, db, &self.to)).await?;
+		run.clr(crate::key::table::lq::prefix(ns, db, &self.to)).await?;
+		run.clr(crate::key::database::tb::prefix(ns, db)).await?;
+		Ok(())
+	}
+
+	/// Rewrite the record ids which are stored within the next batch of records,
+	/// returning whether there were any records in the batch
+	async fn records(
+		&self,
+		run: &mut crate::kvs::Transaction,
+		ns: &str,
+		db: &str,
+		scanner: &mut Scanner,
+	) -> Result<bool, Error> {
+		let base = run.tb_base(ns, db, &self.to).await?;
+		// The record keys contain the table name in the original key format
+		let size = match base.is_compact() {
+			true => 0,
+			false => self.to.len() as i64 - self.name.len() as i64,
+		};
+		let mut st = Stats::default();
+		let res = scanner.next_batch(run).await?;
+		if res.is_empty() {
+			return Ok(false);
+		}
+		for (k, v) in res.into_iter() {
+			let len = v.len();
+			let mut val: Value = v.into();
+			// Records are only rewritten once, even if the rename is resumed
+			if self.rewrite(&mut val) {
+				st.keys += size;
+				let val: Vec<u8> = val.into();
+				st.values += val.len() as i64 - len as i64;
+				run.set(k, val).await?;
+			}
+		}
+		run.record_stats(ns, db, &self.to, st);
+		Ok(true)
+	}
+
+	/// Rewrite the next batch of graph edges of the table, and the edges which point
+	/// back to them, returning whether there were any edges in the batch
+	async fn edges(
+		&self,
+		run: &mut crate::kvs::Transaction,
+		ns: &str,
+		db: &str,
+		scanner: &mut Scanner,
+	) -> Result<bool, Error> {
+		let base = run.tb_base(ns, db, &self.to).await?;
+		let res = scanner.next_batch(run).await?;
+		if res.is_empty() {
+			return Ok(false);
+		}
+		for (k, _) in res.into_iter() {
+			let key = base.name(k.clone());
+			let gra: crate::key::graph::Graph = (&key).into();
+			// Edges between records of this table point at the new table name
+			if gra.ft == self.name.as_str() {
+				let fk = Thing::from((self.to.to_raw(), gra.fk.clone()));
+				let key = crate::key::graph::new(ns, db, &self.to, &gra.id, &gra.eg, &fk);
+				run.del(k).await?;
+				run.set(base.key(key), vec![]).await?;
+				continue;
+			}
+			// These edges have already been rewritten
+			if gra.ft == self.to.as_str() {
+				continue;
+			}
+			// Rewrite the edge which points back at this record
+			let ft = run.tb_base(ns, db, gra.ft).await?;
+			let eg = match gra.eg {
+				Dir::In => Dir::Out,
+				Dir::Out => Dir::In,
+				Dir::Both => Dir::Both,
+			};
+			let old = Thing::from((self.name.to_raw(), gra.id.clone()));
+			let key = ft.key(crate::key::graph::new(ns, db, gra.ft, &gra.fk, &eg, &old));
+			if run.exi(key.clone()).await? {
+				let new = Thing::from((self.to.to_raw(), gra.id.clone()));
+				run.del(key).await?;
+				let key = crate::key::graph::new(ns, db, gra.ft, &gra.fk, &eg, &new);
+				run.set(ft.key(key), vec![]).await?;
+			}
+			// Rewrite the record ids stored within the related record
+			let key = ft.key(crate::key::thing::new(ns, db, gra.ft, &gra.fk));
+			if let Some(v) = run.get(key.clone()).await? {
+				let mut val: Value = v.into();
+				if self.rewrite(&mut val) {
+					run.set(key, val).await?;
+				}
+			}
+		}
+		Ok(true)
+	}
+
+	/// Rewrite the next batch of change feed entries which were recorded for the
+	/// table, returning whether there were any change feed entries in the batch
+	async fn changes(
+		&self,
+		run: &mut crate::kvs::Transaction,
+		ns: &str,
+		db: &str,
+		scanner: &mut Scanner,
+	) -> Result<bool, Error> {
+		let res = scanner.next_batch(run).await?;
+		if res.is_empty() {
+			return Ok(false);
+		}
+		for (k, v) in res.into_iter() {
+			let cf = crate::key::change::Cf::decode(&k)?;
+			if cf.tb != self.name.as_str() {
+				continue;
+			}
+			let TableMutations(_, muts) = v.into();
+			let muts = muts.into_iter().map(|v| self.mutation(v)).collect();
+			let val = TableMutations(self.to.to_raw(), muts);
+			run.del(k.clone()).await?;
+			run.set(crate::key::change::Cf::new(ns, db, cf.vs, &self.to), val).await?;
+		}
+		Ok(true)
+	}
+
+	/// Point a change feed mutation at the new table name
+	fn mutation(&self, v: TableMutation) -> TableMutation {
+		match v {
+			TableMutation::Set(id, mut val) => {
+				self.rewrite(&mut val);
+				TableMutation::Set(self.thing(id), val)
+			}
+			TableMutation::Del(id) => TableMutation::Del(self.thing(id)),
+			TableMutation::DefineTable(mut v) => {
+				v.name = self.to.clone();
+				TableMutation::DefineTable(v)
+			}
+			TableMutation::RemoveTable(mut v) => {
+				v.name = self.to.clone();
+				TableMutation::RemoveTable(v)
+			}
+			TableMutation::DefineField(mut v) => {
+				v.what = self.to.clone();
+				TableMutation::DefineField(v)
+			}
+			TableMutation::RemoveField(mut v) => {
+				v.what = self.to.clone();
+				TableMutation::RemoveField(v)
+			}
+			TableMutation::DefineIndex(mut v) => {
+				v.what = self.to.clone();
+				TableMutation::DefineIndex(v)
+			}
+			TableMutation::RemoveIndex(mut v) => {
+				v.what = self.to.clone();
+				TableMutation::RemoveIndex(v)
+			}
+			TableMutation::DefineEvent(mut v) => {
+				v.what = self.to.clone();
+				TableMutation::DefineEvent(v)
+			}
+			TableMutation::RemoveEvent(mut v) => {
+				v.what = self.to.clone();
+				TableMutation::RemoveEvent(v)
+			}
+		}
+	}
+
+	/// Point a record id at the new table name, if it points at the renamed table
+	fn thing(&self, v: Thing) -> Thing {
+		match v.tb == self.name.as_str() {
+			true => Thing::from((self.to.to_raw(), v.id)),
+			false => v,
+		}
+	}
+
+	/// Point the record ids stored within a record at the new table name,
+	/// returning whether the record was changed
+	fn rewrite(&self, val: &mut Value) -> bool {
+		let mut changed = false;
+		for path in [&*ID, &*IN, &*OUT] {
+			if let Value::Thing(v) = val.pick(path) {
+				if v.tb == self.name.as_str() {
+					val.put(path, Value::Thing(self.thing(v)));
+					changed = true;
+				}
+			}
+		}
+		changed
+	}
+}
+
+impl Display for RenameTableStatement {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "RENAME TABLE {} TO {}", self.name, self.to)
+	}
+}
+
+fn table(i: &str) -> IResult<&str, RenameTableStatement> {
+	let (i, _) = tag_no_case("RENAME")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("TABLE")(i)?;
+	let (i, (name, to)) = names(i)?;
+	Ok((
+		i,
+		RenameTableStatement {
+			name,
+			to,
+		},
+	))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use std::collections::BTreeMap;
+
+	#[test]
+	fn rename_namespace() {
+		let sql = "RENAME NAMESPACE test TO other";
+		let res = rename(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("RENAME NAMESPACE test TO other", format!("{}", out));
+	}
+
+	#[test]
+	fn rename_database() {
+		let sql = "RENAME DB test TO other";
+		let res = rename(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("RENAME DATABASE test TO other", format!("{}", out));
+	}
+
+	#[test]
+	fn rename_table() {
+		let sql = "RENAME TABLE person TO `user`";
+		let res = rename(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("RENAME TABLE person TO user", format!("{}", out));
+	}
+
+	#[test]
+	fn rename_table_without_target() {
+		let sql = "RENAME TABLE person";
+		let res = rename(sql);
+		assert!(res.is_err());
+	}
+
+	#[tokio::test]
+	async fn rename_table_moves_live_queries() {
+		let ds = Datastore::new("memory").await.unwrap().with_notifications();
+		let mut ses = crate::dbs::Session::for_kv().with_ns("test").with_db("test");
+		ses.rt = true;
+		let sql = "
+			DEFINE TABLE person;
+			LIVE SELECT * FROM person;
+			RENAME TABLE person TO user;
+		";
+		let mut res = ds.execute(sql, &ses, None).await.unwrap();
+		res.remove(0).result.unwrap();
+		let Value::Uuid(id) = res.remove(0).result.unwrap() else {
+			panic!("expected a live query id")
+		};
+		res.remove(0).result.unwrap();
+		// The live query is stored on the renamed table
+		let mut tx = ds.transaction(false, false).await.unwrap();
+		let lvs = tx.all_lv("test", "test", "user").await.unwrap();
+		assert_eq!(lvs.len(), 1);
+		assert_eq!(lvs[0].what, Value::Table(Table::from("user")));
+		assert!(tx.all_lv("test", "test", "person").await.unwrap().is_empty());
+		// The node which registered the live query points at the renamed table
+		let lqs = tx.all_lq(&lvs[0].node.0).await.unwrap();
+		assert_eq!(lqs.len(), 1);
+		assert_eq!(lqs[0].lq, id);
+		assert_eq!(lqs[0].tb, "user");
+		tx.cancel().await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn rename_table_resumes_after_interruption() {
+		let ses = crate::dbs::Session::for_kv().with_ns("test").with_db("test");
+		// Store more records than are rewritten in a single batch, in the original key format
+		let ds = Datastore::new("memory").await.unwrap();
+		ds.set_key_format(crate::kvs::KeyFormat::V1).await.unwrap();
+		let people: Vec<Value> = (0..1200)
+			.map(|i| crate::sql::value(&format!("{{ id: person:{i}, num: {i} }}")).unwrap())
+			.collect();
+		let vars = BTreeMap::from([(String::from("people"), Value::from(people))]);
+		let sql = "
+			DEFINE INDEX num ON person FIELDS num UNIQUE;
+			INSERT INTO person $people;
+			RELATE person:0->knows->person:1;
+		";
+		for res in ds.execute(sql, &ses, Some(vars)).await.unwrap() {
+			res.result.unwrap();
+		}
+		// Move the definition and some of the records, and then stop as if the node had failed
+		let stm = RenameTableStatement {
+			name: Ident::from("person"),
+			to: Ident::from("user"),
+		};
+		let key = crate::key::database::rn::new("test", "test", "person").encode().unwrap();
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let mut step = stm.begin(&mut tx, "test", "test", &key).await.unwrap();
+		tx.commit().await.unwrap();
+		while !matches!(step, Step::Records(_)) {
+			assert!(stm.batch(&ds, "test", "test", &key, &mut step).await.unwrap());
+		}
+		assert!(stm.batch(&ds, "test", "test", &key, &mut step).await.unwrap());
+		drop(step);
+		// The table can only be renamed to the same name
+		let sql = "RENAME TABLE person TO other";
+		let res = ds.execute(sql, &ses, None).await.unwrap().remove(0).result;
+		assert!(matches!(res, Err(Error::RenameIncomplete { .. })));
+		// Running the rename again completes it
+		let sql = "
+			RENAME TABLE person TO user;
+			SELECT count() FROM user GROUP ALL;
+			SELECT VALUE id FROM user WHERE num = 1199;
+			SELECT VALUE ->knows->user FROM user:0;
+			CREATE user:other SET num = 10;
+		";
+		let mut res = ds.execute(sql, &ses, None).await.unwrap();
+		res.remove(0).result.unwrap();
+		let val = res.remove(0).result.unwrap();
+		assert_eq!(val, crate::sql::value("[{ count: 1200 }]").unwrap());
+		let val = res.remove(0).result.unwrap();
+		assert_eq!(val, crate::sql::value("[user:1199]").unwrap());
+		let val = res.remove(0).result.unwrap();
+		assert_eq!(val, crate::sql::value("[[user:1]]").unwrap());
+		let res = res.remove(0).result;
+		assert!(matches!(res, Err(Error::IndexExists { .. })));
+		// The progress of the rename was removed
+		let mut tx = ds.transaction(false, false).await.unwrap();
+		assert!(!tx.exi(key).await.unwrap());
+		tx.cancel().await.unwrap();
+	}
+}
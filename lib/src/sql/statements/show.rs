@@ -6,7 +6,9 @@ use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::sql::comment::shouldbespace;
 use crate::sql::common::take_u64;
+use crate::sql::datetime::{datetime, Datetime};
 use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
 use crate::sql::table::{table, Table};
 use crate::sql::value::Value;
 use derive::Store;
@@ -19,12 +21,30 @@ use nom::sequence::preceded;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+// ShowSince is the position in the change feed to start reading from,
+// either a versionstamp or a point in time.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
+pub enum ShowSince {
+	Versionstamp(u64),
+	Timestamp(Datetime),
+}
+
+impl fmt::Display for ShowSince {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Versionstamp(v) => write!(f, "{v}"),
+			Self::Timestamp(v) => write!(f, "{v}"),
+		}
+	}
+}
+
 // ShowStatement is used to show changes in a table or database via
 // the SHOW CHANGES statement.
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store, Hash)]
 pub struct ShowStatement {
 	pub table: Option<Table>,
-	pub since: Option<u64>,
+	pub consumer: Option<Ident>,
+	pub since: Option<ShowSince>,
 	pub limit: Option<u32>,
 }
 
@@ -47,15 +67,47 @@ impl ShowStatement {
 		let mut run = txn.lock().await;
 		// Process the show query
 		let tb = self.table.as_deref();
-		let r = crate::cf::read(
-			&mut run,
-			opt.ns(),
-			opt.db(),
-			tb.map(|x| x.as_str()),
-			self.since,
-			self.limit,
-		)
-		.await?;
+		let r = match (&self.consumer, tb) {
+			// Read the changes not yet acknowledged by the consumer
+			(Some(co), Some(tb)) => {
+				run.get_co(opt.ns(), opt.db(), tb, co).await?;
+				let vs = run.get_cp(opt.ns(), opt.db(), tb, co).await?;
+				crate::cf::read_after(&mut run, opt.ns(), opt.db(), Some(tb), vs, self.limit)
+					.await?
+			}
+			_ => match &self.since {
+				// Read the changes recorded since a point in time
+				Some(ShowSince::Timestamp(v)) => {
+					let ts = v.timestamp().max(0) as u64;
+					// Without a recorded versionstamp the whole change feed is read
+					let vs = run.get_versionstamp_from_timestamp(ts, opt.ns(), opt.db()).await?;
+					crate::cf::read_at(
+						&mut run,
+						opt.ns(),
+						opt.db(),
+						tb.map(|x| x.as_str()),
+						vs,
+						self.limit,
+					)
+					.await?
+				}
+				since => {
+					let since = match since {
+						Some(ShowSince::Versionstamp(v)) => Some(*v),
+						_ => None,
+					};
+					crate::cf::read(
+						&mut run,
+						opt.ns(),
+						opt.db(),
+						tb.map(|x| x.as_str()),
+						since,
+						self.limit,
+					)
+					.await?
+				}
+			},
+		};
 		// Return the changes
 		let mut a = Vec::<Value>::new();
 		for r in r.iter() {
@@ -74,6 +126,9 @@ impl fmt::Display for ShowStatement {
 			Some(ref v) => write!(f, " TABLE {}", v)?,
 			None => write!(f, " DATABASE")?,
 		}
+		if let Some(ref v) = self.consumer {
+			write!(f, " CONSUMER {}", v)?
+		}
 		if let Some(ref v) = self.since {
 			write!(f, " SINCE {}", v)?
 		}
@@ -92,11 +147,18 @@ pub fn table_or_database(i: &str) -> IResult<&str, Option<Table>> {
 	Ok((i, v))
 }
 
-pub fn since(i: &str) -> IResult<&str, u64> {
+pub fn consumer(i: &str) -> IResult<&str, Ident> {
+	let (i, _) = tag_no_case("CONSUMER")(i)?;
+	let (i, _) = shouldbespace(i)?;
+
+	ident(i)
+}
+
+pub fn since(i: &str) -> IResult<&str, ShowSince> {
 	let (i, _) = tag_no_case("SINCE")(i)?;
 	let (i, _) = shouldbespace(i)?;
 
-	take_u64(i)
+	alt((map(take_u64, ShowSince::Versionstamp), map(datetime, ShowSince::Timestamp)))(i)
 }
 
 pub fn limit(i: &str) -> IResult<&str, u32> {
@@ -112,12 +174,21 @@ pub fn show(i: &str) -> IResult<&str, ShowStatement> {
 	let (i, _) = tag_no_case("FOR")(i)?;
 	let (i, _) = shouldbespace(i)?;
 	let (i, table) = table_or_database(i)?;
-	let (i, since) = opt(preceded(shouldbespace, since))(i)?;
+	let (i, consumer) = match table {
+		Some(_) => opt(preceded(shouldbespace, consumer))(i)?,
+		None => (i, None),
+	};
+	// A consumer reads from its acknowledged position
+	let (i, since) = match consumer {
+		Some(_) => (i, None),
+		None => opt(preceded(shouldbespace, since))(i)?,
+	};
 	let (i, limit) = opt(preceded(shouldbespace, limit))(i)?;
 	Ok((
 		i,
 		ShowStatement {
 			table,
+			consumer,
 			since,
 			limit,
 		},
@@ -163,6 +234,16 @@ mod tests {
 		assert_eq!(sql, format!("{}", out))
 	}
 
+	#[test]
+	fn show_table_changes_since_datetime() {
+		let sql = "SHOW CHANGES FOR TABLE person SINCE '2023-09-07T15:03:14Z'";
+		let res = show(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert!(matches!(out.since, Some(ShowSince::Timestamp(_))));
+		assert_eq!(sql, format!("{}", out))
+	}
+
 	#[test]
 	fn show_table_changes_limit() {
 		let sql = "SHOW CHANGES FOR TABLE person LIMIT 10";
@@ -181,6 +262,24 @@ mod tests {
 		assert_eq!(sql, format!("{}", out))
 	}
 
+	#[test]
+	fn show_table_changes_consumer() {
+		let sql = "SHOW CHANGES FOR TABLE person CONSUMER replica LIMIT 10";
+		let res = show(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out))
+	}
+
+	#[test]
+	fn show_table_changes_consumer_since() {
+		let sql = "SHOW CHANGES FOR TABLE person CONSUMER replica SINCE 0";
+		let res = show(sql);
+		assert!(res.is_ok());
+		// A consumer can not be combined with a starting point
+		assert_eq!(" SINCE 0", res.unwrap().0);
+	}
+
 	#[test]
 	fn show_database_changes() {
 		let sql = "SHOW CHANGES FOR DATABASE";
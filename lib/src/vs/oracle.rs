@@ -5,7 +5,10 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use super::{u16_u64_to_versionstamp, u64_to_versionstamp, u64_u16_to_versionstamp, Versionstamp};
+use super::{
+	to_u128_be, try_u128_to_versionstamp, u16_u64_to_versionstamp, u64_to_versionstamp,
+	u64_u16_to_versionstamp, Versionstamp,
+};
 
 // A versionstamp oracle is a source of truth for the current versionstamp of the database.
 // There are several kinds of versionstamp oracles, each provides a different versionstamp
@@ -49,6 +52,18 @@ pub enum Oracle {
 	// EpochCounter is designed to be used instead of the SysTimeCounter when the runtime environment
 	// does not provide a monotonic system clock, and the database is running in a single-node mode.
 	EpochCounter(EpochCounter),
+	// HybridLogicalClock versionstamp oracle is a HLC which uses the system time in milliseconds as
+	// the physical time and a counter as the logical time, both of which are persisted together as
+	// the previous versionstamp of the database.
+	//
+	// Each versionstamp is greater than both the previous versionstamp and the current physical
+	// time, so the versionstamps are monotonic across restarts and across the nodes of a cluster,
+	// even when the clocks of the nodes drift or go backwards, while still approximating the time
+	// at which each change was made.
+	//
+	// This is suitable for multi-node installations, as long as the previous versionstamp is read
+	// and written in the same transaction as the change, so that concurrent transactions conflict.
+	HybridLogicalClock(HybridLogicalClock),
 }
 
 impl Oracle {
@@ -57,10 +72,25 @@ impl Oracle {
 		match self {
 			Oracle::SysTimeCounter(sys) => sys.now(),
 			Oracle::EpochCounter(epoch) => epoch.now(),
+			Oracle::HybridLogicalClock(hlc) => hlc.now(),
 		}
 	}
 }
 
+/// The versionstamp oracle which a datastore uses for its change feeds
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum VersionstampOracle {
+	/// Each storage engine allocates the versionstamps in its own way, which is
+	/// a persisted counter for each database, or the timestamp oracle of TiKV
+	#[default]
+	Engine,
+	/// The versionstamps are allocated by a hybrid logical clock which is
+	/// persisted for each database, and which is monotonic across the nodes
+	/// of a cluster
+	HybridLogicalClock,
+}
+
 pub struct SysTimeCounter {
 	// The first element is the saved physical time of the last versionstamp.
 	// The second element is the in-memory counter that resets every second.
@@ -119,6 +149,36 @@ impl EpochCounter {
 	}
 }
 
+pub struct HybridLogicalClock {
+	// The previous versionstamp, which was persisted by the database
+	prev: Option<Versionstamp>,
+}
+
+impl HybridLogicalClock {
+	pub fn new(prev: Option<Versionstamp>) -> Self {
+		Self {
+			prev,
+		}
+	}
+
+	pub fn now(&mut self) -> Versionstamp {
+		self.now_at(millis_since_unix_epoch())
+	}
+
+	fn now_at(&mut self, physical_time: u64) -> Versionstamp {
+		// Use the current physical time with a zero logical time, unless the previous
+		// versionstamp is not older, in which case the previous versionstamp is incremented.
+		let current = to_u128_be(u64_u16_to_versionstamp(physical_time, 0));
+		let next = match self.prev {
+			Some(prev) => current.max(to_u128_be(prev).saturating_add(1)),
+			None => current,
+		};
+		let vs = try_u128_to_versionstamp(next).unwrap_or([u8::MAX; 10]);
+		self.prev = Some(vs);
+		vs
+	}
+}
+
 #[allow(unused)]
 fn now() -> Versionstamp {
 	let secs = secs_since_unix_epoch();
@@ -133,6 +193,13 @@ fn secs_since_unix_epoch() -> u64 {
 	since_the_epoch.as_secs()
 }
 
+// Returns the number of milliseconds since the Unix Epoch (January 1st, 1970 at UTC).
+fn millis_since_unix_epoch() -> u64 {
+	let start = SystemTime::now();
+	let since_the_epoch = start.duration_since(UNIX_EPOCH).expect("Time went backwards");
+	since_the_epoch.as_millis() as u64
+}
+
 mod tests {
 	#[allow(unused)]
 	use super::*;
@@ -166,4 +233,24 @@ mod tests {
 		let c = to_u128_be(o2.now());
 		assert!(b < c, "b = {}, c = {}", b, c);
 	}
+
+	#[test]
+	fn hybrid_logical_clock() {
+		let mut o = HybridLogicalClock::new(None);
+		// The physical time is used when it has moved on
+		let a = o.now_at(1000);
+		assert_eq!(a, u64_u16_to_versionstamp(1000, 0));
+		let b = o.now_at(2000);
+		assert_eq!(b, u64_u16_to_versionstamp(2000, 0));
+		// The logical time is incremented when the physical time has not moved on
+		let c = o.now_at(2000);
+		assert_eq!(c, u64_u16_to_versionstamp(2000, 1));
+		// The versionstamps are monotonic when the clock goes backwards
+		let d = o.now_at(1500);
+		assert_eq!(d, u64_u16_to_versionstamp(2000, 2));
+		// Another node continues from the persisted versionstamp
+		let mut o = Oracle::HybridLogicalClock(HybridLogicalClock::new(Some(d)));
+		let e = o.now();
+		assert!(to_u128_be(d) < to_u128_be(e), "d = {:?}, e = {:?}", d, e);
+	}
 }
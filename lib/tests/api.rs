@@ -84,6 +84,7 @@ mod api_integration {
 		}
 
 		include!("api/mod.rs");
+		include!("api/live.rs");
 	}
 
 	#[cfg(feature = "protocol-http")]
@@ -173,6 +174,7 @@ mod api_integration {
 		}
 
 		include!("api/mod.rs");
+		include!("api/live.rs");
 		include!("api/backup.rs");
 	}
 
@@ -195,6 +197,7 @@ mod api_integration {
 		}
 
 		include!("api/mod.rs");
+		include!("api/live.rs");
 		include!("api/backup.rs");
 	}
 
@@ -211,6 +214,7 @@ mod api_integration {
 		}
 
 		include!("api/mod.rs");
+		include!("api/live.rs");
 		include!("api/backup.rs");
 	}
 
@@ -227,6 +231,24 @@ mod api_integration {
 		}
 
 		include!("api/mod.rs");
+		include!("api/live.rs");
+		include!("api/backup.rs");
+	}
+
+	#[cfg(feature = "kv-redb")]
+	mod redb {
+		use super::*;
+		use surrealdb::engine::local::Db;
+		use surrealdb::engine::local::Redb;
+
+		async fn new_db() -> Surreal<Db> {
+			init_logger();
+			let path = format!("/tmp/{}.db", Ulid::new());
+			Surreal::new::<Redb>(path.as_str()).await.unwrap()
+		}
+
+		include!("api/mod.rs");
+		include!("api/live.rs");
 		include!("api/backup.rs");
 	}
 
@@ -248,6 +270,7 @@ mod api_integration {
 		}
 
 		include!("api/mod.rs");
+		include!("api/live.rs");
 		include!("api/backup.rs");
 	}
 
@@ -269,6 +292,7 @@ mod api_integration {
 		}
 
 		include!("api/mod.rs");
+		include!("api/live.rs");
 		include!("api/backup.rs");
 	}
 
@@ -0,0 +1,89 @@
+// Tests for live queries
+// Supported by the storage engines and the WS protocol
+
+use futures::StreamExt;
+use std::time::Duration;
+use surrealdb::dbs::Action;
+use tokio::time::timeout;
+
+const LQ_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[tokio::test]
+async fn live_select_table() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let mut users = db.live::<RecordBuf>("user").await.unwrap();
+	// Create a record
+	let _: Option<RecordId> = db
+		.create(("user", "john"))
+		.content(Record {
+			name: "John Doe",
+		})
+		.await
+		.unwrap();
+	let notification = timeout(LQ_TIMEOUT, users.next()).await.unwrap().unwrap().unwrap();
+	assert_eq!(&notification.query_id, users.id());
+	assert_eq!(notification.action, Action::Create);
+	assert_eq!(notification.data.id, thing("user:john").unwrap());
+	assert_eq!(notification.data.name, "John Doe");
+	// Update the record
+	let _: Option<RecordId> = db
+		.update(("user", "john"))
+		.content(Record {
+			name: "Jane Doe",
+		})
+		.await
+		.unwrap();
+	let notification = timeout(LQ_TIMEOUT, users.next()).await.unwrap().unwrap().unwrap();
+	assert_eq!(notification.action, Action::Update);
+	assert_eq!(notification.data.name, "Jane Doe");
+	// Delete notifications only carry the record ID
+	let mut users = db.live::<Thing>("user").await.unwrap();
+	let _: Option<RecordId> = db.delete(("user", "john")).await.unwrap();
+	let notification = timeout(LQ_TIMEOUT, users.next()).await.unwrap().unwrap().unwrap();
+	assert_eq!(notification.action, Action::Delete);
+	assert_eq!(notification.data, thing("user:john").unwrap());
+}
+
+#[tokio::test]
+async fn live_select_query() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let mut response = db
+		.query("CREATE user:jane SET name = 'Jane Doe'")
+		.query("LIVE SELECT * FROM user WHERE name = 'John Doe'")
+		.await
+		.unwrap();
+	response.stream::<RecordBuf>(0).unwrap_err();
+	let mut users = response.stream::<RecordBuf>(1).unwrap();
+	// Only records matching the condition are sent
+	let _: Option<RecordId> = db
+		.create(("user", "jack"))
+		.content(Record {
+			name: "Jack Doe",
+		})
+		.await
+		.unwrap();
+	let _: Option<RecordId> = db
+		.create(("user", "john"))
+		.content(Record {
+			name: "John Doe",
+		})
+		.await
+		.unwrap();
+	let notification = timeout(LQ_TIMEOUT, users.next()).await.unwrap().unwrap().unwrap();
+	assert_eq!(notification.action, Action::Create);
+	assert_eq!(notification.data.id, thing("user:john").unwrap());
+}
+
+#[tokio::test]
+async fn live_select_killed_on_drop() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let users = db.live::<RecordBuf>("user").await.unwrap();
+	let id = users.id().clone();
+	drop(users);
+	// The live query is killed in the background
+	tokio::time::sleep(Duration::from_millis(100)).await;
+	db.kill(id).await.unwrap_err();
+}
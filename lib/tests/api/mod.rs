@@ -716,10 +716,25 @@ async fn changefeed() {
 	let mut response = db.query(sql).await.unwrap();
 	let value: Value = response.take(0).unwrap();
 	let Value::Array(array) = value.clone() else { unreachable!() };
-	assert_eq!(array.len(), 4);
-	// UPDATE user:amos
+	assert_eq!(array.len(), 5);
+	// DEFINE TABLE
 	let a = array.get(0).unwrap();
 	let Value::Object(a) = a else { unreachable!() };
+	let changes = a.get("changes").unwrap().to_owned();
+	assert_eq!(
+		changes,
+		surrealdb::sql::value(
+			"[
+		{
+			define_table: 'DEFINE TABLE user SCHEMALESS CHANGEFEED 1h'
+		}
+	]"
+		)
+		.unwrap()
+	);
+	// UPDATE user:amos
+	let a = array.get(1).unwrap();
+	let Value::Object(a) = a else { unreachable!() };
 	let Value::Number(versionstamp1) = a.get("versionstamp").unwrap() else { unreachable!() };
 	let changes = a.get("changes").unwrap().to_owned();
 	assert_eq!(
@@ -737,7 +752,7 @@ async fn changefeed() {
 		.unwrap()
 	);
 	// UPDATE user:jane
-	let a = array.get(1).unwrap();
+	let a = array.get(2).unwrap();
 	let Value::Object(a) = a else { unreachable!() };
 	let Value::Number(versionstamp2) = a.get("versionstamp").unwrap() else { unreachable!() };
 	assert!(versionstamp1 < versionstamp2);
@@ -757,7 +772,7 @@ async fn changefeed() {
 		.unwrap()
 	);
 	// UPDATE user:amos
-	let a = array.get(2).unwrap();
+	let a = array.get(3).unwrap();
 	let Value::Object(a) = a else { unreachable!() };
 	let Value::Number(versionstamp3) = a.get("versionstamp").unwrap() else { unreachable!() };
 	assert!(versionstamp2 < versionstamp3);
@@ -777,7 +792,7 @@ async fn changefeed() {
 		.unwrap()
 	);
 	// UPDATE table
-	let a = array.get(3).unwrap();
+	let a = array.get(4).unwrap();
 	let Value::Object(a) = a else { unreachable!() };
 	let Value::Number(versionstamp4) = a.get("versionstamp").unwrap() else { unreachable!() };
 	assert!(versionstamp3 < versionstamp4);
@@ -1,8 +1,11 @@
 mod parse;
 use parse::Parse;
+use std::time::{SystemTime, UNIX_EPOCH};
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
 use surrealdb::kvs::Datastore;
+use surrealdb::kvs::VersionstampOracle;
+use surrealdb::sql::Part;
 use surrealdb::sql::Value;
 
 #[tokio::test]
@@ -99,6 +102,22 @@ async fn table_change_feeds() -> Result<(), Error> {
 		"[
 			{
 				versionstamp: 65536,
+				changes: [
+					{
+						define_table: 'DEFINE TABLE person SCHEMALESS CHANGEFEED 1h'
+					}
+				]
+			},
+			{
+				versionstamp: 131072,
+				changes: [
+					{
+						define_field: \"DEFINE FIELD name ON person VALUE IF $input THEN 'Name: ' + $input ELSE $value END ASSERT IF $input THEN $input = /^[A-Z]{1}[a-z]+$/ ELSE true END\"
+					}
+				]
+			},
+			{
+				versionstamp: 196608,
 				changes: [
 					{
 						update: {
@@ -109,7 +128,7 @@ async fn table_change_feeds() -> Result<(), Error> {
 				]
 			},
 			{
-				versionstamp: 131072,
+				versionstamp: 262144,
 				changes: [
 					{
 						update: {
@@ -120,7 +139,7 @@ async fn table_change_feeds() -> Result<(), Error> {
 				]
 			},
 			{
-				versionstamp: 196608,
+				versionstamp: 327680,
 				changes: [
 					{
 						update: {
@@ -131,7 +150,7 @@ async fn table_change_feeds() -> Result<(), Error> {
 				]
 			},
 			{
-				versionstamp: 262144,
+				versionstamp: 393216,
 				changes: [
 					{
 						delete: {
@@ -141,7 +160,7 @@ async fn table_change_feeds() -> Result<(), Error> {
 				]
 			},
 			{
-				versionstamp: 327680,
+				versionstamp: 458752,
 				changes: [
 					{
 						update: {
@@ -157,3 +176,466 @@ async fn table_change_feeds() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn table_change_feeds_with_savepoints() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		BEGIN;
+		SAVEPOINT one;
+		CREATE person:one;
+		SAVEPOINT two;
+		CREATE person:two;
+		ROLLBACK TO two;
+		CREATE person:three;
+		COMMIT;
+		SHOW CHANGES FOR TABLE person SINCE 0;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	// DEFINE TABLE
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	// CREATE
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	// CREATE
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::QueryCancelled)));
+	// CREATE
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	// The rolled back record is not in the change feed
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				versionstamp: 65536,
+				changes: [
+					{
+						define_table: 'DEFINE TABLE person SCHEMALESS CHANGEFEED 1h'
+					}
+				]
+			},
+			{
+				versionstamp: 131072,
+				changes: [
+					{
+						update: {
+							id: person:one
+						}
+					},
+					{
+						update: {
+							id: person:three
+						}
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn database_change_feeds_record_definitions() -> Result<(), Error> {
+	let sql = "
+		DEFINE DATABASE test CHANGEFEED 1h;
+		DEFINE TABLE person;
+		DEFINE FIELD name ON TABLE person TYPE string;
+		DEFINE INDEX name ON TABLE person FIELDS name;
+		DEFINE EVENT log ON TABLE person WHEN true THEN NONE;
+		REMOVE EVENT log ON TABLE person;
+		REMOVE INDEX name ON TABLE person;
+		REMOVE FIELD name ON TABLE person;
+		REMOVE TABLE person;
+		SHOW CHANGES FOR DATABASE SINCE 0;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 10);
+	//
+	for _ in 0..9 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				versionstamp: 65536,
+				changes: [
+					{
+						define_table: 'DEFINE TABLE person SCHEMALESS'
+					}
+				]
+			},
+			{
+				versionstamp: 131072,
+				changes: [
+					{
+						define_field: 'DEFINE FIELD name ON person TYPE string'
+					}
+				]
+			},
+			{
+				versionstamp: 196608,
+				changes: [
+					{
+						define_index: 'DEFINE INDEX name ON person FIELDS name'
+					}
+				]
+			},
+			{
+				versionstamp: 262144,
+				changes: [
+					{
+						define_event: 'DEFINE EVENT log ON person WHEN true THEN NONE'
+					}
+				]
+			},
+			{
+				versionstamp: 327680,
+				changes: [
+					{
+						remove_event: 'REMOVE EVENT log ON person'
+					}
+				]
+			},
+			{
+				versionstamp: 393216,
+				changes: [
+					{
+						remove_index: 'REMOVE INDEX name ON person'
+					}
+				]
+			},
+			{
+				versionstamp: 458752,
+				changes: [
+					{
+						remove_field: 'REMOVE FIELD name ON person'
+					}
+				]
+			},
+			{
+				versionstamp: 524288,
+				changes: [
+					{
+						remove_table: 'REMOVE TABLE person'
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn table_change_feeds_consumers() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		DEFINE CONSUMER replica ON TABLE person;
+		CREATE person:1 SET name = 'Tobie';
+		CREATE person:2 SET name = 'Jaime';
+		SHOW CHANGES FOR TABLE person CONSUMER replica;
+		ACKNOWLEDGE CONSUMER replica ON TABLE person AT 131072;
+		SHOW CHANGES FOR TABLE person CONSUMER replica;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				versionstamp: 65536,
+				changes: [
+					{
+						define_table: 'DEFINE TABLE person SCHEMALESS CHANGEFEED 1h'
+					}
+				]
+			},
+			{
+				versionstamp: 131072,
+				changes: [
+					{
+						update: { id: person:1, name: 'Tobie' }
+					}
+				]
+			},
+			{
+				versionstamp: 196608,
+				changes: [
+					{
+						update: { id: person:2, name: 'Jaime' }
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				versionstamp: 196608,
+				changes: [
+					{
+						update: { id: person:2, name: 'Jaime' }
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Record the current versionstamp, and write a further change
+	dbs.tick_at(10).await?;
+	let sql = "
+		CREATE person:3 SET name = 'Yusuke';
+	";
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 1);
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	// Expire the retention period, keeping unacknowledged changes
+	dbs.tick_at(3611).await?;
+	let sql = "
+		SHOW CHANGES FOR TABLE person SINCE 0;
+		ACKNOWLEDGE CONSUMER replica ON TABLE person AT 327680;
+	";
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				versionstamp: 196608,
+				changes: [
+					{
+						update: { id: person:2, name: 'Jaime' }
+					}
+				]
+			},
+			{
+				versionstamp: 327680,
+				changes: [
+					{
+						update: { id: person:3, name: 'Yusuke' }
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	// Only changes within the retention period are kept now
+	dbs.tick_at(3612).await?;
+	let sql = "
+		SHOW CHANGES FOR TABLE person SINCE 0;
+		ACKNOWLEDGE CONSUMER unknown ON TABLE person AT 0;
+	";
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				versionstamp: 327680,
+				changes: [
+					{
+						update: { id: person:3, name: 'Yusuke' }
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The consumer 'unknown' does not exist"
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn table_change_feeds_consumers_require_change_feed() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person;
+		DEFINE CONSUMER replica ON TABLE person;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The table 'person' does not have a change feed"
+	));
+	// A consumer can not be read from a starting point
+	let sql = "SHOW CHANGES FOR TABLE person CONSUMER replica SINCE 0";
+	let res = dbs.execute(sql, &ses, None).await;
+	assert!(res.is_err());
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn table_change_feeds_consumers_prune_timestamps() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		DEFINE CONSUMER replica ON TABLE person;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// Timestamps outside of the retention period are removed,
+	// even though the consumer has no maximum lag
+	for ts in [10, 20, 30, 3700] {
+		dbs.tick_at(ts).await?;
+	}
+	let beg = surrealdb::key::database::ts::prefix("test", "test");
+	let end = surrealdb::key::database::ts::suffix("test", "test");
+	let mut tx = dbs.transaction(false, false).await?;
+	let res = tx.getr(beg..end, u32::MAX).await?;
+	tx.cancel().await?;
+	let ts = res
+		.iter()
+		.map(|(k, _)| surrealdb::key::database::ts::Ts::decode(k).map(|k| k.ts))
+		.collect::<Result<Vec<_>, _>>()?;
+	assert_eq!(ts, vec![30, 3700]);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn table_change_feeds_since_timestamp() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		CREATE person:1 SET name = 'Tobie';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	// Record the current versionstamp, and write a further change
+	dbs.tick_at(60).await?;
+	let sql = "
+		CREATE person:2 SET name = 'Jaime';
+		SHOW CHANGES FOR TABLE person SINCE '1970-01-01T00:01:30Z';
+		SHOW CHANGES FOR TABLE person SINCE '1970-01-01T00:00:30Z' LIMIT 2;
+	";
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				versionstamp: 262144,
+				changes: [
+					{
+						update: { id: person:2, name: 'Jaime' }
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Nothing was recorded before this point in time
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				versionstamp: 65536,
+				changes: [
+					{
+						define_table: 'DEFINE TABLE person SCHEMALESS CHANGEFEED 1h'
+					}
+				]
+			},
+			{
+				versionstamp: 131072,
+				changes: [
+					{
+						update: { id: person:1, name: 'Tobie' }
+					}
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn table_change_feeds_with_hybrid_logical_clock() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		CREATE person:1 SET name = 'Tobie';
+		CREATE person:2 SET name = 'Jaime';
+		SHOW CHANGES FOR TABLE person SINCE 0;
+	";
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+	let dbs = Datastore::new("memory")
+		.await?
+		.with_versionstamp_oracle(VersionstampOracle::HybridLogicalClock);
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	// The versionstamps start at the physical time in milliseconds
+	let tmp = res.remove(3).result?;
+	let Value::Array(changes) = tmp else {
+		panic!("expected an array of changes, got {tmp}");
+	};
+	assert_eq!(changes.len(), 3);
+	let mut prev = 0;
+	for change in changes.iter() {
+		let Value::Number(vs) = change.pick(&[Part::from("versionstamp")]) else {
+			panic!("expected a versionstamp, got {change}");
+		};
+		let vs = vs.to_int() as u128;
+		assert!(vs >> 16 >= now, "versionstamp {vs} is before {now}");
+		assert!(vs > prev, "versionstamp {vs} is not after {prev}");
+		prev = vs;
+	}
+	Ok(())
+}
@@ -58,6 +58,166 @@ async fn define_statement_database() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn define_statement_database_quota() -> Result<(), Error> {
+	let sql = "
+		DEFINE DATABASE test MAX RECORDS 2 MAX TABLES 1;
+		INFO FOR NS;
+		CREATE person:1;
+		CREATE person:2;
+		CREATE person:3;
+		UPDATE person:1 SET name = 'Tobie';
+		CREATE company:1;
+		DELETE person:2;
+		CREATE person:3;
+		INFO FOR NS STATS;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 10);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			databases: { test: 'DEFINE DATABASE test MAX RECORDS 2 MAX TABLES 1' },
+			logins: {},
+			tokens: {},
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The database 'test' has reached its limit of 2 records"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The database 'test' has reached its limit of 1 tables"
+	));
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&["records".into()]), Value::from(2));
+	assert_eq!(tmp.pick(&["tables".into()]), Value::from(1));
+	assert_eq!(tmp.pick(&["quota".into()]), Value::parse("{}"));
+	assert_eq!(
+		tmp.pick(&["databases".into(), "test".into(), "quota".into()]),
+		Value::parse("{ records: 2, tables: 1 }")
+	);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_database_quota_in_transaction() -> Result<(), Error> {
+	let sql = "
+		DEFINE DATABASE test MAX RECORDS 3;
+		CREATE person:1;
+		BEGIN;
+		CREATE person:2;
+		CREATE person:3;
+		CREATE person:4;
+		COMMIT;
+		CREATE person:2, person:3;
+		INFO FOR DB STATS;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_err());
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The database 'test' has reached its limit of 3 records"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&["records".into()]), Value::from(3));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_namespace_quota() -> Result<(), Error> {
+	let sql = "
+		DEFINE NAMESPACE test MAX STORAGE 1KB;
+		INFO FOR KV;
+		CREATE person:1 SET name = 'Tobie';
+		CREATE person:2 SET name = string::repeat('a', 2000);
+		UPDATE person:1 SET name = string::repeat('a', 2000);
+		UPDATE person:1 SET name = 'Jaime';
+		INFO FOR NS STATS;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			namespaces: { test: 'DEFINE NAMESPACE test MAX STORAGE 1024' },
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(matches!(
+			tmp.err(),
+			Some(e) if e.to_string() == "The namespace 'test' has reached its limit of 1024 bytes of storage"
+		));
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&["records".into()]), Value::from(1));
+	assert_eq!(tmp.pick(&["quota".into()]), Value::parse("{ storage: 1024 }"));
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn define_statement_function() -> Result<(), Error> {
 	let sql = "
@@ -81,7 +241,9 @@ async fn define_statement_function() -> Result<(), Error> {
 			logins: {},
 			tokens: {},
 			functions: { test: 'DEFINE FUNCTION fn::test($first: string, $last: string) { RETURN $first + $last; }' },
+			jobs: {},
 			params: {},
+			runs: {},
 			scopes: {},
 			params: {},
 			scopes: {},
@@ -114,7 +276,9 @@ async fn define_statement_table_drop() -> Result<(), Error> {
 			logins: {},
 			tokens: {},
 			functions: {},
+			jobs: {},
 			params: {},
+			runs: {},
 			scopes: {},
 			tables: { test: 'DEFINE TABLE test DROP SCHEMALESS' },
 		}",
@@ -145,7 +309,9 @@ async fn define_statement_table_schemaless() -> Result<(), Error> {
 			logins: {},
 			tokens: {},
 			functions: {},
+			jobs: {},
 			params: {},
+			runs: {},
 			scopes: {},
 			tables: { test: 'DEFINE TABLE test SCHEMALESS' },
 		}",
@@ -180,7 +346,9 @@ async fn define_statement_table_schemafull() -> Result<(), Error> {
 			logins: {},
 			tokens: {},
 			functions: {},
+			jobs: {},
 			params: {},
+			runs: {},
 			scopes: {},
 			tables: { test: 'DEFINE TABLE test SCHEMAFULL' },
 		}",
@@ -211,7 +379,9 @@ async fn define_statement_table_schemaful() -> Result<(), Error> {
 			logins: {},
 			tokens: {},
 			functions: {},
+			jobs: {},
 			params: {},
+			runs: {},
 			scopes: {},
 			tables: { test: 'DEFINE TABLE test SCHEMAFULL' },
 		}",
@@ -250,6 +420,7 @@ async fn define_statement_event() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			consumers: {},
 			events: { test: 'DEFINE EVENT test ON user WHEN true THEN (CREATE activity SET user = $this, value = $after.email, action = $event)' },
 			fields: {},
 			tables: {},
@@ -307,6 +478,7 @@ async fn define_statement_event_when_event() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		r#"{
+			consumers: {},
 			events: { test: "DEFINE EVENT test ON user WHEN $event = 'CREATE' THEN (CREATE activity SET user = $this, value = $after.email, action = $event)" },
 			fields: {},
 			tables: {},
@@ -364,6 +536,7 @@ async fn define_statement_event_when_logic() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			consumers: {},
 			events: { test: 'DEFINE EVENT test ON user WHEN $before.email != $after.email THEN (CREATE activity SET user = $this, value = $after.email, action = $event)' },
 			fields: {},
 			tables: {},
@@ -413,6 +586,7 @@ async fn define_statement_field() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			consumers: {},
 			events: {},
 			fields: { test: 'DEFINE FIELD test ON user' },
 			tables: {},
@@ -445,6 +619,7 @@ async fn define_statement_field_type() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			consumers: {},
 			events: {},
 			fields: { test: 'DEFINE FIELD test ON user TYPE string' },
 			tables: {},
@@ -477,6 +652,7 @@ async fn define_statement_field_value() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		r#"{
+			consumers: {},
 			events: {},
 			fields: { test: "DEFINE FIELD test ON user VALUE $value OR 'GBR'" },
 			tables: {},
@@ -509,6 +685,7 @@ async fn define_statement_field_assert() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			consumers: {},
 			events: {},
 			fields: { test: 'DEFINE FIELD test ON user ASSERT $value != NONE AND $value = /[A-Z]{3}/' },
 			tables: {},
@@ -541,6 +718,7 @@ async fn define_statement_field_type_value_assert() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		r#"{
+			consumers: {},
 			events: {},
 			fields: { test: "DEFINE FIELD test ON user TYPE string VALUE $value OR 'GBR' ASSERT $value != NONE AND $value = /[A-Z]{3}/" },
 			tables: {},
@@ -583,6 +761,7 @@ async fn define_statement_index_single_simple() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			consumers: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -625,6 +804,7 @@ async fn define_statement_index_single() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			consumers: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -669,6 +849,7 @@ async fn define_statement_index_multiple() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			consumers: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -721,6 +902,7 @@ async fn define_statement_index_single_unique() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			consumers: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -779,6 +961,7 @@ async fn define_statement_index_multiple_unique() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			consumers: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -865,6 +1048,7 @@ async fn define_statement_index_single_unique_existing() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			consumers: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -876,6 +1060,84 @@ async fn define_statement_index_single_unique_existing() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn define_statement_index_concurrently() -> Result<(), Error> {
+	let sql = "
+		CREATE user:1 SET email = 'test@surrealdb.com';
+		CREATE user:2 SET email = 'test@surrealdb.dev';
+		DEFINE INDEX test ON user FIELDS email CONCURRENTLY;
+		CREATE user:3 SET email = 'test@surrealdb.dev';
+		SELECT id FROM user WHERE email = 'test@surrealdb.dev' EXPLAIN;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The index is not used until it has been built
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				detail: {
+					table: 'user'
+				},
+				operation: 'Iterate Table'
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The index is built by the node agent
+	dbs.tick().await?;
+	let sql = "
+		SELECT id FROM user WHERE email = 'test@surrealdb.dev' EXPLAIN;
+		SELECT VALUE id FROM user WHERE email = 'test@surrealdb.dev';
+		INFO FOR TABLE user;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'test',
+						operator: '=',
+						value: 'test@surrealdb.dev'
+					},
+					table: 'user',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[user:2, user:3]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			consumers: {},
+			events: {},
+			fields: {},
+			tables: {},
+			indexes: { test: 'DEFINE INDEX test ON user FIELDS email CONCURRENTLY' },
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn define_statement_index_multiple_unique_existing() -> Result<(), Error> {
 	let sql = "
@@ -912,6 +1174,7 @@ async fn define_statement_index_multiple_unique_existing() -> Result<(), Error>
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			consumers: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -947,6 +1210,7 @@ async fn define_statement_index_single_unique_embedded_multiple() -> Result<(),
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			consumers: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -994,6 +1258,7 @@ async fn define_statement_index_multiple_unique_embedded_multiple() -> Result<()
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			consumers: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -1054,7 +1319,9 @@ async fn define_statement_analyzer() -> Result<(), Error> {
 			logins: {},
 			tokens: {},
 			functions: {},
+			jobs: {},
 			params: {},
+			runs: {},
 			scopes: {},
 			tables: {}
 		}",
@@ -1089,6 +1356,7 @@ async fn define_statement_search_index() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			consumers: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -0,0 +1,252 @@
+mod parse;
+use parse::Parse;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::kvs::Datastore;
+use surrealdb::sql::Value;
+
+fn now() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+#[tokio::test]
+async fn table_ttl_deletes_expired_records() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE session CHANGEFEED 1h TTL 1m;
+		DEFINE EVENT expired ON session WHEN $event = 'DELETE' THEN (
+			CREATE log SET session = $before.id
+		);
+		CREATE session:one SET user = 'tobie';
+		CREATE session:two SET user = 'jaime';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The records have not expired yet
+	dbs.tick_at(now()).await?;
+	let res = &mut dbs.execute("SELECT id FROM session", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: session:one }, { id: session:two }]");
+	assert_eq!(tmp, val);
+	// The records are deleted once they have expired
+	dbs.tick_at(now() + 120).await?;
+	let sql = "
+		SELECT id FROM session;
+		SELECT session FROM log ORDER BY session;
+		SHOW CHANGES FOR TABLE session SINCE 0 LIMIT 10;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ session: session:one }, { session: session:two }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let Value::Array(changes) = tmp else {
+		panic!("Unexpected changes: {tmp}");
+	};
+	let val = Value::parse("[{ delete: { id: session:one } }, { delete: { id: session:two } }]");
+	assert_eq!(changes.last().unwrap().pick(&["changes".into()]), val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn table_ttl_hides_expired_records() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE session TTL 1s;
+		CREATE session:one SET user = 'tobie';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The expired records are not returned before they are deleted
+	tokio::time::sleep(Duration::from_secs(2)).await;
+	let sql = "
+		SELECT * FROM session;
+		SELECT * FROM session:one;
+		INFO FOR TABLE session STATS;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&["records".into()]), Value::from(1));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn table_ttl_ignores_expired_records() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE session TTL 1s;
+		DEFINE INDEX user ON session FIELDS user UNIQUE;
+		CREATE user:tobie;
+		CREATE session:one SET user = 'tobie', token = 'abc';
+		RELATE user:tobie->owns->session:one;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The expired records are not seen by any statement
+	tokio::time::sleep(Duration::from_secs(2)).await;
+	let sql = "
+		DEFINE TABLE session TTL 1h;
+		SELECT VALUE ->owns->session FROM user:tobie;
+		SELECT out FROM owns FETCH out;
+		DELETE session:one RETURN BEFORE;
+		UPDATE session:one SET user = 'tobie';
+		SELECT * FROM session;
+		INFO FOR TABLE session STATS;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[[]]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ out: NONE }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	// The expired record is replaced, rather than updated
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: session:one, user: 'tobie' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: session:one, user: 'tobie' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&["records".into()]), Value::from(1));
+	// The replaced record no longer expires at the original time
+	dbs.tick_at(now() + 120).await?;
+	let res = &mut dbs.execute("SELECT id FROM session", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: session:one }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn table_ttl_creates_over_expired_records() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE session TTL 1s;
+		DEFINE INDEX user ON session FIELDS user UNIQUE;
+		CREATE session:one SET user = 'tobie';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// Writing to an expired record is the same as creating it
+	tokio::time::sleep(Duration::from_secs(2)).await;
+	let sql = "
+		DEFINE TABLE session TTL 1h;
+		UPDATE session:one SET user = 'jaime' RETURN BEFORE;
+		CREATE session:two SET user = 'tobie';
+		INFO FOR TABLE session STATS;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[NONE]");
+	assert_eq!(tmp, val);
+	// The index entries of the expired record are removed
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: session:two, user: 'tobie' }]");
+	assert_eq!(tmp, val);
+	// The statistics match those of the records being created
+	let stats = res.remove(0).result?;
+	let sql = "
+		DEFINE TABLE session TTL 1h;
+		DEFINE INDEX user ON session FIELDS user UNIQUE;
+		CREATE session:one SET user = 'jaime';
+		CREATE session:two SET user = 'tobie';
+		INFO FOR TABLE session STATS;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	let tmp = res.remove(4).result?;
+	assert_eq!(tmp, stats);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn table_without_ttl_keeps_records() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE session TTL 1m;
+		CREATE session:one SET user = 'tobie';
+		DEFINE TABLE session;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// Removing the TTL stops the records from expiring
+	dbs.tick_at(now() + 120).await?;
+	let res = &mut dbs.execute("SELECT id FROM session", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: session:one }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
@@ -0,0 +1,151 @@
+mod parse;
+use parse::Parse;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::kvs::Datastore;
+use surrealdb::sql::Value;
+
+fn stat(val: &Value, key: &str) -> i64 {
+	match val {
+		Value::Object(v) => match v.get(key) {
+			Some(Value::Number(v)) => v.to_int(),
+			v => panic!("Unexpected statistic {key}: {v:?}"),
+		},
+		v => panic!("Unexpected statistics: {v}"),
+	}
+}
+
+#[tokio::test]
+async fn info_for_table_stats() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX name ON person FIELDS name UNIQUE;
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+		CREATE person:other SET name = 'Other';
+		DELETE person:other;
+		UPDATE person:tobie SET name = 'Tobie Morgan Hitchcock';
+		INFO FOR TABLE person STATS;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(stat(&tmp, "records"), 2);
+	assert!(stat(&tmp, "keys") > 0);
+	assert!(stat(&tmp, "values") > 0);
+	assert_eq!(stat(&tmp, "changes"), 0);
+	assert!(stat(&tmp.pick(&["indexes".into()]), "name") > 0);
+	// Removing everything leaves nothing to account for
+	let sql = "
+		DELETE person;
+		REMOVE INDEX name ON person;
+		INFO FOR TABLE person STATS;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			records: 0,
+			keys: 0,
+			values: 0,
+			changes: 0,
+			indexes: {},
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn info_for_table_stats_cancelled() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie';
+		BEGIN;
+		CREATE person:jaime SET name = 'Jaime';
+		CANCEL;
+		BEGIN;
+		CREATE person:other SET name = 'Other';
+		SAVEPOINT one;
+		CREATE person:jaime SET name = 'Jaime';
+		ROLLBACK TO one;
+		COMMIT;
+		INFO FOR TABLE person STATS;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.pop().unwrap().result?;
+	assert_eq!(stat(&tmp, "records"), 2);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn info_for_database_stats() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		DEFINE INDEX name ON person FIELDS name;
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+		CREATE company:surrealdb SET name = 'SurrealDB';
+		INFO FOR TABLE person STATS;
+		INFO FOR TABLE company STATS;
+		INFO FOR DB STATS;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let person = res.remove(0).result?;
+	assert_eq!(stat(&person, "records"), 2);
+	assert!(stat(&person, "changes") > 0);
+	let index = stat(&person.pick(&["indexes".into()]), "name");
+	assert!(index > 0);
+	//
+	let company = res.remove(0).result?;
+	assert_eq!(stat(&company, "records"), 1);
+	assert_eq!(stat(&company, "changes"), 0);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(stat(&tmp, "records"), 3);
+	assert_eq!(stat(&tmp, "keys"), stat(&person, "keys") + stat(&company, "keys"));
+	assert_eq!(stat(&tmp, "values"), stat(&person, "values") + stat(&company, "values"));
+	assert_eq!(stat(&tmp, "changes"), stat(&person, "changes"));
+	assert_eq!(stat(&tmp, "indexes"), index);
+	assert_eq!(tmp.pick(&["tables".into(), "person".into()]), person);
+	assert_eq!(tmp.pick(&["tables".into(), "company".into()]), company);
+	// Merging the stored statistics does not change them
+	dbs.tick_at(10).await?;
+	let res = &mut dbs.execute("INFO FOR TABLE person STATS", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, person);
+	// Expired change feed entries are no longer counted
+	dbs.tick_at(10 + 3601).await?;
+	let res = &mut dbs.execute("INFO FOR TABLE person STATS", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(stat(&tmp, "records"), 2);
+	assert_eq!(stat(&tmp, "changes"), 0);
+	//
+	Ok(())
+}
@@ -0,0 +1,186 @@
+mod parse;
+use parse::Parse;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::kvs::Datastore;
+use surrealdb::sql::Value;
+
+// A time in seconds which is at the start of a five minute interval
+const START: u64 = 1_699_999_800;
+
+#[tokio::test]
+async fn scheduled_job_runs_on_schedule() -> Result<(), Error> {
+	let sql = "
+		DEFINE JOB rollup SCHEDULE '0 */5 * * * *' THEN {
+			UPDATE counter:rollup SET runs += 1;
+		};
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	// The job is scheduled from the first tick after it was defined
+	dbs.tick_at(START + 10).await?;
+	dbs.process_jobs().await;
+	dbs.tick_at(START + 100).await?;
+	dbs.process_jobs().await;
+	let res = &mut dbs.execute("SELECT * FROM counter", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	// The job is claimed once it is due, and is run separately from the tick
+	dbs.tick_at(START + 300).await?;
+	let res = &mut dbs.execute("SELECT * FROM counter", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	dbs.process_jobs().await;
+	dbs.tick_at(START + 310).await?;
+	dbs.process_jobs().await;
+	let res = &mut dbs.execute("SELECT * FROM counter", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: counter:rollup, runs: 1 }]");
+	assert_eq!(tmp, val);
+	// The job runs only once after missing several scheduled times
+	dbs.tick_at(START + 2000).await?;
+	dbs.process_jobs().await;
+	let sql = "
+		SELECT * FROM counter;
+		INFO FOR DB;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: counter:rollup, runs: 2 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let jobs = tmp.pick(&["jobs".into()]);
+	let val = Value::parse(
+		"{ rollup: 'DEFINE JOB rollup SCHEDULE \\'0 */5 * * * *\\' AS DATABASE THEN { UPDATE counter:rollup SET runs += 1; }' }",
+	);
+	assert_eq!(jobs, val);
+	let run = tmp.pick(&["runs".into(), "rollup".into()]);
+	assert_eq!(run.pick(&["at".into()]), Value::parse("'2023-11-14T22:43:20Z'"));
+	assert_eq!(run.pick(&["next".into()]), Value::parse("'2023-11-14T22:45:00Z'"));
+	assert_eq!(run.pick(&["error".into()]), Value::None);
+	assert!(run.pick(&["duration".into()]).is_duration());
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn scheduled_job_records_errors() -> Result<(), Error> {
+	let sql = "
+		DEFINE JOB failing SCHEDULE '* * * * * *' THEN {
+			CREATE person:one;
+			CREATE person:one;
+		};
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	dbs.tick_at(START).await?;
+	dbs.process_jobs().await;
+	dbs.tick_at(START + 1).await?;
+	dbs.process_jobs().await;
+	let sql = "
+		SELECT * FROM person;
+		INFO FOR DB;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	// The changes of the failed run are not committed
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let err = tmp.pick(&["runs".into(), "failing".into(), "error".into()]);
+	let val = Value::from("Database record `person:one` already exists");
+	assert_eq!(err, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn scheduled_job_runs_with_its_identity() -> Result<(), Error> {
+	let sql = "
+		DEFINE SCOPE account;
+		DEFINE TABLE secret SCHEMALESS PERMISSIONS NONE;
+		DEFINE JOB owner SCHEDULE '* * * * * *' THEN {
+			CREATE secret:owner;
+		};
+		DEFINE JOB user SCHEDULE '* * * * * *' AS SCOPE account THEN {
+			CREATE secret:user;
+		};
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	dbs.tick_at(START).await?;
+	dbs.process_jobs().await;
+	dbs.tick_at(START + 1).await?;
+	dbs.process_jobs().await;
+	let res = &mut dbs.execute("SELECT id FROM secret", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: secret:owner }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn scheduled_job_can_be_removed() -> Result<(), Error> {
+	let sql = "
+		DEFINE JOB rollup SCHEDULE '* * * * * *' THEN {
+			UPDATE counter:rollup SET runs += 1;
+		};
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	dbs.tick_at(START).await?;
+	dbs.process_jobs().await;
+	dbs.tick_at(START + 1).await?;
+	dbs.process_jobs().await;
+	let sql = "
+		REMOVE JOB rollup;
+		INFO FOR DB;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&["jobs".into()]), Value::parse("{}"));
+	assert_eq!(tmp.pick(&["runs".into()]), Value::parse("{}"));
+	// The job does not run once it has been removed
+	dbs.tick_at(START + 2).await?;
+	dbs.process_jobs().await;
+	let res = &mut dbs.execute("SELECT * FROM counter", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: counter:rollup, runs: 1 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
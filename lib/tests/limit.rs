@@ -93,3 +93,67 @@ async fn select_limit_fetch() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn select_limit_order_by_id_desc() -> Result<(), Error> {
+	let sql = "
+		CREATE person:1 SET age = 10;
+		CREATE person:2 SET age = 20;
+		CREATE person:3 SET age = 30;
+		CREATE person:4 SET age = 40;
+		SELECT * FROM person ORDER BY id DESC LIMIT 2;
+		SELECT * FROM person:1..4 ORDER BY id DESC LIMIT 2 START 1;
+		SELECT * FROM person ORDER BY id LIMIT 1;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..4 {
+		res.remove(0).result?;
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: person:4,
+				age: 40
+			},
+			{
+				id: person:3,
+				age: 30
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: person:2,
+				age: 20
+			},
+			{
+				id: person:1,
+				age: 10
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: person:1,
+				age: 10
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
@@ -0,0 +1,294 @@
+mod parse;
+use parse::Parse;
+use surrealdb::dbs::Action;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::kvs::Datastore;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn live_select_diff() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?.with_notifications();
+	let mut ses = Session::for_kv().with_ns("test").with_db("test");
+	ses.rt = true;
+	let sql = "
+		CREATE person:test SET name = 'Tobie', age = 18, tags = ['old'];
+		LIVE SELECT DIFF FROM person;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let id = res.remove(0).result?;
+	let Value::Uuid(id) = id else { panic!("expected a live query id") };
+	//
+	let sql = "
+		UPDATE person:test SET name = 'Jaime', age = 19, tags = [];
+		CREATE person:other SET name = 'Yusuke';
+		DELETE person:test;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	let chn = dbs.notifications().unwrap();
+	// UPDATE
+	let tmp = chn.try_recv().unwrap();
+	assert_eq!(tmp.id, id);
+	assert_eq!(tmp.action, Action::Update);
+	let val = Value::parse(
+		"[
+			{ op: 'replace', path: '/age', value: 19 },
+			{ op: 'replace', path: '/name', value: 'Jaime' },
+			{ op: 'remove', path: '/tags/0', value: NULL },
+		]",
+	);
+	assert_eq!(tmp.result, val);
+	// CREATE
+	let tmp = chn.try_recv().unwrap();
+	assert_eq!(tmp.id, id);
+	assert_eq!(tmp.action, Action::Create);
+	let val = Value::parse(
+		"[
+			{ op: 'add', path: '/id', value: person:other },
+			{ op: 'add', path: '/name', value: 'Yusuke' },
+		]",
+	);
+	assert_eq!(tmp.result, val);
+	// DELETE
+	let tmp = chn.try_recv().unwrap();
+	assert_eq!(tmp.id, id);
+	assert_eq!(tmp.action, Action::Delete);
+	assert_eq!(tmp.result, Value::parse("person:test"));
+	//
+	assert!(chn.try_recv().is_err());
+	Ok(())
+}
+
+#[tokio::test]
+async fn live_select_permissions() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?.with_notifications();
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let sql = "
+		DEFINE TABLE post SCHEMALESS
+			PERMISSIONS
+				FOR select WHERE owner = $auth
+				FOR create, update, delete FULL;
+		DEFINE FIELD secret ON post PERMISSIONS FOR select NONE;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// Register the live queries as a scope user
+	let mut usr = Session::for_sc("test", "test", "user");
+	usr.sd = Some(Value::parse("user:one"));
+	usr.rt = true;
+	let sql = "
+		LIVE SELECT * FROM post;
+		LIVE SELECT DIFF FROM post;
+	";
+	let res = &mut dbs.execute(sql, &usr, None).await?;
+	assert_eq!(res.len(), 2);
+	let Value::Uuid(all) = res.remove(0).result? else { panic!("expected a live query id") };
+	let Value::Uuid(diff) = res.remove(0).result? else { panic!("expected a live query id") };
+	// Write records owned by different users
+	let sql = "
+		CREATE post:one SET owner = user:one, text = 'Hello', secret = 'one';
+		CREATE post:two SET owner = user:two, text = 'Hello', secret = 'two';
+		UPDATE post:two SET owner = user:one;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	let chn = dbs.notifications().unwrap();
+	let mut notifications = Vec::new();
+	while let Ok(v) = chn.try_recv() {
+		notifications.push(v);
+	}
+	assert_eq!(notifications.len(), 4);
+	// CREATE post:one
+	let tmp = notifications.iter().find(|n| n.id == all).unwrap();
+	assert_eq!(tmp.action, Action::Create);
+	let val = Value::parse("{ id: post:one, owner: user:one, text: 'Hello' }");
+	assert_eq!(tmp.result, val);
+	let tmp = notifications.iter().find(|n| n.id == diff).unwrap();
+	assert_eq!(tmp.action, Action::Create);
+	let val = Value::parse(
+		"[
+			{ op: 'add', path: '/id', value: post:one },
+			{ op: 'add', path: '/owner', value: user:one },
+			{ op: 'add', path: '/text', value: 'Hello' },
+		]",
+	);
+	assert_eq!(tmp.result, val);
+	// UPDATE post:two
+	let tmp = notifications.iter().rev().find(|n| n.id == all).unwrap();
+	assert_eq!(tmp.action, Action::Update);
+	let val = Value::parse("{ id: post:two, owner: user:one, text: 'Hello' }");
+	assert_eq!(tmp.result, val);
+	let tmp = notifications.iter().rev().find(|n| n.id == diff).unwrap();
+	assert_eq!(tmp.action, Action::Update);
+	let val = Value::parse("[{ op: 'replace', path: '/owner', value: user:one }]");
+	assert_eq!(tmp.result, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn live_select_fetch() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?.with_notifications();
+	let mut ses = Session::for_kv().with_ns("test").with_db("test");
+	ses.rt = true;
+	let sql = "
+		CREATE tag:rust SET name = 'Rust';
+		LIVE SELECT name, string::uppercase(name) AS upper, tags FROM person FETCH tags;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let id = res.remove(0).result?;
+	let Value::Uuid(id) = id else { panic!("expected a live query id") };
+	//
+	let sql = "
+		CREATE person:test SET name = 'Tobie', tags = [tag:rust];
+		SELECT name, string::uppercase(name) AS upper, tags FROM person:test FETCH tags;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				name: 'Tobie',
+				upper: 'TOBIE',
+				tags: [
+					{ id: tag:rust, name: 'Rust' }
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The notification matches the output of the SELECT statement
+	let chn = dbs.notifications().unwrap();
+	let tmp = chn.try_recv().unwrap();
+	assert_eq!(tmp.id, id);
+	assert_eq!(tmp.action, Action::Create);
+	assert_eq!(Value::from(vec![tmp.result]), val);
+	//
+	assert!(chn.try_recv().is_err());
+	Ok(())
+}
+
+#[tokio::test]
+async fn live_select_aggregate_view() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?.with_notifications();
+	let mut ses = Session::for_kv().with_ns("test").with_db("test");
+	ses.rt = true;
+	let sql = "
+		DEFINE TABLE person_by_age AS SELECT count() AS total, age FROM person GROUP BY age;
+		LIVE SELECT * FROM person_by_age;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let id = res.remove(0).result?;
+	let Value::Uuid(id) = id else { panic!("expected a live query id") };
+	//
+	let sql = "
+		CREATE person:one SET age = 18;
+		CREATE person:two SET age = 18;
+		DELETE person:one;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	let chn = dbs.notifications().unwrap();
+	// CREATE person:one
+	let tmp = chn.try_recv().unwrap();
+	assert_eq!(tmp.id, id);
+	assert_eq!(tmp.action, Action::Create);
+	assert_eq!(tmp.result, Value::parse("{ id: person_by_age:[18], age: 18, total: 1 }"));
+	// CREATE person:two
+	let tmp = chn.try_recv().unwrap();
+	assert_eq!(tmp.id, id);
+	assert_eq!(tmp.action, Action::Update);
+	assert_eq!(tmp.result, Value::parse("{ id: person_by_age:[18], age: 18, total: 2 }"));
+	// DELETE person:one
+	let tmp = chn.try_recv().unwrap();
+	assert_eq!(tmp.id, id);
+	assert_eq!(tmp.action, Action::Update);
+	assert_eq!(tmp.result, Value::parse("{ id: person_by_age:[18], age: 18, total: 1 }"));
+	//
+	assert!(chn.try_recv().is_err());
+	Ok(())
+}
+
+#[tokio::test]
+async fn live_select_aggregate_view_projection() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?.with_notifications();
+	let mut ses = Session::for_kv().with_ns("test").with_db("test");
+	ses.rt = true;
+	let sql = "
+		DEFINE TABLE order_totals AS
+			SELECT count() AS orders, math::sum(price) AS spent, customer
+			FROM order GROUP BY customer;
+		LIVE SELECT customer, spent / orders AS average FROM order_totals WHERE orders > 1;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let id = res.remove(0).result?;
+	let Value::Uuid(id) = id else { panic!("expected a live query id") };
+	//
+	let sql = "
+		CREATE order:one SET customer = 'tobie', price = 10;
+		CREATE order:two SET customer = 'tobie', price = 30;
+		CREATE order:three SET customer = 'jaime', price = 50;
+		SELECT customer, spent / orders AS average FROM order_totals WHERE orders > 1;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ customer: 'tobie', average: 20 }]");
+	assert_eq!(tmp, val);
+	// Only the aggregate which matches the condition is sent,
+	// and it matches the output of the SELECT statement
+	let chn = dbs.notifications().unwrap();
+	let tmp = chn.try_recv().unwrap();
+	assert_eq!(tmp.id, id);
+	assert_eq!(tmp.action, Action::Update);
+	assert_eq!(Value::from(vec![tmp.result]), val);
+	//
+	assert!(chn.try_recv().is_err());
+	Ok(())
+}
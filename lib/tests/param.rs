@@ -29,7 +29,9 @@ async fn define_global_param() -> Result<(), Error> {
 			logins: {},
 			tokens: {},
 			functions: {},
+			jobs: {},
 			params: { test: 'DEFINE PARAM $test VALUE 12345' },
+			runs: {},
 			scopes: {},
 			tables: {},
 		}",
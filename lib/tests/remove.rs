@@ -34,7 +34,9 @@ async fn remove_statement_table() -> Result<(), Error> {
 			logins: {},
 			tokens: {},
 			functions: {},
+			jobs: {},
 			params: {},
+			runs: {},
 			scopes: {},
 			tables: {}
 		}",
@@ -68,7 +70,9 @@ async fn remove_statement_analyzer() -> Result<(), Error> {
 			logins: {},
 			tokens: {},
 			functions: {},
+			jobs: {},
 			params: {},
+			runs: {},
 			scopes: {},
 			tables: {}
 		}",
@@ -102,6 +106,7 @@ async fn remove_statement_index() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			consumers: {},
 			events: {},
 			fields: {},
 			indexes: {},
@@ -0,0 +1,303 @@
+mod parse;
+use parse::Parse;
+use std::collections::BTreeMap;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::kvs::Datastore;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn rename_statement_table() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD name ON person TYPE string;
+		DEFINE INDEX name ON person FIELDS name UNIQUE;
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+		RELATE person:tobie->knows->person:jaime SET id = knows:test;
+		RENAME TABLE person TO user;
+		SELECT * FROM user;
+		SELECT * FROM person;
+		SELECT * FROM knows;
+		SELECT VALUE ->knows->user FROM user:tobie;
+		SELECT VALUE <-knows<-user FROM user:jaime;
+		CREATE user:other SET name = 'Tobie';
+		CREATE user:other SET name = 10;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 13);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: user:jaime, name: 'Jaime' },
+			{ id: user:tobie, name: 'Tobie' },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: knows:test, in: user:tobie, out: user:jaime },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[[user:jaime]]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[[user:tobie]]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"Database index `name` already contains 'Tobie', with record `user:other`"#
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Found 10 for field `name`, with record `user:other`, but expected a string"
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn rename_statement_table_in_batches() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	// Store more records than are rewritten in a single batch
+	let people: Vec<Value> = (0..1200)
+		.map(|i| Value::parse(&format!("{{ id: person:{i}, num: {i}, best: person:0 }}")))
+		.collect();
+	let vars = BTreeMap::from([(String::from("people"), Value::from(people))]);
+	let sql = "
+		DEFINE INDEX num ON person FIELDS num UNIQUE;
+		INSERT INTO person $people;
+		RENAME TABLE person TO user;
+		SELECT count() FROM user GROUP ALL;
+		SELECT id, best FROM user WHERE num = 1199;
+	";
+	let res = &mut dbs.execute(sql, &ses, Some(vars)).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ count: 1200 }]");
+	assert_eq!(tmp, val);
+	// Record links stored in other fields are not rewritten
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:1199, best: person:0 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn rename_statement_table_in_transaction() -> Result<(), Error> {
+	let sql = "
+		BEGIN;
+		DEFINE INDEX name ON person FIELDS name UNIQUE;
+		CREATE person:tobie SET name = 'Tobie';
+		RENAME TABLE person TO user;
+		CREATE user:other SET name = 'Tobie';
+		COMMIT;
+		SELECT * FROM user;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_err());
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"Database index `name` already contains 'Tobie', with record `user:other`"#
+	));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn rename_statement_table_exists() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person;
+		DEFINE TABLE user;
+		RENAME TABLE person TO user;
+		RENAME TABLE other TO test;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The table 'user' already exists"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The table 'other' does not exist"
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn rename_statement_view() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person_count AS SELECT count() AS total FROM person GROUP ALL;
+		CREATE person:tobie;
+		RENAME TABLE person TO user;
+		CREATE user:jaime;
+		SELECT * FROM person_count;
+		INFO FOR TABLE user;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person_count:[], total: 2 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			consumers: {},
+			events: {},
+			fields: {},
+			indexes: {},
+			tables: { person_count: 'DEFINE TABLE person_count SCHEMALESS AS SELECT count() AS total FROM user GROUP ALL' },
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn rename_statement_database() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie;
+		RENAME DATABASE test TO other;
+		SELECT * FROM person;
+		USE DB other;
+		SELECT * FROM person;
+		INFO FOR NS;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			databases: { other: 'DEFINE DATABASE other' },
+			logins: {},
+			tokens: {},
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn rename_statement_namespace() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie;
+		RENAME NAMESPACE test TO other;
+		USE NS other DB test;
+		SELECT * FROM person;
+		INFO FOR KV;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			namespaces: { other: 'DEFINE NAMESPACE other' },
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
@@ -0,0 +1,114 @@
+mod parse;
+use parse::Parse;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::kvs::Datastore;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn savepoint_rollback_failed_statement() -> Result<(), Error> {
+	let sql = "
+		BEGIN;
+		CREATE person:one SET name = 'Tobie';
+		SAVEPOINT two;
+		CREATE person:two SET name = 'Jaime';
+		CREATE person:one SET name = 'Jaime';
+		ROLLBACK TO two;
+		CREATE person:three SET name = 'Lizzie';
+		COMMIT;
+		SELECT VALUE id FROM person;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::QueryCancelled)));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::RecordExists { .. })));
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:one, person:three]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn savepoint_nested_release() -> Result<(), Error> {
+	let sql = "
+		BEGIN;
+		SAVEPOINT one;
+		CREATE person:one;
+		SAVEPOINT two;
+		CREATE person:two;
+		RELEASE SAVEPOINT two;
+		SELECT VALUE id FROM person;
+		ROLLBACK TO SAVEPOINT one;
+		SELECT VALUE id FROM person;
+		CREATE person:three;
+		COMMIT;
+		SELECT VALUE id FROM person;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(matches!(tmp, Err(Error::QueryCancelled)));
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:three]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn savepoint_errors() -> Result<(), Error> {
+	let sql = "
+		SAVEPOINT one;
+		BEGIN;
+		CREATE person:one;
+		RELEASE one;
+		COMMIT;
+		SELECT VALUE id FROM person;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::TxSavepointOutsideTransaction)));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::QueryNotExecuted)));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::TxSavepointNotFound { .. })));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
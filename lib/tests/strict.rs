@@ -254,7 +254,9 @@ async fn loose_mode_all_ok() -> Result<(), Error> {
 			logins: {},
 			tokens: {},
 			functions: {},
+			jobs: {},
 			params: {},
+			runs: {},
 			scopes: {},
 			tables: { test: 'DEFINE TABLE test SCHEMALESS PERMISSIONS NONE' },
 		}",
@@ -264,6 +266,7 @@ async fn loose_mode_all_ok() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			consumers: {},
 			events: {},
 			fields: { extra: 'DEFINE FIELD extra ON test VALUE true' },
 			tables: {},
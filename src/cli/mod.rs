@@ -4,6 +4,7 @@ mod config;
 mod export;
 mod import;
 mod isready;
+mod restore;
 mod sql;
 #[cfg(feature = "has-storage")]
 mod start;
@@ -20,6 +21,7 @@ pub use config::CF;
 use export::ExportCommandArguments;
 use import::ImportCommandArguments;
 use isready::IsReadyCommandArguments;
+use restore::RestoreCommandArguments;
 use sql::SqlCommandArguments;
 #[cfg(feature = "has-storage")]
 use start::StartCommandArguments;
@@ -58,6 +60,8 @@ enum Commands {
 	Start(StartCommandArguments),
 	#[command(about = "Backup data to or from an existing database")]
 	Backup(BackupCommandArguments),
+	#[command(about = "Restore a backup into an existing database")]
+	Restore(RestoreCommandArguments),
 	#[command(about = "Import a SurrealQL script into an existing database")]
 	Import(ImportCommandArguments),
 	#[command(about = "Export an existing database as a SurrealQL script")]
@@ -83,6 +87,7 @@ pub async fn init() -> ExitCode {
 		#[cfg(feature = "has-storage")]
 		Commands::Start(args) => start::init(args).await,
 		Commands::Backup(args) => backup::init(args).await,
+		Commands::Restore(args) => restore::init(args).await,
 		Commands::Import(args) => import::init(args).await,
 		Commands::Export(args) => export::init(args).await,
 		Commands::Version(args) => version::init(args).await,
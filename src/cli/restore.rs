@@ -0,0 +1,110 @@
+use crate::cli::abstraction::AuthArguments;
+use crate::cnf::SERVER_AGENT;
+use crate::err::Error;
+use clap::Args;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::header::USER_AGENT;
+use reqwest::{Body, Client};
+#[cfg(feature = "has-storage")]
+use surrealdb::kvs::RestorePoint;
+use tokio::fs::OpenOptions;
+use tokio::io::{stdin, AsyncRead};
+use tokio_util::io::ReaderStream;
+
+const TYPE: &str = "application/octet-stream";
+
+#[derive(Args, Debug)]
+pub struct RestoreCommandArguments {
+	#[arg(help = "Path to the backup file from which to restore, or - to read from stdin")]
+	from: String,
+	#[arg(help = "Remote database, or path to the local datastore, into which to restore")]
+	#[arg(value_parser = super::validator::restore_valid)]
+	into: String,
+	#[command(flatten)]
+	auth: AuthArguments,
+	#[cfg(feature = "has-storage")]
+	#[arg(help = "Encryption key to use for a local datastore")]
+	#[arg(env = "SURREAL_KEY", short = 'k', long = "key")]
+	#[arg(value_parser = super::validator::key_valid)]
+	key: Option<String>,
+	#[cfg(feature = "has-storage")]
+	#[arg(help = "Path to a later backup file whose change feeds are replayed after restoring")]
+	#[arg(long = "changes", requires = "until")]
+	changes: Option<String>,
+	#[cfg(feature = "has-storage")]
+	#[arg(help = "The versionstamp, or datetime, up to which changes are replayed")]
+	#[arg(long = "until", requires = "changes")]
+	#[arg(value_parser = super::validator::restore_point)]
+	until: Option<RestorePoint>,
+}
+
+pub async fn init(
+	RestoreCommandArguments {
+		from,
+		into,
+		auth: AuthArguments {
+			username: user,
+			password: pass,
+		},
+		#[cfg(feature = "has-storage")]
+		key,
+		#[cfg(feature = "has-storage")]
+		changes,
+		#[cfg(feature = "has-storage")]
+		until,
+	}: RestoreCommandArguments,
+) -> Result<(), Error> {
+	// Initialize opentelemetry and logging
+	crate::telemetry::builder().with_log_level("error").init();
+	// Open the backup file
+	let from: Box<dyn AsyncRead + Send + Sync + Unpin> = match from.as_str() {
+		"-" => Box::new(stdin()),
+		from => Box::new(OpenOptions::new().read(true).open(from).await?),
+	};
+	let from = ReaderStream::new(from);
+	// Restore the backup into the destination
+	match into.as_str() {
+		// From File -> Into HTTP
+		into if into.starts_with("http://") || into.starts_with("https://") => {
+			// Change feeds can only be replayed into a local datastore
+			#[cfg(feature = "has-storage")]
+			if changes.is_some() {
+				return Err(Error::OperationUnsupported);
+			}
+			Client::new()
+				.post(format!("{into}/sync"))
+				.basic_auth(user, Some(pass))
+				.header(USER_AGENT, SERVER_AGENT)
+				.header(CONTENT_TYPE, TYPE)
+				.body(Body::wrap_stream(from))
+				.send()
+				.await?
+				.error_for_status()?;
+		}
+		// From File -> Into Datastore
+		#[cfg(feature = "has-storage")]
+		into => {
+			use futures::TryStreamExt;
+			use surrealdb::error::Db as SurrealDbError;
+			use surrealdb::kvs::Datastore;
+			// Open the local datastore
+			let ds = Datastore::new(into).await?.with_encryption_key(key.as_deref(), None).await?;
+			ds.bootstrap().await?;
+			// Restore the backup as it is read
+			let from = from.map_err(|e| SurrealDbError::Backup(e.to_string()));
+			ds.restore(from).await?;
+			// Replay the change feeds of the later backup
+			if let (Some(changes), Some(until)) = (changes, until) {
+				let file = OpenOptions::new().read(true).open(changes).await?;
+				let changes = ReaderStream::new(file);
+				let changes = changes.map_err(|e| SurrealDbError::Backup(e.to_string()));
+				ds.replay(changes, until).await?;
+			}
+		}
+		#[cfg(not(feature = "has-storage"))]
+		_ => return Err(Error::OperationUnsupported),
+	}
+	info!("The backup was restored successfully");
+	// Everything OK
+	Ok(())
+}
@@ -13,6 +13,7 @@ use clap::Args;
 use ipnet::IpNet;
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use tokio_util::sync::CancellationToken;
 
 #[derive(Args, Debug)]
 pub struct StartCommandArguments {
@@ -42,10 +43,6 @@ pub struct StartCommandArguments {
 	listen_addresses: Vec<SocketAddr>,
 	#[command(flatten)]
 	dbs: StartCommandDbsOptions,
-	#[arg(help = "Encryption key to use for on-disk encryption")]
-	#[arg(env = "SURREAL_KEY", short = 'k', long = "key")]
-	#[arg(value_parser = super::validator::key_valid)]
-	key: Option<String>,
 	#[command(flatten)]
 	kvs: Option<StartCommandRemoteTlsOptions>,
 	#[command(flatten)]
@@ -122,10 +119,18 @@ pub async fn init(
 	env::init().await?;
 	// Initiate master auth
 	iam::init().await?;
+	// Shut down the server when a background task fails
+	let ct = CancellationToken::new();
 	// Start the kvs server
-	dbs::init(dbs).await?;
+	let tick = dbs::init(dbs, ct.clone()).await?;
 	// Start the web server
-	net::init().await?;
+	net::init(ct.clone()).await?;
+	// Stop the background tasks
+	ct.cancel();
+	// Return the error which shut down the server
+	if let Ok(Err(e)) = tick.await {
+		return Err(Error::Db(e.into()));
+	}
 	// All ok
 	Ok(())
 }
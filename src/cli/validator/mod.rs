@@ -4,6 +4,10 @@ use std::{
 	str::FromStr,
 	time::Duration,
 };
+#[cfg(feature = "has-storage")]
+use surrealdb::kvs::RestorePoint;
+#[cfg(feature = "has-storage")]
+use surrealdb::kvs::VersionstampOracle;
 
 pub(crate) mod parser;
 
@@ -11,9 +15,11 @@ pub(crate) mod parser;
 pub(crate) fn path_valid(v: &str) -> Result<String, String> {
 	match v {
 		"memory" => Ok(v.to_string()),
+		v if v.starts_with("memory:") => Ok(v.to_string()),
 		v if v.starts_with("file:") => Ok(v.to_string()),
 		v if v.starts_with("rocksdb:") => Ok(v.to_string()),
 		v if v.starts_with("speedb:") => Ok(v.to_string()),
+		v if v.starts_with("redb:") => Ok(v.to_string()),
 		v if v.starts_with("tikv:") => Ok(v.to_string()),
 		v if v.starts_with("fdb:") => Ok(v.to_string()),
 		_ => Err(String::from("Provide a valid database path parameter")),
@@ -45,7 +51,7 @@ pub(crate) fn endpoint_valid(v: &str) -> Result<String, String> {
 
 	let scheme = split_endpoint(v).0;
 	match scheme {
-		"http" | "https" | "ws" | "wss" | "fdb" | "mem" | "rocksdb" | "file" | "tikv" => {
+		"http" | "https" | "ws" | "wss" | "fdb" | "mem" | "rocksdb" | "redb" | "file" | "tikv" => {
 			Ok(v.to_string())
 		}
 		_ => Err(String::from("Provide a valid database connection string")),
@@ -62,6 +68,19 @@ pub(crate) fn into_valid(v: &str) -> Result<String, String> {
 	}
 }
 
+pub(crate) fn restore_valid(v: &str) -> Result<String, String> {
+	match v {
+		v if v.starts_with("http://") => Ok(v.to_string()),
+		v if v.starts_with("https://") => Ok(v.to_string()),
+		#[cfg(feature = "has-storage")]
+		v => path_valid(v).map_err(|_| {
+			String::from("Provide a valid database connection string, or database path")
+		}),
+		#[cfg(not(feature = "has-storage"))]
+		_ => Err(String::from("Provide a valid database connection string")),
+	}
+}
+
 #[cfg(feature = "has-storage")]
 pub(crate) fn key_valid(v: &str) -> Result<String, String> {
 	match v.len() {
@@ -72,7 +91,27 @@ pub(crate) fn key_valid(v: &str) -> Result<String, String> {
 	}
 }
 
+#[cfg(feature = "has-storage")]
+pub(crate) fn restore_point(v: &str) -> Result<RestorePoint, String> {
+	match v.parse::<u128>() {
+		Ok(v) => RestorePoint::versionstamp(v).map_err(|e| e.to_string()),
+		Err(_) => match surrealdb::sql::Datetime::from_str(v) {
+			Ok(v) => Ok(RestorePoint::Timestamp(v.timestamp().max(0) as u64)),
+			Err(_) => Err(String::from("Provide a valid versionstamp or datetime")),
+		},
+	}
+}
+
 #[cfg(feature = "has-storage")]
 pub(crate) fn duration(v: &str) -> Result<Duration, String> {
 	surrealdb::sql::Duration::from_str(v).map(|d| d.0).map_err(|_| String::from("invalid duration"))
 }
+
+#[cfg(feature = "has-storage")]
+pub(crate) fn versionstamp_oracle(v: &str) -> Result<VersionstampOracle, String> {
+	match v {
+		"engine" => Ok(VersionstampOracle::Engine),
+		"hlc" => Ok(VersionstampOracle::HybridLogicalClock),
+		_ => Err(String::from("Provide a valid versionstamp oracle: engine or hlc")),
+	}
+}
@@ -33,6 +33,10 @@ pub const MAX_CONCURRENT_CALLS: usize = 24;
 #[cfg(feature = "has-storage")]
 pub const WEBSOCKET_PING_FREQUENCY: Duration = Duration::from_secs(5);
 
+/// Specifies the frequency with which live query notifications queued by other nodes are delivered
+#[cfg(feature = "has-storage")]
+pub const NOTIFICATION_POLL_FREQUENCY: Duration = Duration::from_millis(100);
+
 /// The version identifier of this build
 pub static PKG_VERSION: Lazy<String> = Lazy::new(|| match option_env!("SURREAL_BUILD_METADATA") {
 	Some(metadata) if !metadata.trim().is_empty() => {
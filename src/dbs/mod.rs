@@ -4,6 +4,9 @@ use clap::Args;
 use once_cell::sync::OnceCell;
 use std::time::Duration;
 use surrealdb::kvs::Datastore;
+use surrealdb::kvs::VersionstampOracle;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
 pub static DB: OnceCell<Datastore> = OnceCell::new();
 
@@ -21,6 +24,36 @@ pub struct StartCommandDbsOptions {
 	#[arg(env = "SURREAL_TRANSACTION_TIMEOUT", long)]
 	#[arg(value_parser = super::cli::validator::duration)]
 	transaction_timeout: Option<Duration>,
+	#[arg(
+		help = "The number of times a transaction is retried after conflicting with another transaction"
+	)]
+	#[arg(env = "SURREAL_TRANSACTION_RETRIES", long)]
+	#[arg(default_value_t = 0)]
+	transaction_retries: u32,
+	#[arg(help = "The interval at which to run node agent tick (including garbage collection)")]
+	#[arg(env = "SURREAL_TICK_INTERVAL", long = "tick-interval")]
+	#[arg(value_parser = super::cli::validator::duration)]
+	#[arg(default_value = "10s")]
+	tick_interval: Duration,
+	#[arg(help = "Encryption key to use for on-disk encryption")]
+	#[arg(env = "SURREAL_KEY", short = 'k', long = "key")]
+	#[arg(value_parser = super::cli::validator::key_valid)]
+	key: Option<String>,
+	#[arg(help = "Previous encryption key, used when rotating the on-disk encryption key")]
+	#[arg(env = "SURREAL_PREVIOUS_KEY", long = "previous-key", requires = "key")]
+	#[arg(value_parser = super::cli::validator::key_valid)]
+	previous_key: Option<String>,
+	#[arg(
+		help = "Whether to migrate the data in the datastore to the latest key format on startup"
+	)]
+	#[arg(env = "SURREAL_MIGRATE_KEY_FORMAT", long = "migrate-key-format")]
+	#[arg(default_value_t = false)]
+	migrate_key_format: bool,
+	#[arg(help = "The oracle which allocates the versionstamps of change feeds (engine or hlc)")]
+	#[arg(env = "SURREAL_VERSIONSTAMP_ORACLE", long = "versionstamp-oracle")]
+	#[arg(value_parser = super::cli::validator::versionstamp_oracle)]
+	#[arg(default_value = "engine")]
+	versionstamp_oracle: VersionstampOracle,
 }
 
 pub async fn init(
@@ -28,8 +61,15 @@ pub async fn init(
 		strict_mode,
 		query_timeout,
 		transaction_timeout,
+		transaction_retries,
+		tick_interval,
+		key,
+		previous_key,
+		migrate_key_format,
+		versionstamp_oracle,
 	}: StartCommandDbsOptions,
-) -> Result<(), Error> {
+	ct: CancellationToken,
+) -> Result<JoinHandle<Result<(), surrealdb::err::Error>>, Error> {
 	// Get local copy of options
 	let opt = CF.get().unwrap();
 	// Log specified strict mode
@@ -42,16 +82,82 @@ pub async fn init(
 	if let Some(v) = transaction_timeout {
 		debug!("Maximum transaction processing timeout is {v:?}");
 	}
+	// Log specified transaction retries
+	if transaction_retries > 0 {
+		debug!("Conflicting transactions are retried up to {transaction_retries} times");
+	}
+	// Log specified tick interval
+	debug!("Node agent tick interval is {tick_interval:?}");
+	// Log specified versionstamp oracle
+	debug!("Change feed versionstamp oracle is {versionstamp_oracle:?}");
+	// Log specified encryption
+	if key.is_some() {
+		debug!("On-disk encryption is enabled");
+	}
 	// Parse and setup the desired kv datastore
 	let dbs = Datastore::new(&opt.path)
 		.await?
 		.with_notifications()
 		.with_strict_mode(strict_mode)
 		.with_query_timeout(query_timeout)
-		.with_transaction_timeout(transaction_timeout);
+		.with_transaction_timeout(transaction_timeout)
+		.with_transaction_retries(transaction_retries)
+		.with_lease_duration(tick_interval * 3)
+		.with_versionstamp_oracle(versionstamp_oracle)
+		.with_encryption_key(key.as_deref(), previous_key.as_deref())
+		.await?;
 	dbs.bootstrap().await?;
+	// Migrate the datastore to the latest key format
+	if migrate_key_format {
+		dbs.migrate_key_format().await?;
+	}
 	// Store database instance
 	let _ = DB.set(dbs);
+	// Run the node agent tick in the background
+	let tick = tokio::spawn(async move {
+		let mut interval = tokio::time::interval(tick_interval);
+		loop {
+			tokio::select! {
+				// Stop when the server is shut down
+				_ = ct.cancelled() => return Ok(()),
+				_ = interval.tick() => {}
+			}
+			match DB.get().unwrap().tick().await {
+				Ok(_) => {}
+				// The live queries of this node were archived by another node,
+				// so the server is shut down, to be restarted to rejoin the cluster
+				Err(
+					e @ surrealdb::err::Error::NdRemoved {
+						..
+					},
+				) => {
+					error!("Error running node agent tick: {e}");
+					ct.cancel();
+					return Err(e);
+				}
+				Err(e) => error!("Error running node agent tick: {e}"),
+			}
+		}
+	});
+	// Deliver live query notifications queued by other nodes
+	tokio::spawn(async move {
+		let mut interval = tokio::time::interval(crate::cnf::NOTIFICATION_POLL_FREQUENCY);
+		loop {
+			interval.tick().await;
+			if let Err(e) = DB.get().unwrap().process_notifications().await {
+				error!("Error delivering live query notifications: {e}");
+			}
+		}
+	});
+	// Run the scheduled jobs claimed by this node
+	tokio::spawn(async move {
+		let mut interval = tokio::time::interval(tick_interval);
+		loop {
+			interval.tick().await;
+			// Long running jobs do not hold up the jobs which are claimed later
+			tokio::spawn(DB.get().unwrap().process_jobs());
+		}
+	});
 	// All ok
-	Ok(())
+	Ok(tick)
 }
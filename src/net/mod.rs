@@ -42,6 +42,7 @@ use crate::err::Error;
 use crate::net::signals::graceful_shutdown;
 use crate::telemetry::metrics::HttpMetricsLayer;
 use axum_server::tls_rustls::RustlsConfig;
+use tokio_util::sync::CancellationToken;
 
 const LOG: &str = "surrealdb::net";
 
@@ -53,7 +54,7 @@ struct AppState {
 	client_ip: client_ip::ClientIp,
 }
 
-pub async fn init() -> Result<(), Error> {
+pub async fn init(ct: CancellationToken) -> Result<(), Error> {
 	// Get local copy of options
 	let opt = CF.get().unwrap();
 
@@ -133,7 +134,7 @@ pub async fn init() -> Result<(), Error> {
 
 	// Setup the graceful shutdown with no timeout
 	let handle = Handle::new();
-	graceful_shutdown(handle.clone(), None);
+	graceful_shutdown(ct, handle.clone(), None);
 
 	if let (Some(cert), Some(key)) = (&opt.crt, &opt.key) {
 		// configure certificate and private key used by https
@@ -37,7 +37,7 @@ use axum::{
 };
 
 // Mapping of WebSocketID to WebSocket
-type WebSockets = RwLock<HashMap<Uuid, Sender<Message>>>;
+type WebSockets = RwLock<HashMap<Uuid, (Arc<RwLock<Rpc>>, Sender<Message>)>>;
 // Mapping of LiveQueryID to WebSocketID
 type LiveQueries = RwLock<HashMap<Uuid, Uuid>>;
 
@@ -133,18 +133,16 @@ impl Rpc {
 			}
 		});
 		// Send notifications to the client
-		let moved_rpc = rpc.clone();
 		tokio::task::spawn(async move {
-			let rpc = moved_rpc;
 			if let Some(channel) = DB.get().unwrap().notifications() {
 				while let Ok(notification) = channel.recv().await {
 					// Find which WebSocket the notification belongs to
 					if let Some(ws_id) = LIVE_QUERIES.read().await.get(&notification.id) {
 						// Check to see if the WebSocket exists
-						if let Some(websocket) = WEBSOCKETS.read().await.get(ws_id) {
+						if let Some((rpc, websocket)) = WEBSOCKETS.read().await.get(ws_id) {
 							// Serialize the message to send
 							let message = res::success(None, notification);
-							// Get the current output format
+							// Get the output format of that WebSocket
 							let format = rpc.read().await.format.clone();
 							// Send the notification to the client
 							message.send(format, websocket.clone()).await;
@@ -194,7 +192,7 @@ impl Rpc {
 		// Log that the WebSocket has connected
 		trace!("WebSocket {} connected", id);
 		// Store this WebSocket in the list of WebSockets
-		WEBSOCKETS.write().await.insert(id, chn);
+		WEBSOCKETS.write().await.insert(id, (rpc.clone(), chn));
 	}
 
 	async fn disconnected(rpc: Arc<RwLock<Rpc>>) {
@@ -407,6 +405,7 @@ impl Rpc {
 			"json" | "application/json" => self.format = Output::Json,
 			"cbor" | "application/cbor" => self.format = Output::Cbor,
 			"pack" | "application/pack" => self.format = Output::Pack,
+			"full" | "application/surrealdb" => self.format = Output::Full,
 			_ => return Err(Error::InvalidType),
 		};
 		Ok(Value::None)
@@ -1,14 +1,23 @@
 use std::time::Duration;
 
 use axum_server::Handle;
+use tokio_util::sync::CancellationToken;
 
 use crate::err::Error;
 
-/// Start a graceful shutdown on the Axum Handle when a shutdown signal is received.
-pub fn graceful_shutdown(handle: Handle, dur: Option<Duration>) {
+/// Start a graceful shutdown on the Axum Handle when a shutdown signal is received,
+/// or when the cancellation token is cancelled by a failed background task.
+pub fn graceful_shutdown(ct: CancellationToken, handle: Handle, dur: Option<Duration>) {
 	tokio::spawn(async move {
-		let result = listen().await.expect("Failed to listen to shutdown signal");
-		info!(target: super::LOG, "{} received. Start graceful shutdown...", result);
+		tokio::select! {
+			result = listen() => {
+				let result = result.expect("Failed to listen to shutdown signal");
+				info!(target: super::LOG, "{} received. Start graceful shutdown...", result);
+			}
+			_ = ct.cancelled() => {
+				info!(target: super::LOG, "Background task failed. Start graceful shutdown...");
+			}
+		}
 
 		handle.graceful_shutdown(dur)
 	});
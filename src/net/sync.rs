@@ -1,20 +1,84 @@
+use crate::dbs::DB;
+use crate::err::Error;
+use axum::extract::BodyStream;
+use axum::extract::DefaultBodyLimit;
 use axum::response::IntoResponse;
 use axum::routing::get;
 use axum::Router;
+use axum::{response::Response, Extension};
+use bytes::Bytes;
+use futures::TryStreamExt;
+use http::StatusCode;
 use http_body::Body as HttpBody;
+use hyper::body::Body;
+use surrealdb::dbs::Session;
+use surrealdb::error::Db as SurrealDbError;
 
 pub(super) fn router<S, B>() -> Router<S, B>
 where
 	B: HttpBody + Send + 'static,
+	B::Data: Into<Bytes>,
+	B::Error: Into<axum::BoxError>,
 	S: Clone + Send + Sync + 'static,
 {
-	Router::new().route("/sync", get(save).post(load))
+	Router::new().route("/sync", get(save).post(load)).route_layer(DefaultBodyLimit::disable())
 }
 
-async fn load() -> impl IntoResponse {
-	"Load"
+async fn load(
+	Extension(session): Extension<Session>,
+	body: BodyStream,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+	// Check the permissions
+	match session.au.is_kv() {
+		true => {
+			// Get the datastore reference
+			let db = DB.get().unwrap();
+			// Restore the backup as it is received
+			let body = body.map_err(|e| SurrealDbError::Backup(e.to_string()));
+			match db.restore(body).await {
+				Ok(_) => Ok(StatusCode::OK),
+				Err(err) => Err(Error::from(err)),
+			}
+		}
+		// The user does not have the correct permissions
+		_ => Err(Error::InvalidAuth),
+	}
 }
 
-async fn save() -> impl IntoResponse {
-	"Save"
+async fn save(
+	Extension(session): Extension<Session>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+	// Check the permissions
+	match session.au.is_kv() {
+		true => {
+			// Get the datastore reference
+			let db = DB.get().unwrap();
+			// Create a chunked response
+			let (mut chn, bdy) = Body::channel();
+			// Create a new bounded channel
+			let (snd, rcv) = surrealdb::channel::new(1);
+			// Spawn a new datastore backup
+			let task = tokio::spawn(db.backup(snd));
+			// Process all processed values
+			tokio::spawn(async move {
+				while let Ok(v) = rcv.recv().await {
+					// Stop the backup if the client has disconnected
+					if chn.send_data(Bytes::from(v)).await.is_err() {
+						break;
+					}
+				}
+				// Closing the channel cancels the backup
+				drop(rcv);
+				// Ensure that a failed backup is not mistaken for a complete one
+				if let Ok(Err(err)) = task.await {
+					error!("The datastore backup failed: {err}");
+					chn.abort();
+				}
+			});
+			// Return the chunked body
+			Ok(Response::builder().status(StatusCode::OK).body(bdy).unwrap())
+		}
+		// The user does not have the correct permissions
+		_ => Err((StatusCode::FORBIDDEN, "Invalid permissions")),
+	}
 }
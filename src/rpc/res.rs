@@ -98,7 +98,8 @@ impl Response {
 				Message::Binary(res)
 			}
 			Output::Full => {
-				let res = surrealdb::sql::serde::serialize(&self).unwrap();
+				// The binary format has no field names, so the id is always included
+				let res = surrealdb::sql::serde::serialize(&(&self.id, &self.result)).unwrap();
 				Message::Binary(res)
 			}
 		};